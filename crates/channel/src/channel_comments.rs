@@ -0,0 +1,389 @@
+use crate::Channel;
+use anyhow::{anyhow, Result};
+use client::{
+    proto,
+    user::{User, UserStore},
+    ChannelId, Client, Subscription, TypedEnvelope,
+};
+use collections::{BTreeMap, HashSet};
+use gpui::{AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task};
+use language::{
+    proto::{deserialize_anchor, serialize_anchor},
+    Anchor, BufferSnapshot, Point,
+};
+use rpc::AnyProtoClient;
+use std::{cmp::Reverse, sync::Arc};
+use time::OffsetDateTime;
+
+/// The review comments left by the members of a channel on the code of its projects.
+pub struct ChannelComments {
+    pub channel_id: ChannelId,
+    threads: BTreeMap<u64, CommentThread>,
+    user_store: Model<UserStore>,
+    rpc: Arc<Client>,
+    _subscription: Subscription,
+}
+
+/// A conversation about a position in a project file.
+#[derive(Clone, Debug)]
+pub struct CommentThread {
+    pub id: u64,
+    /// The path of the file, starting with the name of its worktree.
+    pub path: Arc<str>,
+    pub position: CommentPosition,
+    pub created_by: Arc<User>,
+    pub resolved_by: Option<Arc<User>>,
+    pub comments: Vec<Comment>,
+}
+
+/// The commented position in a file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommentPosition {
+    /// The commented row, when the thread was started.
+    pub line: u32,
+    /// The text of the commented row and of the rows around it, to find that row again once the
+    /// file changed.
+    pub context: String,
+    /// The commented position, along with the ID of the shared project whose buffer it was
+    /// created in. Anchors can only be resolved in that buffer, so they're only used while that
+    /// project is shared.
+    pub anchor: Option<(u64, Anchor)>,
+}
+
+/// The number of rows stored as context on each side of a commented row.
+const CONTEXT_ROWS: u32 = 1;
+
+impl CommentPosition {
+    /// Returns the position of the given row of a buffer, which belongs to the shared project
+    /// with the given ID if any.
+    pub fn new(buffer: &BufferSnapshot, row: u32, project_id: Option<u64>) -> Self {
+        let row = row.min(buffer.max_point().row);
+        // Rows before the start or past the end of the buffer are left empty.
+        let context = (0..=2 * CONTEXT_ROWS)
+            .map(|ix| {
+                (row + ix)
+                    .checked_sub(CONTEXT_ROWS)
+                    .filter(|context_row| *context_row <= buffer.max_point().row)
+                    .map(|context_row| {
+                        let start = Point::new(context_row, 0);
+                        let end = Point::new(context_row, buffer.line_len(context_row));
+                        buffer.text_for_range(start..end).collect::<String>()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let anchor = buffer.anchor_after(Point::new(row, 0));
+        Self {
+            line: row,
+            context,
+            anchor: project_id.map(|project_id| (project_id, anchor)),
+        }
+    }
+
+    /// Returns the commented row in the given buffer, which belongs to the shared project with the
+    /// given ID if any.
+    ///
+    /// The anchor is used when the buffer is the one the thread was started in. Otherwise, this is
+    /// the row with the commented text whose surroundings match best, or the commented row if that
+    /// text is gone.
+    pub fn row(&self, buffer: &BufferSnapshot, project_id: Option<u64>) -> u32 {
+        if let Some((anchor_project_id, anchor)) = &self.anchor {
+            if project_id == Some(*anchor_project_id) && buffer.can_resolve(anchor) {
+                return buffer.summary_for_anchor::<Point>(anchor).row;
+            }
+        }
+
+        let max_row = buffer.max_point().row;
+        let context = self.context.split('\n').collect::<Vec<_>>();
+        let Some(commented_line) = context.get(CONTEXT_ROWS as usize) else {
+            return self.line.min(max_row);
+        };
+        let text = buffer.text();
+        let lines = text.split('\n').collect::<Vec<_>>();
+        let line = |row: i64| {
+            usize::try_from(row)
+                .ok()
+                .and_then(|row| lines.get(row))
+                .copied()
+                .unwrap_or_default()
+        };
+        (0..lines.len() as i64)
+            .filter(|row| line(*row) == *commented_line)
+            .max_by_key(|row| {
+                let matching_context = context
+                    .iter()
+                    .enumerate()
+                    .filter(|(ix, context_line)| {
+                        line(row + *ix as i64 - CONTEXT_ROWS as i64) == **context_line
+                    })
+                    .count();
+                (matching_context, Reverse(row.abs_diff(self.line as i64)))
+            })
+            .map_or(self.line.min(max_row), |row| row as u32)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Comment {
+    pub id: u64,
+    pub sender: Arc<User>,
+    pub body: String,
+    pub timestamp: OffsetDateTime,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChannelCommentsEvent {
+    ThreadUpdated { thread_id: u64 },
+    ThreadsReloaded,
+}
+
+impl EventEmitter<ChannelCommentsEvent> for ChannelComments {}
+
+pub fn init(client: &AnyProtoClient) {
+    client.add_model_message_handler(ChannelComments::handle_update_comment_thread);
+}
+
+impl ChannelComments {
+    pub async fn new(
+        channel: Arc<Channel>,
+        user_store: Model<UserStore>,
+        client: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Model<Self>> {
+        let channel_id = channel.id;
+        let subscription = client.subscribe_to_entity(channel_id.0)?;
+
+        let response = client
+            .request(proto::GetCommentThreads {
+                channel_id: channel_id.0,
+            })
+            .await?;
+        let threads = CommentThread::from_proto_vec(response.threads, &user_store, &mut cx).await?;
+
+        cx.new_model(|cx| Self {
+            channel_id,
+            threads: threads
+                .into_iter()
+                .map(|thread| (thread.id, thread))
+                .collect(),
+            user_store,
+            rpc: client,
+            _subscription: subscription.set_model(&cx.handle(), &mut cx.to_async()),
+        })
+    }
+
+    pub fn threads(&self) -> impl Iterator<Item = &CommentThread> {
+        self.threads.values()
+    }
+
+    pub fn threads_for_path<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &CommentThread> {
+        self.threads
+            .values()
+            .filter(move |thread| thread.path.as_ref() == path)
+    }
+
+    pub fn thread(&self, thread_id: u64) -> Option<&CommentThread> {
+        self.threads.get(&thread_id)
+    }
+
+    /// Starts a thread on the given position of a file, returning the ID of the new thread.
+    pub fn create_thread(
+        &mut self,
+        path: Arc<str>,
+        position: CommentPosition,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<u64>> {
+        if body.trim().is_empty() {
+            return Task::ready(Err(anyhow!("comment can't be empty")));
+        }
+        let (project_id, anchor) = match position.anchor {
+            Some((project_id, anchor)) => (Some(project_id), anchor),
+            None => (None, Anchor::MIN),
+        };
+        let request = self.rpc.request(proto::CreateCommentThread {
+            channel_id: self.channel_id.0,
+            path: path.to_string(),
+            project_id,
+            anchor: Some(serialize_anchor(&anchor)),
+            line: position.line,
+            context: position.context,
+            body,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let thread = request
+                .await?
+                .thread
+                .ok_or_else(|| anyhow!("missing thread"))?;
+            let thread_id = thread.id;
+            Self::insert_thread(this.upgrade(), thread, &mut cx).await?;
+            Ok(thread_id)
+        })
+    }
+
+    pub fn reply(
+        &mut self,
+        thread_id: u64,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if body.trim().is_empty() {
+            return Task::ready(Err(anyhow!("comment can't be empty")));
+        }
+        let request = self.rpc.request(proto::AddComment {
+            channel_id: self.channel_id.0,
+            thread_id,
+            body,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let thread = request
+                .await?
+                .thread
+                .ok_or_else(|| anyhow!("missing thread"))?;
+            Self::insert_thread(this.upgrade(), thread, &mut cx).await
+        })
+    }
+
+    pub fn set_resolved(
+        &mut self,
+        thread_id: u64,
+        resolved: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.rpc.request(proto::SetCommentThreadResolved {
+            channel_id: self.channel_id.0,
+            thread_id,
+            resolved,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let thread = request
+                .await?
+                .thread
+                .ok_or_else(|| anyhow!("missing thread"))?;
+            Self::insert_thread(this.upgrade(), thread, &mut cx).await
+        })
+    }
+
+    /// Reloads all threads, to catch up on the comments made while disconnected.
+    pub fn rejoin(&mut self, cx: &mut ModelContext<Self>) {
+        let user_store = self.user_store.clone();
+        let request = self.rpc.request(proto::GetCommentThreads {
+            channel_id: self.channel_id.0,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let response = request.await?;
+            let threads =
+                CommentThread::from_proto_vec(response.threads, &user_store, &mut cx).await?;
+            this.update(&mut cx, |this, cx| {
+                this.threads = threads
+                    .into_iter()
+                    .map(|thread| (thread.id, thread))
+                    .collect();
+                cx.emit(ChannelCommentsEvent::ThreadsReloaded);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    async fn handle_update_comment_thread(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::UpdateCommentThread>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let thread = message
+            .payload
+            .thread
+            .ok_or_else(|| anyhow!("empty thread"))?;
+        Self::insert_thread(Some(this), thread, &mut cx).await
+    }
+
+    async fn insert_thread(
+        this: Option<Model<Self>>,
+        thread: proto::CommentThread,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let Some(this) = this else {
+            return Ok(());
+        };
+        let user_store = this.update(cx, |this, _| this.user_store.clone())?;
+        let thread = CommentThread::from_proto_vec(vec![thread], &user_store, cx)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("missing thread"))?;
+        this.update(cx, |this, cx| {
+            let thread_id = thread.id;
+            this.threads.insert(thread_id, thread);
+            cx.emit(ChannelCommentsEvent::ThreadUpdated { thread_id });
+        })
+    }
+}
+
+impl CommentThread {
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_by.is_some()
+    }
+
+    async fn from_proto_vec(
+        threads: Vec<proto::CommentThread>,
+        user_store: &Model<UserStore>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Vec<Self>> {
+        let user_ids = threads
+            .iter()
+            .flat_map(|thread| {
+                thread
+                    .comments
+                    .iter()
+                    .map(|comment| comment.sender_id)
+                    .chain(Some(thread.created_by))
+                    .chain(thread.resolved_by)
+            })
+            .collect::<HashSet<_>>();
+        user_store
+            .update(cx, |user_store, cx| {
+                user_store.get_users(user_ids.into_iter().collect(), cx)
+            })?
+            .await?;
+
+        user_store.update(cx, |user_store, _| {
+            let get_user = |user_id| {
+                user_store
+                    .get_cached_user(user_id)
+                    .ok_or_else(|| anyhow!("missing user {user_id}"))
+            };
+            threads
+                .into_iter()
+                .map(|thread| {
+                    Ok(Self {
+                        id: thread.id,
+                        path: thread.path.into(),
+                        position: CommentPosition {
+                            line: thread.line,
+                            context: thread.context,
+                            anchor: thread
+                                .project_id
+                                .zip(thread.anchor.and_then(deserialize_anchor)),
+                        },
+                        created_by: get_user(thread.created_by)?,
+                        resolved_by: thread.resolved_by.map(get_user).transpose()?,
+                        comments: thread
+                            .comments
+                            .into_iter()
+                            .map(|comment| {
+                                Ok(Comment {
+                                    id: comment.id,
+                                    sender: get_user(comment.sender_id)?,
+                                    body: comment.body,
+                                    timestamp: OffsetDateTime::from_unix_timestamp(
+                                        comment.timestamp as i64,
+                                    )?,
+                                })
+                            })
+                            .collect::<Result<_>>()?,
+                    })
+                })
+                .collect()
+        })?
+    }
+}
@@ -1,6 +1,9 @@
 mod channel_index;
 
-use crate::{channel_buffer::ChannelBuffer, channel_chat::ChannelChat, ChannelMessage};
+use crate::{
+    channel_buffer::ChannelBuffer, channel_chat::ChannelChat, channel_comments::ChannelComments,
+    ChannelMessage,
+};
 use anyhow::{anyhow, Result};
 use channel_index::ChannelIndex;
 use client::{ChannelId, Client, ClientSettings, Subscription, User, UserId, UserStore};
@@ -42,6 +45,7 @@ pub struct ChannelStore {
     update_channels_tx: mpsc::UnboundedSender<proto::UpdateChannels>,
     opened_buffers: HashMap<ChannelId, OpenedModelHandle<ChannelBuffer>>,
     opened_chats: HashMap<ChannelId, OpenedModelHandle<ChannelChat>>,
+    opened_comments: HashMap<ChannelId, OpenedModelHandle<ChannelComments>>,
     client: Arc<Client>,
     did_subscribe: bool,
     user_store: Model<UserStore>,
@@ -199,6 +203,7 @@ impl ChannelStore {
             outgoing_invites: Default::default(),
             opened_buffers: Default::default(),
             opened_chats: Default::default(),
+            opened_comments: Default::default(),
             update_channels_tx,
             client,
             user_store,
@@ -450,6 +455,21 @@ impl ChannelStore {
         )
     }
 
+    pub fn open_channel_comments(
+        &mut self,
+        channel_id: ChannelId,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Model<ChannelComments>>> {
+        let client = self.client.clone();
+        let user_store = self.user_store.clone();
+        self.open_channel_resource(
+            channel_id,
+            |this| &mut this.opened_comments,
+            |channel, cx| ChannelComments::new(channel, user_store, client, cx),
+            cx,
+        )
+    }
+
     /// Asynchronously open a given resource associated with a channel.
     ///
     /// Make sure that the resource is only opened once, even if this method
@@ -914,6 +934,14 @@ impl ChannelStore {
             }
         }
 
+        for comments in self.opened_comments.values() {
+            if let OpenedModelHandle::Open(comments) = comments {
+                if let Some(comments) = comments.upgrade() {
+                    comments.update(cx, |comments, cx| comments.rejoin(cx));
+                }
+            }
+        }
+
         let mut buffer_versions = Vec::new();
         for buffer in self.opened_buffers.values() {
             if let OpenedModelHandle::Open(buffer) = buffer {
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS "channel_comment_threads" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "path" TEXT NOT NULL,
+    "anchor" BLOB NOT NULL,
+    "line" INTEGER NOT NULL,
+    "created_by" INTEGER NOT NULL REFERENCES users (id),
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "resolved_by" INTEGER REFERENCES users (id),
+    "resolved_at" TIMESTAMP
+);
+CREATE INDEX "index_channel_comment_threads_on_channel_id" ON "channel_comment_threads" ("channel_id");
+
+CREATE TABLE IF NOT EXISTS "channel_comments" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "thread_id" INTEGER NOT NULL REFERENCES channel_comment_threads (id) ON DELETE CASCADE,
+    "sender_id" INTEGER NOT NULL REFERENCES users (id),
+    "body" TEXT NOT NULL,
+    "sent_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX "index_channel_comments_on_thread_id" ON "channel_comments" ("thread_id");
@@ -0,0 +1,3 @@
+ALTER TABLE "users" ADD COLUMN "oidc_issuer" TEXT;
+ALTER TABLE "users" ADD COLUMN "oidc_subject" TEXT;
+CREATE UNIQUE INDEX "index_users_on_oidc_issuer_and_oidc_subject" ON "users" ("oidc_issuer", "oidc_subject");
@@ -0,0 +1,2 @@
+ALTER TABLE "channel_comment_threads" ADD COLUMN "project_id" INTEGER;
+ALTER TABLE "channel_comment_threads" ADD COLUMN "context" TEXT NOT NULL DEFAULT '';
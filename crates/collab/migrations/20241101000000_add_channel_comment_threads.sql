@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS channel_comment_threads (
+    id SERIAL PRIMARY KEY,
+    channel_id INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    path TEXT NOT NULL,
+    anchor BYTEA NOT NULL,
+    line INTEGER NOT NULL,
+    created_by INTEGER NOT NULL REFERENCES users (id),
+    created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    resolved_by INTEGER REFERENCES users (id),
+    resolved_at TIMESTAMP WITHOUT TIME ZONE
+);
+
+CREATE INDEX "ix_channel_comment_threads_on_channel_id" ON channel_comment_threads (channel_id);
+
+CREATE TABLE IF NOT EXISTS channel_comments (
+    id SERIAL PRIMARY KEY,
+    thread_id INTEGER NOT NULL REFERENCES channel_comment_threads (id) ON DELETE CASCADE,
+    sender_id INTEGER NOT NULL REFERENCES users (id),
+    body TEXT NOT NULL,
+    sent_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX "ix_channel_comments_on_thread_id" ON channel_comments (thread_id);
@@ -0,0 +1 @@
+ALTER TABLE channel_comment_threads ADD COLUMN project_id INTEGER, ADD COLUMN context TEXT NOT NULL DEFAULT '';
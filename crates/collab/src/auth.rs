@@ -1,3 +1,7 @@
+mod oidc;
+mod self_hosted;
+mod static_tokens;
+
 use crate::{
     db::{self, AccessTokenId, Database, UserId},
     rpc::Principal,
@@ -22,6 +26,10 @@ use std::sync::OnceLock;
 use std::{sync::Arc, time::Instant};
 use subtle::ConstantTimeEq;
 
+pub use self_hosted::{
+    redirect_rpc_discovery, routes as self_hosted_routes, AuthBackend, AuthBackendKind,
+};
+
 /// Validates the authorization header and adds an Extension<Principal> to the request.
 /// Authorization: <user-id> <token>
 ///   <token> can be an access_token attached to that user, or an access token of an admin
@@ -0,0 +1,175 @@
+use anyhow::{anyhow, Context as _, Result};
+use dashmap::DashMap;
+use reqwest::Url;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+/// How long a user has to complete a sign-in with the identity provider.
+const SIGN_IN_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Authenticates users with the authorization code flow of an OpenID Connect identity provider.
+pub struct Oidc {
+    http: reqwest::Client,
+    issuer: String,
+    client_id: String,
+    client_secret: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+    pending_sign_ins: DashMap<String, PendingSignIn>,
+}
+
+/// The native app that started a sign-in, to which the access token will be sent.
+pub struct PendingSignIn {
+    pub native_app_port: u16,
+    pub native_app_public_key: String,
+    started_at: Instant,
+}
+
+/// A user signed in with the identity provider. Users are identified by the issuer and the
+/// subject, as the login is chosen by them.
+pub struct OidcUser {
+    pub issuer: String,
+    pub subject: String,
+    pub login: String,
+    pub email: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct UserInfo {
+    sub: String,
+    preferred_username: Option<String>,
+    email: Option<String>,
+}
+
+impl Oidc {
+    /// Reads the endpoints of the identity provider from its discovery document.
+    pub async fn discover(
+        issuer_url: &str,
+        client_id: String,
+        client_secret: String,
+    ) -> Result<Self> {
+        let http = reqwest::Client::new();
+        let discovery_url = format!(
+            "{}/.well-known/openid-configuration",
+            issuer_url.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = http
+            .get(&discovery_url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("failed to fetch {discovery_url}"))?
+            .json()
+            .await
+            .context("failed to parse OpenID provider metadata")?;
+
+        Ok(Self {
+            http,
+            issuer: metadata.issuer,
+            client_id,
+            client_secret,
+            authorization_endpoint: metadata.authorization_endpoint,
+            token_endpoint: metadata.token_endpoint,
+            userinfo_endpoint: metadata.userinfo_endpoint,
+            pending_sign_ins: DashMap::default(),
+        })
+    }
+
+    /// Returns the URL of the identity provider's sign-in page, remembering which native app
+    /// to send the resulting access token to.
+    pub fn authorization_url(
+        &self,
+        redirect_uri: &str,
+        native_app_port: u16,
+        native_app_public_key: String,
+    ) -> Result<String> {
+        self.pending_sign_ins
+            .retain(|_, sign_in| sign_in.started_at.elapsed() < SIGN_IN_TIMEOUT);
+
+        let state = crate::auth::random_token();
+        let url = Url::parse_with_params(
+            &self.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("scope", "openid profile email"),
+                ("client_id", self.client_id.as_str()),
+                ("redirect_uri", redirect_uri),
+                ("state", state.as_str()),
+            ],
+        )?;
+        self.pending_sign_ins.insert(
+            state,
+            PendingSignIn {
+                native_app_port,
+                native_app_public_key,
+                started_at: Instant::now(),
+            },
+        );
+        Ok(url.into())
+    }
+
+    pub fn take_pending_sign_in(&self, state: &str) -> Option<PendingSignIn> {
+        self.pending_sign_ins
+            .remove(state)
+            .map(|(_, sign_in)| sign_in)
+            .filter(|sign_in| sign_in.started_at.elapsed() < SIGN_IN_TIMEOUT)
+    }
+
+    /// Exchanges the code returned by the identity provider for the signed-in user.
+    pub async fn exchange_code(&self, code: &str, redirect_uri: &str) -> Result<OidcUser> {
+        let token: TokenResponse = self
+            .http
+            .post(&self.token_endpoint)
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context("failed to exchange authorization code")?
+            .json()
+            .await?;
+
+        let user_info: UserInfo = self
+            .http
+            .get(&self.userinfo_endpoint)
+            .bearer_auth(&token.access_token)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context("failed to fetch user info")?
+            .json()
+            .await?;
+
+        if user_info.sub.is_empty() {
+            return Err(anyhow!("identity provider returned an empty subject"));
+        }
+        let login = user_info
+            .preferred_username
+            .filter(|login| !login.is_empty())
+            .unwrap_or_else(|| user_info.sub.clone());
+        Ok(OidcUser {
+            issuer: self.issuer.clone(),
+            subject: user_info.sub,
+            login,
+            email: user_info.email,
+        })
+    }
+}
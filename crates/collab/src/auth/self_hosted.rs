@@ -0,0 +1,396 @@
+//! Sign-in for self-hosted servers, which authenticate users themselves instead of going
+//! through the editsync.khulnasoft.com website.
+
+use super::{
+    create_access_token, encrypt_access_token,
+    oidc::{Oidc, PendingSignIn},
+    static_tokens::StaticTokens,
+};
+use crate::{db::User, AppState, Config, Error, Result};
+use anyhow::{anyhow, Context as _};
+use axum::{
+    body::Body,
+    extract::{Form, Query},
+    http::{self, Method, Request, StatusCode},
+    middleware::Next,
+    response::{Html, IntoResponse, Redirect},
+    routing::get,
+    Extension, Router,
+};
+use reqwest::Url;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// The ways a self-hosted server can authenticate its users.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthBackendKind {
+    /// A file of logins and token hashes, read from `AUTH_STATIC_TOKENS_PATH`.
+    StaticTokens,
+    /// An OpenID Connect identity provider, configured with `OIDC_ISSUER_URL`,
+    /// `OIDC_CLIENT_ID` and `OIDC_CLIENT_SECRET`.
+    Oidc,
+}
+
+pub enum AuthBackend {
+    StaticTokens(StaticTokens),
+    Oidc(Oidc),
+}
+
+impl AuthBackend {
+    pub async fn new(config: &Config) -> anyhow::Result<Self> {
+        if config.public_url.is_none() {
+            return Err(anyhow!("self-hosted servers require PUBLIC_URL"));
+        }
+
+        match config.auth_backend {
+            Some(AuthBackendKind::StaticTokens) => {
+                let path = config
+                    .auth_static_tokens_path
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("missing AUTH_STATIC_TOKENS_PATH"))?;
+                Ok(Self::StaticTokens(StaticTokens::load(path)?))
+            }
+            Some(AuthBackendKind::Oidc) => {
+                let issuer_url = config
+                    .oidc_issuer_url
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("missing OIDC_ISSUER_URL"))?;
+                let client_id = config
+                    .oidc_client_id
+                    .clone()
+                    .ok_or_else(|| anyhow!("missing OIDC_CLIENT_ID"))?;
+                let client_secret = config
+                    .oidc_client_secret
+                    .clone()
+                    .ok_or_else(|| anyhow!("missing OIDC_CLIENT_SECRET"))?;
+                Ok(Self::Oidc(
+                    Oidc::discover(issuer_url, client_id, client_secret).await?,
+                ))
+            }
+            None => Err(anyhow!("self-hosted servers require AUTH_BACKEND")),
+        }
+    }
+}
+
+pub fn routes() -> Router<(), Body> {
+    Router::new()
+        .route(
+            "/native_app_signin",
+            get(get_native_app_sign_in).post(post_native_app_sign_in),
+        )
+        .route(
+            "/native_app_signin_succeeded",
+            get(native_app_sign_in_succeeded),
+        )
+        .route("/auth/oidc/callback", get(oidc_callback))
+}
+
+/// Clients find the websocket endpoint by following the redirect of a plain `GET /rpc`, which
+/// the hosted deployment serves from the website. Self-hosted servers redirect to themselves.
+pub async fn redirect_rpc_discovery<B>(req: Request<B>, next: Next<B>) -> impl IntoResponse {
+    let is_discovery = req.method() == Method::GET
+        && req.uri().path() == "/rpc"
+        && !req.headers().contains_key(http::header::UPGRADE);
+    if !is_discovery {
+        return Ok(next.run(req).await);
+    }
+
+    let state = req.extensions().get::<Arc<AppState>>().unwrap();
+    let public_url = public_url(state)?;
+    Ok::<_, Error>(Redirect::temporary(&format!("{public_url}/rpc")).into_response())
+}
+
+#[derive(Deserialize)]
+struct NativeAppSignInParams {
+    native_app_port: u16,
+    native_app_public_key: String,
+}
+
+#[derive(Deserialize)]
+struct StaticTokenSignInForm {
+    native_app_port: u16,
+    native_app_public_key: String,
+    login: String,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct OidcCallbackParams {
+    code: String,
+    state: String,
+}
+
+async fn get_native_app_sign_in(
+    Extension(app): Extension<Arc<AppState>>,
+    Query(params): Query<NativeAppSignInParams>,
+) -> Result<axum::response::Response> {
+    match auth_backend(&app)? {
+        AuthBackend::StaticTokens(_) => {
+            Ok(Html(static_token_sign_in_page(&params, None)).into_response())
+        }
+        AuthBackend::Oidc(oidc) => {
+            let url = oidc.authorization_url(
+                &oidc_redirect_uri(&app)?,
+                params.native_app_port,
+                params.native_app_public_key,
+            )?;
+            Ok(Redirect::temporary(&url).into_response())
+        }
+    }
+}
+
+async fn post_native_app_sign_in(
+    Extension(app): Extension<Arc<AppState>>,
+    Form(form): Form<StaticTokenSignInForm>,
+) -> Result<axum::response::Response> {
+    let AuthBackend::StaticTokens(tokens) = auth_backend(&app)? else {
+        return Err(Error::http(
+            StatusCode::NOT_FOUND,
+            "static token sign-in is disabled".to_string(),
+        ));
+    };
+
+    let Some(user) = tokens.authenticate(&form.login, &form.token) else {
+        let params = NativeAppSignInParams {
+            native_app_port: form.native_app_port,
+            native_app_public_key: form.native_app_public_key,
+        };
+        let page = static_token_sign_in_page(&params, Some("Invalid login or token."));
+        return Ok((StatusCode::UNAUTHORIEDITSYNC, Html(page)).into_response());
+    };
+
+    let user = app
+        .db
+        .get_or_create_user_by_login(&user.login, user.email.as_deref(), Some(user.admin))
+        .await?;
+    let redirect = complete_sign_in(
+        &app,
+        &user,
+        form.native_app_port,
+        form.native_app_public_key,
+    )
+    .await?;
+    Ok(redirect.into_response())
+}
+
+async fn oidc_callback(
+    Extension(app): Extension<Arc<AppState>>,
+    Query(params): Query<OidcCallbackParams>,
+) -> Result<Redirect> {
+    let AuthBackend::Oidc(oidc) = auth_backend(&app)? else {
+        return Err(Error::http(
+            StatusCode::NOT_FOUND,
+            "OIDC sign-in is disabled".to_string(),
+        ));
+    };
+
+    let PendingSignIn {
+        native_app_port,
+        native_app_public_key,
+        ..
+    } = oidc.take_pending_sign_in(&params.state).ok_or_else(|| {
+        Error::http(
+            StatusCode::BAD_REQUEST,
+            "unknown or expired sign-in".to_string(),
+        )
+    })?;
+
+    let user = oidc
+        .exchange_code(&params.code, &oidc_redirect_uri(&app)?)
+        .await?;
+    let user = app
+        .db
+        .get_or_create_user_by_oidc_subject(
+            &user.issuer,
+            &user.subject,
+            &user.login,
+            user.email.as_deref(),
+        )
+        .await?;
+    complete_sign_in(&app, &user, native_app_port, native_app_public_key).await
+}
+
+async fn native_app_sign_in_succeeded() -> Html<&'static str> {
+    Html(concat!(
+        "<!DOCTYPE html><html><head><title>Signed in</title></head><body>",
+        "<p>You are signed in. You can close this page and return to Editsync.</p>",
+        "</body></html>"
+    ))
+}
+
+/// Sends a new access token for the given user to the native app that started the sign-in.
+async fn complete_sign_in(
+    app: &AppState,
+    user: &User,
+    native_app_port: u16,
+    native_app_public_key: String,
+) -> Result<Redirect> {
+    let access_token = create_access_token(&app.db, user.id, None).await?;
+    let encrypted_access_token = encrypt_access_token(&access_token, native_app_public_key)?;
+
+    let url = Url::parse_with_params(
+        &format!("http://127.0.0.1:{native_app_port}/"),
+        &[
+            ("user_id", user.id.to_string()),
+            ("access_token", encrypted_access_token),
+        ],
+    )
+    .context("failed to build native app URL")?;
+    Ok(Redirect::temporary(url.as_str()))
+}
+
+fn auth_backend(app: &AppState) -> Result<&AuthBackend> {
+    app.auth_backend.as_deref().ok_or_else(|| {
+        Error::http(
+            StatusCode::NOT_FOUND,
+            "sign-in is handled by the website".to_string(),
+        )
+    })
+}
+
+fn public_url(app: &AppState) -> Result<&str> {
+    Ok(app
+        .config
+        .public_url
+        .as_deref()
+        .ok_or_else(|| anyhow!("missing PUBLIC_URL"))?
+        .trim_end_matches('/'))
+}
+
+fn oidc_redirect_uri(app: &AppState) -> Result<String> {
+    Ok(format!("{}/auth/oidc/callback", public_url(app)?))
+}
+
+fn static_token_sign_in_page(params: &NativeAppSignInParams, error: Option<&str>) -> String {
+    let error = error
+        .map(|error| format!("<p class=\"error\">{}</p>", escape_html(error)))
+        .unwrap_or_default();
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><title>Sign in to Editsync</title></head>
+<body>
+<h1>Sign in to Editsync</h1>
+{error}
+<form method="post" action="/native_app_signin">
+<input type="hidden" name="native_app_port" value="{port}">
+<input type="hidden" name="native_app_public_key" value="{public_key}">
+<p><label>Login <input name="login" autofocus required></label></p>
+<p><label>Token <input name="token" type="password" required></label></p>
+<p><button type="submit">Sign in</button></p>
+</form>
+</body>
+</html>"#,
+        port = params.native_app_port,
+        public_key = escape_html(&params.native_app_public_key),
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        auth::verify_access_token,
+        db::{tests::TestDb, UserId},
+        executor::Executor,
+        RateLimiter,
+    };
+    use tower::ServiceExt as _;
+
+    #[gpui::test]
+    async fn test_static_token_sign_in(cx: &mut gpui::TestAppContext) {
+        let test_db = TestDb::sqlite(cx.executor().clone());
+        let db = test_db.db().clone();
+        let tokens = StaticTokens::parse(
+            r#"
+            [[users]]
+            login = "alice"
+            email = "alice@example.com"
+            token_sha256 = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+            admin = true
+            "#,
+        )
+        .unwrap();
+        let app = Arc::new(AppState {
+            db: db.clone(),
+            llm_db: None,
+            livekit_client: None,
+            blob_store_client: None,
+            stripe_client: None,
+            stripe_billing: None,
+            rate_limiter: Arc::new(RateLimiter::new(db.clone())),
+            executor: Executor::Deterministic(cx.executor().clone()),
+            kinesis_client: None,
+            auth_backend: Some(Arc::new(AuthBackend::StaticTokens(tokens))),
+            config: Config::test(),
+        });
+        let (public_key, private_key) = rpc::auth::keypair().unwrap();
+        let public_key = String::try_from(public_key).unwrap();
+
+        let sign_in = |login: &str, token: &str| {
+            let form = Url::parse_with_params(
+                "http://localhost/",
+                [
+                    ("native_app_port", "9999"),
+                    ("native_app_public_key", &public_key),
+                    ("login", login),
+                    ("token", token),
+                ],
+            )
+            .unwrap()
+            .query()
+            .unwrap()
+            .to_string();
+            let request = Request::post("/native_app_signin")
+                .header(
+                    http::header::CONTENT_TYPE,
+                    "application/x-www-form-urlencoded",
+                )
+                .body(Body::from(form))
+                .unwrap();
+            routes().layer(Extension(app.clone())).oneshot(request)
+        };
+
+        let response = sign_in("alice", "wrong").await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIEDITSYNC);
+
+        // Signing in redirects to the native app with an access token encrypted for it.
+        let response = sign_in("alice", "test").await.unwrap();
+        assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+        let location = response.headers()[http::header::LOCATION].to_str().unwrap();
+        let url = Url::parse(location).unwrap();
+        assert_eq!(url.port(), Some(9999));
+        let params = url
+            .query_pairs()
+            .collect::<std::collections::HashMap<_, _>>();
+        let user_id = UserId::from_proto(params["user_id"].parse().unwrap());
+        let access_token = private_key.decrypt_string(&params["access_token"]).unwrap();
+        assert!(
+            verify_access_token(&access_token, user_id, &db)
+                .await
+                .unwrap()
+                .is_valid
+        );
+
+        let user = db.get_user_by_id(user_id).await.unwrap().unwrap();
+        assert_eq!(user.github_login, "alice");
+        assert_eq!(user.email_address.as_deref(), Some("alice@example.com"));
+        assert!(user.admin);
+    }
+}
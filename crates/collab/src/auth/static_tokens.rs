@@ -0,0 +1,95 @@
+use anyhow::{Context as _, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use subtle::ConstantTimeEq;
+
+/// Authenticates users against a fixed list of logins and tokens, read from a TOML file:
+///
+/// ```toml
+/// [[users]]
+/// login = "nathan"
+/// email = "nathan@example.com"
+/// # The hex-encoded SHA-256 of the user's token.
+/// token_sha256 = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+/// admin = true
+/// ```
+pub struct StaticTokens {
+    users: Vec<StaticTokenUser>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StaticTokenUser {
+    pub login: String,
+    pub email: Option<String>,
+    token_sha256: String,
+    #[serde(default)]
+    pub admin: bool,
+}
+
+#[derive(Deserialize)]
+struct StaticTokensFile {
+    users: Vec<StaticTokenUser>,
+}
+
+impl StaticTokens {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read static tokens from {path:?}"))?;
+        Self::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let file: StaticTokensFile =
+            toml::from_str(contents).context("failed to parse static tokens")?;
+        Ok(Self { users: file.users })
+    }
+
+    /// Returns the user with the given login, if the given token is theirs.
+    pub fn authenticate(&self, login: &str, token: &str) -> Option<&StaticTokenUser> {
+        let token_sha256 = hex::encode(Sha256::digest(token.as_bytes()));
+        self.users.iter().find(|user| {
+            user.login == login
+                && bool::from(
+                    user.token_sha256
+                        .to_ascii_lowercase()
+                        .as_bytes()
+                        .ct_eq(token_sha256.as_bytes()),
+                )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authenticate() {
+        let tokens = StaticTokens::parse(
+            r#"
+            [[users]]
+            login = "alice"
+            email = "alice@example.com"
+            token_sha256 = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08"
+            admin = true
+
+            [[users]]
+            login = "bob"
+            token_sha256 = "60303ae22b998861bce3b28f33eec1be758a213c86c93c076dbe9f558c11c752"
+            "#,
+        )
+        .unwrap();
+
+        let alice = tokens.authenticate("alice", "test").unwrap();
+        assert_eq!(alice.email.as_deref(), Some("alice@example.com"));
+        assert!(alice.admin);
+
+        let bob = tokens.authenticate("bob", "test2").unwrap();
+        assert!(!bob.admin);
+
+        assert!(tokens.authenticate("alice", "test2").is_none());
+        assert!(tokens.authenticate("bob", "test").is_none());
+        assert!(tokens.authenticate("carol", "test").is_none());
+    }
+}
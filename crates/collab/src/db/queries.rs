@@ -6,6 +6,7 @@ pub mod billing_preferences;
 pub mod billing_subscriptions;
 pub mod buffers;
 pub mod channels;
+pub mod comments;
 pub mod contacts;
 pub mod contributors;
 pub mod embeddings;
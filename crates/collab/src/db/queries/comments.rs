@@ -0,0 +1,249 @@
+use super::*;
+use prost::Message as _;
+use time::OffsetDateTime;
+
+impl Database {
+    /// Returns the comment threads in the given channel, along with their comments.
+    pub async fn get_channel_comment_threads(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::CommentThread>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let rows = channel_comment_thread::Entity::find()
+                .filter(channel_comment_thread::Column::ChannelId.eq(channel_id))
+                .order_by_asc(channel_comment_thread::Column::Id)
+                .all(&*tx)
+                .await?;
+
+            self.load_comment_threads(rows, &tx).await
+        })
+        .await
+    }
+
+    /// Starts a new comment thread on a position in a project file.
+    ///
+    /// The anchor is only meaningful in the buffer it was created in, which belongs to the given
+    /// shared project, so the line and its context are stored to find the position again later.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_channel_comment_thread(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        path: &str,
+        project_id: Option<ProjectId>,
+        anchor: &proto::Anchor,
+        line: u32,
+        context: &str,
+        body: &str,
+        timestamp: OffsetDateTime,
+    ) -> Result<(channel::Model, proto::CommentThread)> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let timestamp = primitive_timestamp(timestamp);
+            let thread = channel_comment_thread::ActiveModel {
+                id: ActiveValue::NotSet,
+                channel_id: ActiveValue::Set(channel_id),
+                path: ActiveValue::Set(path.to_string()),
+                project_id: ActiveValue::Set(project_id),
+                anchor: ActiveValue::Set(anchor.encode_to_vec()),
+                line: ActiveValue::Set(line as i32),
+                context: ActiveValue::Set(context.to_string()),
+                created_by: ActiveValue::Set(user_id),
+                created_at: ActiveValue::Set(timestamp),
+                resolved_by: ActiveValue::Set(None),
+                resolved_at: ActiveValue::Set(None),
+            }
+            .insert(&*tx)
+            .await?;
+            self.insert_comment(thread.id, user_id, body, timestamp, &tx)
+                .await?;
+
+            let thread = self.load_comment_thread(thread, &tx).await?;
+            Ok((channel, thread))
+        })
+        .await
+    }
+
+    /// Adds a reply to an existing comment thread.
+    pub async fn create_channel_comment(
+        &self,
+        channel_id: ChannelId,
+        thread_id: CommentThreadId,
+        user_id: UserId,
+        body: &str,
+        timestamp: OffsetDateTime,
+    ) -> Result<(channel::Model, proto::CommentThread)> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let thread = self
+                .get_comment_thread_internal(channel_id, thread_id, &tx)
+                .await?;
+            self.insert_comment(
+                thread_id,
+                user_id,
+                body,
+                primitive_timestamp(timestamp),
+                &tx,
+            )
+            .await?;
+
+            let thread = self.load_comment_thread(thread, &tx).await?;
+            Ok((channel, thread))
+        })
+        .await
+    }
+
+    /// Marks a comment thread as resolved by the given user, or reopens it.
+    pub async fn set_channel_comment_thread_resolved(
+        &self,
+        channel_id: ChannelId,
+        thread_id: CommentThreadId,
+        user_id: UserId,
+        resolved: bool,
+        timestamp: OffsetDateTime,
+    ) -> Result<(channel::Model, proto::CommentThread)> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let thread = self
+                .get_comment_thread_internal(channel_id, thread_id, &tx)
+                .await?;
+            let (resolved_by, resolved_at) = if resolved {
+                (Some(user_id), Some(primitive_timestamp(timestamp)))
+            } else {
+                (None, None)
+            };
+            let thread = channel_comment_thread::ActiveModel {
+                id: ActiveValue::Unchanged(thread.id),
+                channel_id: ActiveValue::NotSet,
+                path: ActiveValue::NotSet,
+                project_id: ActiveValue::NotSet,
+                anchor: ActiveValue::NotSet,
+                line: ActiveValue::NotSet,
+                context: ActiveValue::NotSet,
+                created_by: ActiveValue::NotSet,
+                created_at: ActiveValue::NotSet,
+                resolved_by: ActiveValue::Set(resolved_by),
+                resolved_at: ActiveValue::Set(resolved_at),
+            }
+            .update(&*tx)
+            .await?;
+
+            let thread = self.load_comment_thread(thread, &tx).await?;
+            Ok((channel, thread))
+        })
+        .await
+    }
+
+    async fn get_comment_thread_internal(
+        &self,
+        channel_id: ChannelId,
+        thread_id: CommentThreadId,
+        tx: &DatabaseTransaction,
+    ) -> Result<channel_comment_thread::Model> {
+        Ok(channel_comment_thread::Entity::find_by_id(thread_id)
+            .filter(channel_comment_thread::Column::ChannelId.eq(channel_id))
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such comment thread"))?)
+    }
+
+    async fn insert_comment(
+        &self,
+        thread_id: CommentThreadId,
+        user_id: UserId,
+        body: &str,
+        timestamp: PrimitiveDateTime,
+        tx: &DatabaseTransaction,
+    ) -> Result<()> {
+        channel_comment::ActiveModel {
+            id: ActiveValue::NotSet,
+            thread_id: ActiveValue::Set(thread_id),
+            sender_id: ActiveValue::Set(user_id),
+            body: ActiveValue::Set(body.to_string()),
+            sent_at: ActiveValue::Set(timestamp),
+        }
+        .insert(tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn load_comment_thread(
+        &self,
+        row: channel_comment_thread::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<proto::CommentThread> {
+        self.load_comment_threads(vec![row], tx)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("no such comment thread"))
+    }
+
+    /// Converts the given threads to their proto representation, loading their comments.
+    ///
+    /// The rows must be ordered by ID.
+    async fn load_comment_threads(
+        &self,
+        rows: Vec<channel_comment_thread::Model>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::CommentThread>> {
+        let mut threads = rows
+            .into_iter()
+            .map(|row| {
+                Ok(proto::CommentThread {
+                    id: row.id.to_proto(),
+                    channel_id: row.channel_id.to_proto(),
+                    path: row.path,
+                    project_id: row.project_id.map(|project_id| project_id.to_proto()),
+                    anchor: Some(
+                        proto::Anchor::decode(row.anchor.as_slice())
+                            .map_err(|error| anyhow!("{}", error))?,
+                    ),
+                    line: row.line as u32,
+                    context: row.context,
+                    created_by: row.created_by.to_proto(),
+                    resolved_by: row.resolved_by.map(|user_id| user_id.to_proto()),
+                    comments: Vec::new(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut comments = channel_comment::Entity::find()
+            .filter(channel_comment::Column::ThreadId.is_in(threads.iter().map(|t| t.id)))
+            .order_by_asc(channel_comment::Column::Id)
+            .stream(tx)
+            .await?;
+        while let Some(comment) = comments.next().await {
+            let comment = comment?;
+            let thread_id = comment.thread_id.to_proto();
+            if let Ok(ix) = threads.binary_search_by_key(&thread_id, |thread| thread.id) {
+                threads[ix].comments.push(proto::Comment {
+                    id: comment.id.to_proto(),
+                    sender_id: comment.sender_id.to_proto(),
+                    body: comment.body,
+                    timestamp: comment.sent_at.assume_utc().unix_timestamp() as u64,
+                });
+            }
+        }
+
+        Ok(threads)
+    }
+}
+
+fn primitive_timestamp(timestamp: OffsetDateTime) -> PrimitiveDateTime {
+    let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+    PrimitiveDateTime::new(timestamp.date(), timestamp.time())
+}
@@ -176,6 +176,119 @@ impl Database {
         }
     }
 
+    /// Returns the user with the given login, creating it if needed. Used by the static token
+    /// auth backend, whose users don't have a GitHub account: they are given a negative
+    /// `github_user_id` so that they can never collide with one. An `admin` of `None` leaves
+    /// the flag of existing users unchanged, as does an `email` of `None` for their email.
+    pub async fn get_or_create_user_by_login(
+        &self,
+        login: &str,
+        email: Option<&str>,
+        admin: Option<bool>,
+    ) -> Result<User> {
+        self.transaction(|tx| async move {
+            if let Some(user) = user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(login))
+                .one(&*tx)
+                .await?
+            {
+                let mut user = user.into_active_model();
+                if let Some(email) = email {
+                    user.email_address = ActiveValue::set(Some(email.into()));
+                }
+                if let Some(admin) = admin {
+                    user.admin = ActiveValue::set(admin);
+                }
+                return Ok(user.update(&*tx).await?);
+            }
+
+            let github_user_id = self.next_self_hosted_github_user_id(&*tx).await?;
+            Ok(user::Entity::insert(user::ActiveModel {
+                email_address: ActiveValue::set(email.map(|email| email.into())),
+                github_login: ActiveValue::set(login.into()),
+                github_user_id: ActiveValue::set(github_user_id),
+                admin: ActiveValue::set(admin.unwrap_or(false)),
+                invite_count: ActiveValue::set(0),
+                invite_code: ActiveValue::set(None),
+                metrics_id: ActiveValue::set(Uuid::new_v4()),
+                ..Default::default()
+            })
+            .exec_with_returning(&*tx)
+            .await?)
+        })
+        .await
+    }
+
+    /// Returns the user who signs in with the given OpenID Connect issuer and subject, creating
+    /// it if needed. Users are never matched by login, which identity providers let users
+    /// choose: new users are given the preferred login, or a variant of it if it's taken, and
+    /// existing users keep theirs. An `email` of `None` leaves the email of existing users
+    /// unchanged.
+    pub async fn get_or_create_user_by_oidc_subject(
+        &self,
+        issuer: &str,
+        subject: &str,
+        preferred_login: &str,
+        email: Option<&str>,
+    ) -> Result<User> {
+        self.transaction(|tx| async move {
+            if let Some(user) = user::Entity::find()
+                .filter(user::Column::OidcIssuer.eq(issuer))
+                .filter(user::Column::OidcSubject.eq(subject))
+                .one(&*tx)
+                .await?
+            {
+                let Some(email) = email else {
+                    return Ok(user);
+                };
+                let mut user = user.into_active_model();
+                user.email_address = ActiveValue::set(Some(email.into()));
+                return Ok(user.update(&*tx).await?);
+            }
+
+            let mut login = preferred_login.to_string();
+            let mut suffix = 1;
+            while user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(login.as_str()))
+                .one(&*tx)
+                .await?
+                .is_some()
+            {
+                suffix += 1;
+                login = format!("{preferred_login}-{suffix}");
+            }
+
+            let github_user_id = self.next_self_hosted_github_user_id(&*tx).await?;
+            Ok(user::Entity::insert(user::ActiveModel {
+                email_address: ActiveValue::set(email.map(|email| email.into())),
+                github_login: ActiveValue::set(login),
+                github_user_id: ActiveValue::set(github_user_id),
+                oidc_issuer: ActiveValue::set(Some(issuer.into())),
+                oidc_subject: ActiveValue::set(Some(subject.into())),
+                admin: ActiveValue::set(false),
+                invite_count: ActiveValue::set(0),
+                invite_code: ActiveValue::set(None),
+                metrics_id: ActiveValue::set(Uuid::new_v4()),
+                ..Default::default()
+            })
+            .exec_with_returning(&*tx)
+            .await?)
+        })
+        .await
+    }
+
+    /// Returns the `github_user_id` of the next user created by a self-hosted auth backend,
+    /// which is negative so that it can never collide with a GitHub account's.
+    async fn next_self_hosted_github_user_id(&self, tx: &DatabaseTransaction) -> Result<i32> {
+        let lowest_github_user_id = user::Entity::find()
+            .filter(user::Column::GithubUserId.lt(0))
+            .order_by_asc(user::Column::GithubUserId)
+            .one(tx)
+            .await?
+            .map_or(0, |user| user.github_user_id);
+        Ok(lowest_github_user_id - 1)
+    }
+
     /// get_all_users returns the next page of users. To get more call again with
     /// the same limit and the page incremented by 1.
     pub async fn get_all_users(&self, page: u32, limit: u32) -> Result<Vec<User>> {
@@ -8,6 +8,8 @@ pub mod buffer_snapshot;
 pub mod channel;
 pub mod channel_buffer_collaborator;
 pub mod channel_chat_participant;
+pub mod channel_comment;
+pub mod channel_comment_thread;
 pub mod channel_member;
 pub mod channel_message;
 pub mod channel_message_mention;
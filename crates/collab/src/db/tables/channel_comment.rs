@@ -0,0 +1,44 @@
+use crate::db::{CommentId, CommentThreadId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "channel_comments")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: CommentId,
+    pub thread_id: CommentThreadId,
+    pub sender_id: UserId,
+    pub body: String,
+    pub sent_at: PrimitiveDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::channel_comment_thread::Entity",
+        from = "Column::ThreadId",
+        to = "super::channel_comment_thread::Column::Id"
+    )]
+    Thread,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::SenderId",
+        to = "super::user::Column::Id"
+    )]
+    Sender,
+}
+
+impl Related<super::channel_comment_thread::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Thread.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sender.def()
+    }
+}
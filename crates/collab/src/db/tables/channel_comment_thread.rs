@@ -0,0 +1,49 @@
+use crate::db::{ChannelId, CommentThreadId, ProjectId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "channel_comment_threads")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: CommentThreadId,
+    pub channel_id: ChannelId,
+    pub path: String,
+    /// The shared project whose buffer the anchor belongs to, if the file was shared.
+    pub project_id: Option<ProjectId>,
+    /// The position of the thread in the commented buffer, as an encoded `proto::Anchor`.
+    pub anchor: Vec<u8>,
+    pub line: i32,
+    /// The text around the commented line, to find it again once the file changed.
+    pub context: String,
+    pub created_by: UserId,
+    pub created_at: PrimitiveDateTime,
+    pub resolved_by: Option<UserId>,
+    pub resolved_at: Option<PrimitiveDateTime>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::channel::Entity",
+        from = "Column::ChannelId",
+        to = "super::channel::Column::Id"
+    )]
+    Channel,
+    #[sea_orm(has_many = "super::channel_comment::Entity")]
+    Comments,
+}
+
+impl Related<super::channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Channel.def()
+    }
+}
+
+impl Related<super::channel_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comments.def()
+    }
+}
@@ -22,6 +22,9 @@ pub struct Model {
     pub created_at: NaiveDateTime,
     pub accepted_tos_at: Option<NaiveDateTime>,
     pub custom_llm_monthly_allowance_in_cents: Option<i32>,
+    /// The issuer and subject identifying users who sign in with OpenID Connect.
+    pub oidc_issuer: Option<String>,
+    pub oidc_subject: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
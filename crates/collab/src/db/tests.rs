@@ -1,6 +1,7 @@
 mod billing_subscription_tests;
 mod buffer_tests;
 mod channel_tests;
+mod comment_tests;
 mod contributor_tests;
 mod db_tests;
 // we only run postgres tests on macos right now
@@ -44,10 +45,7 @@ impl TestDb {
             let mut db = Database::new(options, Executor::Deterministic(background))
                 .await
                 .unwrap();
-            let sql = include_str!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/migrations.sqlite/20221109000000_test_schema.sql"
-            ));
+            let sql = include_str!("tests/test_schema.sql");
             db.pool
                 .execute(sea_orm::Statement::from_string(
                     db.pool.get_database_backend(),
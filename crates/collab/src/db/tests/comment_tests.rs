@@ -0,0 +1,140 @@
+use super::new_test_user;
+use crate::{
+    db::{ChannelRole, CommentThreadId, Database, ProjectId},
+    test_both_dbs,
+};
+use rpc::proto;
+use std::sync::Arc;
+use time::OffsetDateTime;
+
+test_both_dbs!(
+    test_channel_comment_threads,
+    test_channel_comment_threads_postgres,
+    test_channel_comment_threads_sqlite
+);
+
+async fn test_channel_comment_threads(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+    let user_c = new_test_user(db, "user_c@example.com").await;
+    let channel = db
+        .create_channel("channel", None, user_a)
+        .await
+        .unwrap()
+        .0
+        .id;
+    db.invite_channel_member(channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, user_b, true)
+        .await
+        .unwrap();
+
+    let anchor = proto::Anchor {
+        replica_id: 1,
+        timestamp: 5,
+        offset: 42,
+        bias: proto::Bias::Left as i32,
+        buffer_id: Some(3),
+    };
+    let (_, thread) = db
+        .create_channel_comment_thread(
+            channel,
+            user_a,
+            "project/src/main.rs",
+            Some(ProjectId::from_proto(11)),
+            &anchor,
+            7,
+            "fn main() {\n    run();\n}",
+            "Should this return a Result?",
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(thread.path, "project/src/main.rs");
+    assert_eq!(thread.project_id, Some(11));
+    assert_eq!(thread.anchor, Some(anchor.clone()));
+    assert_eq!(thread.line, 7);
+    assert_eq!(thread.context, "fn main() {\n    run();\n}");
+    assert_eq!(thread.resolved_by, None);
+
+    let thread_id = CommentThreadId::from_proto(thread.id);
+    let (_, thread) = db
+        .create_channel_comment(
+            channel,
+            thread_id,
+            user_b,
+            "Yes, good catch.",
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        thread
+            .comments
+            .iter()
+            .map(|comment| (comment.sender_id, comment.body.as_str()))
+            .collect::<Vec<_>>(),
+        &[
+            (user_a.to_proto(), "Should this return a Result?"),
+            (user_b.to_proto(), "Yes, good catch."),
+        ]
+    );
+
+    // Only channel members can see or reply to comments.
+    db.get_channel_comment_threads(channel, user_c)
+        .await
+        .unwrap_err();
+    db.create_channel_comment(
+        channel,
+        thread_id,
+        user_c,
+        "Hello",
+        OffsetDateTime::now_utc(),
+    )
+    .await
+    .unwrap_err();
+
+    let (_, thread) = db
+        .set_channel_comment_thread_resolved(
+            channel,
+            thread_id,
+            user_b,
+            true,
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(thread.resolved_by, Some(user_b.to_proto()));
+
+    let threads = db
+        .get_channel_comment_threads(channel, user_a)
+        .await
+        .unwrap();
+    assert_eq!(threads, &[thread]);
+
+    let (_, thread) = db
+        .set_channel_comment_thread_resolved(
+            channel,
+            thread_id,
+            user_a,
+            false,
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(thread.resolved_by, None);
+    assert_eq!(thread.comments.len(), 2);
+
+    // Threads can't be accessed through another channel.
+    let other_channel = db.create_channel("other", None, user_a).await.unwrap().0.id;
+    db.create_channel_comment(
+        other_channel,
+        thread_id,
+        user_a,
+        "Hello",
+        OffsetDateTime::now_utc(),
+    )
+    .await
+    .unwrap_err();
+}
@@ -43,3 +43,117 @@ async fn test_accepted_tos(db: &Arc<Database>) {
     let user = db.get_user_by_id(user_id).await.unwrap().unwrap();
     assert!(user.accepted_tos_at.is_none());
 }
+
+test_both_dbs!(
+    test_get_or_create_user_by_login,
+    test_get_or_create_user_by_login_postgres,
+    test_get_or_create_user_by_login_sqlite
+);
+
+async fn test_get_or_create_user_by_login(db: &Arc<Database>) {
+    let user_id = db
+        .create_user(
+            "user1@example.com",
+            false,
+            NewUserParams {
+                github_login: "user1".to_string(),
+                github_user_id: 1,
+            },
+        )
+        .await
+        .unwrap()
+        .user_id;
+
+    // Existing users are found by login, and their details are updated.
+    let user = db
+        .get_or_create_user_by_login("user1", Some("user1@corp.example"), Some(true))
+        .await
+        .unwrap();
+    assert_eq!(user.id, user_id);
+    assert_eq!(user.github_user_id, 1);
+    assert_eq!(user.email_address.as_deref(), Some("user1@corp.example"));
+    assert!(user.admin);
+
+    // New users are given distinct negative GitHub user ids.
+    let user2 = db
+        .get_or_create_user_by_login("user2", None, Some(false))
+        .await
+        .unwrap();
+    let user3 = db
+        .get_or_create_user_by_login("user3", None, None)
+        .await
+        .unwrap();
+    assert_eq!(user2.github_user_id, -1);
+    assert_eq!(user3.github_user_id, -2);
+
+    let user2_again = db
+        .get_or_create_user_by_login("user2", None, Some(false))
+        .await
+        .unwrap();
+    assert_eq!(user2_again.id, user2.id);
+
+    // A missing email leaves the existing one unchanged.
+    let user = db
+        .get_or_create_user_by_login("user1", None, None)
+        .await
+        .unwrap();
+    assert_eq!(user.email_address.as_deref(), Some("user1@corp.example"));
+    assert!(user.admin);
+}
+
+test_both_dbs!(
+    test_get_or_create_user_by_oidc_subject,
+    test_get_or_create_user_by_oidc_subject_postgres,
+    test_get_or_create_user_by_oidc_subject_sqlite
+);
+
+async fn test_get_or_create_user_by_oidc_subject(db: &Arc<Database>) {
+    let existing_user_id = db
+        .create_user(
+            "user1@example.com",
+            false,
+            NewUserParams {
+                github_login: "user1".to_string(),
+                github_user_id: 1,
+            },
+        )
+        .await
+        .unwrap()
+        .user_id;
+
+    // Users choosing the login of another user don't sign in as them.
+    let user = db
+        .get_or_create_user_by_oidc_subject(
+            "https://idp.example",
+            "subject-1",
+            "user1",
+            Some("user1@idp.example"),
+        )
+        .await
+        .unwrap();
+    assert_ne!(user.id, existing_user_id);
+    assert_eq!(user.github_login, "user1-2");
+    assert_eq!(user.email_address.as_deref(), Some("user1@idp.example"));
+    assert!(user.github_user_id < 0);
+
+    // Users are found by their subject, even if they changed their login or omit their email.
+    let user_again = db
+        .get_or_create_user_by_oidc_subject("https://idp.example", "subject-1", "renamed", None)
+        .await
+        .unwrap();
+    assert_eq!(user_again.id, user.id);
+    assert_eq!(user_again.github_login, "user1-2");
+    assert_eq!(
+        user_again.email_address.as_deref(),
+        Some("user1@idp.example")
+    );
+
+    // The same subject from another issuer is another user.
+    let other_user = db
+        .get_or_create_user_by_oidc_subject("https://other-idp.example", "subject-1", "user3", None)
+        .await
+        .unwrap();
+    assert_ne!(other_user.id, user.id);
+    assert_eq!(other_user.github_login, "user3");
+    assert_ne!(other_user.github_user_id, user.github_user_id);
+}
@@ -144,6 +144,8 @@ impl std::error::Error for Error {}
 
 #[derive(Clone, Deserialize)]
 pub struct Config {
+    #[serde(default)]
+    pub profile: ServerProfile,
     pub http_port: u16,
     pub database_url: String,
     pub migrations_path: Option<PathBuf>,
@@ -184,6 +186,13 @@ pub struct Config {
     pub stripe_api_key: Option<String>,
     pub supermaven_admin_api_key: Option<Arc<str>>,
     pub user_backfiller_github_access_token: Option<Arc<str>>,
+    /// The URL at which clients reach this server, used in self-hosted sign-in redirects.
+    pub public_url: Option<String>,
+    pub auth_backend: Option<auth::AuthBackendKind>,
+    pub auth_static_tokens_path: Option<PathBuf>,
+    pub oidc_issuer_url: Option<String>,
+    pub oidc_client_id: Option<String>,
+    pub oidc_client_secret: Option<String>,
 }
 
 impl Config {
@@ -191,6 +200,14 @@ impl Config {
         self.editsync_environment == "development".into()
     }
 
+    pub fn is_self_hosted(&self) -> bool {
+        self.profile == ServerProfile::SelfHosted
+    }
+
+    pub fn is_sqlite(&self) -> bool {
+        self.database_url.starts_with("sqlite:")
+    }
+
     /// Returns the base `editsync.khulnasoft.com` URL.
     pub fn editsync_dot_dev_url(&self) -> &str {
         match self.editsync_environment.as_ref() {
@@ -203,6 +220,7 @@ impl Config {
     #[cfg(test)]
     pub fn test() -> Self {
         Self {
+            profile: ServerProfile::Hosted,
             http_port: 0,
             database_url: "".into(),
             database_max_connections: 0,
@@ -243,10 +261,29 @@ impl Config {
             kinesis_access_key: None,
             kinesis_secret_key: None,
             kinesis_stream: None,
+            public_url: None,
+            auth_backend: None,
+            auth_static_tokens_path: None,
+            oidc_issuer_url: None,
+            oidc_client_id: None,
+            oidc_client_secret: None,
         }
     }
 }
 
+/// The set of services a collab server is deployed with.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerProfile {
+    /// The editsync.khulnasoft.com deployment, with billing, the LLM service and GitHub sign-in
+    /// through the website.
+    #[default]
+    Hosted,
+    /// A deployment on a private network, with collaboration only. Users sign in with the
+    /// configured [`auth::AuthBackend`], against this server directly.
+    SelfHosted,
+}
+
 /// The service mode that collab should run in.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, strum::Display)]
 #[strum(serialize_all = "snake_case")]
@@ -281,6 +318,7 @@ pub struct AppState {
     pub rate_limiter: Arc<RateLimiter>,
     pub executor: Executor,
     pub kinesis_client: Option<::aws_sdk_kinesis::Client>,
+    pub auth_backend: Option<Arc<auth::AuthBackend>>,
     pub config: Config,
 }
 
@@ -291,7 +329,9 @@ impl AppState {
         let mut db = Database::new(db_options, Executor::Production).await?;
         db.initialize_notification_kinds().await?;
 
-        let llm_db = if let Some((llm_database_url, llm_database_max_connections)) = config
+        let llm_db = if config.is_self_hosted() {
+            None
+        } else if let Some((llm_database_url, llm_database_max_connections)) = config
             .llm_database_url
             .clone()
             .zip(config.llm_database_max_connections)
@@ -321,7 +361,16 @@ impl AppState {
         };
 
         let db = Arc::new(db);
-        let stripe_client = build_stripe_client(&config).map(Arc::new).log_err();
+        let stripe_client = if config.is_self_hosted() {
+            None
+        } else {
+            build_stripe_client(&config).map(Arc::new).log_err()
+        };
+        let auth_backend = if config.is_self_hosted() {
+            Some(Arc::new(auth::AuthBackend::new(&config).await?))
+        } else {
+            None
+        };
         let this = Self {
             db: db.clone(),
             llm_db,
@@ -338,6 +387,7 @@ impl AppState {
             } else {
                 None
             },
+            auth_backend,
             config,
         };
         Ok(Arc::new(this))
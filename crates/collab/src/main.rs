@@ -3,13 +3,14 @@ use axum::headers::HeaderMapExt;
 use axum::{
     extract::MatchedPath,
     http::{Request, Response},
+    middleware,
     routing::get,
     Extension, Router,
 };
 use collab::api::billing::sync_llm_usage_with_stripe_periodically;
 use collab::api::CloudflareIpCountryHeader;
 use collab::llm::{db::LlmDatabase, log_usage_periodically};
-use collab::migrations::run_database_migrations;
+use collab::migrations::{apply_database_migrations, run_database_migrations};
 use collab::user_backfiller::spawn_user_backfiller;
 use collab::{api::billing::poll_stripe_events_periodically, llm::LlmState, ServiceMode};
 use collab::{
@@ -86,6 +87,12 @@ async fn main() -> Result<()> {
             init_tracing(&config);
             init_panic_hook();
 
+            if config.is_self_hosted() && mode.is_llm() && mode != ServiceMode::All {
+                Err(anyhow!(
+                    "the LLM service is not available on self-hosted servers"
+                ))?;
+            }
+
             let mut app = Router::new()
                 .route("/", get(handle_root))
                 .route("/healthz", get(handle_liveness_probe))
@@ -96,7 +103,7 @@ async fn main() -> Result<()> {
 
             let mut on_shutdown = None;
 
-            if mode.is_llm() {
+            if mode.is_llm() && !config.is_self_hosted() {
                 setup_llm_database(&config).await?;
 
                 let state = LlmState::new(config.clone(), Executor::Production).await?;
@@ -134,40 +141,50 @@ async fn main() -> Result<()> {
                     let rpc_server = collab::rpc::Server::new(epoch, state.clone());
                     rpc_server.start().await?;
 
-                    poll_stripe_events_periodically(state.clone(), rpc_server.clone());
-
-                    app = app
-                        .merge(collab::api::routes(rpc_server.clone()))
-                        .merge(collab::rpc::routes(rpc_server.clone()));
+                    if state.config.is_self_hosted() {
+                        app = app
+                            .merge(collab::auth::self_hosted_routes())
+                            .merge(collab::rpc::routes(rpc_server.clone()))
+                            .layer(middleware::from_fn(collab::auth::redirect_rpc_discovery));
+                    } else {
+                        poll_stripe_events_periodically(state.clone(), rpc_server.clone());
+
+                        app = app
+                            .merge(collab::api::routes(rpc_server.clone()))
+                            .merge(collab::rpc::routes(rpc_server.clone()));
+                    }
 
                     on_shutdown = Some(Box::new(move || rpc_server.teardown()));
                 }
 
                 if mode.is_api() {
                     fetch_extensions_from_blob_store_periodically(state.clone());
-                    spawn_user_backfiller(state.clone());
-
-                    let llm_db = maybe!(async {
-                        let database_url = state
-                            .config
-                            .llm_database_url
-                            .as_ref()
-                            .ok_or_else(|| anyhow!("missing LLM_DATABASE_URL"))?;
-                        let max_connections = state
-                            .config
-                            .llm_database_max_connections
-                            .ok_or_else(|| anyhow!("missing LLM_DATABASE_MAX_CONNECTIONS"))?;
-
-                        let mut db_options = db::ConnectOptions::new(database_url);
-                        db_options.max_connections(max_connections);
-                        LlmDatabase::new(db_options, state.executor.clone()).await
-                    })
-                    .await
-                    .trace_err();
 
-                    if let Some(mut llm_db) = llm_db {
-                        llm_db.initialize().await?;
-                        sync_llm_usage_with_stripe_periodically(state.clone());
+                    if !state.config.is_self_hosted() {
+                        spawn_user_backfiller(state.clone());
+
+                        let llm_db = maybe!(async {
+                            let database_url = state
+                                .config
+                                .llm_database_url
+                                .as_ref()
+                                .ok_or_else(|| anyhow!("missing LLM_DATABASE_URL"))?;
+                            let max_connections = state
+                                .config
+                                .llm_database_max_connections
+                                .ok_or_else(|| anyhow!("missing LLM_DATABASE_MAX_CONNECTIONS"))?;
+
+                            let mut db_options = db::ConnectOptions::new(database_url);
+                            db_options.max_connections(max_connections);
+                            LlmDatabase::new(db_options, state.executor.clone()).await
+                        })
+                        .await
+                        .trace_err();
+
+                        if let Some(mut llm_db) = llm_db {
+                            llm_db.initialize().await?;
+                            sync_llm_usage_with_stripe_periodically(state.clone());
+                        }
                     }
 
                     app = app
@@ -269,16 +286,18 @@ async fn setup_app_database(config: &Config) -> Result<()> {
     let db_options = db::ConnectOptions::new(config.database_url.clone());
     let mut db = Database::new(db_options, Executor::Production).await?;
 
-    let migrations_path = config.migrations_path.as_deref().unwrap_or_else(|| {
-        #[cfg(feature = "sqlite")]
-        let default_migrations = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations.sqlite");
-        #[cfg(not(feature = "sqlite"))]
-        let default_migrations = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations");
-
-        Path::new(default_migrations)
-    });
-
-    let migrations = run_database_migrations(db.options(), migrations_path).await?;
+    let migrations = if let Some(migrations_path) = &config.migrations_path {
+        run_database_migrations(db.options(), migrations_path).await?
+    } else {
+        // Self-hosted servers can run on SQLite, picked by the database URL at runtime, so the
+        // migrations for both databases are embedded.
+        let migrator = if config.is_sqlite() {
+            sqlx::migrate!("./migrations.sqlite")
+        } else {
+            sqlx::migrate!("./migrations")
+        };
+        apply_database_migrations(db.options(), migrator.iter().cloned()).await?
+    };
     for (migration, duration) in migrations {
         log::info!(
             "Migrated {} {} {:?}",
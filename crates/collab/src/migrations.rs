@@ -15,7 +15,15 @@ pub async fn run_database_migrations(
     let migrations = MigrationSource::resolve(migrations_path.as_ref())
         .await
         .map_err(|err| anyhow!("failed to load migrations: {err:?}"))?;
+    apply_database_migrations(database_options, migrations).await
+}
 
+/// Applies the given migrations to the specified database, skipping the ones that were already
+/// applied. Used with the migrations embedded in the binary by [`sqlx::migrate!`].
+pub async fn apply_database_migrations(
+    database_options: &ConnectOptions,
+    migrations: impl IntoIterator<Item = Migration>,
+) -> Result<Vec<(Migration, Duration)>> {
     let mut connection = sqlx::AnyConnection::connect(database_options.get_url()).await?;
 
     connection.ensure_migrations_table().await?;
@@ -6,9 +6,9 @@ use crate::{
     auth,
     db::{
         self, BufferId, Capability, Channel, ChannelId, ChannelRole, ChannelsForUser,
-        CreatedChannelMessage, Database, InviteMemberResult, MembershipUpdated, MessageId,
-        NotificationId, Project, ProjectId, RejoinedProject, RemoveChannelMemberResult, ReplicaId,
-        RespondToChannelInvite, RoomId, ServerId, UpdatedChannelMessage, User, UserId,
+        CommentThreadId, CreatedChannelMessage, Database, InviteMemberResult, MembershipUpdated,
+        MessageId, NotificationId, Project, ProjectId, RejoinedProject, RemoveChannelMemberResult,
+        ReplicaId, RespondToChannelInvite, RoomId, ServerId, UpdatedChannelMessage, User, UserId,
     },
     executor::Executor,
     AppState, Config, Error, RateLimit, Result,
@@ -301,6 +301,8 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::GetReferences>)
             .add_request_handler(forward_find_search_candidates_request)
             .add_request_handler(forward_read_only_project_request::<proto::GetDocumentHighlights>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSelectionRanges>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetInlineValues>)
             .add_request_handler(forward_read_only_project_request::<proto::GetProjectSymbols>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferForSymbol>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferById>)
@@ -331,8 +333,14 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::CreateProjectEntry>)
             .add_request_handler(forward_mutating_project_request::<proto::RenameProjectEntry>)
             .add_request_handler(forward_mutating_project_request::<proto::CopyProjectEntry>)
+            .add_request_handler(forward_read_only_project_request::<proto::ReadProjectEntry>)
+            .add_request_handler(forward_mutating_project_request::<proto::WriteProjectEntry>)
             .add_request_handler(forward_mutating_project_request::<proto::DeleteProjectEntry>)
             .add_request_handler(forward_mutating_project_request::<proto::ExpandProjectEntry>)
+            .add_request_handler(
+                forward_mutating_project_request::<proto::WillPerformFileOperation>,
+            )
+            .add_request_handler(forward_mutating_project_request::<proto::ApplyResourceOperations>)
             .add_request_handler(forward_mutating_project_request::<proto::OnTypeFormatting>)
             .add_request_handler(forward_mutating_project_request::<proto::SaveBuffer>)
             .add_request_handler(forward_mutating_project_request::<proto::BlameBuffer>)
@@ -346,6 +354,9 @@ impl Server {
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateDiffBase>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UpdateSharedTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UnshareTerminal>)
+            .add_request_handler(forward_mutating_project_request::<proto::SharedTerminalInput>)
             .add_request_handler(get_users)
             .add_request_handler(fuzzy_search_users)
             .add_request_handler(request_contact)
@@ -373,6 +384,10 @@ impl Server {
             .add_request_handler(update_channel_message)
             .add_request_handler(get_channel_messages)
             .add_request_handler(get_channel_messages_by_id)
+            .add_request_handler(get_comment_threads)
+            .add_request_handler(create_comment_thread)
+            .add_request_handler(add_comment)
+            .add_request_handler(set_comment_thread_resolved)
             .add_request_handler(get_notifications)
             .add_request_handler(mark_notification_as_read)
             .add_request_handler(move_channel)
@@ -3927,6 +3942,139 @@ async fn get_channel_messages_by_id(
     Ok(())
 }
 
+/// Retrieve the comment threads in a channel
+async fn get_comment_threads(
+    request: proto::GetCommentThreads,
+    response: Response<proto::GetCommentThreads>,
+    session: Session,
+) -> Result<()> {
+    let threads = session
+        .db()
+        .await
+        .get_channel_comment_threads(ChannelId::from_proto(request.channel_id), session.user_id())
+        .await?;
+    response.send(proto::GetCommentThreadsResponse { threads })?;
+    Ok(())
+}
+
+/// Start a comment thread on a position in a project file
+async fn create_comment_thread(
+    request: proto::CreateCommentThread,
+    response: Response<proto::CreateCommentThread>,
+    session: Session,
+) -> Result<()> {
+    let body = validate_comment_body(&request.body)?;
+    let anchor = request
+        .anchor
+        .ok_or_else(|| anyhow!("anchor can't be blank"))?;
+    let (channel, thread) = session
+        .db()
+        .await
+        .create_channel_comment_thread(
+            ChannelId::from_proto(request.channel_id),
+            session.user_id(),
+            &request.path,
+            request.project_id.map(ProjectId::from_proto),
+            &anchor,
+            request.line,
+            &request.context,
+            &body,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    comment_thread_updated(&channel, &thread, &session).await;
+    response.send(proto::CommentThreadResponse {
+        thread: Some(thread),
+    })?;
+    Ok(())
+}
+
+/// Reply to a comment thread
+async fn add_comment(
+    request: proto::AddComment,
+    response: Response<proto::AddComment>,
+    session: Session,
+) -> Result<()> {
+    let body = validate_comment_body(&request.body)?;
+    let (channel, thread) = session
+        .db()
+        .await
+        .create_channel_comment(
+            ChannelId::from_proto(request.channel_id),
+            CommentThreadId::from_proto(request.thread_id),
+            session.user_id(),
+            &body,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    comment_thread_updated(&channel, &thread, &session).await;
+    response.send(proto::CommentThreadResponse {
+        thread: Some(thread),
+    })?;
+    Ok(())
+}
+
+/// Resolve or reopen a comment thread
+async fn set_comment_thread_resolved(
+    request: proto::SetCommentThreadResolved,
+    response: Response<proto::SetCommentThreadResolved>,
+    session: Session,
+) -> Result<()> {
+    let (channel, thread) = session
+        .db()
+        .await
+        .set_channel_comment_thread_resolved(
+            ChannelId::from_proto(request.channel_id),
+            CommentThreadId::from_proto(request.thread_id),
+            session.user_id(),
+            request.resolved,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    comment_thread_updated(&channel, &thread, &session).await;
+    response.send(proto::CommentThreadResponse {
+        thread: Some(thread),
+    })?;
+    Ok(())
+}
+
+fn validate_comment_body(body: &str) -> Result<String> {
+    let body = body.trim();
+    if body.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!("comment is too long"))?;
+    }
+    if body.is_empty() {
+        return Err(anyhow!("comment can't be blank"))?;
+    }
+    Ok(body.to_string())
+}
+
+/// Send an updated comment thread to the other members of its channel
+async fn comment_thread_updated(
+    channel: &db::channel::Model,
+    thread: &proto::CommentThread,
+    session: &Session,
+) {
+    let pool = &*session.connection_pool().await;
+    broadcast(
+        Some(session.connection_id),
+        pool.channel_connection_ids(channel.root_id())
+            .filter_map(|(connection_id, role)| {
+                role.can_see_channel(channel.visibility)
+                    .then_some(connection_id)
+            }),
+        |connection_id| {
+            session.peer.send(
+                connection_id,
+                proto::UpdateCommentThread {
+                    channel_id: channel.id.to_proto(),
+                    thread: Some(thread.clone()),
+                },
+            )
+        },
+    );
+}
+
 /// Retrieve the current users notifications
 async fn get_notifications(
     request: proto::GetNotifications,
@@ -4021,6 +4169,12 @@ async fn get_llm_api_token(
     response: Response<proto::GetLlmToken>,
     session: Session,
 ) -> Result<()> {
+    if session.app_state.config.is_self_hosted() {
+        Err(anyhow!(
+            "language models are not available on self-hosted servers"
+        ))?
+    }
+
     let db = session.db().await;
 
     let flags = db.get_user_flags(session.user_id()).await?;
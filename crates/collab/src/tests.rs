@@ -8,6 +8,7 @@ use client::ChannelId;
 use gpui::{Model, TestAppContext};
 
 mod channel_buffer_tests;
+mod channel_comment_tests;
 mod channel_guest_tests;
 mod channel_message_tests;
 mod channel_tests;
@@ -18,7 +19,9 @@ mod notification_tests;
 mod random_channel_buffer_tests;
 mod random_project_collaboration_tests;
 mod randomieditsync_test_helpers;
+mod recent_edits_tests;
 mod remote_editing_collaboration_tests;
+mod session_recording_tests;
 mod test_server;
 
 use language::{tree_sitter_rust, Language, LanguageConfig, LanguageMatcher};
@@ -0,0 +1,266 @@
+use crate::{rpc::RECONNECT_TIMEOUT, tests::TestServer};
+use call::ActiveCall;
+use channel::{ChannelComments, CommentPosition};
+use gpui::{BackgroundExecutor, Model, TestAppContext};
+use language::Buffer;
+use serde_json::json;
+
+#[gpui::test]
+async fn test_channel_comment_threads(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let comments_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.open_channel_comments(channel_id, cx)
+        })
+        .await
+        .unwrap();
+    let comments_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.open_channel_comments(channel_id, cx)
+        })
+        .await
+        .unwrap();
+
+    let buffer = cx_a.new_model(|cx| Buffer::local("fn one() {}\nfn two() {}\n", cx));
+    let position = buffer.read_with(cx_a, |buffer, _| {
+        CommentPosition::new(&buffer.snapshot(), 1, None)
+    });
+    let thread_id = comments_a
+        .update(cx_a, |comments, cx| {
+            comments.create_thread(
+                "project/src/lib.rs".into(),
+                position.clone(),
+                "Is this still used?".into(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+
+    executor.run_until_parked();
+    comments_b.read_with(cx_b, |comments, _| {
+        let threads = comments
+            .threads_for_path("project/src/lib.rs")
+            .collect::<Vec<_>>();
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].position, position);
+        assert_eq!(threads[0].position.line, 1);
+        assert_eq!(threads[0].created_by.github_login, "user_a");
+        assert!(!threads[0].is_resolved());
+    });
+
+    comments_b
+        .update(cx_b, |comments, cx| {
+            comments.reply(thread_id, "Only by the tests.".into(), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        thread_comments(&comments_a, thread_id, cx_a),
+        &[
+            ("user_a".to_string(), "Is this still used?".to_string()),
+            ("user_b".to_string(), "Only by the tests.".to_string()),
+        ]
+    );
+
+    comments_a
+        .update(cx_a, |comments, cx| {
+            comments.set_resolved(thread_id, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    comments_b.read_with(cx_b, |comments, _| {
+        let thread = comments.thread(thread_id).unwrap();
+        assert_eq!(thread.resolved_by.as_ref().unwrap().github_login, "user_a");
+    });
+
+    // Threads are reloaded after reconnecting.
+    server.forbid_connections();
+    server.disconnect_client(client_b.peer_id().unwrap());
+    comments_a
+        .update(cx_a, |comments, cx| {
+            comments.set_resolved(thread_id, false, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    comments_b.read_with(cx_b, |comments, _| {
+        assert!(comments.thread(thread_id).unwrap().is_resolved());
+    });
+
+    server.allow_connections();
+    executor.advance_clock(RECONNECT_TIMEOUT);
+    comments_b.read_with(cx_b, |comments, _| {
+        assert!(!comments.thread(thread_id).unwrap().is_resolved());
+    });
+}
+
+#[gpui::test]
+async fn test_comment_positions_in_reopened_buffers(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "lib.rs": "fn one() {}\nfn two() {}\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    let comments_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.open_channel_comments(channel_id, cx)
+        })
+        .await
+        .unwrap();
+    let comments_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.open_channel_comments(channel_id, cx)
+        })
+        .await
+        .unwrap();
+
+    // Client A comments on `fn two`, in the buffer of the shared project.
+    let buffer_a = project_a
+        .update(cx_a, |project, cx| {
+            project.open_buffer((worktree_id, "lib.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let position = buffer_a.read_with(cx_a, |buffer, _| {
+        CommentPosition::new(&buffer.snapshot(), 1, Some(project_id))
+    });
+    let thread_id = comments_a
+        .update(cx_a, |comments, cx| {
+            comments.create_thread("a/lib.rs".into(), position, "Rename this".into(), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // While the project is shared, the thread follows the edits of the commented buffer.
+    let buffer_b = project_b
+        .update(cx_b, |project, cx| {
+            project.open_buffer((worktree_id, "lib.rs"), cx)
+        })
+        .await
+        .unwrap();
+    buffer_b.update(cx_b, |buffer, cx| {
+        buffer.edit([(0..0, "fn zero() {}\n")], None, cx)
+    });
+    executor.run_until_parked();
+    let thread_b = comments_b.read_with(cx_b, |comments, _| {
+        comments.thread(thread_id).unwrap().clone()
+    });
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(
+            thread_b.position.row(&buffer.snapshot(), Some(project_id)),
+            2
+        );
+    });
+    buffer_a.read_with(cx_a, |buffer, _| {
+        assert_eq!(
+            thread_b.position.row(&buffer.snapshot(), Some(project_id)),
+            2
+        );
+    });
+
+    // Once the project is no longer shared and the file is reopened with other changes, the
+    // stored anchor is ignored, and the thread is placed using the text around the comment.
+    project_a
+        .update(cx_a, |project, cx| project.unshare(cx))
+        .unwrap();
+    drop(buffer_a);
+    executor.run_until_parked();
+    client_a
+        .fs()
+        .insert_file(
+            "/a/lib.rs",
+            "// Numbers\nfn zero() {}\nfn one() {}\nfn two() {}\n".into(),
+        )
+        .await;
+    executor.run_until_parked();
+    let buffer_a = project_a
+        .update(cx_a, |project, cx| {
+            project.open_buffer((worktree_id, "lib.rs"), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    let project_id_a = project_a.read_with(cx_a, |project, _| project.remote_id());
+    assert_eq!(project_id_a, None);
+    buffer_a.read_with(cx_a, |buffer, _| {
+        assert_eq!(
+            buffer.text(),
+            "// Numbers\nfn zero() {}\nfn one() {}\nfn two() {}\n"
+        );
+        assert_eq!(thread_b.position.row(&buffer.snapshot(), project_id_a), 3);
+    });
+
+    // When the commented line is gone, the thread stays on the commented row.
+    buffer_a.update(cx_a, |buffer, cx| {
+        buffer.set_text("fn three() {}\nfn four() {}\n", cx)
+    });
+    buffer_a.read_with(cx_a, |buffer, _| {
+        assert_eq!(thread_b.position.row(&buffer.snapshot(), project_id_a), 1);
+    });
+}
+
+fn thread_comments(
+    comments: &Model<ChannelComments>,
+    thread_id: u64,
+    cx: &mut TestAppContext,
+) -> Vec<(String, String)> {
+    comments.read_with(cx, |comments, _| {
+        comments
+            .thread(thread_id)
+            .unwrap()
+            .comments
+            .iter()
+            .map(|comment| (comment.sender.github_login.clone(), comment.body.clone()))
+            .collect()
+    })
+}
@@ -46,6 +46,7 @@ use std::{
     },
     time::Duration,
 };
+use terminal::{terminal_settings::CursorShape, TerminalBuilder};
 use unindent::Unindent as _;
 use workspace::Pane;
 
@@ -6747,3 +6748,239 @@ async fn test_remote_git_branches(
 
     assert_eq!(host_branch.as_ref(), "totally-new-branch");
 }
+
+#[gpui::test]
+async fn test_shared_terminal(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    cx_a.update(terminal::init);
+    cx_b.update(terminal::init);
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    client_a.fs().insert_tree("/a", json!({})).await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    // The host shares a terminal, which the guest can view but not write to.
+    let terminal_a = cx_a.new_model(|cx| {
+        let (completion_tx, _) = smol::channel::bounded(1);
+        let mut terminal =
+            TerminalBuilder::new_mirror(CursorShape::default(), completion_tx).subscribe(cx);
+        terminal.set_mirrored_content("$ cargo test\r\nok", Some("zsh".into()), cx);
+        terminal
+    });
+    project_a
+        .update(cx_a, |project, cx| project.share_terminal(&terminal_a, cx))
+        .unwrap();
+    executor.run_until_parked();
+
+    let terminal_b = project_b.read_with(cx_b, |project, _| {
+        let terminals = project.remote_shared_terminals().collect::<Vec<_>>();
+        assert_eq!(terminals.len(), 1);
+        terminals[0].1.clone()
+    });
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert_eq!(terminal.title(false), "zsh");
+        assert_eq!(terminal.last_n_non_empty_lines(2), ["$ cargo test", "ok"]);
+    });
+
+    terminal_b.update(cx_b, |terminal, _| terminal.input("ls".into()));
+    executor.run_until_parked();
+    terminal_a.read_with(cx_a, |terminal, _| {
+        assert_eq!(terminal.typing_collaborator(), None);
+    });
+
+    // Once the host allows it, the guest's input is written to the host's terminal.
+    project_a.update(cx_a, |project, cx| {
+        project.set_terminal_write_access(&terminal_a, client_b.user_id().unwrap(), true, cx)
+    });
+    executor.run_until_parked();
+    terminal_b.update(cx_b, |terminal, _| terminal.input("ls".into()));
+    executor.run_until_parked();
+    terminal_a.read_with(cx_a, |terminal, _| {
+        assert_eq!(
+            terminal.typing_collaborator().map(|login| login.as_ref()),
+            Some("user_b")
+        );
+    });
+    executor.advance_clock(Duration::from_secs(5));
+    executor.run_until_parked();
+    terminal_a.read_with(cx_a, |terminal, _| {
+        assert_eq!(terminal.typing_collaborator(), None);
+    });
+
+    // When the host stops sharing the terminal, the guest no longer has it.
+    project_a.update(cx_a, |project, cx| {
+        project.unshare_terminal(&terminal_a, cx)
+    });
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, _| {
+        assert_eq!(project.remote_shared_terminals().count(), 0);
+    });
+}
+
+#[gpui::test]
+async fn test_guests_cannot_read_private_entries(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/a",
+            json!({
+                ".env": "SECRET=1",
+                "main.rs": "fn main() {}",
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    let worktree_b = project_b.read_with(cx_b, |project, cx| {
+        project.worktree_for_id(worktree_id, cx).unwrap()
+    });
+    let entry_id = |path: &str, cx: &mut TestAppContext| {
+        worktree_b.read_with(cx, |worktree, _| worktree.entry_for_path(path).unwrap().id)
+    };
+
+    let main_entry_id = entry_id("main.rs", cx_b);
+    let content = worktree_b
+        .update(cx_b, |worktree, cx| worktree.read_entry(main_entry_id, cx))
+        .await
+        .unwrap();
+    assert_eq!(content, b"fn main() {}");
+
+    let env_entry_id = entry_id(".env", cx_b);
+    worktree_b
+        .update(cx_b, |worktree, cx| worktree.read_entry(env_entry_id, cx))
+        .await
+        .unwrap_err();
+}
+
+#[gpui::test]
+async fn test_copy_entry_between_remote_worktrees(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/a",
+            json!({
+                ".git": {},
+                ".gitignore": "dir/ignored\n",
+                "dir": {
+                    "file.txt": "file",
+                    "ignored": {
+                        "nested": {
+                            "ignored.txt": "ignored",
+                        },
+                    },
+                },
+            }),
+        )
+        .await;
+    client_a.fs().insert_tree("/b", json!({})).await;
+    let (project_a, worktree_id_a) = client_a.build_local_project("/a", cx_a).await;
+    let worktree_id_b = project_a
+        .update(cx_a, |project, cx| {
+            project.find_or_create_worktree("/b", true, cx)
+        })
+        .await
+        .unwrap()
+        .0
+        .read_with(cx_a, |worktree, _| worktree.id());
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    // The guest copies a directory holding an ignored directory, which isn't loaded yet.
+    let (source_entry_id, target_worktree) = project_b.read_with(cx_b, |project, cx| {
+        let source_worktree = project.worktree_for_id(worktree_id_a, cx).unwrap();
+        let source_worktree = source_worktree.read(cx);
+        assert!(
+            source_worktree
+                .entry_for_path("dir/ignored")
+                .unwrap()
+                .is_ignored
+        );
+        assert!(source_worktree
+            .entry_for_path("dir/ignored/nested")
+            .is_none());
+        (
+            source_worktree.entry_for_path("dir").unwrap().id,
+            project.worktree_for_id(worktree_id_b, cx).unwrap(),
+        )
+    });
+    project_b
+        .update(cx_b, |project, cx| {
+            project.worktree_store().update(cx, |worktree_store, cx| {
+                worktree_store.copy_entry_to_worktree(
+                    source_entry_id,
+                    target_worktree,
+                    Path::new("dir"),
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    executor.run_until_parked();
+
+    assert_eq!(
+        client_a
+            .fs()
+            .load("/b/dir/file.txt".as_ref())
+            .await
+            .unwrap(),
+        "file"
+    );
+    assert_eq!(
+        client_a
+            .fs()
+            .load("/b/dir/ignored/nested/ignored.txt".as_ref())
+            .await
+            .unwrap(),
+        "ignored"
+    );
+}
@@ -0,0 +1,85 @@
+use crate::tests::TestServer;
+use call::ActiveCall;
+use collab_ui::recent_edits::{OpenRecentEdits, RecentEditsView, ENTRY_INTERVAL, MAX_RECENT_EDITS};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use serde_json::json;
+use std::time::Duration;
+
+#[gpui::test]
+async fn test_recent_edits(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "main.rs": "fn main() {}\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    let buffer_a = project_a
+        .update(cx_a, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    cx_a.dispatch_action(OpenRecentEdits);
+    let recent_edits = workspace_a.update(cx_a, |workspace, cx| {
+        workspace.active_item_as::<RecentEditsView>(cx).unwrap()
+    });
+    let entries =
+        |cx: &mut VisualTestContext| recent_edits.update(cx, |view, cx| view.timeline_entries(cx));
+    let entry =
+        |login: &str, count: usize| (Some(login.to_string()), "a/main.rs".to_string(), count);
+
+    // Edits made by the same participant within the entry interval are grouped, and entries
+    // are attributed to the participant who made them.
+    buffer_b.update(cx_b, |buffer, cx| buffer.edit([(0..0, "// b\n")], None, cx));
+    executor.run_until_parked();
+    executor.advance_clock(Duration::from_secs(10));
+    buffer_a.update(cx_a, |buffer, cx| buffer.edit([(0..0, "// a\n")], None, cx));
+    executor.run_until_parked();
+    executor.advance_clock(Duration::from_secs(10));
+    buffer_b.update(cx_b, |buffer, cx| buffer.edit([(0..0, "// b\n")], None, cx));
+    executor.run_until_parked();
+    assert_eq!(entries(cx_a), [entry("user_b", 2), entry("user_a", 1)]);
+
+    // Edits further apart than the entry interval start a new entry.
+    executor.advance_clock(ENTRY_INTERVAL.unsigned_abs() + Duration::from_secs(1));
+    buffer_b.update(cx_b, |buffer, cx| buffer.edit([(0..0, "// b\n")], None, cx));
+    executor.run_until_parked();
+    assert_eq!(
+        entries(cx_a),
+        [entry("user_b", 1), entry("user_b", 2), entry("user_a", 1)]
+    );
+
+    // Only the most recent edits are remembered.
+    buffer_a.update(cx_a, |buffer, cx| {
+        for _ in 0..MAX_RECENT_EDITS {
+            buffer.edit([(0..0, "a")], None, cx);
+        }
+    });
+    executor.run_until_parked();
+    assert_eq!(entries(cx_a), [entry("user_a", MAX_RECENT_EDITS)]);
+}
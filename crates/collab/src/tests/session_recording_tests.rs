@@ -0,0 +1,168 @@
+use crate::tests::TestServer;
+use call::ActiveCall;
+use channel::MessageParams;
+use collab_ui::session_recording::{
+    open_recording, SessionReplayView, StartSessionRecording, StopSessionRecording,
+    RECORDING_FLUSH_INTERVAL,
+};
+use editor::Editor;
+use fs::Fs as _;
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use language::Point;
+use serde_json::json;
+use workspace::Workspace;
+
+#[gpui::test]
+async fn test_record_and_replay_session(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    for (call, cx) in [(&active_call_a, &mut *cx_a), (&active_call_b, &mut *cx_b)] {
+        call.update(cx, |call, cx| call.join_channel(channel_id, cx))
+            .await
+            .unwrap();
+    }
+
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "main.rs": "fn main() {}\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let (workspace_b, cx_b) = client_b.build_workspace(&project_b, cx_b);
+    // Client A also shows their project in a second window, which shares its recording.
+    let window_a2 = cx_a
+        .add_window(|cx| Workspace::new(None, project_a.clone(), client_a.app_state.clone(), cx));
+    let cx_a2 = &mut VisualTestContext::from_window(*window_a2, cx_a);
+    workspace_a
+        .update(cx_a, |workspace, cx| {
+            workspace.open_path((worktree_id, "main.rs"), None, true, cx)
+        })
+        .await
+        .unwrap();
+
+    // Client A records the session, while client B edits the file and moves their cursor.
+    cx_a.dispatch_action(StartSessionRecording);
+    executor.run_until_parked();
+    cx_a2.dispatch_action(StartSessionRecording);
+    executor.run_until_parked();
+    let editor_b = workspace_b
+        .update(cx_b, |workspace, cx| {
+            workspace.open_path((worktree_id, "main.rs"), None, true, cx)
+        })
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    editor_b.update(cx_b, |editor, cx| {
+        editor.change_selections(None, cx, |selections| selections.select_ranges([0..0]));
+        editor.handle_input("// Entry point\n", cx);
+        editor.change_selections(None, cx, |selections| selections.select_ranges([18..18]));
+    });
+    executor.run_until_parked();
+
+    // What was recorded so far is written to disk while recording.
+    executor.advance_clock(RECORDING_FLUSH_INTERVAL);
+    executor.run_until_parked();
+    let recording_files = client_a
+        .fs()
+        .files()
+        .into_iter()
+        .filter(|path| {
+            path.extension()
+                .map_or(false, |ext| ext == "editsync-session")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(recording_files.len(), 1);
+    let recording_path = recording_files[0].clone();
+    assert!(!client_a
+        .fs()
+        .load_bytes(&recording_path)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Client B follows client A, and sends a message to the channel.
+    workspace_b.update(cx_b, |workspace, cx| {
+        workspace.follow(client_a.peer_id().unwrap(), cx)
+    });
+    executor.run_until_parked();
+    chat_b
+        .update(cx_b, |chat, cx| {
+            chat.send_message(
+                MessageParams {
+                    text: "Looks good".into(),
+                    mentions: Vec::new(),
+                    reply_to_message_id: None,
+                },
+                cx,
+            )
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    cx_a2.dispatch_action(StopSessionRecording);
+    executor.run_until_parked();
+
+    workspace_a
+        .update(cx_a, |workspace, cx| {
+            open_recording(workspace, recording_path.clone(), cx)
+        })
+        .await
+        .unwrap();
+    let replay = workspace_a.update(cx_a, |workspace, cx| {
+        workspace
+            .active_item_as::<SessionReplayView>(cx)
+            .unwrap()
+            .read(cx)
+            .replay()
+            .clone()
+    });
+    replay.update(cx_a, |replay, cx| replay.replay_to_end(cx));
+    replay.update(cx_a, |replay, cx| {
+        assert_eq!(
+            replay.buffer_text("a/main.rs", cx).as_deref(),
+            Some("// Entry point\nfn main() {}\n")
+        );
+        assert!(replay
+            .cursors("a/main.rs", cx)
+            .contains(&("user_b".into(), Point::new(1, 3))));
+        assert_eq!(
+            replay.log_entries(),
+            [
+                ("user_b".into(), "started following user_a".to_string()),
+                ("user_b".into(), "Looks good".to_string()),
+            ]
+        );
+    });
+}
@@ -2,7 +2,7 @@ use crate::{
     db::{tests::TestDb, NewUserParams, UserId},
     executor::Executor,
     rpc::{EditsyncVersion, Principal, Server, CLEANUP_TIMEOUT, RECONNECT_TIMEOUT},
-    AppState, Config, RateLimiter,
+    AppState, Config, RateLimiter, ServerProfile,
 };
 use anyhow::anyhow;
 use call::ActiveCall;
@@ -519,7 +519,9 @@ impl TestServer {
             rate_limiter: Arc::new(RateLimiter::new(test_db.db().clone())),
             executor,
             kinesis_client: None,
+            auth_backend: None,
             config: Config {
+                profile: ServerProfile::Hosted,
                 http_port: 0,
                 database_url: "".into(),
                 database_max_connections: 0,
@@ -560,6 +562,12 @@ impl TestServer {
                 kinesis_stream: None,
                 kinesis_access_key: None,
                 kinesis_secret_key: None,
+                public_url: None,
+                auth_backend: None,
+                auth_static_tokens_path: None,
+                oidc_issuer_url: None,
+                oidc_client_id: None,
+                oidc_client_secret: None,
             },
         })
     }
@@ -0,0 +1,567 @@
+//! Review comments on project files, shared with the members of the channel whose call is active.
+//! Comment threads are rendered as blocks below the commented lines, in the editors of the files.
+
+use std::{any::Any, mem, sync::Arc};
+
+use anyhow::{anyhow, Context as _, Result};
+use call::ActiveCall;
+use channel::{
+    ChannelComments, ChannelCommentsEvent, ChannelStore, CommentPosition, CommentThread,
+};
+use client::ChannelId;
+use collections::{HashMap, HashSet};
+use editor::{
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+    Addon, Editor,
+};
+use gpui::{
+    actions, AppContext, FocusableView, Model, Subscription, Task, View, ViewContext,
+    VisualContext as _, WeakModel, WeakView, WindowContext,
+};
+use language::{Buffer, Point};
+use project::Project;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, Avatar, Tooltip};
+use util::ResultExt as _;
+use workspace::{notifications::NotificationId, Toast, Workspace};
+
+actions!(collab, [AddComment]);
+
+/// The number of lines reserved below a thread's comments, for the reply editor and buttons.
+const REPLY_LINES: u32 = 3;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(add_comment);
+            cx.subscribe(&cx.view().clone(), |workspace, _, event, cx| {
+                if let workspace::Event::ItemAdded { item } = event {
+                    if let Some(editor) = item.act_as::<Editor>(cx) {
+                        let project = workspace.project().downgrade();
+                        EditorComments::attach(editor, project, cx);
+                    }
+                }
+            })
+            .detach();
+        },
+    )
+    .detach();
+}
+
+fn add_comment(workspace: &mut Workspace, _: &AddComment, cx: &mut ViewContext<Workspace>) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some(comments) = editor
+        .read(cx)
+        .addon::<CommentsAddon>()
+        .map(|addon| addon.comments.clone())
+    else {
+        return;
+    };
+    if let Err(error) = comments.update(cx, |comments, cx| comments.start_thread(cx)) {
+        struct AddCommentToast;
+
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<AddCommentToast>(),
+                error.to_string(),
+            ),
+            cx,
+        );
+    }
+}
+
+struct CommentsAddon {
+    comments: View<EditorComments>,
+}
+
+impl Addon for CommentsAddon {
+    fn to_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Keeps the comment blocks of an editor in sync with the comments of the current channel.
+struct EditorComments {
+    editor: WeakView<Editor>,
+    project: WeakModel<Project>,
+    channel_id: Option<ChannelId>,
+    channel_comments: Option<Model<ChannelComments>>,
+    threads: HashMap<u64, ThreadBlock>,
+    composer: Option<ThreadBlock>,
+    open_comments: Task<()>,
+    comments_subscription: Option<Subscription>,
+    _call_subscription: Option<Subscription>,
+}
+
+struct ThreadBlock {
+    block_id: CustomBlockId,
+    view: View<CommentThreadView>,
+}
+
+impl EditorComments {
+    fn attach(editor: View<Editor>, project: WeakModel<Project>, cx: &mut WindowContext) {
+        if editor.read(cx).addon::<CommentsAddon>().is_some() || buffer_path(&editor, cx).is_none()
+        {
+            return;
+        }
+        let comments = cx.new_view(|cx| {
+            let mut this = Self {
+                editor: editor.downgrade(),
+                project,
+                channel_id: None,
+                channel_comments: None,
+                threads: HashMap::default(),
+                composer: None,
+                open_comments: Task::ready(()),
+                comments_subscription: None,
+                _call_subscription: ActiveCall::try_global(cx)
+                    .map(|call| cx.observe(&call, |this, _, cx| this.channel_changed(cx))),
+            };
+            this.channel_changed(cx);
+            this
+        });
+        editor.update(cx, |editor, _| {
+            editor.register_addon(CommentsAddon { comments });
+        });
+    }
+
+    fn channel_changed(&mut self, cx: &mut ViewContext<Self>) {
+        let channel_id = ActiveCall::try_global(cx).and_then(|call| call.read(cx).channel_id(cx));
+        if channel_id == self.channel_id {
+            return;
+        }
+
+        self.channel_id = channel_id;
+        self.channel_comments = None;
+        self.comments_subscription = None;
+        self.open_comments = Task::ready(());
+        self.dismiss_composer(cx);
+        self.sync_threads(cx);
+
+        if let Some(channel_id) = channel_id {
+            let open_comments = ChannelStore::global(cx)
+                .update(cx, |store, cx| store.open_channel_comments(channel_id, cx));
+            self.open_comments = cx.spawn(|this, mut cx| async move {
+                let Some(comments) = open_comments.await.log_err() else {
+                    return;
+                };
+                this.update(&mut cx, |this, cx| {
+                    if this.channel_id == Some(channel_id) {
+                        this.comments_subscription =
+                            Some(cx.subscribe(&comments, Self::handle_comments_event));
+                        this.channel_comments = Some(comments);
+                        this.sync_threads(cx);
+                    }
+                })
+                .ok();
+            });
+        }
+    }
+
+    fn handle_comments_event(
+        &mut self,
+        _: Model<ChannelComments>,
+        _: &ChannelCommentsEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.sync_threads(cx);
+    }
+
+    /// Replaces the blocks of the editor with ones for the current threads on its file, keeping
+    /// the views of threads that still exist so that their drafted replies are preserved.
+    fn sync_threads(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let mut old_threads = mem::take(&mut self.threads);
+        let mut blocks_to_remove = HashSet::default();
+
+        let mut new_threads = Vec::new();
+        if let Some((channel_comments, (buffer, path))) =
+            self.channel_comments.clone().zip(buffer_path(&editor, cx))
+        {
+            let buffer = buffer.read(cx).snapshot();
+            let project_id = self.project_id(cx);
+            let threads = channel_comments
+                .read(cx)
+                .threads_for_path(&path)
+                .cloned()
+                .collect::<Vec<_>>();
+            for thread in threads {
+                let row = thread.position.row(&buffer, project_id);
+                let thread_id = thread.id;
+                let view = if let Some(old_thread) = old_threads.remove(&thread_id) {
+                    blocks_to_remove.insert(old_thread.block_id);
+                    old_thread
+                        .view
+                        .update(cx, |view, cx| view.set_thread(thread, cx));
+                    old_thread.view
+                } else {
+                    let channel_comments = channel_comments.clone();
+                    let editor_comments = cx.view().downgrade();
+                    cx.new_view(|cx| {
+                        CommentThreadView::new(
+                            ThreadTarget::Existing(thread),
+                            channel_comments,
+                            editor_comments,
+                            cx,
+                        )
+                    })
+                };
+                new_threads.push((thread_id, row, view));
+            }
+        }
+        blocks_to_remove.extend(old_threads.into_values().map(|thread| thread.block_id));
+
+        editor.update(cx, |editor, cx| {
+            editor.remove_blocks(blocks_to_remove, None, cx);
+            let blocks = new_threads
+                .iter()
+                .map(|(_, row, view)| thread_block(Point::new(*row, 0), view, editor, cx))
+                .collect::<Vec<_>>();
+            let block_ids = editor.insert_blocks(blocks, None, cx);
+            for ((thread_id, _, view), block_id) in new_threads.into_iter().zip(block_ids) {
+                self.threads
+                    .insert(thread_id, ThreadBlock { block_id, view });
+            }
+        });
+    }
+
+    /// Opens an editor for the first comment of a new thread, below the newest cursor.
+    fn start_thread(&mut self, cx: &mut ViewContext<Self>) -> Result<()> {
+        let channel_comments = self
+            .channel_comments
+            .clone()
+            .ok_or_else(|| anyhow!("Join a channel's call to comment on its projects"))?;
+        let editor = self.editor.upgrade().context("editor was dropped")?;
+        let (buffer, path) = buffer_path(&editor, cx).context("editor has no file")?;
+        self.dismiss_composer(cx);
+
+        let row = editor.update(cx, |editor, cx| {
+            editor.selections.newest::<Point>(cx).head().row
+        });
+        let position = CommentPosition::new(&buffer.read(cx).snapshot(), row, self.project_id(cx));
+        let editor_comments = cx.view().downgrade();
+        let view = cx.new_view(|cx| {
+            CommentThreadView::new(
+                ThreadTarget::New { path, position },
+                channel_comments,
+                editor_comments,
+                cx,
+            )
+        });
+        let block_id = editor.update(cx, |editor, cx| {
+            let block = thread_block(Point::new(row, 0), &view, editor, cx);
+            editor.insert_blocks([block], None, cx)[0]
+        });
+        cx.focus_view(&view);
+        self.composer = Some(ThreadBlock { block_id, view });
+        Ok(())
+    }
+
+    /// The ID of the editor's project while it's shared, which identifies the buffers that
+    /// comment anchors can be resolved in.
+    fn project_id(&self, cx: &AppContext) -> Option<u64> {
+        self.project.upgrade()?.read(cx).remote_id()
+    }
+
+    fn dismiss_composer(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(composer) = self.composer.take() {
+            if let Some(editor) = self.editor.upgrade() {
+                editor.update(cx, |editor, cx| {
+                    editor.remove_blocks(HashSet::from_iter([composer.block_id]), None, cx);
+                    editor.focus(cx);
+                });
+            }
+        }
+    }
+}
+
+impl Render for EditorComments {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        gpui::Empty
+    }
+}
+
+fn thread_block(
+    position: Point,
+    view: &View<CommentThreadView>,
+    editor: &Editor,
+    cx: &AppContext,
+) -> BlockProperties<editor::Anchor> {
+    let position = editor.buffer().read(cx).snapshot(cx).anchor_after(position);
+    BlockProperties {
+        placement: BlockPlacement::Below(position),
+        height: view.read(cx).block_height(),
+        style: BlockStyle::Flex,
+        render: Arc::new({
+            let view = view.clone();
+            move |cx: &mut BlockContext| {
+                div()
+                    .block_mouse_down()
+                    .pl(cx.anchor_x)
+                    .pr(cx.gutter_dimensions.full_width())
+                    .child(view.clone())
+                    .into_any_element()
+            }
+        }),
+        priority: 0,
+    }
+}
+
+/// Returns the buffer of an editor for a single file, with that file's path in the project.
+fn buffer_path(editor: &View<Editor>, cx: &AppContext) -> Option<(Model<Buffer>, Arc<str>)> {
+    let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+    let path = buffer.read(cx).file()?.full_path(cx);
+    Some((buffer, path.to_string_lossy().into()))
+}
+
+enum ThreadTarget {
+    Existing(CommentThread),
+    New {
+        path: Arc<str>,
+        position: CommentPosition,
+    },
+}
+
+struct CommentThreadView {
+    target: ThreadTarget,
+    channel_comments: Model<ChannelComments>,
+    editor_comments: WeakView<EditorComments>,
+    reply_editor: View<Editor>,
+    local_timezone: UtcOffset,
+    pending_request: Option<Task<()>>,
+}
+
+impl CommentThreadView {
+    fn new(
+        target: ThreadTarget,
+        channel_comments: Model<ChannelComments>,
+        editor_comments: WeakView<EditorComments>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let placeholder = match &target {
+            ThreadTarget::Existing(_) => "Reply…",
+            ThreadTarget::New { .. } => "Add a comment…",
+        };
+        let reply_editor = cx.new_view(|cx| {
+            let mut editor = Editor::auto_height(REPLY_LINES as usize - 1, cx);
+            editor.set_placeholder_text(placeholder, cx);
+            editor
+        });
+        let local_offset = chrono::Local::now().offset().local_minus_utc();
+        Self {
+            target,
+            channel_comments,
+            editor_comments,
+            reply_editor,
+            local_timezone: UtcOffset::from_whole_seconds(local_offset).unwrap(),
+            pending_request: None,
+        }
+    }
+
+    fn set_thread(&mut self, thread: CommentThread, cx: &mut ViewContext<Self>) {
+        self.target = ThreadTarget::Existing(thread);
+        cx.notify();
+    }
+
+    /// The height of the thread's block, in lines.
+    fn block_height(&self) -> u32 {
+        match &self.target {
+            ThreadTarget::Existing(thread) if thread.is_resolved() => 2,
+            ThreadTarget::Existing(thread) => {
+                let comment_lines = thread
+                    .comments
+                    .iter()
+                    .map(|comment| 1 + comment.body.lines().count().max(1) as u32)
+                    .sum::<u32>();
+                comment_lines + REPLY_LINES + 1
+            }
+            ThreadTarget::New { .. } => REPLY_LINES + 1,
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let body = self.reply_editor.read(cx).text(cx);
+        if body.trim().is_empty() || self.pending_request.is_some() {
+            return;
+        }
+
+        let request = self
+            .channel_comments
+            .update(cx, |comments, cx| match &self.target {
+                ThreadTarget::Existing(thread) => comments.reply(thread.id, body, cx),
+                ThreadTarget::New { path, position } => {
+                    let create = comments.create_thread(path.clone(), position.clone(), body, cx);
+                    cx.spawn(|_, _| async move { create.await.map(|_| ()) })
+                }
+            });
+        let is_new = matches!(self.target, ThreadTarget::New { .. });
+        self.pending_request = Some(cx.spawn(|this, mut cx| async move {
+            let result = request.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_request = None;
+                if result.log_err().is_some() {
+                    this.reply_editor.update(cx, |editor, cx| editor.clear(cx));
+                    if is_new {
+                        this.editor_comments
+                            .update(cx, |comments, cx| comments.dismiss_composer(cx))
+                            .ok();
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &editor::actions::Cancel, cx: &mut ViewContext<Self>) {
+        if matches!(self.target, ThreadTarget::New { .. }) {
+            self.editor_comments
+                .update(cx, |comments, cx| comments.dismiss_composer(cx))
+                .ok();
+        } else {
+            cx.propagate();
+        }
+    }
+
+    fn set_resolved(&mut self, resolved: bool, cx: &mut ViewContext<Self>) {
+        let ThreadTarget::Existing(thread) = &self.target else {
+            return;
+        };
+        let thread_id = thread.id;
+        self.channel_comments
+            .update(cx, |comments, cx| {
+                comments.set_resolved(thread_id, resolved, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn render_comment(&self, comment: &channel::Comment, cx: &ViewContext<Self>) -> AnyElement {
+        v_flex()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Avatar::new(comment.sender.avatar_uri.clone()).size(rems(1.)))
+                    .child(
+                        Label::new(comment.sender.github_login.clone())
+                            .size(LabelSize::Small)
+                            .weight(FontWeight::BOLD),
+                    )
+                    .child(
+                        Label::new(time_format::format_localieditsync_timestamp(
+                            comment.timestamp,
+                            OffsetDateTime::now_utc(),
+                            self.local_timezone,
+                            time_format::TimestampFormat::EnhancedAbsolute,
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(div().text_ui(cx).child(comment.body.clone()))
+            .into_any_element()
+    }
+}
+
+impl FocusableView for CommentThreadView {
+    fn focus_handle(&self, cx: &AppContext) -> gpui::FocusHandle {
+        self.reply_editor.focus_handle(cx)
+    }
+}
+
+impl Render for CommentThreadView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let container = v_flex()
+            .key_context("CommentThread")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .w_full()
+            .max_w(rems(40.))
+            .p_1()
+            .gap_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().editor_subheader_background);
+
+        let thread = match &self.target {
+            ThreadTarget::Existing(thread) => Some(thread),
+            ThreadTarget::New { .. } => None,
+        };
+        if let Some(thread) = thread.filter(|thread| thread.is_resolved()) {
+            let resolved_by = thread
+                .resolved_by
+                .as_ref()
+                .map(|user| user.github_login.clone())
+                .unwrap_or_default();
+            return container.child(
+                h_flex()
+                    .gap_2()
+                    .child(Icon::new(IconName::Check).color(Color::Success))
+                    .child(
+                        Label::new(format!(
+                            "Resolved by {resolved_by} · {} comments",
+                            thread.comments.len()
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        Button::new("reopen", "Reopen")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, cx| this.set_resolved(false, cx))),
+                    ),
+            );
+        }
+
+        let is_new = thread.is_none();
+        container
+            .children(
+                thread
+                    .into_iter()
+                    .flat_map(|thread| &thread.comments)
+                    .map(|comment| self.render_comment(comment, cx)),
+            )
+            .child(
+                div()
+                    .p_1()
+                    .rounded_md()
+                    .bg(cx.theme().colors().editor_background)
+                    .child(self.reply_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_end()
+                    .when(is_new, |this| {
+                        this.child(
+                            Button::new("cancel", "Cancel")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, cx| {
+                                    this.cancel(&editor::actions::Cancel, cx)
+                                })),
+                        )
+                    })
+                    .when(!is_new, |this| {
+                        this.child(
+                            Button::new("resolve", "Resolve")
+                                .label_size(LabelSize::Small)
+                                .tooltip(|cx| Tooltip::text("Mark this thread as resolved", cx))
+                                .on_click(cx.listener(|this, _, cx| this.set_resolved(true, cx))),
+                        )
+                    })
+                    .child(
+                        Button::new("comment", if is_new { "Comment" } else { "Reply" })
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .disabled(self.pending_request.is_some())
+                            .on_click(cx.listener(|this, _, cx| this.confirm(&menu::Confirm, cx))),
+                    ),
+            )
+    }
+}
@@ -1,9 +1,12 @@
 pub mod channel_view;
 pub mod chat_panel;
+mod code_comments;
 pub mod collab_panel;
 pub mod notification_panel;
 pub mod notifications;
 mod panel_settings;
+pub mod recent_edits;
+pub mod session_recording;
 
 use std::{rc::Rc, sync::Arc};
 
@@ -29,9 +32,12 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
 
     channel_view::init(cx);
     chat_panel::init(cx);
+    code_comments::init(cx);
     collab_panel::init(cx);
     notification_panel::init(cx);
     notifications::init(app_state, cx);
+    recent_edits::init(cx);
+    session_recording::init(cx);
     title_bar::init(cx);
 }
 
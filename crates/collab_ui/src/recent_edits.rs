@@ -0,0 +1,654 @@
+//! A timeline of the recent edits made to a project's buffers, by the local user and by their
+//! collaborators. Edits are read from the operation history of the buffers, and attributed to
+//! participants by the replica that made them.
+
+use std::{collections::VecDeque, sync::Arc, time::Instant};
+
+use client::{User, UserStore};
+use collections::{HashMap, HashSet};
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model, ModelContext,
+    Subscription, View, ViewContext, WeakModel, WeakView,
+};
+use language::{Anchor, Buffer, BufferEvent, BufferId, Point, ReplicaId};
+use project::{buffer_store::BufferStoreEvent, Project};
+use time::{Duration, OffsetDateTime, UtcOffset};
+use ui::{prelude::*, Avatar, Tooltip};
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace, WorkspaceId,
+};
+
+actions!(collab, [OpenRecentEdits]);
+
+/// The number of edit operations remembered for each project.
+pub const MAX_RECENT_EDITS: usize = 1000;
+
+/// Edits made by the same participant to the same file are shown as one entry, as long as they
+/// are no further apart than this.
+pub const ENTRY_INTERVAL: Duration = Duration::minutes(1);
+
+/// The number of deleted and of inserted lines shown in the diff of each entry.
+const MAX_DIFF_LINES: usize = 3;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
+            let recent_edits = cx.new_model(|cx| {
+                RecentEdits::new(
+                    workspace.project().clone(),
+                    workspace.app_state().user_store.clone(),
+                    cx,
+                )
+            });
+            workspace.register_action(move |workspace, _: &OpenRecentEdits, cx| {
+                open_recent_edits(workspace, recent_edits.clone(), cx)
+            });
+        },
+    )
+    .detach();
+}
+
+fn open_recent_edits(
+    workspace: &mut Workspace,
+    recent_edits: Model<RecentEdits>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    if let Some(view) = workspace.items_of_type::<RecentEditsView>(cx).next() {
+        workspace.activate_item(&view, true, true, cx);
+        return;
+    }
+
+    let workspace_handle = cx.view().downgrade();
+    let view = cx.new_view(|cx| {
+        RecentEditsView::new(
+            workspace_handle,
+            workspace.project().clone(),
+            workspace.app_state().user_store.clone(),
+            recent_edits,
+            cx,
+        )
+    });
+    workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+}
+
+/// Records when each edit to the project's buffers was made, and by whom.
+struct RecentEdits {
+    project: Model<Project>,
+    user_store: Model<UserStore>,
+    edits: VecDeque<RecentEdit>,
+    buffers: HashMap<BufferId, TrackedBuffer>,
+    /// When tracking started, by the executor's clock and in UTC. Edits are dated by the
+    /// executor's clock, which tests can advance.
+    started_at: (Instant, OffsetDateTime),
+    _subscription: Subscription,
+}
+
+struct TrackedBuffer {
+    buffer: WeakModel<Buffer>,
+    observed_version: clock::Global,
+    _subscription: Subscription,
+}
+
+#[derive(Clone)]
+struct RecentEdit {
+    buffer_id: BufferId,
+    operation: clock::Lamport,
+    user_id: Option<u64>,
+    edited_at: OffsetDateTime,
+}
+
+impl RecentEdits {
+    fn new(
+        project: Model<Project>,
+        user_store: Model<UserStore>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        let buffer_store = project.read(cx).buffer_store().clone();
+        let subscription = cx.subscribe(&buffer_store, |this, _, event, cx| match event {
+            BufferStoreEvent::BufferAdded(buffer) => this.track_buffer(buffer, cx),
+            BufferStoreEvent::BufferDropped(buffer_id) => {
+                this.buffers.remove(buffer_id);
+                this.edits.retain(|edit| edit.buffer_id != *buffer_id);
+                cx.notify();
+            }
+            BufferStoreEvent::BufferChangedFilePath { .. } => cx.notify(),
+        });
+
+        let mut this = Self {
+            project: project.clone(),
+            user_store,
+            edits: VecDeque::new(),
+            buffers: HashMap::default(),
+            started_at: (cx.background_executor().now(), OffsetDateTime::now_utc()),
+            _subscription: subscription,
+        };
+        for buffer in project.read(cx).opened_buffers(cx) {
+            this.track_buffer(&buffer, cx);
+        }
+        this
+    }
+
+    /// Starts recording the edits made to the given buffer. Edits made before then can't be
+    /// dated, so they are left out of the timeline.
+    fn track_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        self.buffers.insert(
+            buffer_id,
+            TrackedBuffer {
+                buffer: buffer.downgrade(),
+                observed_version: buffer.read(cx).version(),
+                _subscription: cx.subscribe(buffer, |this, buffer, event, cx| {
+                    if let BufferEvent::Edited = event {
+                        this.buffer_edited(buffer, cx);
+                    }
+                }),
+            },
+        );
+    }
+
+    fn buffer_edited(&mut self, buffer: Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer = buffer.read(cx);
+        let Some(tracked) = self.buffers.get_mut(&buffer.remote_id()) else {
+            return;
+        };
+        let version = buffer.version();
+
+        // Every operation that wasn't observed yet is newer than the last one observed from
+        // its replica, so the scan can start from the oldest of those.
+        let Some(first_unobserved) = version
+            .iter()
+            .filter(|timestamp| {
+                timestamp.value > tracked.observed_version.get(timestamp.replica_id)
+            })
+            .map(|timestamp| tracked.observed_version.get(timestamp.replica_id) + 1)
+            .min()
+        else {
+            return;
+        };
+
+        let project = self.project.read(cx);
+        let (started_at, started_at_utc) = self.started_at;
+        let edited_at = started_at_utc + (cx.background_executor().now() - started_at);
+        let start = clock::Lamport {
+            replica_id: 0,
+            value: first_unobserved,
+        };
+        for (timestamp, operation) in buffer.operations().iter_from(&start) {
+            if operation.is_edit() && !tracked.observed_version.observed(*timestamp) {
+                self.edits.push_back(RecentEdit {
+                    buffer_id: buffer.remote_id(),
+                    operation: *timestamp,
+                    user_id: user_id_for_replica(
+                        project,
+                        &self.user_store,
+                        timestamp.replica_id,
+                        cx,
+                    ),
+                    edited_at,
+                });
+            }
+        }
+        tracked.observed_version = version;
+
+        while self.edits.len() > MAX_RECENT_EDITS {
+            self.edits.pop_front();
+        }
+        cx.notify();
+    }
+
+    /// Groups the recorded edits into timeline entries, most recent first.
+    fn entries(&self) -> Vec<TimelineEntry> {
+        let mut entries = Vec::<TimelineEntry>::new();
+        let mut open_entries = HashMap::<(BufferId, Option<u64>), usize>::default();
+        for edit in self.edits.iter().rev() {
+            let Some(buffer) = self
+                .buffers
+                .get(&edit.buffer_id)
+                .and_then(|tracked| tracked.buffer.upgrade())
+            else {
+                continue;
+            };
+
+            let key = (edit.buffer_id, edit.user_id);
+            if let Some(&ix) = open_entries.get(&key) {
+                let entry = &mut entries[ix];
+                if entry.first_edited_at - edit.edited_at <= ENTRY_INTERVAL {
+                    entry.operations.insert(edit.operation);
+                    entry.first_edited_at = edit.edited_at;
+                    continue;
+                }
+            }
+
+            open_entries.insert(key, entries.len());
+            entries.push(TimelineEntry {
+                buffer,
+                user_id: edit.user_id,
+                operations: HashSet::from_iter([edit.operation]),
+                first_edited_at: edit.edited_at,
+                last_edited_at: edit.edited_at,
+            });
+        }
+        entries
+    }
+}
+
+pub(crate) fn user_id_for_replica(
+    project: &Project,
+    user_store: &Model<UserStore>,
+    replica_id: ReplicaId,
+    cx: &AppContext,
+) -> Option<u64> {
+    if replica_id == project.replica_id() {
+        user_store.read(cx).current_user().map(|user| user.id)
+    } else {
+        project
+            .collaborators()
+            .values()
+            .find(|collaborator| collaborator.replica_id == replica_id)
+            .map(|collaborator| collaborator.user_id)
+    }
+}
+
+struct TimelineEntry {
+    buffer: Model<Buffer>,
+    user_id: Option<u64>,
+    operations: HashSet<clock::Lamport>,
+    first_edited_at: OffsetDateTime,
+    last_edited_at: OffsetDateTime,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum GroupBy {
+    Participant,
+    File,
+}
+
+pub struct RecentEditsView {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    recent_edits: Model<RecentEdits>,
+    user_store: Model<UserStore>,
+    group_by: GroupBy,
+    focus_handle: FocusHandle,
+    local_timezone: UtcOffset,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RecentEditsView {
+    fn new(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        user_store: Model<UserStore>,
+        recent_edits: Model<RecentEdits>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let local_offset = chrono::Local::now().offset().local_minus_utc();
+        Self {
+            workspace,
+            _subscriptions: vec![
+                cx.observe(&recent_edits, |_, _, cx| cx.notify()),
+                // Collaborators joining and leaving change how participants are shown.
+                cx.observe(&project, |_, _, cx| cx.notify()),
+                cx.observe(&user_store, |_, _, cx| cx.notify()),
+            ],
+            project,
+            recent_edits,
+            user_store,
+            group_by: GroupBy::Participant,
+            focus_handle: cx.focus_handle(),
+            local_timezone: UtcOffset::from_whole_seconds(local_offset).unwrap(),
+        }
+    }
+
+    /// Returns the participant, path and number of edits of each timeline entry, most recent
+    /// first.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn timeline_entries(&self, cx: &AppContext) -> Vec<(Option<String>, String, usize)> {
+        self.recent_edits
+            .read(cx)
+            .entries()
+            .into_iter()
+            .map(|entry| {
+                let login = self
+                    .user(entry.user_id, cx)
+                    .map(|user| user.github_login.clone());
+                let path = entry
+                    .buffer
+                    .read(cx)
+                    .file()
+                    .map(|file| file.full_path(cx).to_string_lossy().to_string())
+                    .unwrap_or_default();
+                (login, path, entry.operations.len())
+            })
+            .collect()
+    }
+
+    fn jump_to_edit(&self, buffer: Model<Buffer>, anchor: Anchor, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let position = buffer.read(cx).summary_for_anchor::<Point>(&anchor);
+            let pane = workspace.active_pane().clone();
+            let editor = workspace.open_project_item::<Editor>(pane, buffer, true, true, cx);
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                    selections.select_ranges([position..position])
+                });
+            });
+        });
+    }
+
+    fn user(&self, user_id: Option<u64>, cx: &AppContext) -> Option<Arc<User>> {
+        self.user_store.read(cx).get_cached_user(user_id?)
+    }
+
+    /// Whether the given user is currently in the project.
+    fn is_present(&self, user_id: Option<u64>, cx: &AppContext) -> bool {
+        let Some(user_id) = user_id else {
+            return false;
+        };
+        self.user_store
+            .read(cx)
+            .current_user()
+            .map_or(false, |user| user.id == user_id)
+            || self
+                .project
+                .read(cx)
+                .collaborators()
+                .values()
+                .any(|collaborator| collaborator.user_id == user_id)
+    }
+
+    fn render_participant(&self, user_id: Option<u64>, cx: &ViewContext<Self>) -> AnyElement {
+        let user = self.user(user_id, cx);
+        let is_current_user = user_id.is_some()
+            && self.user_store.read(cx).current_user().map(|user| user.id) == user_id;
+        let color = if is_current_user {
+            cx.theme().players().local()
+        } else if let Some(index) =
+            user_id.and_then(|user_id| self.user_store.read(cx).participant_indices().get(&user_id))
+        {
+            cx.theme().players().color_for_participant(index.0)
+        } else {
+            cx.theme().players().absent()
+        };
+        let is_present = self.is_present(user_id, cx);
+
+        h_flex()
+            .gap_2()
+            .children(
+                user.as_ref()
+                    .map(|user| Avatar::new(user.avatar_uri.clone()).size(rems(1.))),
+            )
+            .child(div().size_2().rounded_full().bg(if is_present {
+                color.cursor
+            } else {
+                cx.theme().players().absent().cursor
+            }))
+            .child(
+                Label::new(
+                    user.map(|user| SharedString::from(user.github_login.clone()))
+                        .unwrap_or_else(|| "Unknown participant".into()),
+                )
+                .weight(FontWeight::BOLD),
+            )
+            .when(!is_present, |this| {
+                this.child(
+                    Label::new("left the project")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn render_path(&self, buffer: &Model<Buffer>, cx: &AppContext) -> AnyElement {
+        let path = buffer
+            .read(cx)
+            .file()
+            .map(|file| file.full_path(cx).to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        Label::new(path).into_any_element()
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        entry: &TimelineEntry,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<AnyElement> {
+        let snapshot = entry.buffer.read(cx).text_snapshot();
+        let summary = snapshot.summarize_edits(&entry.operations);
+        let inserted_text = summary
+            .ranges
+            .iter()
+            .flat_map(|range| snapshot.text_for_range(range.clone()))
+            .collect::<String>();
+        if inserted_text.is_empty() && summary.deleted_text.is_empty() {
+            return None;
+        }
+
+        let first_range = summary.ranges.first()?;
+        let anchor = snapshot.anchor_before(first_range.start);
+        let row = snapshot.offset_to_point(first_range.start).row;
+        let deleted_lines = summary
+            .deleted_text
+            .iter()
+            .flat_map(|text| text.lines())
+            .collect::<Vec<_>>();
+        let inserted_lines = inserted_text.lines().collect::<Vec<_>>();
+
+        let timestamp = time_format::format_localieditsync_timestamp(
+            entry.last_edited_at,
+            OffsetDateTime::now_utc(),
+            self.local_timezone,
+            time_format::TimestampFormat::Relative,
+        );
+        let title = match self.group_by {
+            GroupBy::Participant => self.render_path(&entry.buffer, cx),
+            GroupBy::File => self.render_participant(entry.user_id, cx),
+        };
+
+        let buffer = entry.buffer.clone();
+        Some(
+            v_flex()
+                .id(ix)
+                .px_2()
+                .py_1()
+                .gap_1()
+                .rounded_md()
+                .cursor_pointer()
+                .hover(|style| style.bg(cx.theme().colors().element_hover))
+                .tooltip(|cx| Tooltip::text("Jump to Edit", cx))
+                .on_click(
+                    cx.listener(move |this, _, cx| this.jump_to_edit(buffer.clone(), anchor, cx)),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(title)
+                        .child(
+                            Label::new(format!("line {}", row + 1))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(div().flex_1())
+                        .child(
+                            Label::new(format!(
+                                "+{} −{}",
+                                inserted_lines.len(),
+                                deleted_lines.len()
+                            ))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        )
+                        .child(
+                            Label::new(timestamp)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .child(
+                    v_flex()
+                        .px_2()
+                        .py_1()
+                        .rounded_sm()
+                        .bg(cx.theme().colors().editor_background)
+                        .font_buffer(cx)
+                        .text_buffer(cx)
+                        .children(render_diff_lines(&deleted_lines, "- ", Color::Deleted))
+                        .children(render_diff_lines(&inserted_lines, "+ ", Color::Created)),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+fn render_diff_lines(lines: &[&str], prefix: &str, color: Color) -> Vec<AnyElement> {
+    let mut elements = lines
+        .iter()
+        .take(MAX_DIFF_LINES)
+        .map(|line| {
+            Label::new(format!("{prefix}{line}"))
+                .color(color)
+                .single_line()
+                .into_any_element()
+        })
+        .collect::<Vec<_>>();
+    if lines.len() > MAX_DIFF_LINES {
+        elements.push(
+            Label::new(format!("{prefix}… {} more", lines.len() - MAX_DIFF_LINES))
+                .color(Color::Muted)
+                .into_any_element(),
+        );
+    }
+    elements
+}
+
+impl Render for RecentEditsView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entries = self.recent_edits.read(cx).entries();
+
+        // Sections are ordered by their most recent entry, which comes first in `entries`.
+        let mut sections = Vec::<(Model<Buffer>, Option<u64>, Vec<AnyElement>)>::new();
+        for (ix, entry) in entries.iter().enumerate() {
+            let Some(element) = self.render_entry(ix, entry, cx) else {
+                continue;
+            };
+            let section = sections
+                .iter_mut()
+                .find(|(buffer, user_id, _)| match self.group_by {
+                    GroupBy::Participant => *user_id == entry.user_id,
+                    GroupBy::File => *buffer == entry.buffer,
+                });
+            match section {
+                Some((_, _, elements)) => elements.push(element),
+                None => sections.push((entry.buffer.clone(), entry.user_id, vec![element])),
+            }
+        }
+
+        let group_by = self.group_by;
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Recent Edits").size(LabelSize::Large))
+                    .child(div().flex_1())
+                    .child(Label::new("Group by").color(Color::Muted))
+                    .child(
+                        Button::new("group-by-participant", "Participant")
+                            .toggle_state(group_by == GroupBy::Participant)
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.group_by = GroupBy::Participant;
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Button::new("group-by-file", "File")
+                            .toggle_state(group_by == GroupBy::File)
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.group_by = GroupBy::File;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("recent-edits")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .p_2()
+                    .gap_3()
+                    .when(sections.is_empty(), |this| {
+                        this.child(
+                            Label::new("Edits made to open files will be listed here.")
+                                .color(Color::Muted),
+                        )
+                    })
+                    .children(sections.into_iter().map(|(buffer, user_id, elements)| {
+                        let header = match group_by {
+                            GroupBy::Participant => self.render_participant(user_id, cx),
+                            GroupBy::File => self.render_path(&buffer, cx),
+                        };
+                        v_flex().gap_1().child(header).children(elements)
+                    })),
+            )
+    }
+}
+
+impl EventEmitter<ItemEvent> for RecentEditsView {}
+
+impl FocusableView for RecentEditsView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for RecentEditsView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _cx: &WindowContext) -> Option<SharedString> {
+        Some("Recent Edits".into())
+    }
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        Some(cx.new_view(|cx| {
+            Self::new(
+                self.workspace.clone(),
+                self.project.clone(),
+                self.user_store.clone(),
+                self.recent_edits.clone(),
+                cx,
+            )
+        }))
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
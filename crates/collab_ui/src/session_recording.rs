@@ -0,0 +1,776 @@
+//! Local recordings of collaboration sessions. A recording captures the edits and cursors of the
+//! project's participants, who follows whom, and the messages sent to the call's channel, without
+//! any audio or video, so that the session can be replayed later in a read-only workspace item.
+//!
+//! Recordings are saved to the session recordings directory as a sequence of length-delimited
+//! `SessionRecordingEvent` protobuf messages. They are written periodically while recording, so
+//! that a session is kept even if Editsync quits before the recording is stopped.
+
+mod replay;
+
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use call::ActiveCall;
+use channel::{ChannelChat, ChannelChatEvent, ChannelMessageId, ChannelStore};
+use client::{ChannelId, Client, User, UserStore};
+use collections::{HashMap, HashSet};
+use futures::{channel::mpsc, AsyncWriteExt as _, StreamExt as _};
+use gpui::{
+    actions, AppContext, EntityId, Global, Model, ModelContext, Subscription, Task, ViewContext,
+    WeakModel,
+};
+use language::{
+    proto::serialize_operation, Anchor, Buffer, BufferId, CursorShape, Operation, ReplicaId,
+    Selection,
+};
+use project::{buffer_store::BufferStoreEvent, Fs, Project};
+use rpc::proto::{self, session_recording_event::Event, Message as _};
+use time::OffsetDateTime;
+use util::ResultExt as _;
+use workspace::{notifications::NotificationId, Toast, Workspace};
+
+use crate::recent_edits::user_id_for_replica;
+
+pub use replay::{open_recording, SessionReplay, SessionReplayView};
+
+actions!(
+    collab,
+    [
+        StartSessionRecording,
+        StopSessionRecording,
+        OpenSessionRecording
+    ]
+);
+
+/// The version of the recording format, which is bumped whenever replaying a recording would
+/// require something that older versions don't record.
+const RECORDING_VERSION: u32 = 1;
+
+/// The extension of recording files.
+const RECORDING_EXTENSION: &str = "editsync-session";
+
+/// How often the events recorded so far are written to the recording's file.
+pub const RECORDING_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
+            let recorder = project_recorder(workspace.project(), cx);
+            workspace.register_action({
+                let recorder = recorder.clone();
+                move |workspace, _: &StartSessionRecording, cx| {
+                    start_recording(workspace, &recorder, cx)
+                }
+            });
+            workspace.register_action(move |workspace, _: &StopSessionRecording, cx| {
+                stop_recording(workspace, &recorder, cx)
+            });
+            workspace.register_action(replay::open_session_recording);
+        },
+    )
+    .detach();
+}
+
+/// The recorder of each project, which is shared by all the workspaces showing the project, so
+/// that it is recorded once and its recording can be stopped from any of its windows.
+#[derive(Default)]
+struct ProjectRecorders(HashMap<EntityId, WeakModel<SessionRecorder>>);
+
+impl Global for ProjectRecorders {}
+
+fn project_recorder(project: &Model<Project>, cx: &mut AppContext) -> Model<SessionRecorder> {
+    let recorders = &mut cx.default_global::<ProjectRecorders>().0;
+    recorders.retain(|_, recorder| recorder.upgrade().is_some());
+    if let Some(recorder) = recorders
+        .get(&project.entity_id())
+        .and_then(WeakModel::upgrade)
+    {
+        return recorder;
+    }
+
+    let recorder = cx.new_model(SessionRecorder::new);
+    cx.default_global::<ProjectRecorders>()
+        .0
+        .insert(project.entity_id(), recorder.downgrade());
+    recorder
+}
+
+struct SessionRecordingToast;
+
+fn show_toast(workspace: &mut Workspace, message: String, cx: &mut ViewContext<Workspace>) {
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<SessionRecordingToast>(), message).autohide(),
+        cx,
+    );
+}
+
+fn start_recording(
+    workspace: &mut Workspace,
+    recorder: &Model<SessionRecorder>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    if recorder.read(cx).recording.is_some() {
+        show_toast(
+            workspace,
+            "This session is already being recorded.".into(),
+            cx,
+        );
+        return;
+    }
+
+    let project = workspace.project().clone();
+    let app_state = workspace.app_state().clone();
+    recorder.update(cx, |recorder, cx| {
+        recorder.start(
+            project,
+            app_state.user_store.clone(),
+            app_state.client.clone(),
+            app_state.fs.clone(),
+            cx,
+        )
+    });
+    show_toast(
+        workspace,
+        "Recording this session. Run \"collab: stop session recording\" to save it.".into(),
+        cx,
+    );
+}
+
+fn stop_recording(
+    workspace: &mut Workspace,
+    recorder: &Model<SessionRecorder>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(recording) = recorder.update(cx, |recorder, _| recorder.recording.take()) else {
+        show_toast(workspace, "This session isn't being recorded.".into(), cx);
+        return;
+    };
+
+    let saved = recording.finish();
+    cx.spawn(|workspace, mut cx| async move {
+        let path = saved.await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let message = format!("Saved session recording to {}", path.display());
+            let workspace_handle = cx.view().downgrade();
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<SessionRecordingToast>(), message).on_click(
+                    "Replay",
+                    move |cx| {
+                        workspace_handle
+                            .update(cx, |workspace, cx| {
+                                replay::open_recording(workspace, path.clone(), cx)
+                                    .detach_and_log_err(cx)
+                            })
+                            .ok();
+                    },
+                ),
+                cx,
+            );
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn project_name(project: &Model<Project>, cx: &AppContext) -> String {
+    project
+        .read(cx)
+        .worktree_root_names(cx)
+        .next()
+        .unwrap_or("session")
+        .to_string()
+}
+
+fn recording_file_name(project_name: &str, now: OffsetDateTime) -> String {
+    format!(
+        "{project_name}-{:04}{:02}{:02}-{:02}{:02}{:02}.{RECORDING_EXTENSION}",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    )
+}
+
+/// Decodes the events of a recording, checking that this version can replay it.
+fn decode_recording(mut contents: &[u8]) -> Result<Vec<proto::SessionRecordingEvent>> {
+    let mut events = Vec::new();
+    while !contents.is_empty() {
+        events.push(proto::SessionRecordingEvent::decode_length_delimited(
+            &mut contents,
+        )?);
+    }
+
+    match events.first().and_then(|event| event.event.as_ref()) {
+        Some(Event::Started(started)) if started.version <= RECORDING_VERSION => Ok(events),
+        Some(Event::Started(_)) => Err(anyhow!(
+            "this recording was made by a newer version of Editsync"
+        )),
+        _ => Err(anyhow!("not a session recording")),
+    }
+}
+
+/// Records the session of a project, while a recording is in progress.
+struct SessionRecorder {
+    recording: Option<Recording>,
+}
+
+struct Recording {
+    project: Model<Project>,
+    user_store: Model<UserStore>,
+    client: Arc<Client>,
+    started_at: Instant,
+    started_at_utc: OffsetDateTime,
+    /// The events recorded since the recording was last written.
+    events: Vec<proto::SessionRecordingEvent>,
+    /// The operations of each buffer at the time it was first recorded, which are serialized in
+    /// the background and stored in its `buffer_opened` event when it is written.
+    pending_buffer_operations: Vec<(usize, Task<Vec<proto::Operation>>)>,
+    batches_tx: mpsc::UnboundedSender<RecordingBatch>,
+    /// Writes the batches of events to the recording's file, returning its path once the
+    /// recording is finished.
+    writer: Task<Result<PathBuf>>,
+    _flush_task: Task<()>,
+    buffers: HashMap<BufferId, RecordedBuffer>,
+    /// The users recorded so far, and the replica by which each of them edits the buffers.
+    users: HashSet<u64>,
+    users_by_replica_id: HashMap<ReplicaId, u64>,
+    /// The user followed by each following user.
+    follows: HashMap<u64, u64>,
+    /// Selections are recorded as operations with timestamps of their own, which only have to
+    /// increase for a replay to apply them in order.
+    selections_timestamp: u32,
+    channel_id: Option<ChannelId>,
+    recorded_message_ids: HashSet<u64>,
+    open_chat: Task<()>,
+    chat_subscription: Option<Subscription>,
+    room_subscription: Option<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// Events to append to a recording's file.
+struct RecordingBatch {
+    events: Vec<proto::SessionRecordingEvent>,
+    pending_buffer_operations: Vec<(usize, Task<Vec<proto::Operation>>)>,
+}
+
+struct RecordedBuffer {
+    observed_version: clock::Global,
+    selections: HashMap<ReplicaId, RecordedSelections>,
+    _subscription: Subscription,
+}
+
+#[derive(PartialEq)]
+struct RecordedSelections {
+    selections: Vec<Selection<Anchor>>,
+    line_mode: bool,
+    cursor_shape: CursorShape,
+}
+
+impl SessionRecorder {
+    fn new(cx: &mut ModelContext<Self>) -> Self {
+        // Keep what was recorded when the project's last workspace is closed during a recording.
+        cx.on_release(|this, _| {
+            if let Some(recording) = this.recording.take() {
+                recording.finish().detach();
+            }
+        })
+        .detach();
+        Self { recording: None }
+    }
+
+    fn start(
+        &mut self,
+        project: Model<Project>,
+        user_store: Model<UserStore>,
+        client: Arc<Client>,
+        fs: Arc<dyn Fs>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer_store = project.read(cx).buffer_store().clone();
+        let mut subscriptions = vec![cx.subscribe(&buffer_store, |this, _, event, cx| {
+            let Some(recording) = this.recording.as_mut() else {
+                return;
+            };
+            match event {
+                BufferStoreEvent::BufferAdded(buffer) => recording.record_buffer(buffer, cx),
+                BufferStoreEvent::BufferDropped(buffer_id) => {
+                    recording.buffers.remove(buffer_id);
+                }
+                BufferStoreEvent::BufferChangedFilePath { .. } => {}
+            }
+        })];
+        if let Some(call) = ActiveCall::try_global(cx) {
+            subscriptions.push(cx.observe(&call, |this, _, cx| {
+                if let Some(recording) = this.recording.as_mut() {
+                    recording.call_changed(cx);
+                }
+            }));
+        }
+
+        let started_at_utc = OffsetDateTime::now_utc();
+        let project_name = project_name(&project, cx);
+        let path = paths::session_recordings_dir()
+            .join(recording_file_name(&project_name, started_at_utc));
+        let started = Event::Started(proto::SessionRecordingStarted {
+            version: RECORDING_VERSION,
+            started_at: Some(proto::Timestamp {
+                seconds: started_at_utc.unix_timestamp() as u64,
+                nanos: 0,
+            }),
+            project_name,
+        });
+        let (batches_tx, batches_rx) = mpsc::unbounded();
+        let mut recording = Recording {
+            project: project.clone(),
+            user_store,
+            client,
+            started_at: Instant::now(),
+            started_at_utc,
+            events: Vec::new(),
+            pending_buffer_operations: Vec::new(),
+            batches_tx,
+            writer: cx
+                .background_executor()
+                .spawn(write_recording(fs, path, batches_rx)),
+            _flush_task: Task::ready(()),
+            buffers: HashMap::default(),
+            users: HashSet::default(),
+            users_by_replica_id: HashMap::default(),
+            follows: HashMap::default(),
+            selections_timestamp: 0,
+            channel_id: None,
+            recorded_message_ids: HashSet::default(),
+            open_chat: Task::ready(()),
+            chat_subscription: None,
+            room_subscription: None,
+            _subscriptions: subscriptions,
+        };
+        recording.push_event(started);
+        for buffer in project.read(cx).opened_buffers(cx) {
+            recording.record_buffer(&buffer, cx);
+        }
+        recording.call_changed(cx);
+        recording.flush();
+        recording._flush_task = cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor()
+                    .timer(RECORDING_FLUSH_INTERVAL)
+                    .await;
+                let is_recording = this.update(&mut cx, |this, _| {
+                    this.recording.as_mut().map(Recording::flush).is_some()
+                });
+                if !matches!(is_recording, Ok(true)) {
+                    break;
+                }
+            }
+        });
+        self.recording = Some(recording);
+    }
+}
+
+/// Appends the batches of events of a recording to its file as they are received.
+async fn write_recording(
+    fs: Arc<dyn Fs>,
+    path: PathBuf,
+    mut batches_rx: mpsc::UnboundedReceiver<RecordingBatch>,
+) -> Result<PathBuf> {
+    if let Some(dir) = path.parent() {
+        fs.create_dir(dir).await?;
+    }
+    let mut file = fs.open_append(&path).await?;
+    while let Some(batch) = batches_rx.next().await {
+        let mut events = batch.events;
+        for (ix, operations) in batch.pending_buffer_operations {
+            let operations = operations.await;
+            if let Some(Event::BufferOpened(opened)) = &mut events[ix].event {
+                opened.operations = operations;
+            }
+        }
+        let mut contents = Vec::new();
+        for event in events {
+            event.encode_length_delimited(&mut contents)?;
+        }
+        file.write_all(&contents).await?;
+        file.flush().await?;
+    }
+    Ok(path)
+}
+
+impl Recording {
+    /// Sends the events recorded since the last flush to be written to the recording's file.
+    fn flush(&mut self) {
+        if self.events.is_empty() {
+            return;
+        }
+        self.batches_tx
+            .unbounded_send(RecordingBatch {
+                events: std::mem::take(&mut self.events),
+                pending_buffer_operations: std::mem::take(&mut self.pending_buffer_operations),
+            })
+            .ok();
+    }
+
+    /// Stops recording, returning the path of the recording once all of it is written.
+    fn finish(mut self) -> Task<Result<PathBuf>> {
+        self.flush();
+        self.writer
+    }
+
+    fn push_event(&mut self, event: Event) {
+        self.events.push(proto::SessionRecordingEvent {
+            elapsed_ms: self.started_at.elapsed().as_millis() as u64,
+            event: Some(event),
+        });
+    }
+
+    /// Records the given user, if they weren't recorded yet or if they now edit by a different
+    /// replica.
+    fn record_user(&mut self, user: &User, replica_id: Option<ReplicaId>) {
+        let is_new_user = self.users.insert(user.id);
+        let is_new_replica = replica_id.map_or(false, |replica_id| {
+            self.users_by_replica_id.insert(replica_id, user.id) != Some(user.id)
+        });
+        if is_new_user || is_new_replica {
+            self.push_event(Event::Participant(proto::SessionRecordingParticipant {
+                user_id: user.id,
+                github_login: user.github_login.clone(),
+                avatar_url: user.avatar_uri.to_string(),
+                replica_id: replica_id.map(|replica_id| replica_id as u32),
+            }));
+        }
+    }
+
+    fn record_replica(&mut self, replica_id: ReplicaId, cx: &AppContext) {
+        let user = user_id_for_replica(self.project.read(cx), &self.user_store, replica_id, cx)
+            .and_then(|user_id| self.user_store.read(cx).get_cached_user(user_id));
+        if let Some(user) = user {
+            self.record_user(&user, Some(replica_id));
+        }
+    }
+
+    /// Records the current state of the given buffer, and starts recording its changes.
+    fn record_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<SessionRecorder>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        if self.buffers.contains_key(&buffer_id) {
+            return;
+        }
+
+        let (mut state, path, operations, version) = buffer.read_with(cx, |buffer, cx| {
+            let path = buffer
+                .file()
+                .map(|file| file.full_path(cx).to_string_lossy().to_string())
+                .unwrap_or_else(|| "untitled".to_string());
+            (
+                buffer.to_proto(cx),
+                path,
+                buffer.serialize_ops(None, cx),
+                buffer.version(),
+            )
+        });
+        state.file = None;
+
+        // Only the text is needed from the history; the selections are recorded below.
+        let operations = cx.background_executor().spawn(async move {
+            operations
+                .await
+                .into_iter()
+                .filter(|operation| {
+                    matches!(
+                        operation.variant,
+                        Some(proto::operation::Variant::Edit(_))
+                            | Some(proto::operation::Variant::Undo(_))
+                    )
+                })
+                .collect()
+        });
+        self.pending_buffer_operations
+            .push((self.events.len(), operations));
+        self.push_event(Event::BufferOpened(proto::SessionRecordingBufferOpened {
+            buffer_id: buffer_id.into(),
+            path,
+            state: Some(state),
+            operations: Vec::new(),
+        }));
+
+        for timestamp in version.iter() {
+            self.record_replica(timestamp.replica_id, cx);
+        }
+        self.buffers.insert(
+            buffer_id,
+            RecordedBuffer {
+                observed_version: version,
+                selections: HashMap::default(),
+                _subscription: cx.observe(buffer, |this, buffer, cx| {
+                    if let Some(recording) = this.recording.as_mut() {
+                        recording.buffer_changed(buffer, cx);
+                    }
+                }),
+            },
+        );
+        self.buffer_changed(buffer.clone(), cx);
+    }
+
+    /// Records the edits made to the given buffer since it was last recorded, followed by the
+    /// selections that changed.
+    fn buffer_changed(&mut self, buffer: Model<Buffer>, cx: &AppContext) {
+        let buffer = buffer.read(cx);
+        let buffer_id = buffer.remote_id();
+        let Some(recorded) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+
+        let mut operations = Vec::new();
+        let mut replica_ids = HashSet::default();
+        let version = buffer.version();
+        if version != recorded.observed_version {
+            let start = clock::Lamport {
+                replica_id: 0,
+                value: version
+                    .iter()
+                    .filter(|timestamp| !recorded.observed_version.observed(*timestamp))
+                    .map(|timestamp| recorded.observed_version.get(timestamp.replica_id) + 1)
+                    .min()
+                    .unwrap_or(0),
+            };
+            for (timestamp, operation) in buffer.operations().iter_from(&start) {
+                if !recorded.observed_version.observed(*timestamp) {
+                    operations.push(serialize_operation(&Operation::Buffer(operation.clone())));
+                    replica_ids.insert(timestamp.replica_id);
+                }
+            }
+            recorded.observed_version = version;
+        }
+
+        let snapshot = buffer.snapshot();
+        let mut selections = HashMap::default();
+        for (replica_id, line_mode, cursor_shape, replica_selections) in
+            snapshot.selections_in_range(Anchor::MIN..Anchor::MAX, true)
+        {
+            selections.insert(
+                replica_id,
+                RecordedSelections {
+                    selections: replica_selections.cloned().collect(),
+                    line_mode,
+                    cursor_shape,
+                },
+            );
+        }
+        let mut changed_selections = Vec::new();
+        for (replica_id, selections) in &selections {
+            if recorded.selections.get(replica_id) != Some(selections) {
+                changed_selections.push((*replica_id, Some(selections)));
+            }
+        }
+        for replica_id in recorded.selections.keys() {
+            if !selections.contains_key(replica_id) {
+                changed_selections.push((*replica_id, None));
+            }
+        }
+        for (replica_id, selections) in changed_selections {
+            self.selections_timestamp += 1;
+            operations.push(serialize_operation(&Operation::UpdateSelections {
+                selections: selections
+                    .map(|selections| selections.selections.iter().cloned().collect())
+                    .unwrap_or_default(),
+                lamport_timestamp: clock::Lamport {
+                    replica_id,
+                    value: self.selections_timestamp,
+                },
+                line_mode: selections.map_or(false, |selections| selections.line_mode),
+                cursor_shape: selections
+                    .map(|selections| selections.cursor_shape)
+                    .unwrap_or_default(),
+            }));
+            replica_ids.insert(replica_id);
+        }
+        recorded.selections = selections;
+
+        if operations.is_empty() {
+            return;
+        }
+        for replica_id in replica_ids {
+            self.record_replica(replica_id, cx);
+        }
+        self.push_event(Event::BufferOperations(
+            proto::SessionRecordingBufferOperations {
+                buffer_id: buffer_id.into(),
+                operations,
+            },
+        ));
+    }
+
+    fn call_changed(&mut self, cx: &mut ModelContext<SessionRecorder>) {
+        let room = ActiveCall::try_global(cx).and_then(|call| call.read(cx).room().cloned());
+        self.room_subscription = room.as_ref().map(|room| {
+            cx.observe(room, |this, _, cx| {
+                if let Some(recording) = this.recording.as_mut() {
+                    recording.follows_changed(cx);
+                }
+            })
+        });
+        self.follows_changed(cx);
+
+        let channel_id = room.and_then(|room| room.read(cx).channel_id());
+        if channel_id == self.channel_id {
+            return;
+        }
+        self.channel_id = channel_id;
+        self.chat_subscription = None;
+        self.open_chat = Task::ready(());
+        if let Some(channel_id) = channel_id {
+            let open_chat = ChannelStore::global(cx)
+                .update(cx, |store, cx| store.open_channel_chat(channel_id, cx));
+            self.open_chat = cx.spawn(|this, mut cx| async move {
+                let Some(chat) = open_chat.await.log_err() else {
+                    return;
+                };
+                this.update(&mut cx, |this, cx| {
+                    let Some(recording) = this.recording.as_mut() else {
+                        return;
+                    };
+                    if recording.channel_id == Some(channel_id) {
+                        recording.chat_subscription =
+                            Some(cx.subscribe(&chat, |this, chat, event, cx| {
+                                if let Some(recording) = this.recording.as_mut() {
+                                    recording.chat_event(chat, event, cx);
+                                }
+                            }));
+                    }
+                })
+                .ok();
+            });
+        }
+    }
+
+    fn follows_changed(&mut self, cx: &AppContext) {
+        let mut follows = HashMap::default();
+        let room = ActiveCall::try_global(cx).and_then(|call| call.read(cx).room().cloned());
+        if let Some((room, project_id)) = room.zip(self.project.read(cx).remote_id()) {
+            let room = room.read(cx);
+            let mut users_by_peer_id = room
+                .remote_participants()
+                .values()
+                .map(|participant| (participant.peer_id, participant.user.clone()))
+                .collect::<HashMap<_, _>>();
+            if let Some((peer_id, user)) = self
+                .client
+                .peer_id()
+                .zip(self.user_store.read(cx).current_user())
+            {
+                users_by_peer_id.insert(peer_id, user);
+            }
+
+            for (leader_peer_id, leader) in &users_by_peer_id {
+                for follower_peer_id in room.followers_for(*leader_peer_id, project_id) {
+                    if let Some(follower) = users_by_peer_id.get(follower_peer_id) {
+                        self.record_user(follower, None);
+                        self.record_user(leader, None);
+                        follows.insert(follower.id, leader.id);
+                    }
+                }
+            }
+        }
+
+        let mut changed_follows = Vec::new();
+        for (follower_id, leader_id) in &follows {
+            if self.follows.get(follower_id) != Some(leader_id) {
+                changed_follows.push((*follower_id, Some(*leader_id)));
+            }
+        }
+        for follower_id in self.follows.keys() {
+            if !follows.contains_key(follower_id) {
+                changed_follows.push((*follower_id, None));
+            }
+        }
+        for (follower_id, leader_id) in changed_follows {
+            self.push_event(Event::Follow(proto::SessionRecordingFollow {
+                follower_id,
+                leader_id,
+            }));
+        }
+        self.follows = follows;
+    }
+
+    /// Records the messages sent to the channel since the recording started. Messages only
+    /// have an id once the server acknowledged them, which is when they are recorded.
+    fn chat_event(&mut self, chat: Model<ChannelChat>, event: &ChannelChatEvent, cx: &AppContext) {
+        let ChannelChatEvent::MessagesUpdated {
+            old_range,
+            new_count,
+        } = event
+        else {
+            return;
+        };
+
+        let chat = chat.read(cx);
+        for ix in old_range.start..old_range.start + new_count {
+            let message = chat.message(ix);
+            let ChannelMessageId::Saved(message_id) = message.id else {
+                continue;
+            };
+            // Message timestamps only have a precision of seconds.
+            if message.timestamp.unix_timestamp() < self.started_at_utc.unix_timestamp()
+                || !self.recorded_message_ids.insert(message_id)
+            {
+                continue;
+            }
+            let (sender, body) = (message.sender.clone(), message.body.clone());
+            self.record_user(&sender, None);
+            self.push_event(Event::ChatMessage(proto::SessionRecordingChatMessage {
+                sender_id: sender.id,
+                body,
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_recording() {
+        let started = proto::SessionRecordingEvent {
+            elapsed_ms: 0,
+            event: Some(Event::Started(proto::SessionRecordingStarted {
+                version: RECORDING_VERSION,
+                started_at: None,
+                project_name: "project".into(),
+            })),
+        };
+        let chat_message = proto::SessionRecordingEvent {
+            elapsed_ms: 1500,
+            event: Some(Event::ChatMessage(proto::SessionRecordingChatMessage {
+                sender_id: 1,
+                body: "hi".into(),
+            })),
+        };
+
+        let mut contents = Vec::new();
+        for event in [&started, &chat_message] {
+            event.encode_length_delimited(&mut contents).unwrap();
+        }
+        assert_eq!(
+            decode_recording(&contents).unwrap(),
+            vec![started.clone(), chat_message.clone()]
+        );
+
+        let mut contents = Vec::new();
+        chat_message.encode_length_delimited(&mut contents).unwrap();
+        assert!(decode_recording(&contents).is_err());
+
+        let mut newer = started;
+        if let Some(Event::Started(started)) = &mut newer.event {
+            started.version = RECORDING_VERSION + 1;
+        }
+        let mut contents = Vec::new();
+        newer.encode_length_delimited(&mut contents).unwrap();
+        assert!(decode_recording(&contents).is_err());
+    }
+}
@@ -0,0 +1,888 @@
+//! Replays session recordings in a read-only workspace item. The recorded buffers are rebuilt
+//! from their operations, and the participants' cursors are shown as those of collaborators.
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use client::{proto::PeerId, Collaborator, ParticipantIndex};
+use collections::HashMap;
+use editor::{scroll::Autoscroll, CollaborationHub, Editor};
+use gpui::{
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model, ModelContext,
+    PathPromptOptions, SharedUri, Subscription, Task, View, ViewContext, WindowContext,
+};
+use language::{
+    proto::deserialize_operation, Anchor, Buffer, Capability, LanguageRegistry, Operation, Point,
+    ReplicaId,
+};
+use rpc::proto::{self, session_recording_event::Event};
+use time::OffsetDateTime;
+use ui::{prelude::*, Avatar, ListItem};
+use util::ResultExt as _;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace, WorkspaceId,
+};
+
+use super::{decode_recording, OpenSessionRecording};
+
+/// The replica of the replayed buffers. It never edits them, so it must not be the replica of
+/// any recorded participant.
+const REPLAY_REPLICA_ID: ReplicaId = ReplicaId::MAX - 1;
+
+/// How often the replay advances while playing.
+const TICK: Duration = Duration::from_millis(50);
+
+const SPEEDS: [f32; 5] = [0.5, 1., 2., 4., 8.];
+
+/// Pauses between recorded events are shortened to this, so that replays don't linger on idle
+/// periods of a session.
+const MAX_IDLE_MS: u64 = 2000;
+
+pub(super) fn open_session_recording(
+    _: &mut Workspace,
+    _: &OpenSessionRecording,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+    });
+    cx.spawn(|workspace, mut cx| async move {
+        let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+            return Ok(());
+        };
+        workspace
+            .update(&mut cx, |workspace, cx| open_recording(workspace, path, cx))?
+            .await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Opens a replay of the recording at the given path in the workspace.
+pub fn open_recording(
+    workspace: &mut Workspace,
+    path: PathBuf,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let fs = workspace.app_state().fs.clone();
+    let languages = workspace.app_state().languages.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let contents = fs.load_bytes(&path).await?;
+        let events = cx
+            .background_executor()
+            .spawn(async move { decode_recording(&contents) })
+            .await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let replay = cx.new_model(|cx| SessionReplay::new(events, languages, cx));
+            let view = cx.new_view(|cx| SessionReplayView::new(replay, cx));
+            workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+        })
+    })
+}
+
+/// The state of a recorded session at some point of its replay.
+pub struct SessionReplay {
+    events: Vec<proto::SessionRecordingEvent>,
+    languages: Arc<LanguageRegistry>,
+    project_name: SharedString,
+    started_at: Option<OffsetDateTime>,
+    /// How much of the recording has been replayed, in milliseconds since it started.
+    position_ms: u64,
+    next_event_ix: usize,
+    speed_ix: usize,
+    playback: Option<Task<()>>,
+    buffers: Vec<ReplayedBuffer>,
+    users: HashMap<u64, RecordedUser>,
+    collaborators: HashMap<PeerId, Collaborator>,
+    participant_indices: HashMap<u64, ParticipantIndex>,
+    follows: HashMap<u64, u64>,
+    log: Vec<LogEntry>,
+}
+
+struct ReplayedBuffer {
+    id: u64,
+    path: SharedString,
+    buffer: Model<Buffer>,
+}
+
+struct RecordedUser {
+    github_login: SharedString,
+    avatar_url: SharedUri,
+}
+
+struct LogEntry {
+    elapsed_ms: u64,
+    user_id: u64,
+    kind: LogEntryKind,
+}
+
+enum LogEntryKind {
+    StartedFollowing { leader_id: u64 },
+    StoppedFollowing,
+    ChatMessage { body: SharedString },
+}
+
+pub enum SessionReplayEvent {
+    /// The replay restarted, so all of its buffers were replaced.
+    Reset,
+    /// A participant edited a buffer or moved their cursor in it.
+    Changed {
+        buffer_id: u64,
+        cursor: Option<Anchor>,
+    },
+}
+
+impl EventEmitter<SessionReplayEvent> for SessionReplay {}
+
+impl SessionReplay {
+    fn new(
+        events: Vec<proto::SessionRecordingEvent>,
+        languages: Arc<LanguageRegistry>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        let (project_name, started_at) = match events.first().and_then(|e| e.event.as_ref()) {
+            Some(Event::Started(started)) => (
+                started.project_name.clone().into(),
+                started.started_at.as_ref().and_then(|timestamp| {
+                    OffsetDateTime::from_unix_timestamp(timestamp.seconds as i64).ok()
+                }),
+            ),
+            _ => ("session".into(), None),
+        };
+        let mut this = Self {
+            events,
+            languages,
+            project_name,
+            started_at,
+            position_ms: 0,
+            next_event_ix: 0,
+            speed_ix: 1,
+            playback: None,
+            buffers: Vec::new(),
+            users: HashMap::default(),
+            collaborators: HashMap::default(),
+            participant_indices: HashMap::default(),
+            follows: HashMap::default(),
+            log: Vec::new(),
+        };
+        this.apply_events(cx);
+        this
+    }
+
+    fn duration_ms(&self) -> u64 {
+        self.events.last().map_or(0, |event| event.elapsed_ms)
+    }
+
+    fn is_playing(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.next_event_ix == self.events.len()
+    }
+
+    fn speed(&self) -> f32 {
+        SPEEDS[self.speed_ix]
+    }
+
+    fn set_speed(&mut self, speed_ix: usize, cx: &mut ModelContext<Self>) {
+        self.speed_ix = speed_ix;
+        cx.notify();
+    }
+
+    fn play(&mut self, cx: &mut ModelContext<Self>) {
+        if self.is_finished() {
+            self.restart(cx);
+        }
+        self.playback = Some(cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor().timer(TICK).await;
+                let is_playing = this
+                    .update(&mut cx, |this, cx| this.advance(TICK, cx))
+                    .unwrap_or(false);
+                if !is_playing {
+                    break;
+                }
+            }
+        }));
+        cx.notify();
+    }
+
+    fn pause(&mut self, cx: &mut ModelContext<Self>) {
+        self.playback = None;
+        cx.notify();
+    }
+
+    /// Rebuilds the recorded session from its beginning.
+    fn restart(&mut self, cx: &mut ModelContext<Self>) {
+        self.position_ms = 0;
+        self.next_event_ix = 0;
+        self.buffers.clear();
+        self.users.clear();
+        self.collaborators.clear();
+        self.participant_indices.clear();
+        self.follows.clear();
+        self.log.clear();
+        cx.emit(SessionReplayEvent::Reset);
+        self.apply_events(cx);
+        cx.notify();
+    }
+
+    /// Replays the events that happened in the given amount of time, at the current speed.
+    /// Returns whether there is anything left to replay.
+    fn advance(&mut self, elapsed: Duration, cx: &mut ModelContext<Self>) -> bool {
+        if let Some(next_event) = self.events.get(self.next_event_ix) {
+            self.position_ms = self
+                .position_ms
+                .max(next_event.elapsed_ms.saturating_sub(MAX_IDLE_MS));
+        }
+        self.position_ms += (elapsed.as_millis() as f32 * self.speed()) as u64;
+        self.apply_events(cx);
+        if self.is_finished() {
+            self.position_ms = self.duration_ms();
+            self.playback = None;
+        }
+        cx.notify();
+        !self.is_finished()
+    }
+
+    /// Replays all of the remaining events at once.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn replay_to_end(&mut self, cx: &mut ModelContext<Self>) {
+        self.position_ms = self.duration_ms();
+        self.apply_events(cx);
+        cx.notify();
+    }
+
+    /// Returns the text of the replayed buffer with the given path.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn buffer_text(&self, path: &str, cx: &AppContext) -> Option<String> {
+        self.buffers
+            .iter()
+            .find(|replayed| replayed.path.as_ref() == path)
+            .map(|replayed| replayed.buffer.read(cx).text())
+    }
+
+    /// Returns the cursors of the participants in the replayed buffer with the given path, as
+    /// their login and position.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn cursors(&self, path: &str, cx: &AppContext) -> Vec<(SharedString, Point)> {
+        let Some(replayed) = self
+            .buffers
+            .iter()
+            .find(|replayed| replayed.path.as_ref() == path)
+        else {
+            return Vec::new();
+        };
+        let snapshot = replayed.buffer.read(cx).snapshot();
+        let mut cursors = snapshot
+            .selections_in_range(Anchor::MIN..Anchor::MAX, false)
+            .filter_map(|(replica_id, _, _, selections)| {
+                let collaborator = self
+                    .collaborators
+                    .values()
+                    .find(|collaborator| collaborator.replica_id == replica_id)?;
+                let user = self.users.get(&collaborator.user_id)?;
+                let selection = selections.last()?;
+                let head = snapshot.summary_for_anchor::<Point>(&selection.head());
+                Some((user.github_login.clone(), head))
+            })
+            .collect::<Vec<_>>();
+        cursors.sort();
+        cursors
+    }
+
+    /// Returns the entries of the log, each as the login of its participant and its description.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn log_entries(&self) -> Vec<(SharedString, String)> {
+        self.log
+            .iter()
+            .map(|entry| {
+                let login = self
+                    .users
+                    .get(&entry.user_id)
+                    .map_or_else(|| "unknown".into(), |user| user.github_login.clone());
+                (login, self.describe_log_entry(entry))
+            })
+            .collect()
+    }
+
+    fn describe_log_entry(&self, entry: &LogEntry) -> String {
+        match &entry.kind {
+            LogEntryKind::StartedFollowing { leader_id } => format!(
+                "started following {}",
+                self.users
+                    .get(leader_id)
+                    .map_or("someone", |leader| leader.github_login.as_ref())
+            ),
+            LogEntryKind::StoppedFollowing => "stopped following".to_string(),
+            LogEntryKind::ChatMessage { body } => body.to_string(),
+        }
+    }
+
+    fn apply_events(&mut self, cx: &mut ModelContext<Self>) {
+        while let Some(next_event) = self.events.get(self.next_event_ix) {
+            if next_event.elapsed_ms > self.position_ms {
+                break;
+            }
+            let ix = self.next_event_ix;
+            self.next_event_ix += 1;
+            // The event is taken out while it is applied, which needs the rest of the replay.
+            if let Some(event) = self.events[ix].event.take() {
+                self.apply_event(&event, cx);
+                self.events[ix].event = Some(event);
+            }
+        }
+    }
+
+    fn apply_event(&mut self, event: &Event, cx: &mut ModelContext<Self>) {
+        match event {
+            Event::Started(_) => {}
+            Event::Participant(participant) => {
+                self.users.insert(
+                    participant.user_id,
+                    RecordedUser {
+                        github_login: participant.github_login.clone().into(),
+                        avatar_url: participant.avatar_url.clone().into(),
+                    },
+                );
+                let next_index = ParticipantIndex(self.participant_indices.len() as u32);
+                self.participant_indices
+                    .entry(participant.user_id)
+                    .or_insert(next_index);
+                if let Some(replica_id) = participant.replica_id {
+                    let peer_id = PeerId {
+                        owner_id: 0,
+                        id: replica_id,
+                    };
+                    self.collaborators.insert(
+                        peer_id,
+                        Collaborator {
+                            peer_id,
+                            replica_id: replica_id as ReplicaId,
+                            user_id: participant.user_id,
+                            is_host: false,
+                        },
+                    );
+                }
+            }
+            Event::BufferOpened(opened) => self.open_buffer(opened, cx),
+            Event::BufferOperations(operations) => {
+                self.apply_operations(operations, cx);
+            }
+            Event::Follow(follow) => {
+                let kind = match follow.leader_id {
+                    Some(leader_id) => {
+                        self.follows.insert(follow.follower_id, leader_id);
+                        LogEntryKind::StartedFollowing { leader_id }
+                    }
+                    None => {
+                        self.follows.remove(&follow.follower_id);
+                        LogEntryKind::StoppedFollowing
+                    }
+                };
+                self.push_log_entry(follow.follower_id, kind);
+            }
+            Event::ChatMessage(message) => self.push_log_entry(
+                message.sender_id,
+                LogEntryKind::ChatMessage {
+                    body: message.body.clone().into(),
+                },
+            ),
+        }
+    }
+
+    fn push_log_entry(&mut self, user_id: u64, kind: LogEntryKind) {
+        let elapsed_ms = self
+            .events
+            .get(self.next_event_ix.saturating_sub(1))
+            .map_or(0, |event| event.elapsed_ms);
+        self.log.push(LogEntry {
+            elapsed_ms,
+            user_id,
+            kind,
+        });
+    }
+
+    fn open_buffer(
+        &mut self,
+        opened: &proto::SessionRecordingBufferOpened,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(state) = opened.state.clone() else {
+            return;
+        };
+        let Some(buffer) =
+            Buffer::from_proto(REPLAY_REPLICA_ID, Capability::ReadOnly, state, None).log_err()
+        else {
+            return;
+        };
+        let operations = deserialize_operations(&opened.operations);
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = buffer;
+            buffer.set_language_registry(self.languages.clone());
+            buffer.apply_ops(operations, cx);
+            buffer
+        });
+
+        let languages = self.languages.clone();
+        let path = PathBuf::from(&opened.path);
+        cx.spawn({
+            let buffer = buffer.clone();
+            |_, mut cx| async move {
+                if let Ok(language) = languages.language_for_file_path(&path).await {
+                    buffer
+                        .update(&mut cx, |buffer, cx| {
+                            buffer.set_language(Some(language), cx)
+                        })
+                        .ok();
+                }
+            }
+        })
+        .detach();
+
+        self.buffers
+            .retain(|replayed| replayed.id != opened.buffer_id);
+        self.buffers.push(ReplayedBuffer {
+            id: opened.buffer_id,
+            path: opened.path.clone().into(),
+            buffer,
+        });
+        cx.emit(SessionReplayEvent::Changed {
+            buffer_id: opened.buffer_id,
+            cursor: None,
+        });
+    }
+
+    fn apply_operations(
+        &mut self,
+        operations: &proto::SessionRecordingBufferOperations,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(replayed) = self
+            .buffers
+            .iter()
+            .find(|replayed| replayed.id == operations.buffer_id)
+        else {
+            return;
+        };
+        let operations = deserialize_operations(&operations.operations);
+        let replica_id = operations
+            .iter()
+            .rev()
+            .find_map(|operation| match operation {
+                Operation::Buffer(operation) => Some(operation.timestamp().replica_id),
+                Operation::UpdateSelections {
+                    lamport_timestamp, ..
+                } => Some(lamport_timestamp.replica_id),
+                _ => None,
+            });
+
+        let buffer_id = replayed.id;
+        let cursor = replayed.buffer.update(cx, |buffer, cx| {
+            buffer.apply_ops(operations, cx);
+            let snapshot = buffer.snapshot();
+            let replica_id = replica_id?;
+            let (_, _, _, selections) = snapshot
+                .selections_in_range(Anchor::MIN..Anchor::MAX, false)
+                .find(|(selections_replica_id, ..)| *selections_replica_id == replica_id)?;
+            selections.last().map(|selection| selection.head())
+        });
+        cx.emit(SessionReplayEvent::Changed { buffer_id, cursor });
+    }
+}
+
+fn deserialize_operations(operations: &[proto::Operation]) -> Vec<Operation> {
+    operations
+        .iter()
+        .filter_map(|operation| deserialize_operation(operation.clone()).log_err())
+        .collect()
+}
+
+fn format_elapsed(elapsed_ms: u64) -> String {
+    let seconds = elapsed_ms / 1000;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Shows the recorded participants' cursors as those of collaborators.
+struct SessionReplayCollaborationHub(Model<SessionReplay>);
+
+impl CollaborationHub for SessionReplayCollaborationHub {
+    fn collaborators<'a>(&self, cx: &'a AppContext) -> &'a HashMap<PeerId, Collaborator> {
+        &self.0.read(cx).collaborators
+    }
+
+    fn user_participant_indices<'a>(
+        &self,
+        cx: &'a AppContext,
+    ) -> &'a HashMap<u64, ParticipantIndex> {
+        &self.0.read(cx).participant_indices
+    }
+
+    fn user_names(&self, cx: &AppContext) -> HashMap<u64, SharedString> {
+        self.0
+            .read(cx)
+            .users
+            .iter()
+            .map(|(user_id, user)| (*user_id, user.github_login.clone()))
+            .collect()
+    }
+}
+
+pub struct SessionReplayView {
+    replay: Model<SessionReplay>,
+    editors: HashMap<u64, View<Editor>>,
+    shown_buffer_id: Option<u64>,
+    /// Whether to show the buffer in which participants were last active.
+    follow_activity: bool,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl SessionReplayView {
+    fn new(replay: Model<SessionReplay>, cx: &mut ViewContext<Self>) -> Self {
+        let shown_buffer_id = replay.read(cx).buffers.last().map(|replayed| replayed.id);
+        Self {
+            _subscription: cx.subscribe(&replay, Self::handle_replay_event),
+            replay,
+            editors: HashMap::default(),
+            shown_buffer_id,
+            follow_activity: true,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn handle_replay_event(
+        &mut self,
+        _: Model<SessionReplay>,
+        event: &SessionReplayEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            SessionReplayEvent::Reset => {
+                self.editors.clear();
+                self.shown_buffer_id = None;
+            }
+            SessionReplayEvent::Changed { buffer_id, cursor } => {
+                if self.shown_buffer_id.is_some() && !self.follow_activity {
+                    return;
+                }
+                self.shown_buffer_id = Some(*buffer_id);
+                if let Some((editor, cursor)) = self.editor(*buffer_id, cx).zip(*cursor) {
+                    editor.update(cx, |editor, cx| {
+                        let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+                            return;
+                        };
+                        let position = buffer.read(cx).summary_for_anchor::<Point>(&cursor);
+                        editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                            selections.select_ranges([position..position])
+                        });
+                    });
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    /// Returns the editor of the given replayed buffer, creating it if needed.
+    fn editor(&mut self, buffer_id: u64, cx: &mut ViewContext<Self>) -> Option<View<Editor>> {
+        if let Some(editor) = self.editors.get(&buffer_id) {
+            return Some(editor.clone());
+        }
+
+        let buffer = self
+            .replay
+            .read(cx)
+            .buffers
+            .iter()
+            .find(|replayed| replayed.id == buffer_id)?
+            .buffer
+            .clone();
+        let replay = self.replay.clone();
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer, None, cx);
+            editor.set_read_only(true);
+            editor.set_collaboration_hub(Box::new(SessionReplayCollaborationHub(replay)));
+            editor
+        });
+        self.editors.insert(buffer_id, editor.clone());
+        Some(editor)
+    }
+
+    pub fn replay(&self) -> &Model<SessionReplay> {
+        &self.replay
+    }
+
+    fn show_buffer(&mut self, buffer_id: u64, cx: &mut ViewContext<Self>) {
+        self.shown_buffer_id = Some(buffer_id);
+        self.follow_activity = false;
+        cx.notify();
+    }
+
+    fn render_user(&self, user_id: u64, cx: &ViewContext<Self>) -> AnyElement {
+        let replay = self.replay.read(cx);
+        let user = replay.users.get(&user_id);
+        let color = replay
+            .participant_indices
+            .get(&user_id)
+            .map(|index| cx.theme().players().color_for_participant(index.0))
+            .unwrap_or_else(|| cx.theme().players().absent());
+        h_flex()
+            .gap_1()
+            .children(user.map(|user| Avatar::new(user.avatar_url.clone()).size(rems(1.))))
+            .child(div().size_2().rounded_full().bg(color.cursor))
+            .child(
+                Label::new(
+                    user.map(|user| user.github_login.clone())
+                        .unwrap_or_else(|| "Unknown participant".into()),
+                )
+                .weight(FontWeight::BOLD),
+            )
+            .into_any_element()
+    }
+
+    fn render_controls(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let replay = self.replay.read(cx);
+        let (is_playing, speed_ix) = (replay.is_playing(), replay.speed_ix);
+        let time = format!(
+            "{} / {}",
+            format_elapsed(replay.position_ms),
+            format_elapsed(replay.duration_ms())
+        );
+        let title = match replay.started_at {
+            Some(started_at) => format!(
+                "{} — recorded {}",
+                replay.project_name,
+                time_format::format_localieditsync_timestamp(
+                    started_at,
+                    OffsetDateTime::now_utc(),
+                    local_timezone(),
+                    time_format::TimestampFormat::EnhancedAbsolute,
+                )
+            ),
+            None => replay.project_name.to_string(),
+        };
+
+        h_flex()
+            .p_2()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Button::new("play-pause", if is_playing { "Pause" } else { "Play" })
+                    .when(!is_playing, |this| this.icon(IconName::Play))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.replay.update(cx, |replay, cx| {
+                            if replay.is_playing() {
+                                replay.pause(cx)
+                            } else {
+                                replay.play(cx)
+                            }
+                        })
+                    })),
+            )
+            .child(
+                Button::new("restart", "Restart")
+                    .icon(IconName::RotateCcw)
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.replay.update(cx, |replay, cx| replay.restart(cx))
+                    })),
+            )
+            .child(Label::new(time).color(Color::Muted))
+            .child(div().flex_1().child(Label::new(title).single_line()))
+            .child(Label::new("Speed").color(Color::Muted))
+            .children(SPEEDS.iter().enumerate().map(|(ix, speed)| {
+                Button::new(("speed", ix), format!("{speed}×"))
+                    .toggle_state(ix == speed_ix)
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.replay
+                            .update(cx, |replay, cx| replay.set_speed(ix, cx))
+                    }))
+            }))
+    }
+
+    fn render_sidebar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let replay = self.replay.read(cx);
+        let mut user_ids = replay.participant_indices.iter().collect::<Vec<_>>();
+        user_ids.sort_by_key(|(_, index)| index.0);
+        let participants = user_ids
+            .into_iter()
+            .map(|(user_id, _)| {
+                let following = replay.follows.get(user_id).and_then(|leader_id| {
+                    replay
+                        .users
+                        .get(leader_id)
+                        .map(|leader| format!("following {}", leader.github_login))
+                });
+                v_flex()
+                    .child(self.render_user(*user_id, cx))
+                    .children(following.map(|following| {
+                        Label::new(following)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                    }))
+            })
+            .collect::<Vec<_>>();
+        let files = replay
+            .buffers
+            .iter()
+            .map(|replayed| {
+                let buffer_id = replayed.id;
+                ListItem::new(("file", buffer_id as usize))
+                    .toggle_state(self.shown_buffer_id == Some(buffer_id))
+                    .on_click(cx.listener(move |this, _, cx| this.show_buffer(buffer_id, cx)))
+                    .child(Label::new(replayed.path.clone()).single_line())
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("session-replay-sidebar")
+            .w(rems(16.))
+            .h_full()
+            .p_2()
+            .gap_2()
+            .overflow_y_scroll()
+            .border_r_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new("Participants").color(Color::Muted))
+            .children(participants)
+            .child(
+                h_flex()
+                    .child(Label::new("Files").color(Color::Muted))
+                    .child(div().flex_1())
+                    .child(
+                        Button::new("follow-activity", "Follow Activity")
+                            .label_size(LabelSize::Small)
+                            .toggle_state(self.follow_activity)
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.follow_activity = !this.follow_activity;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .children(files)
+    }
+
+    fn render_log(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let replay = self.replay.read(cx);
+        let entries = replay
+            .log
+            .iter()
+            .rev()
+            .map(|entry| {
+                let description = replay.describe_log_entry(entry);
+                v_flex()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(self.render_user(entry.user_id, cx))
+                            .child(
+                                Label::new(format_elapsed(entry.elapsed_ms))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                    .child(Label::new(description).color(match entry.kind {
+                        LogEntryKind::ChatMessage { .. } => Color::Default,
+                        _ => Color::Muted,
+                    }))
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("session-replay-log")
+            .w(rems(16.))
+            .h_full()
+            .p_2()
+            .gap_2()
+            .overflow_y_scroll()
+            .border_l_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new("Follows and Chat").color(Color::Muted))
+            .when(entries.is_empty(), |this| {
+                this.child(Label::new("Nothing yet.").color(Color::Muted))
+            })
+            .children(entries)
+    }
+}
+
+fn local_timezone() -> time::UtcOffset {
+    let local_offset = chrono::Local::now().offset().local_minus_utc();
+    time::UtcOffset::from_whole_seconds(local_offset).unwrap()
+}
+
+impl Render for SessionReplayView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let editor = self
+            .shown_buffer_id
+            .and_then(|buffer_id| self.editor(buffer_id, cx));
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_controls(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(self.render_sidebar(cx))
+                    .child(
+                        div()
+                            .flex_1()
+                            .h_full()
+                            .overflow_hidden()
+                            .children(editor)
+                            .when(self.shown_buffer_id.is_none(), |this| {
+                                this.p_2().child(
+                                    Label::new("No files were open in this session yet.")
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    .child(self.render_log(cx)),
+            )
+    }
+}
+
+impl EventEmitter<ItemEvent> for SessionReplayView {}
+
+impl FocusableView for SessionReplayView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for SessionReplayView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, cx: &WindowContext) -> Option<SharedString> {
+        Some(format!("Replay: {}", self.replay.read(cx).project_name).into())
+    }
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::Play))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        Some(cx.new_view(|cx| Self::new(self.replay.clone(), cx)))
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
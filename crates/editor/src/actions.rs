@@ -278,6 +278,7 @@ gpui::actions!(
         GoToTypeDefinitionSplit,
         HalfPageDown,
         HalfPageUp,
+        HideInlineValues,
         Hover,
         Indent,
         InsertUuidV4,
@@ -357,6 +358,7 @@ gpui::actions!(
         SelectUp,
         ShowCharacterPalette,
         ShowInlineCompletion,
+        ShowInlineValues,
         ShowSignatureHelp,
         ShuffleLines,
         SortLinesCaseInsensitive,
@@ -689,6 +689,17 @@ impl<'a> HighlightedChunk<'a> {
     }
 }
 
+/// An outline item enclosing the top of the viewport whose first line has been scrolled out of
+/// view, such as a function, an impl or a class.
+#[derive(Clone, Debug)]
+pub struct StickyScope {
+    pub range: Range<Anchor>,
+    /// The display row containing the start of the scope.
+    pub start_row: DisplayRow,
+    /// The display row containing the end of the scope.
+    pub end_row: DisplayRow,
+}
+
 #[derive(Clone)]
 pub struct DisplaySnapshot {
     pub buffer_snapshot: MultiBufferSnapshot,
@@ -1110,6 +1121,43 @@ impl DisplaySnapshot {
         self.block_snapshot.sticky_header_excerpt(row.0)
     }
 
+    /// Returns the scopes to pin at the top of the viewport when it's scrolled to the given row,
+    /// from the outermost to the innermost, according to the language's outline query.
+    ///
+    /// A scope is only pinned when its first line would otherwise be hidden, either by being
+    /// scrolled out of view or by being covered by the scopes pinned above it.
+    pub fn sticky_scopes(&self, top_row: DisplayRow, max_count: usize) -> Vec<StickyScope> {
+        let mut scopes = Vec::new();
+        if max_count == 0 {
+            return scopes;
+        }
+
+        let offset = DisplayPoint::new(top_row, 0).to_offset(self, Bias::Left);
+        let Some((_, items)) = self.buffer_snapshot.symbols_containing(offset, None) else {
+            return scopes;
+        };
+        for item in items {
+            let start_row = item.range.start.to_display_point(self).row();
+            let end_row = item.range.end.to_display_point(self).row();
+            if start_row.0 >= top_row.0 + scopes.len() as u32 {
+                break;
+            }
+            if start_row == end_row {
+                continue;
+            }
+
+            scopes.push(StickyScope {
+                range: item.range,
+                start_row,
+                end_row,
+            });
+            if scopes.len() == max_count {
+                break;
+            }
+        }
+        scopes
+    }
+
     pub fn block_for_id(&self, id: BlockId) -> Option<Block> {
         self.block_snapshot.block_for_id(id)
     }
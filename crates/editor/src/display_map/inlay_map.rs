@@ -62,6 +62,22 @@ impl Inlay {
             text: text.into(),
         }
     }
+
+    pub fn inline_value<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::InlineValue(id),
+            position,
+            text: text.into(),
+        }
+    }
+
+    pub fn replacement_preview<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::ReplacementPreview(id),
+            position,
+            text: text.into(),
+        }
+    }
 }
 
 impl sum_tree::Item for Transform {
@@ -284,7 +300,9 @@ impl<'a> Iterator for InlayChunks<'a> {
                             }
                         })
                     }
-                    InlayId::Hint(_) => self.highlight_styles.inlay_hint,
+                    InlayId::Hint(_) | InlayId::InlineValue(_) => self.highlight_styles.inlay_hint,
+                    // Styled by the editor through inlay highlights.
+                    InlayId::ReplacementPreview(_) => None,
                 };
                 let next_inlay_highlight_endpoint;
                 let offset_in_inlay = self.output_offset - self.transforms.start().0;
@@ -28,6 +28,7 @@ mod hover_popover;
 mod hunk_diff;
 mod indent_guides;
 mod inlay_hint_cache;
+mod inline_values;
 pub mod items;
 mod linked_editing_ranges;
 mod lsp_ext;
@@ -35,8 +36,10 @@ mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod proposed_changes_editor;
+mod replacement_previews;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_ranges;
 mod selections_collection;
 pub mod tasks;
 
@@ -61,7 +64,8 @@ use convert_case::{Case, Casing};
 use display_map::*;
 pub use display_map::{DisplayPoint, FoldPlaceholder};
 pub use editor_settings::{
-    CurrentLineHighlight, EditorSettings, ScrollBeyondLastLine, SearchSettings, ShowScrollbar,
+    CurrentLineHighlight, EditorSettings, ExpandSelectionSource, ScrollBeyondLastLine,
+    SearchSettings, ShowMinimap, ShowScrollbar,
 };
 pub use editor_settings_controls::*;
 use element::LineWithInvisibles;
@@ -94,6 +98,7 @@ use indent_guides::ActiveIndentGuidesState;
 use inlay_hint_cache::{InlayHintCache, InlaySplice, InvalidationStrategy};
 pub use inline_completion::Direction;
 use inline_completion::{InlineCompletionProvider, InlineCompletionProviderHandle};
+use inline_values::InlineValues;
 pub use items::MAX_TAB_TITLE_LEN;
 use itertools::Itertools;
 use language::{
@@ -108,6 +113,7 @@ use mouse_context_menu::MouseContextMenu;
 pub use proposed_changes_editor::{
     ProposedChangeLocation, ProposedChangesEditor, ProposedChangesEditorToolbar,
 };
+use replacement_previews::ReplacementPreviews;
 use similar::{ChangeTag, TextDiff};
 use std::iter::Peekable;
 use task::{ResolvedTask, TaskTemplate, TaskVariables};
@@ -131,8 +137,8 @@ use project::{
     buffer_store::BufferChangeSet,
     lsp_store::{FormatTarget, FormatTrigger, OpenLspBufferHandle},
     project_settings::{GitGutterSetting, ProjectSettings},
-    CodeAction, Completion, CompletionIntent, DocumentHighlight, InlayHint, Location, LocationLink,
-    LspStore, Project, ProjectItem, ProjectTransaction, TaskSourceKind,
+    CodeAction, Completion, CompletionIntent, DocumentHighlight, InlayHint, InlineValue, Location,
+    LocationLink, LspStore, Project, ProjectItem, ProjectTransaction, TaskSourceKind,
 };
 use rand::prelude::*;
 use rpc::{proto::*, ErrorExt};
@@ -261,6 +267,8 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     InlineCompletion(usize),
     Hint(usize),
+    InlineValue(usize),
+    ReplacementPreview(usize),
 }
 
 impl InlayId {
@@ -268,6 +276,8 @@ impl InlayId {
         match self {
             Self::InlineCompletion(id) => *id,
             Self::Hint(id) => *id,
+            Self::InlineValue(id) => *id,
+            Self::ReplacementPreview(id) => *id,
         }
     }
 }
@@ -305,10 +315,26 @@ pub fn init(cx: &mut AppContext) {
     workspace::register_serializable_item::<Editor>(cx);
 
     cx.observe_new_views(
-        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
             workspace.register_action(Editor::new_file);
             workspace.register_action(Editor::new_file_vertical);
             workspace.register_action(Editor::new_file_horizontal);
+
+            let project = workspace.project().clone();
+            cx.subscribe(&project, |workspace, _, event, cx| {
+                if let project::Event::FileOperationEditsProposed { title, edits } = event {
+                    if !workspace.is_main_workspace_for_project(cx) {
+                        return;
+                    }
+                    ProposedChangesEditor::open_file_operation_edits(
+                        workspace,
+                        title.clone(),
+                        edits,
+                        cx,
+                    );
+                }
+            })
+            .detach();
         },
     )
     .detach();
@@ -591,6 +617,7 @@ pub struct Editor {
     autoclose_regions: Vec<AutocloseRegion>,
     snippet_stack: InvalidationStack<SnippetState>,
     select_larger_syntax_node_stack: Vec<Box<[Selection<usize>]>>,
+    selection_range_task: Option<Task<Option<()>>>,
     ime_transaction: Option<TransactionId>,
     active_diagnostics: Option<ActiveDiagnosticGroup>,
     soft_wrap_mode_override: Option<language_settings::SoftWrap>,
@@ -658,6 +685,8 @@ pub struct Editor {
     enable_inline_completions: bool,
     show_inline_completions_override: Option<bool>,
     inlay_hint_cache: InlayHintCache,
+    inline_values: InlineValues,
+    replacement_previews: ReplacementPreviews,
     diff_map: DiffMap,
     next_inlay_id: usize,
     _subscriptions: Vec<Subscription>,
@@ -1229,6 +1258,7 @@ impl Editor {
             autoclose_regions: Default::default(),
             snippet_stack: Default::default(),
             select_larger_syntax_node_stack: Vec::new(),
+            selection_range_task: None,
             ime_transaction: Default::default(),
             active_diagnostics: None,
             soft_wrap_mode_override,
@@ -1293,6 +1323,8 @@ impl Editor {
             inline_completion_provider: None,
             active_inline_completion: None,
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
+            inline_values: InlineValues::default(),
+            replacement_previews: ReplacementPreviews::default(),
             diff_map: DiffMap::default(),
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
@@ -8773,41 +8805,38 @@ impl Editor {
         _: &SelectLargerSyntaxNode,
         cx: &mut ViewContext<Self>,
     ) {
+        if EditorSettings::get_global(cx).expand_selection_source
+            == ExpandSelectionSource::LanguageServer
+        {
+            if let Some(task) = selection_ranges::select_larger_selection_ranges(self, cx) {
+                self.selection_range_task = Some(task);
+                return;
+            }
+        }
+
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
-        let buffer = self.buffer.read(cx).snapshot(cx);
         let old_selections = self.selections.all::<usize>(cx).into_boxed_slice();
+        let new_ranges = old_selections
+            .iter()
+            .map(|selection| larger_syntax_node_range(&display_map, selection.range()))
+            .collect::<Vec<_>>();
+        self.select_larger_ranges(old_selections, new_ranges, cx);
+    }
 
-        let mut stack = mem::take(&mut self.select_larger_syntax_node_stack);
+    /// Replaces each of the `old_selections` with the corresponding range in `new_ranges`,
+    /// remembering the old selections so that `SelectSmallerSyntaxNode` can restore them.
+    fn select_larger_ranges(
+        &mut self,
+        old_selections: Box<[Selection<usize>]>,
+        new_ranges: Vec<Range<usize>>,
+        cx: &mut ViewContext<Self>,
+    ) {
         let mut selected_larger_node = false;
         let new_selections = old_selections
             .iter()
-            .map(|selection| {
-                let old_range = selection.start..selection.end;
-                let mut new_range = old_range.clone();
-                let mut new_node = None;
-                while let Some((node, containing_range)) = buffer.syntax_ancestor(new_range.clone())
-                {
-                    new_node = Some(node);
-                    new_range = containing_range;
-                    if !display_map.intersects_fold(new_range.start)
-                        && !display_map.intersects_fold(new_range.end)
-                    {
-                        break;
-                    }
-                }
-
-                if let Some(node) = new_node {
-                    // Log the ancestor, to support using this action as a way to explore TreeSitter
-                    // nodes. Parent and grandparent are also logged because this operation will not
-                    // visit nodes that have the same range as their parent.
-                    log::info!("Node: {node:?}");
-                    let parent = node.parent();
-                    log::info!("Parent: {parent:?}");
-                    let grandparent = parent.and_then(|x| x.parent());
-                    log::info!("Grandparent: {grandparent:?}");
-                }
-
-                selected_larger_node |= new_range != old_range;
+            .zip(new_ranges)
+            .map(|(selection, new_range)| {
+                selected_larger_node |= new_range != selection.range();
                 Selection {
                     id: selection.id,
                     start: new_range.start,
@@ -8819,12 +8848,13 @@ impl Editor {
             .collect::<Vec<_>>();
 
         if selected_larger_node {
-            stack.push(old_selections);
+            self.select_larger_syntax_node_stack.push(old_selections);
+            let stack = mem::take(&mut self.select_larger_syntax_node_stack);
             self.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select(new_selections);
             });
+            self.select_larger_syntax_node_stack = stack;
         }
-        self.select_larger_syntax_node_stack = stack;
     }
 
     pub fn select_smaller_syntax_node(
@@ -12215,6 +12245,7 @@ impl Editor {
                 if self.has_active_inline_completion() {
                     self.update_visible_inline_completion(cx);
                 }
+                self.clear_all_inline_values(cx);
                 if let Some(buffer) = buffer_edited {
                     let buffer_id = buffer.read(cx).remote_id();
                     if !self.registered_buffers.contains_key(&buffer_id) {
@@ -13451,6 +13482,25 @@ pub trait SemanticsProvider {
         new_name: String,
         cx: &mut AppContext,
     ) -> Option<Task<Result<ProjectTransaction>>>;
+
+    /// Returns, for each position, the chain of ranges that contain it,
+    /// ordered from the innermost range to the outermost one.
+    fn selection_ranges(
+        &self,
+        buffer: &Model<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>>;
+
+    /// Returns the values to display inline for the given range, for execution stopped at
+    /// `stopped_location`.
+    fn inline_values(
+        &self,
+        buffer: &Model<Buffer>,
+        range: Range<text::Anchor>,
+        stopped_location: Range<text::Anchor>,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Vec<InlineValue>>>>;
 }
 
 pub trait CompletionProvider {
@@ -13857,6 +13907,62 @@ impl SemanticsProvider for Model<Project> {
             project.perform_rename(buffer.clone(), position, new_name, cx)
         }))
     }
+
+    fn selection_ranges(
+        &self,
+        buffer: &Model<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.selection_ranges(buffer, positions, cx)
+        }))
+    }
+
+    fn inline_values(
+        &self,
+        buffer: &Model<Buffer>,
+        range: Range<text::Anchor>,
+        stopped_location: Range<text::Anchor>,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Vec<InlineValue>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.inline_values(buffer, range, stopped_location, cx)
+        }))
+    }
+}
+
+/// Returns the range of the smallest syntax node that strictly contains `old_range` and whose
+/// boundaries are not folded, or `old_range` itself if there is no such node.
+fn larger_syntax_node_range(
+    display_map: &DisplaySnapshot,
+    old_range: Range<usize>,
+) -> Range<usize> {
+    let buffer = &display_map.buffer_snapshot;
+    let mut new_range = old_range.clone();
+    let mut new_node = None;
+    while let Some((node, containing_range)) = buffer.syntax_ancestor(new_range.clone()) {
+        new_node = Some(node);
+        new_range = containing_range;
+        if !display_map.intersects_fold(new_range.start)
+            && !display_map.intersects_fold(new_range.end)
+        {
+            break;
+        }
+    }
+
+    if let Some(node) = new_node {
+        // Log the ancestor, to support using this action as a way to explore TreeSitter
+        // nodes. Parent and grandparent are also logged because this operation will not
+        // visit nodes that have the same range as their parent.
+        log::info!("Node: {node:?}");
+        let parent = node.parent();
+        log::info!("Parent: {parent:?}");
+        let grandparent = parent.and_then(|x| x.parent());
+        log::info!("Grandparent: {grandparent:?}");
+    }
+
+    new_range
 }
 
 fn inlay_hint_settings(
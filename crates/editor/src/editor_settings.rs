@@ -14,6 +14,8 @@ pub struct EditorSettings {
     pub hover_popover_delay: u64,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
+    pub minimap: Minimap,
+    pub sticky_scroll: StickyScroll,
     pub gutter: Gutter,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f32,
@@ -36,6 +38,8 @@ pub struct EditorSettings {
     pub show_signature_help_after_edits: bool,
     pub jupyter: Jupyter,
     pub show_inline_completions_in_menu: bool,
+    #[serde(default)]
+    pub expand_selection_source: ExpandSelectionSource,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
@@ -75,6 +79,20 @@ pub enum DoubleClickInMultibuffer {
     Open,
 }
 
+/// Where `editor::SelectLargerSyntaxNode` looks for the next, larger selection.
+///
+/// Default: syntax
+#[derive(Default, Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpandSelectionSource {
+    /// Expand to the enclosing tree-sitter syntax node.
+    #[default]
+    Syntax,
+    /// Expand to the next range reported by the language server's `textDocument/selectionRange`,
+    /// falling back to tree-sitter syntax nodes where the server reports nothing.
+    LanguageServer,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Jupyter {
     /// Whether the Jupyter feature is enabled.
@@ -110,6 +128,21 @@ pub struct Scrollbar {
     pub axes: ScrollbarAxes,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Minimap {
+    pub show: ShowMinimap,
+    pub width: f32,
+    pub git_diff: bool,
+    pub search_results: bool,
+    pub diagnostics: bool,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScroll {
+    pub enabled: bool,
+    pub max_lines: usize,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Gutter {
     pub line_numbers: bool,
@@ -135,6 +168,20 @@ pub enum ShowScrollbar {
     Never,
 }
 
+/// When to show the minimap in the editor.
+///
+/// Default: never
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShowMinimap {
+    /// Show the minimap when the file doesn't fit in the editor.
+    Auto,
+    /// Always show the minimap.
+    Always,
+    /// Never show the minimap.
+    Never,
+}
+
 /// Forcefully enable or disable the scrollbar for each axis
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -223,6 +270,10 @@ pub struct EditorSettingsContent {
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar related settings
     pub scrollbar: Option<ScrollbarContent>,
+    /// Minimap related settings
+    pub minimap: Option<MinimapContent>,
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
     /// Whether the editor will scroll beyond the last line.
@@ -307,6 +358,11 @@ pub struct EditorSettingsContent {
     /// Default: true
     pub show_inline_completions_in_menu: Option<bool>,
 
+    /// Where to look for the next selection when expanding it with `editor::SelectLargerSyntaxNode`.
+    ///
+    /// Default: syntax
+    pub expand_selection_source: Option<ExpandSelectionSource>,
+
     /// Jupyter REPL settings.
     pub jupyter: Option<JupyterContent>,
 }
@@ -360,6 +416,45 @@ pub struct ScrollbarContent {
     pub axes: Option<ScrollbarAxesContent>,
 }
 
+/// Minimap related settings
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct MinimapContent {
+    /// When to show the minimap in the editor.
+    ///
+    /// Default: never
+    pub show: Option<ShowMinimap>,
+    /// The maximum width of the minimap, in pixels.
+    ///
+    /// Default: 100.0
+    pub width: Option<f32>,
+    /// Whether to show git diff indicators in the minimap.
+    ///
+    /// Default: true
+    pub git_diff: Option<bool>,
+    /// Whether to highlight buffer search results in the minimap.
+    ///
+    /// Default: true
+    pub search_results: Option<bool>,
+    /// Whether to highlight diagnostics in the minimap.
+    ///
+    /// Default: true
+    pub diagnostics: Option<bool>,
+}
+
+/// Sticky scroll related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// Whether to pin the first lines of the scopes enclosing the top of the viewport,
+    /// such as functions, impls or classes, while scrolling through them.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The maximum number of scope lines pinned at the top of the viewport.
+    ///
+    /// Default: 5
+    pub max_lines: Option<usize>,
+}
+
 /// Forcefully enable or disable the scrollbar for each axis
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct ScrollbarAxesContent {
@@ -5482,6 +5482,180 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_select_larger_smaller_syntax_node_via_lsp(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.expand_selection_source = Some(ExpandSelectionSource::LanguageServer);
+            });
+        });
+    });
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    fn selection_range(line: u32, ranges: &[(u32, u32)]) -> lsp::SelectionRange {
+        ranges
+            .iter()
+            .rev()
+            .fold(None, |parent, (start, end)| {
+                Some(lsp::SelectionRange {
+                    range: lsp::Range::new(
+                        lsp::Position::new(line, *start),
+                        lsp::Position::new(line, *end),
+                    ),
+                    parent: parent.map(Box::new),
+                })
+            })
+            .unwrap()
+    }
+
+    let mut requests =
+        cx.handle_request::<lsp::request::SelectionRangeRequest, _, _>(|_, params, _| async move {
+            Ok(Some(
+                params
+                    .positions
+                    .into_iter()
+                    .map(|position| match position.line {
+                        1 => selection_range(1, &[(16, 19), (16, 24), (12, 25)]),
+                        _ => selection_range(position.line, &[(16, 21), (12, 22)]),
+                    })
+                    .collect(),
+            ))
+        });
+
+    cx.set_state(indoc! {"
+        fn main() {
+            let a = foo(oneˇ, two);
+            let b = bar(thˇree);
+        }
+    "});
+
+    cx.update_editor(|editor, cx| editor.select_larger_syntax_node(&SelectLargerSyntaxNode, cx));
+    requests.next().await;
+    cx.executor().run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = foo(«oneˇ», two);
+            let b = bar(«threeˇ»);
+        }
+    "});
+
+    cx.update_editor(|editor, cx| editor.select_larger_syntax_node(&SelectLargerSyntaxNode, cx));
+    requests.next().await;
+    cx.executor().run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = foo(«one, twoˇ»);
+            let b = «bar(three)ˇ»;
+        }
+    "});
+
+    // Once the server has no larger range for a selection, fall back to syntax nodes.
+    cx.update_editor(|editor, cx| editor.select_larger_syntax_node(&SelectLargerSyntaxNode, cx));
+    requests.next().await;
+    cx.executor().run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = «foo(one, two)ˇ»;
+            «let b = bar(three);ˇ»
+        }
+    "});
+
+    cx.update_editor(|editor, cx| editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = foo(«one, twoˇ»);
+            let b = «bar(three)ˇ»;
+        }
+    "});
+}
+
+#[gpui::test]
+async fn test_inline_values(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            inline_value_provider: Some(lsp::OneOf::Left(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state(indoc! {"
+        fn main() {
+            let a = 1;
+            let b = a + 1;ˇ
+        }
+    "});
+
+    let mut requests =
+        cx.handle_request::<lsp::request::InlineValueRequest, _, _>(|_, _, _| async move {
+            Ok(Some(vec![
+                lsp::InlineValue::Text(lsp::InlineValueText {
+                    range: lsp::Range::new(lsp::Position::new(1, 8), lsp::Position::new(1, 9)),
+                    text: "a = 1".to_string(),
+                }),
+                lsp::InlineValue::Text(lsp::InlineValueText {
+                    range: lsp::Range::new(lsp::Position::new(2, 8), lsp::Position::new(2, 9)),
+                    text: "b = 2".to_string(),
+                }),
+                lsp::InlineValue::EvaluatableExpression(lsp::InlineValueEvaluatableExpression {
+                    range: lsp::Range::new(lsp::Position::new(2, 12), lsp::Position::new(2, 13)),
+                    expression: None,
+                }),
+            ]))
+        });
+    cx.update_editor(|editor, cx| editor.show_inline_values(&ShowInlineValues, cx));
+    requests.next().await;
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.display_text(cx),
+            "fn main() {\n    let a = 1;  a = 1\n    let b = a + 1;  b = 2\n}\n"
+        );
+    });
+
+    // Values from other sources are shown alongside the ones from the language server.
+    enum Evaluation {}
+    cx.update_editor(|editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        editor.set_inline_values::<Evaluation>(
+            [(snapshot.anchor_before(Point::new(2, 4)), "2\n".to_string())],
+            cx,
+        );
+        assert_eq!(
+            editor.display_text(cx),
+            "fn main() {\n    let a = 1;  a = 1\n    let b = a + 1;  b = 2  2\n}\n"
+        );
+
+        editor.clear_inline_values::<Evaluation>(cx);
+        assert_eq!(
+            editor.display_text(cx),
+            "fn main() {\n    let a = 1;  a = 1\n    let b = a + 1;  b = 2\n}\n"
+        );
+    });
+
+    // Editing the buffer discards all inline values.
+    cx.simulate_keystroke("x");
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.display_text(cx),
+            "fn main() {\n    let a = 1;\n    let b = a + 1;x\n}\n"
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_autoindent(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -14923,6 +15097,56 @@ pub(crate) fn update_test_project_settings(
     });
 }
 
+#[gpui::test]
+async fn test_file_operation_edits_open_once_per_project(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/root", json!({ "main.rs": "mod utils;\n" }))
+        .await;
+    let project = Project::test(fs, ["/root".as_ref()], cx).await;
+    let workspaces = [
+        cx.add_window(|cx| Workspace::test_new(project.clone(), cx)),
+        cx.add_window(|cx| Workspace::test_new(project.clone(), cx)),
+    ];
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/root/main.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    // Every workspace of the project is notified about the edits requested for a rename.
+    project.update(cx, |_, cx| {
+        let range = buffer.read(cx).anchor_before(4)..buffer.read(cx).anchor_after(9);
+        cx.emit(project::Event::FileOperationEditsProposed {
+            title: "Rename utils.rs".to_string(),
+            edits: project::FileOperationEdits {
+                buffer_edits: vec![(buffer.clone(), vec![(range, "helpers".to_string())])],
+                resource_operations: Vec::new(),
+            },
+        });
+    });
+    cx.run_until_parked();
+
+    let proposed_changes_editors = workspaces
+        .iter()
+        .map(|workspace| {
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.items_of_type::<ProposedChangesEditor>(cx).count()
+                })
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        proposed_changes_editors.iter().sum::<usize>(),
+        1,
+        "The edits should be opened for review in a single workspace, \
+        but got {proposed_changes_editors:?}"
+    );
+}
+
 pub(crate) fn init_test(cx: &mut TestAppContext, f: fn(&mut AllLanguageSettingsContent)) {
     cx.update(|cx| {
         assets::Assets.load_test_fonts(cx);
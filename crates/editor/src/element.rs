@@ -6,7 +6,7 @@ use crate::{
     },
     editor_settings::{
         CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, ScrollBeyondLastLine,
-        ShowScrollbar,
+        ShowMinimap, ShowScrollbar,
     },
     git::blame::{CommitDetails, GitBlame},
     hover_popover::{
@@ -16,7 +16,7 @@ use crate::{
     hunk_status,
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition, MouseContextMenu},
-    scroll::{axis_pair, scroll_amount::ScrollAmount, AxisPair},
+    scroll::{axis_pair, scroll_amount::ScrollAmount, Autoscroll, AxisPair},
     BlockId, ChunkReplacement, CursorShape, CustomBlockId, DisplayPoint, DisplayRow,
     DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode, EditorSettings,
     EditorSnapshot, EditorStyle, ExpandExcerpts, FocusedBlock, GutterDimensions, HalfPageDown,
@@ -36,8 +36,9 @@ use gpui::{
     Element, ElementInputHandler, Entity, FontId, GlobalElementId, Hitbox, Hsla,
     InteractiveElement, IntoElement, Length, ModifiersChangedEvent, MouseButton, MouseDownEvent,
     MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta, ScrollWheelEvent,
-    ShapedLine, SharedString, Size, StatefulInteractiveElement, Style, Styled, Subscription,
-    TextRun, TextStyleRefinement, View, ViewContext, WeakView, WindowContext,
+    ShapedLine, SharedString, Size, StatefulInteractiveElement, Style, Styled, StyledText,
+    Subscription, TextRun, TextStyle, TextStyleRefinement, View, ViewContext, WeakView,
+    WindowContext,
 };
 use itertools::Itertools;
 use language::{
@@ -367,6 +368,8 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_relative_line_numbers);
         register_action(view, cx, Editor::toggle_indent_guides);
         register_action(view, cx, Editor::toggle_inlay_hints);
+        register_action(view, cx, Editor::show_inline_values);
+        register_action(view, cx, Editor::hide_inline_values);
         register_action(view, cx, Editor::toggle_inline_completions);
         register_action(view, cx, hover_popover::hover);
         register_action(view, cx, Editor::reveal_in_finder);
@@ -1375,6 +1378,213 @@ impl EditorElement {
         axis_pair(horizontal_scrollbar, vertical_scrollbar)
     }
 
+    /// Returns the width taken by the minimap on the right side of the editor, if it's shown.
+    fn minimap_width(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Bounds<Pixels>,
+        gutter_width: Pixels,
+        line_height: Pixels,
+        cx: &WindowContext,
+    ) -> Pixels {
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+        let show_minimap = snapshot.mode == EditorMode::Full
+            && self.editor.read(cx).show_scrollbars
+            && match minimap_settings.show {
+                ShowMinimap::Auto => {
+                    let line_count = snapshot.buffer_snapshot.max_point().row + 1;
+                    line_count as f32 > bounds.size.height / line_height
+                }
+                ShowMinimap::Always => true,
+                ShowMinimap::Never => false,
+            };
+
+        if show_minimap {
+            px(minimap_settings.width)
+                .min((bounds.size.width - gutter_width) / 3.)
+                .max(Pixels::ZERO)
+        } else {
+            Pixels::ZERO
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn layout_minimap(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Option<Bounds<Pixels>>,
+        scroll_position: gpui::Point<f32>,
+        height_in_lines: f32,
+        max_scroll_top: f32,
+        cx: &mut WindowContext,
+    ) -> Option<MinimapLayout> {
+        let bounds = bounds?;
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+        let max_row = snapshot.max_point().row();
+        let total_rows = max_row.next_row().as_f32();
+        let minimap_rows = bounds.size.height / MinimapLayout::LINE_HEIGHT;
+
+        // When the file doesn't fit in the minimap, the minimap scrolls along with the editor, so
+        // that its top and bottom are reached at the same time as the editor's.
+        let scroll_top = if total_rows > minimap_rows && max_scroll_top > 0. {
+            (scroll_position.y / max_scroll_top).clamp(0., 1.) * (total_rows - minimap_rows)
+        } else {
+            0.
+        };
+
+        let mut layout = MinimapLayout {
+            hitbox: cx.insert_hitbox(bounds, false),
+            scroll_top,
+            visible_rows: scroll_position.y..scroll_position.y + height_in_lines,
+            total_rows,
+            minimap_rows,
+            max_scroll_top,
+            markers: Vec::new(),
+            blocks: Vec::new(),
+        };
+
+        let start_row = DisplayRow(scroll_top.floor() as u32);
+        let end_row = DisplayRow(cmp::min(
+            (scroll_top + minimap_rows).ceil() as u32,
+            max_row.next_row().0,
+        ));
+        if start_row >= end_row {
+            return Some(layout);
+        }
+
+        let start_point = DisplayPoint::new(start_row, 0).to_point(&snapshot.display_snapshot);
+        let end_point = if end_row > max_row {
+            snapshot.buffer_snapshot.max_point()
+        } else {
+            DisplayPoint::new(end_row, 0).to_point(&snapshot.display_snapshot)
+        };
+        let theme = cx.theme().clone();
+
+        if minimap_settings.search_results {
+            let color = theme.colors().search_match_background;
+            let row_ranges = self
+                .editor
+                .read(cx)
+                .background_highlight_row_ranges::<BufferSearchHighlights>(
+                    snapshot.buffer_snapshot.anchor_before(start_point)
+                        ..snapshot.buffer_snapshot.anchor_after(end_point),
+                    &snapshot.display_snapshot,
+                    MinimapLayout::MAX_SEARCH_RESULTS,
+                );
+            for range in row_ranges {
+                let rows = range.start().row()..range.end().row().next_row();
+                layout
+                    .markers
+                    .push(fill(layout.bounds_for_rows(rows, None), color));
+            }
+        }
+
+        if minimap_settings.diagnostics {
+            let diagnostics = snapshot
+                .buffer_snapshot
+                .diagnostics_in_range::<_, Point>(start_point..end_point, false)
+                // We want to sort by severity, in order to paint the most severe diagnostics last.
+                .sorted_by_key(|diagnostic| std::cmp::Reverse(diagnostic.diagnostic.severity));
+            for diagnostic in diagnostics {
+                let color = match diagnostic.diagnostic.severity {
+                    DiagnosticSeverity::ERROR => theme.status().error,
+                    DiagnosticSeverity::WARNING => theme.status().warning,
+                    DiagnosticSeverity::INFORMATION => theme.status().info,
+                    _ => theme.status().hint,
+                };
+                let start_row = diagnostic
+                    .range
+                    .start
+                    .to_display_point(&snapshot.display_snapshot)
+                    .row();
+                let end_row = diagnostic
+                    .range
+                    .end
+                    .to_display_point(&snapshot.display_snapshot)
+                    .row();
+                layout.markers.push(fill(
+                    layout.bounds_for_rows(start_row..end_row.next_row(), None),
+                    color.opacity(0.4),
+                ));
+            }
+        }
+
+        if minimap_settings.git_diff {
+            for hunk in snapshot
+                .diff_map
+                .diff_hunks_in_range(start_point..end_point, &snapshot.buffer_snapshot)
+            {
+                let start_row = MultiBufferPoint::new(hunk.row_range.start.0, 0)
+                    .to_display_point(&snapshot.display_snapshot)
+                    .row();
+                let mut end_row = MultiBufferPoint::new(hunk.row_range.end.0, 0)
+                    .to_display_point(&snapshot.display_snapshot)
+                    .row();
+                if end_row == start_row {
+                    end_row = end_row.next_row();
+                }
+                let color = match hunk_status(&hunk) {
+                    DiffHunkStatus::Added => theme.status().created,
+                    DiffHunkStatus::Modified => theme.status().modified,
+                    DiffHunkStatus::Removed => theme.status().deleted,
+                };
+                layout.markers.push(fill(
+                    layout.bounds_for_rows(start_row..end_row, Some(MinimapLayout::HUNK_WIDTH)),
+                    color,
+                ));
+            }
+        }
+
+        // Draw every run of non-whitespace characters as a block in the color of its syntax highlight.
+        let text_left = bounds.left() + MinimapLayout::HUNK_WIDTH * 2.;
+        let max_columns = ((bounds.right() - text_left) / MinimapLayout::COLUMN_WIDTH) as usize;
+        let default_color = self.style.text.color;
+        let mut row = start_row;
+        let mut column = 0;
+        for chunk in snapshot.highlighted_chunks(start_row..end_row, true, &self.style) {
+            let color = chunk
+                .style
+                .and_then(|style| style.color)
+                .unwrap_or(default_color)
+                .opacity(0.6);
+            for (ix, line) in chunk.text.split('\n').enumerate() {
+                if ix > 0 {
+                    row.0 += 1;
+                    column = 0;
+                }
+
+                let mut run_start = None;
+                for character in line.chars().chain(iter::once(' ')) {
+                    if !character.is_whitespace() {
+                        run_start.get_or_insert(column);
+                    } else if let Some(run_start) = run_start.take() {
+                        if run_start < max_columns {
+                            let y = layout.y_for_row(row.as_f32());
+                            let block_bounds = Bounds::from_corners(
+                                point(
+                                    text_left + MinimapLayout::COLUMN_WIDTH * run_start as f32,
+                                    y,
+                                ),
+                                point(
+                                    text_left
+                                        + MinimapLayout::COLUMN_WIDTH
+                                            * column.min(max_columns) as f32,
+                                    y + MinimapLayout::BLOCK_HEIGHT,
+                                ),
+                            );
+                            layout.blocks.push(fill(block_bounds, color));
+                        }
+                    }
+                    column += 1;
+                }
+                // Don't count the whitespace appended to close the last run.
+                column -= 1;
+            }
+        }
+
+        Some(layout)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn prepaint_crease_toggles(
         &self,
@@ -3013,6 +3223,121 @@ impl EditorElement {
         header
     }
 
+    /// Lays out the first lines of the scopes enclosing the top of the viewport, pinned above the
+    /// text. Clicking one of them scrolls to its scope.
+    #[allow(clippy::too_many_arguments)]
+    fn layout_sticky_scopes(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<f32>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        content_origin: gpui::Point<Pixels>,
+        line_height: Pixels,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        text_hitbox: &Hitbox,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        let sticky_scroll = EditorSettings::get_global(cx).sticky_scroll;
+        if !sticky_scroll.enabled
+            || snapshot.mode != EditorMode::Full
+            || snapshot.buffer_snapshot.show_headers()
+        {
+            return Vec::new();
+        }
+
+        let scopes = snapshot.sticky_scopes(
+            DisplayRow(scroll_position.y as u32),
+            sticky_scroll.max_lines,
+        );
+        let colors = cx.theme().colors();
+        let background_color = colors.editor_background;
+        let border_color = colors.border_variant;
+        let line_number_style = TextStyle {
+            color: colors.editor_line_number,
+            ..self.style.text.clone()
+        };
+        let width = gutter_hitbox.size.width + text_hitbox.size.width;
+        let text_left = content_origin.x - gutter_hitbox.left() - scroll_pixel_position.x;
+
+        let scope_count = scopes.len();
+        let mut elements = Vec::with_capacity(scope_count);
+        for (ix, scope) in scopes.into_iter().enumerate() {
+            let mut text = String::new();
+            let mut highlights = Vec::new();
+            for chunk in snapshot.highlighted_chunks(
+                scope.start_row..scope.start_row.next_row(),
+                true,
+                &self.style,
+            ) {
+                let chunk_text = chunk.text.split('\n').next().unwrap_or_default();
+                if let Some(style) = chunk.style {
+                    highlights.push((text.len()..text.len() + chunk_text.len(), style));
+                }
+                text.push_str(chunk_text);
+                if chunk_text.len() < chunk.text.len() {
+                    break;
+                }
+            }
+            let line_number =
+                (scope.range.start.to_point(&snapshot.buffer_snapshot).row + 1).to_string();
+
+            let editor = self.editor.clone();
+            let target = scope.range.start;
+            let mut element = div()
+                .relative()
+                .w(width)
+                .h(line_height)
+                .overflow_hidden()
+                .bg(background_color)
+                .when(ix + 1 == scope_count, |div| {
+                    div.border_b_1().border_color(border_color)
+                })
+                .cursor_pointer()
+                .child(
+                    h_flex()
+                        .absolute()
+                        .left_0()
+                        .w(gutter_hitbox.size.width - gutter_dimensions.right_padding)
+                        .h_full()
+                        .justify_end()
+                        .child(
+                            StyledText::new(line_number).with_highlights(&line_number_style, None),
+                        ),
+                )
+                .child(
+                    div()
+                        .absolute()
+                        .left(text_left)
+                        .child(StyledText::new(text).with_highlights(&self.style.text, highlights)),
+                )
+                .on_mouse_down(MouseButton::Left, move |_, cx| {
+                    cx.stop_propagation();
+                    cx.focus(&editor.focus_handle(cx));
+                    editor.update(cx, |editor, cx| {
+                        // Keep the scopes enclosing this one pinned above it.
+                        editor.change_selections(Some(Autoscroll::top_relative(ix)), cx, |s| {
+                            s.select_anchor_ranges([target..target])
+                        });
+                    });
+                })
+                .into_any_element();
+
+            // Push the line up as the end of its scope scrolls past it.
+            let top = (ix as f32).min(scope.end_row.as_f32() - scroll_position.y) * line_height;
+            element.prepaint_as_root(
+                point(gutter_hitbox.left(), text_hitbox.top() + top),
+                size(
+                    AvailableSpace::Definite(width),
+                    AvailableSpace::Definite(line_height),
+                ),
+                cx,
+            );
+            elements.push(element);
+        }
+        elements
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_context_menu(
         &self,
@@ -4605,6 +4930,119 @@ impl EditorElement {
         }
     }
 
+    fn paint_minimap(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        let Some(minimap) = layout.minimap.take() else {
+            return;
+        };
+        let hitbox = minimap.hitbox.clone();
+        let thumb_bounds = minimap.thumb_bounds();
+        let is_dragging = self
+            .editor
+            .read(cx)
+            .scroll_manager
+            .minimap_drag_offset()
+            .is_some();
+
+        let colors = cx.theme().colors();
+        let background_color = colors.editor_background;
+        let thumb_color = if is_dragging || hitbox.is_hovered(cx) {
+            colors.scrollbar_thumb_hover_background
+        } else {
+            colors.scrollbar_thumb_background
+        };
+        let thumb_border_color = colors.scrollbar_thumb_border;
+
+        cx.paint_layer(hitbox.bounds, |cx| {
+            cx.paint_quad(fill(hitbox.bounds, background_color));
+            for marker in minimap.markers.iter().chain(&minimap.blocks) {
+                cx.paint_quad(marker.clone());
+            }
+            cx.paint_quad(quad(
+                thumb_bounds,
+                Corners::default(),
+                thumb_color.opacity(0.5),
+                Edges::all(ScrollbarLayout::BORDER_WIDTH),
+                thumb_border_color,
+            ));
+        });
+
+        cx.set_cursor_style(CursorStyle::Arrow, &hitbox);
+
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            let minimap = minimap.clone();
+            move |event: &MouseMoveEvent, phase, cx| {
+                if phase == DispatchPhase::Capture {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    let Some(drag_offset) = editor.scroll_manager.minimap_drag_offset() else {
+                        return;
+                    };
+                    if event.pressed_button == Some(MouseButton::Left) {
+                        let thumb_top = event.position.y - drag_offset - minimap.hitbox.top();
+                        let mut position = editor.scroll_position(cx);
+                        position.y = minimap.scroll_top_for_thumb_top(thumb_top);
+                        editor.set_scroll_position(position, cx);
+                        cx.stop_propagation();
+                    } else {
+                        editor.scroll_manager.set_minimap_drag_offset(None, cx);
+                    }
+                })
+            }
+        });
+
+        if is_dragging {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_minimap_drag_offset(None, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        } else {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |event: &MouseDownEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture
+                        || event.button != MouseButton::Left
+                        || !hitbox.is_hovered(cx)
+                    {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        let y = event.position.y;
+                        let drag_offset =
+                            if (thumb_bounds.top()..thumb_bounds.bottom()).contains(&y) {
+                                y - thumb_bounds.top()
+                            } else {
+                                // Center the viewport on the clicked row, and keep dragging it from there.
+                                let visible_row_count =
+                                    minimap.visible_rows.end - minimap.visible_rows.start;
+                                let mut position = editor.scroll_position(cx);
+                                position.y = (minimap.row_for_y(y) - visible_row_count / 2.)
+                                    .clamp(0., minimap.max_scroll_top);
+                                editor.set_scroll_position(position, cx);
+                                thumb_bounds.size.height / 2.
+                            };
+                        editor
+                            .scroll_manager
+                            .set_minimap_drag_offset(Some(drag_offset), cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
     fn collect_fast_scrollbar_markers(
         &self,
         layout: &EditorLayout,
@@ -6023,7 +6461,14 @@ impl Element for EditorElement {
                         self.max_line_number_width(&snapshot, cx),
                         cx,
                     );
-                    let text_width = bounds.size.width - gutter_dimensions.width;
+                    let minimap_width = self.minimap_width(
+                        &snapshot,
+                        bounds,
+                        gutter_dimensions.width,
+                        line_height,
+                        cx,
+                    );
+                    let text_width = bounds.size.width - gutter_dimensions.width - minimap_width;
 
                     let editor_width = text_width - gutter_dimensions.margin - em_width;
 
@@ -6076,8 +6521,16 @@ impl Element for EditorElement {
                     let content_origin =
                         text_hitbox.origin + point(gutter_dimensions.margin, Pixels::ZERO);
 
-                    let scrollbar_bounds =
-                        Bounds::from_corners(content_origin, bounds.bottom_right());
+                    let scrollbar_bounds = Bounds::from_corners(
+                        content_origin,
+                        point(bounds.right() - minimap_width, bounds.bottom()),
+                    );
+                    let minimap_bounds = (minimap_width > Pixels::ZERO).then(|| {
+                        Bounds::from_corners(
+                            point(bounds.right() - minimap_width, bounds.top()),
+                            bounds.bottom_right(),
+                        )
+                    });
 
                     let height_in_lines = scrollbar_bounds.size.height / line_height;
 
@@ -6423,6 +6876,20 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let sticky_scopes = cx.with_element_namespace("sticky_scopes", |cx| {
+                        self.layout_sticky_scopes(
+                            &snapshot,
+                            scroll_position,
+                            scroll_pixel_position,
+                            content_origin,
+                            line_height,
+                            &gutter_dimensions,
+                            &gutter_hitbox,
+                            &text_hitbox,
+                            cx,
+                        )
+                    });
+
                     let scroll_max = point(
                         ((scroll_width - scrollbar_bounds.size.width) / em_width).max(0.0),
                         max_scroll_top,
@@ -6502,6 +6969,14 @@ impl Element for EditorElement {
                         non_visible_cursors,
                         cx,
                     );
+                    let minimap = self.layout_minimap(
+                        &snapshot,
+                        minimap_bounds,
+                        scroll_position,
+                        height_in_lines,
+                        max_scroll_top,
+                        cx,
+                    );
 
                     let gutter_settings = EditorSettings::get_global(cx).gutter;
 
@@ -6730,6 +7205,7 @@ impl Element for EditorElement {
                         display_hunks,
                         content_origin,
                         scrollbars_layout,
+                        minimap,
                         active_rows,
                         highlighted_rows,
                         highlighted_ranges,
@@ -6752,6 +7228,7 @@ impl Element for EditorElement {
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scopes,
                     }
                 })
             })
@@ -6834,12 +7311,23 @@ impl Element for EditorElement {
                         });
                     }
 
+                    if !layout.sticky_scopes.is_empty() {
+                        cx.with_element_namespace("sticky_scopes", |cx| {
+                            // Paint the innermost scopes first, so that outer scopes that are
+                            // pushed up slide under the ones above them.
+                            for sticky_scope in layout.sticky_scopes.iter_mut().rev() {
+                                sticky_scope.paint(cx);
+                            }
+                        });
+                    }
+
                     cx.with_element_namespace("blocks", |cx| {
                         if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
                             sticky_header.paint(cx)
                         }
                     });
 
+                    self.paint_minimap(layout, cx);
                     self.paint_scrollbars(layout, cx);
                     self.paint_inline_completion_popover(layout, cx);
                     self.paint_mouse_context_menu(layout, cx);
@@ -6920,6 +7408,7 @@ pub struct EditorLayout {
     gutter_hitbox: Hitbox,
     content_origin: gpui::Point<Pixels>,
     scrollbars_layout: AxisPair<Option<ScrollbarLayout>>,
+    minimap: Option<MinimapLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
@@ -6947,6 +7436,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scopes: Vec<AnyElement>,
 }
 
 impl EditorLayout {
@@ -7084,6 +7574,74 @@ impl ScrollbarLayout {
     }
 }
 
+#[derive(Clone)]
+struct MinimapLayout {
+    hitbox: Hitbox,
+    /// The display row shown at the top of the minimap.
+    scroll_top: f32,
+    /// The display rows visible in the editor, which are covered by the minimap's thumb.
+    visible_rows: Range<f32>,
+    total_rows: f32,
+    /// The number of display rows that fit in the minimap.
+    minimap_rows: f32,
+    max_scroll_top: f32,
+    markers: Vec<PaintQuad>,
+    blocks: Vec<PaintQuad>,
+}
+
+impl MinimapLayout {
+    const LINE_HEIGHT: Pixels = px(3.0);
+    const BLOCK_HEIGHT: Pixels = px(2.0);
+    const COLUMN_WIDTH: Pixels = px(1.0);
+    const HUNK_WIDTH: Pixels = px(2.0);
+    const MAX_SEARCH_RESULTS: usize = 10_000;
+
+    fn y_for_row(&self, row: f32) -> Pixels {
+        self.hitbox.top() + (row - self.scroll_top) * Self::LINE_HEIGHT
+    }
+
+    fn row_for_y(&self, y: Pixels) -> f32 {
+        self.scroll_top + (y - self.hitbox.top()) / Self::LINE_HEIGHT
+    }
+
+    /// The bounds of the given rows, spanning the whole minimap unless a width is given.
+    fn bounds_for_rows(&self, rows: Range<DisplayRow>, width: Option<Pixels>) -> Bounds<Pixels> {
+        let left = self.hitbox.left();
+        Bounds::from_corners(
+            point(left, self.y_for_row(rows.start.as_f32())),
+            point(
+                width.map_or(self.hitbox.right(), |width| left + width),
+                self.y_for_row(rows.end.as_f32()),
+            ),
+        )
+    }
+
+    fn thumb_bounds(&self) -> Bounds<Pixels> {
+        Bounds::from_corners(
+            point(self.hitbox.left(), self.y_for_row(self.visible_rows.start)),
+            point(self.hitbox.right(), self.y_for_row(self.visible_rows.end)),
+        )
+    }
+
+    /// The editor scroll position that places the top of the thumb at the given distance from
+    /// the top of the minimap.
+    fn scroll_top_for_thumb_top(&self, thumb_top: Pixels) -> f32 {
+        // The thumb moves by the scroll delta, minus the delta of the minimap's own scroll.
+        let rows_per_scroll_row = if self.total_rows > self.minimap_rows && self.max_scroll_top > 0.
+        {
+            1. - (self.total_rows - self.minimap_rows) / self.max_scroll_top
+        } else {
+            1.
+        };
+        let scroll_top = if rows_per_scroll_row > 0. {
+            thumb_top / Self::LINE_HEIGHT / rows_per_scroll_row
+        } else {
+            thumb_top / self.hitbox.size.height * self.max_scroll_top
+        };
+        scroll_top.clamp(0., self.max_scroll_top)
+    }
+}
+
 struct CreaseTrailerLayout {
     element: AnyElement,
     bounds: Bounds<Pixels>,
@@ -7556,12 +8114,14 @@ mod tests {
     use super::*;
     use crate::{
         display_map::{BlockPlacement, BlockProperties},
+        editor_settings::{MinimapContent, StickyScrollContent},
         editor_tests::{init_test, update_test_language_settings},
         Editor, MultiBuffer,
     };
     use gpui::{TestAppContext, VisualTestContext};
-    use language::language_settings;
+    use language::{language_settings, Buffer, Language, LanguageConfig};
     use log::info;
+    use settings::SettingsStore;
     use similar::DiffableStr;
     use std::num::NonZeroU32;
     use util::test::sample_text;
@@ -7852,6 +8412,133 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_minimap_layout(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        show: Some(ShowMinimap::Always),
+                        width: Some(80.),
+                        git_diff: None,
+                        search_results: None,
+                        diagnostics: None,
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(400, 6, 'a'), cx);
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        let minimap = state.minimap.as_ref().unwrap();
+        assert_eq!(minimap.hitbox.size.width, px(80.));
+        assert_eq!(minimap.hitbox.right(), state.hitbox.right());
+        assert_eq!(state.text_hitbox.right(), minimap.hitbox.left());
+        assert!(!minimap.blocks.is_empty());
+
+        // The file doesn't fit in the minimap, so the thumb starts at the top, and dragging it
+        // down by its own height moves the editor further than that.
+        assert_eq!(minimap.scroll_top, 0.);
+        let thumb_bounds = minimap.thumb_bounds();
+        assert_eq!(thumb_bounds.top(), minimap.hitbox.top());
+        assert_eq!(minimap.scroll_top_for_thumb_top(Pixels::ZERO), 0.);
+        let visible_row_count = minimap.visible_rows.end - minimap.visible_rows.start;
+        assert!(minimap.scroll_top_for_thumb_top(thumb_bounds.size.height) > visible_row_count);
+        assert_eq!(
+            minimap.scroll_top_for_thumb_top(minimap.hitbox.size.height),
+            minimap.max_scroll_top
+        );
+
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap.as_mut().unwrap().show = Some(ShowMinimap::Never);
+                });
+            });
+        });
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style)
+        });
+        assert!(state.minimap.is_none());
+        assert_eq!(state.text_hitbox.right(), state.hitbox.right());
+    }
+
+    #[gpui::test]
+    fn test_sticky_scopes(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.sticky_scroll = Some(StickyScrollContent {
+                        enabled: Some(true),
+                        max_lines: None,
+                    });
+                });
+            });
+        });
+
+        let language = Arc::new(
+            Language::new(
+                LanguageConfig::default(),
+                Some(tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_outline_query(
+                r#"
+                (mod_item "mod" @context name: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+            )
+            .unwrap(),
+        );
+        let body = "        let x = 1;\n".repeat(40);
+        let text = format!("mod outer {{\n    fn inner() {{\n{body}    }}\n}}\n");
+        let window = cx.add_window(|cx| {
+            let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+            let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+        cx.executor().run_until_parked();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        assert!(state.sticky_scopes.is_empty());
+
+        // Inside the function body, both the module and the function are pinned.
+        editor.update(cx, |editor, cx| {
+            editor.set_scroll_position(point(0., 10.), cx);
+        });
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        assert_eq!(state.sticky_scopes.len(), 2);
+
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.sticky_scroll.as_mut().unwrap().max_lines = Some(1);
+                });
+            });
+        });
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style)
+        });
+        assert_eq!(state.sticky_scopes.len(), 1);
+    }
+
     #[gpui::test]
     fn test_all_invisibles_drawing(cx: &mut TestAppContext) {
         const TAB_SIZE: u32 = 4;
@@ -0,0 +1,165 @@
+use std::any::TypeId;
+
+use collections::{BTreeMap, HashMap};
+use futures::future;
+use gpui::{Task, ViewContext};
+use multi_buffer::{Anchor, MultiBufferRow, ToPoint as _};
+use project::InlineValueKind;
+use util::{post_inc, ResultExt};
+
+use crate::{
+    actions::{HideInlineValues, ShowInlineValues},
+    display_map::Inlay,
+    Editor, InlayId,
+};
+
+/// The inlays displaying values at the end of lines, such as the results of the last evaluation
+/// of a REPL cell, grouped by the type of the source that provided them.
+///
+/// Inline values are only valid for the text they were computed for, so all of them are removed
+/// as soon as the buffer is edited.
+#[derive(Default)]
+pub(super) struct InlineValues {
+    inlays_by_source: HashMap<TypeId, Vec<InlayId>>,
+    fetch_task: Option<Task<Option<()>>>,
+}
+
+impl InlineValues {
+    pub(super) fn is_empty(&self) -> bool {
+        self.inlays_by_source.is_empty()
+    }
+}
+
+/// The source of inline values reported by language servers.
+enum LspInlineValues {}
+
+impl Editor {
+    /// Displays the given values at the end of the lines containing their positions, replacing the
+    /// values previously set for the same source type. Values on the same line are joined together.
+    pub fn set_inline_values<T: 'static>(
+        &mut self,
+        values: impl IntoIterator<Item = (Anchor, String)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut values_by_row = BTreeMap::<MultiBufferRow, Vec<String>>::new();
+        for (position, value) in values {
+            let value = value.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+            if value.is_empty() {
+                continue;
+            }
+            let row = MultiBufferRow(position.to_point(&snapshot).row);
+            values_by_row.entry(row).or_default().push(value);
+        }
+
+        let mut to_insert = Vec::with_capacity(values_by_row.len());
+        for (row, values) in values_by_row {
+            let position = snapshot.anchor_after(snapshot.clip_point(
+                text::Point::new(row.0, snapshot.line_len(row)),
+                text::Bias::Left,
+            ));
+            to_insert.push(Inlay::inline_value(
+                post_inc(&mut self.next_inlay_id),
+                position,
+                format!("  {}", values.join(", ")),
+            ));
+        }
+
+        let to_remove = if to_insert.is_empty() {
+            self.inline_values
+                .inlays_by_source
+                .remove(&TypeId::of::<T>())
+                .unwrap_or_default()
+        } else {
+            self.inline_values
+                .inlays_by_source
+                .insert(
+                    TypeId::of::<T>(),
+                    to_insert.iter().map(|inlay| inlay.id).collect(),
+                )
+                .unwrap_or_default()
+        };
+        if !to_remove.is_empty() || !to_insert.is_empty() {
+            self.splice_inlays(to_remove, to_insert, cx);
+        }
+    }
+
+    /// Removes the values set for the given source type.
+    pub fn clear_inline_values<T: 'static>(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(to_remove) = self
+            .inline_values
+            .inlays_by_source
+            .remove(&TypeId::of::<T>())
+        {
+            self.splice_inlays(to_remove, Vec::new(), cx);
+        }
+    }
+
+    pub(super) fn clear_all_inline_values(&mut self, cx: &mut ViewContext<Self>) {
+        self.inline_values.fetch_task = None;
+        if self.inline_values.is_empty() {
+            return;
+        }
+        let to_remove = self
+            .inline_values
+            .inlays_by_source
+            .drain()
+            .flat_map(|(_, inlays)| inlays)
+            .collect();
+        self.splice_inlays(to_remove, Vec::new(), cx);
+    }
+
+    /// Requests inline values for the visible part of every buffer from its language server.
+    ///
+    /// There is no debug session to resolve variable lookups and evaluatable expressions against,
+    /// so only the values that the server reports as text are displayed.
+    pub fn show_inline_values(&mut self, _: &ShowInlineValues, cx: &mut ViewContext<Self>) {
+        let Some(provider) = self.semantics_provider.clone() else {
+            return;
+        };
+
+        let mut requests = Vec::new();
+        for (excerpt_id, (buffer, _, range)) in self.excerpts_for_inlay_hints_query(None, cx) {
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            let range =
+                buffer_snapshot.anchor_before(range.start)..buffer_snapshot.anchor_after(range.end);
+            let stopped_location = range.end..range.end;
+            if let Some(request) = provider.inline_values(&buffer, range, stopped_location, cx) {
+                requests.push(async move { (excerpt_id, request.await) });
+            }
+        }
+        if requests.is_empty() {
+            return;
+        }
+
+        self.inline_values.fetch_task = Some(cx.spawn(|editor, mut cx| async move {
+            let responses = future::join_all(requests).await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    let snapshot = editor.buffer.read(cx).snapshot(cx);
+                    let mut values = Vec::new();
+                    for (excerpt_id, response) in responses {
+                        let Some(inline_values) = response.log_err() else {
+                            continue;
+                        };
+                        for inline_value in inline_values {
+                            let InlineValueKind::Text(text) = inline_value.kind else {
+                                continue;
+                            };
+                            if let Some(position) =
+                                snapshot.anchor_in_excerpt(excerpt_id, inline_value.range.end)
+                            {
+                                values.push((position, text));
+                            }
+                        }
+                    }
+                    editor.set_inline_values::<LspInlineValues>(values, cx);
+                })
+                .ok()
+        }));
+    }
+
+    pub fn hide_inline_values(&mut self, _: &HideInlineValues, cx: &mut ViewContext<Self>) {
+        self.clear_all_inline_values(cx);
+    }
+}
@@ -297,6 +297,7 @@ impl FollowableItem for Editor {
                 }
                 _ => false,
             },
+            _ => false,
         }
     }
 
@@ -306,7 +307,9 @@ impl FollowableItem for Editor {
         message: update_view::Variant,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<()>> {
-        let update_view::Variant::Editor(message) = message;
+        let update_view::Variant::Editor(message) = message else {
+            return Task::ready(Err(anyhow!("received a non-editor update for an editor")));
+        };
         let project = project.clone();
         cx.spawn(|this, mut cx| async move {
             update_editor_from_message(this, project, message, &mut cx).await
@@ -718,6 +721,13 @@ impl Item for Editor {
         self.buffer().read(cx).read(cx).has_conflict()
     }
 
+    fn has_unsaved_edits(&self, cx: &AppContext) -> bool {
+        self.buffer().read(cx).all_buffers().iter().any(|buffer| {
+            let buffer = buffer.read(cx);
+            buffer.has_edits_since(buffer.saved_version())
+        })
+    }
+
     fn can_save(&self, cx: &AppContext) -> bool {
         let buffer = &self.buffer().read(cx);
         if let Some(buffer) = buffer.as_singleton() {
@@ -1316,17 +1326,8 @@ impl SearchableItem for Editor {
         query: &SearchQuery,
         cx: &mut ViewContext<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
-        let text: Cow<_> = if text.len() == 1 {
-            text.first().cloned().unwrap().into()
-        } else {
-            let joined_chunks = text.join("");
-            joined_chunks.into()
-        };
-
-        if let Some(replacement) = query.replacement_for(&text) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        if let Some(replacement) = replacement_for_match(&snapshot, identifier, query) {
             self.transact(cx, |this, cx| {
                 this.edit([(identifier.clone(), Arc::from(&*replacement))], cx);
             });
@@ -1338,19 +1339,10 @@ impl SearchableItem for Editor {
         query: &SearchQuery,
         cx: &mut ViewContext<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
+        let snapshot = self.buffer.read(cx).snapshot(cx);
         let mut edits = vec![];
         for m in matches {
-            let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
-            let text: Cow<_> = if text.len() == 1 {
-                text.first().cloned().unwrap().into()
-            } else {
-                let joined_chunks = text.join("");
-                joined_chunks.into()
-            };
-
-            if let Some(replacement) = query.replacement_for(&text) {
+            if let Some(replacement) = replacement_for_match(&snapshot, m, query) {
                 edits.push((m.clone(), Arc::from(&*replacement)));
             }
         }
@@ -1500,6 +1492,25 @@ impl SearchableItem for Editor {
     }
 }
 
+/// Returns the text that replaces the given match of the query, if the query has a replacement.
+pub fn replacement_for_match(
+    snapshot: &MultiBufferSnapshot,
+    range: &Range<Anchor>,
+    query: &SearchQuery,
+) -> Option<String> {
+    if query.is_structural() {
+        // Structural replacements are expanded from the syntax tree around the match.
+        let excerpt = snapshot.excerpt_containing(range.clone())?;
+        let buffer_range = excerpt.map_range_to_buffer(range.to_offset(snapshot));
+        return query.structural_replacement_for(excerpt.buffer(), buffer_range);
+    }
+
+    let text = snapshot.text_for_range(range.clone()).collect::<Cow<str>>();
+    query
+        .replacement_for(&text)
+        .map(|replacement| replacement.into_owned())
+}
+
 pub fn active_match_index(
     ranges: &[Range<Anchor>],
     cursor: &Anchor,
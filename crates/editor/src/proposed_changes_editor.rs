@@ -1,13 +1,15 @@
 use crate::{ApplyAllDiffHunks, Editor, EditorEvent, SemanticsProvider};
 use collections::HashSet;
 use futures::{channel::mpsc, future::join_all};
-use gpui::{AppContext, EventEmitter, FocusableView, Model, Render, Subscription, Task, View};
+use gpui::{
+    AppContext, EventEmitter, FocusableView, Model, PromptLevel, Render, Subscription, Task, View,
+};
 use language::{Buffer, BufferEvent, Capability};
 use multi_buffer::{ExcerptRange, MultiBuffer};
-use project::{buffer_store::BufferChangeSet, Project};
+use project::{buffer_store::BufferChangeSet, FileOperationEdits, Project};
 use smol::stream::StreamExt;
 use std::{any::TypeId, ops::Range, rc::Rc, time::Duration};
-use text::ToOffset;
+use text::{Point, ToOffset, ToPoint};
 use ui::{prelude::*, ButtonLike, KeyBinding};
 use workspace::{
     searchable::SearchableItemHandle, Item, ItemHandle as _, ToolbarItemEvent, ToolbarItemLocation,
@@ -135,6 +137,84 @@ impl ProposedChangesEditor {
         this
     }
 
+    /// Opens the edits that language servers requested for a file operation, so that they can
+    /// be reviewed before being applied. The files they want created, renamed or deleted are
+    /// only touched once the user confirms it.
+    pub fn open_file_operation_edits(
+        workspace: &mut Workspace,
+        title: String,
+        edits: &FileOperationEdits,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        if !edits.resource_operations.is_empty() {
+            let operations = edits.resource_operations.clone();
+            let detail = operations
+                .iter()
+                .map(describe_resource_operation)
+                .collect::<Vec<_>>()
+                .join("\n");
+            let answer = cx.prompt(
+                PromptLevel::Info,
+                &format!("{title}: language servers also requested these file changes"),
+                Some(&detail),
+                &["Apply", "Skip"],
+            );
+            let lsp_store = project.read(cx).lsp_store();
+            cx.spawn(|_, mut cx| async move {
+                if answer.await? == 0 {
+                    lsp_store
+                        .update(&mut cx, |lsp_store, cx| {
+                            lsp_store.apply_resource_operations(operations, cx)
+                        })?
+                        .await?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        }
+
+        if edits.buffer_edits.iter().all(|(_, edits)| edits.is_empty()) {
+            return;
+        }
+
+        let locations = edits
+            .buffer_edits
+            .iter()
+            .map(|(buffer, edits)| {
+                let snapshot = buffer.read(cx);
+                let mut ranges: Vec<Range<Point>> = Vec::new();
+                for (range, _) in edits {
+                    let mut range = range.to_point(snapshot);
+                    range.start.column = 0;
+                    range.end.column = snapshot.line_len(range.end.row);
+                    match ranges.last_mut() {
+                        Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+                        _ => ranges.push(range),
+                    }
+                }
+                ProposedChangeLocation {
+                    buffer: buffer.clone(),
+                    ranges,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let editor = cx.new_view(|cx| {
+            let editor = ProposedChangesEditor::new(title, locations, Some(project), cx);
+            for (buffer, edits) in &edits.buffer_edits {
+                if let Some(branch) = editor.branch_buffer_for_base(buffer) {
+                    branch.update(cx, |branch, cx| {
+                        branch.edit(edits.iter().cloned(), None, cx)
+                    });
+                }
+            }
+            editor.recalculate_all_buffer_diffs();
+            editor
+        });
+        workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+    }
+
     pub fn branch_buffer_for_base(&self, base_buffer: &Model<Buffer>) -> Option<Model<Buffer>> {
         self.buffer_entries.iter().find_map(|entry| {
             if &entry.base == base_buffer {
@@ -268,6 +348,20 @@ impl ProposedChangesEditor {
     }
 }
 
+fn describe_resource_operation(operation: &lsp::ResourceOp) -> String {
+    match operation {
+        lsp::ResourceOp::Create(create) => format!("Create {}", create.uri.path()),
+        lsp::ResourceOp::Rename(rename) => {
+            format!(
+                "Rename {} to {}",
+                rename.old_uri.path(),
+                rename.new_uri.path()
+            )
+        }
+        lsp::ResourceOp::Delete(delete) => format!("Delete {}", delete.uri.path()),
+    }
+}
+
 impl Render for ProposedChangesEditor {
     fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
         div()
@@ -502,4 +596,34 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
     ) -> Option<Task<gpui::Result<project::ProjectTransaction>>> {
         None
     }
+
+    fn selection_ranges(
+        &self,
+        buffer: &Model<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut AppContext,
+    ) -> Option<Task<gpui::Result<Vec<Vec<Range<text::Anchor>>>>>> {
+        let buffer = self.to_base(&buffer, &positions, cx)?;
+        self.0.selection_ranges(&buffer, positions, cx)
+    }
+
+    fn inline_values(
+        &self,
+        buffer: &Model<Buffer>,
+        range: Range<text::Anchor>,
+        stopped_location: Range<text::Anchor>,
+        cx: &mut AppContext,
+    ) -> Option<Task<gpui::Result<Vec<project::InlineValue>>>> {
+        let buffer = self.to_base(
+            &buffer,
+            &[
+                range.start,
+                range.end,
+                stopped_location.start,
+                stopped_location.end,
+            ],
+            cx,
+        )?;
+        self.0.inline_values(&buffer, range, stopped_location, cx)
+    }
 }
@@ -0,0 +1,77 @@
+use std::{mem, ops::Range};
+
+use gpui::{px, HighlightStyle, StrikethroughStyle, ViewContext};
+use multi_buffer::Anchor;
+use theme::ActiveTheme as _;
+use util::post_inc;
+
+use crate::{display_map::Inlay, hover_links::InlayHighlight, Editor, InlayId};
+
+/// The inlays previewing pending replacements, such as the ones of a project search, right after
+/// the text they would replace.
+#[derive(Default)]
+pub(super) struct ReplacementPreviews {
+    inlays: Vec<InlayId>,
+}
+
+impl Editor {
+    /// Previews replacing each range with the given text, displayed as a diff: the replaced text is
+    /// struck through and followed by its replacement. Replaces the previous previews.
+    pub fn set_replacement_previews(
+        &mut self,
+        previews: impl IntoIterator<Item = (Range<Anchor>, String)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let to_remove = mem::take(&mut self.replacement_previews.inlays);
+        self.clear_highlights::<ReplacementPreviews>(cx);
+
+        let mut replaced_ranges = Vec::new();
+        let mut to_insert = Vec::new();
+        for (range, replacement) in previews {
+            if !replacement.is_empty() {
+                to_insert.push(Inlay::replacement_preview(
+                    post_inc(&mut self.next_inlay_id),
+                    range.end,
+                    replacement,
+                ));
+            }
+            replaced_ranges.push(range);
+        }
+
+        let inlay_highlights = to_insert
+            .iter()
+            .map(|inlay| InlayHighlight {
+                inlay: inlay.id,
+                inlay_position: inlay.position,
+                range: 0..inlay.text.len(),
+            })
+            .collect::<Vec<_>>();
+        self.replacement_previews.inlays = to_insert.iter().map(|inlay| inlay.id).collect();
+        self.splice_inlays(to_remove, to_insert, cx);
+
+        let status = cx.theme().status();
+        let replaced_style = HighlightStyle {
+            background_color: Some(status.deleted_background),
+            strikethrough: Some(StrikethroughStyle {
+                thickness: px(1.),
+                color: Some(status.deleted),
+            }),
+            ..HighlightStyle::default()
+        };
+        let replacement_style = HighlightStyle {
+            color: Some(status.created),
+            background_color: Some(status.created_background),
+            ..HighlightStyle::default()
+        };
+        if !replaced_ranges.is_empty() {
+            self.highlight_text::<ReplacementPreviews>(replaced_ranges, replaced_style, cx);
+        }
+        if !inlay_highlights.is_empty() {
+            self.highlight_inlays::<ReplacementPreviews>(inlay_highlights, replacement_style, cx);
+        }
+    }
+
+    pub fn clear_replacement_previews(&mut self, cx: &mut ViewContext<Self>) {
+        self.set_replacement_previews(None, cx);
+    }
+}
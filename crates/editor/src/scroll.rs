@@ -183,6 +183,8 @@ pub struct ScrollManager {
     show_scrollbars: bool,
     hide_scrollbar_task: Option<Task<()>>,
     dragging_scrollbar: AxisPair<bool>,
+    /// While the minimap viewport is being dragged, the distance from its top to the mouse.
+    minimap_drag_offset: Option<Pixels>,
     visible_line_count: Option<f32>,
     forbid_vertical_scroll: bool,
 }
@@ -197,6 +199,7 @@ impl ScrollManager {
             show_scrollbars: true,
             hide_scrollbar_task: None,
             dragging_scrollbar: axis_pair(false, false),
+            minimap_drag_offset: None,
             last_autoscroll: None,
             visible_line_count: None,
             forbid_vertical_scroll: false,
@@ -371,6 +374,19 @@ impl ScrollManager {
         cx.notify();
     }
 
+    pub fn minimap_drag_offset(&self) -> Option<Pixels> {
+        self.minimap_drag_offset
+    }
+
+    pub fn set_minimap_drag_offset(
+        &mut self,
+        offset: Option<Pixels>,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        self.minimap_drag_offset = offset;
+        cx.notify();
+    }
+
     pub fn clamp_scroll_left(&mut self, max: f32) -> bool {
         if max < self.anchor.offset.x {
             self.anchor.offset.x = max;
@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use collections::HashMap;
+use gpui::{Model, Task, ViewContext};
+use language::Buffer;
+use multi_buffer::{ExcerptId, ToOffset};
+use text::BufferId;
+use util::ResultExt;
+
+use crate::{larger_syntax_node_range, Editor};
+
+/// A selection that is expanded through the language server: its index among the editor's
+/// selections, the excerpt it lives in and the position sent to the server.
+type SelectionPosition = (usize, ExcerptId, text::Anchor);
+
+/// Expands every selection to the next larger range reported by the language server's
+/// `textDocument/selectionRange`, issuing one request per buffer.
+///
+/// Selections for which the server reports no larger range fall back to tree-sitter syntax nodes.
+/// Returns `None` when no request could be made, in which case the caller should expand the
+/// selections synchronously using the syntax tree.
+pub(super) fn select_larger_selection_ranges(
+    editor: &mut Editor,
+    cx: &mut ViewContext<Editor>,
+) -> Option<Task<Option<()>>> {
+    let provider = editor.semantics_provider.clone()?;
+    let old_selections = editor.selections.all::<usize>(cx).into_boxed_slice();
+
+    let mut positions_by_buffer =
+        HashMap::<BufferId, (Model<Buffer>, Vec<SelectionPosition>)>::default();
+    {
+        let multi_buffer = editor.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        for (ix, selection) in old_selections.iter().enumerate() {
+            let start = snapshot.anchor_before(selection.start);
+            let Some(buffer_id) = start.buffer_id else {
+                continue;
+            };
+            let Some(buffer) = multi_buffer.buffer(buffer_id) else {
+                continue;
+            };
+            positions_by_buffer
+                .entry(buffer_id)
+                .or_insert_with(|| (buffer, Vec::new()))
+                .1
+                .push((ix, start.excerpt_id, start.text_anchor));
+        }
+    }
+
+    let mut requests = Vec::new();
+    for (buffer, positions) in positions_by_buffer.into_values() {
+        let anchors = positions.iter().map(|(_, _, anchor)| *anchor).collect();
+        if let Some(request) = provider.selection_ranges(&buffer, anchors, cx) {
+            requests.push(async move { (positions, request.await) });
+        }
+    }
+    if requests.is_empty() {
+        return None;
+    }
+
+    Some(cx.spawn(|editor, mut cx| async move {
+        let responses = futures::future::join_all(requests).await;
+        editor
+            .update(&mut cx, |editor, cx| {
+                // The selections moved while we were waiting for the server, drop the response.
+                let current_ranges = editor
+                    .selections
+                    .all::<usize>(cx)
+                    .into_iter()
+                    .map(|selection| selection.range());
+                if !current_ranges.eq(old_selections.iter().map(|selection| selection.range())) {
+                    return;
+                }
+
+                let display_map = editor.display_map.update(cx, |map, cx| map.snapshot(cx));
+                let snapshot = &display_map.buffer_snapshot;
+                let mut lsp_ranges: Vec<Option<Range<usize>>> = vec![None; old_selections.len()];
+                for (positions, response) in responses {
+                    let Some(chains) = response.log_err() else {
+                        continue;
+                    };
+                    for ((ix, excerpt_id, _), chain) in positions.into_iter().zip(chains) {
+                        let old_range = old_selections[ix].range();
+                        lsp_ranges[ix] = chain.into_iter().find_map(|range| {
+                            let start = snapshot
+                                .anchor_in_excerpt(excerpt_id, range.start)?
+                                .to_offset(snapshot);
+                            let end = snapshot
+                                .anchor_in_excerpt(excerpt_id, range.end)?
+                                .to_offset(snapshot);
+                            let contains_old_range = start <= old_range.start
+                                && end >= old_range.end
+                                && (start..end) != old_range;
+                            let is_folded = display_map.intersects_fold(start)
+                                || display_map.intersects_fold(end);
+                            (contains_old_range && !is_folded).then_some(start..end)
+                        });
+                    }
+                }
+
+                let new_ranges = old_selections
+                    .iter()
+                    .zip(lsp_ranges)
+                    .map(|(selection, lsp_range)| {
+                        lsp_range.unwrap_or_else(|| {
+                            larger_syntax_node_range(&display_map, selection.range())
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                editor.select_larger_ranges(old_selections, new_ranges, cx);
+            })
+            .ok()
+    }))
+}
@@ -323,6 +323,8 @@ fn initialize_panels(prompt_builder: Arc<PromptBuilder>, cx: &mut ViewContext<Wo
         );
         let assistant_panel =
             assistant::AssistantPanel::load(workspace_handle.clone(), prompt_builder, cx.clone());
+        let ports_panel =
+            recent_projects::ports_panel::PortsPanel::load(workspace_handle.clone(), cx.clone());
 
         let (
             project_panel,
@@ -332,6 +334,7 @@ fn initialize_panels(prompt_builder: Arc<PromptBuilder>, cx: &mut ViewContext<Wo
             chat_panel,
             notification_panel,
             assistant_panel,
+            ports_panel,
         ) = futures::try_join!(
             project_panel,
             outline_panel,
@@ -340,6 +343,7 @@ fn initialize_panels(prompt_builder: Arc<PromptBuilder>, cx: &mut ViewContext<Wo
             chat_panel,
             notification_panel,
             assistant_panel,
+            ports_panel,
         )?;
 
         workspace_handle.update(&mut cx, |workspace, cx| {
@@ -349,7 +353,8 @@ fn initialize_panels(prompt_builder: Arc<PromptBuilder>, cx: &mut ViewContext<Wo
             workspace.add_panel(channels_panel, cx);
             workspace.add_panel(chat_panel, cx);
             workspace.add_panel(notification_panel, cx);
-            workspace.add_panel(assistant_panel, cx)
+            workspace.add_panel(assistant_panel, cx);
+            workspace.add_panel(ports_panel, cx)
         })?;
 
         let git_ui_enabled = git_ui_feature_flag.await;
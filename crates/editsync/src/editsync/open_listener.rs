@@ -380,7 +380,8 @@ async fn open_workspaces(
                         SshSettings::get_global(cx)
                             .connection_options_for(ssh.host, ssh.port, ssh.user)
                     });
-                    if let Ok(connection_options) = connection_options {
+                    if let Ok(mut connection_options) = connection_options {
+                        connection_options.container = ssh.container;
                         cx.spawn(|mut cx| async move {
                             open_ssh_project(
                                 connection_options,
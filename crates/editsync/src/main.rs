@@ -495,6 +495,7 @@ fn main() {
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
         toolchain_selector::init(cx);
+        layout_selector::init(cx);
         theme_selector::init(cx);
         language_tools::init(cx);
         call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -852,10 +853,11 @@ async fn restore_or_create_workspace(
                     task.await?;
                 }
                 SerialieditsyncWorkspaceLocation::Ssh(ssh) => {
-                    let connection_options = cx.update(|cx| {
+                    let mut connection_options = cx.update(|cx| {
                         SshSettings::get_global(cx)
                             .connection_options_for(ssh.host, ssh.port, ssh.user)
                     })?;
+                    connection_options.container = ssh.container;
                     let app_state = app_state.clone();
                     cx.spawn(move |mut cx| async move {
                         recent_projects::open_ssh_project(
@@ -2,6 +2,7 @@
 mod file_finder_tests;
 
 pub mod file_finder_settings;
+mod frecency;
 mod new_path_prompt;
 mod open_path_prompt;
 
@@ -12,7 +13,8 @@ use collections::HashMap;
 use editor::{scroll::Autoscroll, Bias, Editor};
 use file_finder_settings::{FileFinderSettings, FileFinderWidth};
 use file_icons::FileIcons;
-use fuzzy::{CharBag, PathMatch, PathMatchCandidate};
+use frecency::{Frecency, FrecentPathMatchCandidateSet};
+use fuzzy::{CharBag, PathMatch, PathMatchCandidate, PathSymbolMatch, StringMatchCandidate};
 use gpui::{
     actions, Action, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle,
     FocusableView, KeyContext, Model, Modifiers, ModifiersChangedEvent, ParentElement, Render,
@@ -20,7 +22,7 @@ use gpui::{
 };
 use new_path_prompt::NewPathPrompt;
 use open_path_prompt::OpenPathPrompt;
-use picker::{Picker, PickerDelegate};
+use picker::{Picker, PickerDelegate, PickerSettings, PreviewLocation};
 use project::{PathMatchCandidateSet, Project, ProjectPath, WorktreeId};
 use settings::Settings;
 use std::{
@@ -30,8 +32,9 @@ use std::{
         atomic::{self, AtomicBool},
         Arc,
     },
+    time::Duration,
 };
-use text::Point;
+use text::{Point, PointUtf16, ToPoint, Unclipped};
 use ui::{
     prelude::*, ContextMenu, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, PopoverMenu,
     PopoverMenuHandle,
@@ -61,10 +64,12 @@ pub struct FileFinder {
 
 pub fn init_settings(cx: &mut AppContext) {
     FileFinderSettings::register(cx);
+    PickerSettings::register(cx);
 }
 
 pub fn init(cx: &mut AppContext) {
     init_settings(cx);
+    frecency::init(cx);
     cx.observe_new_views(FileFinder::register).detach();
     cx.observe_new_views(NewPathPrompt::register).detach();
     cx.observe_new_views(OpenPathPrompt::register).detach();
@@ -137,6 +142,7 @@ impl FileFinder {
                 .update(&mut cx, |workspace, cx| {
                     let project = workspace.project().clone();
                     let weak_workspace = cx.view().downgrade();
+                    let frecency = Frecency::load(workspace, cx);
                     workspace.toggle_modal(cx, |cx| {
                         let delegate = FileFinderDelegate::new(
                             cx.view().downgrade(),
@@ -145,6 +151,7 @@ impl FileFinder {
                             currently_opened_path,
                             history_items.collect(),
                             separate_history,
+                            frecency,
                             cx,
                         );
 
@@ -237,6 +244,7 @@ impl FileFinder {
                             worktree_id: WorktreeId::from_usize(m.0.worktree_id),
                             path: m.0.path.clone(),
                         },
+                        Match::Symbol(m) => m.project_path(),
                     };
                     let open_task = workspace.update(cx, move |workspace, cx| {
                         workspace.split_path_preview(path, false, Some(split_direction), cx)
@@ -308,6 +316,7 @@ pub struct FileFinderDelegate {
     cancel_flag: Arc<AtomicBool>,
     history_items: Vec<FoundPath>,
     separate_history: bool,
+    frecency: Frecency,
     first_update: bool,
     popover_menu_handle: PopoverMenuHandle<ContextMenu>,
     focus_handle: FocusHandle,
@@ -359,6 +368,7 @@ enum Match {
         panel_match: Option<ProjectPanelOrdMatch>,
     },
     Search(ProjectPanelOrdMatch),
+    Symbol(SymbolMatch),
 }
 
 impl Match {
@@ -366,6 +376,7 @@ impl Match {
         match self {
             Match::History { path, .. } => &path.project.path,
             Match::Search(panel_match) => &panel_match.0.path,
+            Match::Symbol(symbol_match) => &symbol_match.0.path_match.path,
         }
     }
 
@@ -373,10 +384,52 @@ impl Match {
         match self {
             Match::History { panel_match, .. } => panel_match.as_ref(),
             Match::Search(panel_match) => Some(&panel_match),
+            Match::Symbol(_) => None,
         }
     }
 }
 
+/// A symbol matched by a `path@symbol` or a `#symbol` query, along with the position to jump to.
+#[derive(Debug, Clone)]
+struct SymbolMatch(PathSymbolMatch, SymbolPosition);
+
+#[derive(Debug, Clone, Copy)]
+enum SymbolPosition {
+    /// The start of an item of the file's outline.
+    Outline(Point),
+    /// The start of a symbol reported by a language server.
+    LanguageServer(Unclipped<PointUtf16>),
+}
+
+impl SymbolMatch {
+    fn project_path(&self) -> ProjectPath {
+        ProjectPath {
+            worktree_id: WorktreeId::from_usize(self.0.path_match.worktree_id),
+            path: self.0.path_match.path.clone(),
+        }
+    }
+}
+
+impl PartialEq for SymbolMatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for SymbolMatch {}
+
+impl PartialOrd for SymbolMatch {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SymbolMatch {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
 impl Matches {
     fn len(&self) -> usize {
         self.matches.len()
@@ -596,14 +649,83 @@ pub enum Event {
     Dismissed,
 }
 
+const MAX_SYMBOL_SEARCH_FILES: usize = 5;
+
+/// Symbol searches parse files or query language servers, so they wait for the user to stop
+/// typing.
+const SYMBOL_SEARCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
 #[derive(Debug, Clone)]
 struct FileSearchQuery {
     raw_query: String,
     file_query_end: Option<usize>,
     path_position: PathWithPosition,
+    symbol_query: Option<SymbolQuery>,
+}
+
+/// The symbol part of `path@symbol` and `#symbol` queries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SymbolQuery {
+    /// Symbols of the outlines of the files matching the path part of the query, or of the
+    /// currently opened file when the query has no path part. Outlines are computed from syntax
+    /// trees, so no language server is needed, and an empty query lists every outline item.
+    InFiles(String),
+    /// Symbols of the whole workspace, as reported by language servers.
+    InWorkspace(String),
+}
+
+impl SymbolQuery {
+    fn text(&self) -> &str {
+        match self {
+            SymbolQuery::InFiles(text) | SymbolQuery::InWorkspace(text) => text,
+        }
+    }
 }
 
 impl FileSearchQuery {
+    fn parse(raw_query: &str) -> Self {
+        if let Some(symbol_query) = raw_query.strip_prefix('#') {
+            return Self {
+                raw_query: raw_query.to_owned(),
+                file_query_end: Some(0),
+                path_position: PathWithPosition::from_path(PathBuf::new()),
+                symbol_query: Some(SymbolQuery::InWorkspace(symbol_query.to_owned())),
+            };
+        }
+
+        // Paths may contain `@` too, as in `node_modules/@types/node` or `icon@2x.png`: symbols are
+        // only searched when the whole query matches no path, see `spawn_file_symbol_search`.
+        if let Some((path_query, symbol_query)) = raw_query
+            .rsplit_once('@')
+            .filter(|(_, symbol_query)| !symbol_query.contains(std::path::is_separator))
+        {
+            return Self {
+                raw_query: raw_query.to_owned(),
+                file_query_end: Some(path_query.len()),
+                path_position: PathWithPosition::from_path(PathBuf::from(path_query)),
+                symbol_query: Some(SymbolQuery::InFiles(symbol_query.to_owned())),
+            };
+        }
+
+        Self::parse_path(raw_query)
+    }
+
+    /// Parses the query as a path, ignoring the `@` and `#` symbol syntax.
+    fn parse_path(raw_query: &str) -> Self {
+        let path_position = PathWithPosition::parse_str(raw_query);
+        Self {
+            raw_query: raw_query.to_owned(),
+            file_query_end: if path_position.path.to_str().unwrap_or(raw_query) == raw_query {
+                None
+            } else {
+                // Safe to unwrap as we won't get here when the unwrap in if fails
+                Some(path_position.path.to_str().unwrap().len())
+            },
+            path_position,
+            symbol_query: None,
+        }
+    }
+
     fn path_query(&self) -> &str {
         match self.file_query_end {
             Some(file_path_end) => &self.raw_query[..file_path_end],
@@ -620,6 +742,7 @@ impl FileFinderDelegate {
         currently_opened_path: Option<FoundPath>,
         history_items: Vec<FoundPath>,
         separate_history: bool,
+        frecency: Frecency,
         cx: &mut ViewContext<FileFinder>,
     ) -> Self {
         Self::subscribe_to_updates(&project, cx);
@@ -638,6 +761,7 @@ impl FileFinderDelegate {
             cancel_flag: Arc::new(AtomicBool::new(false)),
             history_items,
             separate_history,
+            frecency,
             first_update: true,
             popover_menu_handle: PopoverMenuHandle::default(),
             focus_handle: cx.focus_handle(),
@@ -658,40 +782,54 @@ impl FileFinderDelegate {
         .detach();
     }
 
-    fn spawn_search(
-        &mut self,
-        query: FileSearchQuery,
-        cx: &mut ViewContext<Picker<Self>>,
-    ) -> Task<()> {
-        let relative_to = self
-            .currently_opened_path
+    fn relative_to(&self) -> Option<Arc<Path>> {
+        self.currently_opened_path
             .as_ref()
-            .map(|found_path| Arc::clone(&found_path.project.path));
+            .map(|found_path| Arc::clone(&found_path.project.path))
+    }
+
+    fn path_candidate_sets(&self, cx: &AppContext) -> Vec<FrecentPathMatchCandidateSet> {
         let worktrees = self
             .project
             .read(cx)
             .visible_worktrees(cx)
             .collect::<Vec<_>>();
         let include_root_name = worktrees.len() > 1;
-        let candidate_sets = worktrees
+        worktrees
             .into_iter()
             .map(|worktree| {
                 let worktree = worktree.read(cx);
-                PathMatchCandidateSet {
-                    snapshot: worktree.snapshot(),
-                    include_ignored: worktree
-                        .root_entry()
-                        .map_or(false, |entry| entry.is_ignored),
-                    include_root_name,
-                    candidates: project::Candidates::Files,
+                FrecentPathMatchCandidateSet {
+                    candidate_set: PathMatchCandidateSet {
+                        snapshot: worktree.snapshot(),
+                        include_ignored: worktree
+                            .root_entry()
+                            .map_or(false, |entry| entry.is_ignored),
+                        include_root_name,
+                        candidates: project::Candidates::Files,
+                    },
+                    score_factors: self.frecency.for_worktree(worktree.id()),
                 }
             })
-            .collect::<Vec<_>>();
+            .collect()
+    }
 
+    /// Cancels the pending search, returning the id and the cancellation flag of a new one.
+    fn start_search(&mut self) -> (usize, Arc<AtomicBool>) {
         let search_id = util::post_inc(&mut self.search_count);
         self.cancel_flag.store(true, atomic::Ordering::Relaxed);
         self.cancel_flag = Arc::new(AtomicBool::new(false));
-        let cancel_flag = self.cancel_flag.clone();
+        (search_id, self.cancel_flag.clone())
+    }
+
+    fn spawn_search(
+        &mut self,
+        query: FileSearchQuery,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Task<()> {
+        let relative_to = self.relative_to();
+        let candidate_sets = self.path_candidate_sets(cx);
+        let (search_id, cancel_flag) = self.start_search();
         cx.spawn(|picker, mut cx| async move {
             let matches = fuzzy::match_path_sets(
                 candidate_sets.as_slice(),
@@ -716,6 +854,236 @@ impl FileFinderDelegate {
         })
     }
 
+    /// Searches the symbols of the files best matching the path part of a `path@symbol` query,
+    /// or of the currently opened file. Queries matching paths, like `icon@2x.png`, are searched
+    /// as paths instead.
+    fn spawn_file_symbol_search(
+        &mut self,
+        query: FileSearchQuery,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Task<()> {
+        let relative_to = self.relative_to();
+        let candidate_sets = self.path_candidate_sets(cx);
+        let currently_opened_path = self.currently_opened_path.clone();
+        let project = self.project.clone();
+        let (search_id, cancel_flag) = self.start_search();
+        cx.spawn(|picker, mut cx| async move {
+            let path_query = FileSearchQuery::parse_path(&query.raw_query);
+            let path_matches = fuzzy::match_path_sets(
+                candidate_sets.as_slice(),
+                path_query.path_query(),
+                relative_to.clone(),
+                false,
+                100,
+                &cancel_flag,
+                cx.background_executor().clone(),
+            )
+            .await;
+            if !path_matches.is_empty() {
+                let did_cancel = cancel_flag.load(atomic::Ordering::Relaxed);
+                let matches = path_matches.into_iter().map(ProjectPanelOrdMatch);
+                picker
+                    .update(&mut cx, |picker, cx| {
+                        picker
+                            .delegate
+                            .set_search_matches(search_id, did_cancel, path_query, matches, cx)
+                    })
+                    .log_err();
+                return;
+            }
+
+            cx.background_executor().timer(SYMBOL_SEARCH_DEBOUNCE).await;
+            if cancel_flag.load(atomic::Ordering::Relaxed) {
+                return;
+            }
+
+            let file_matches = if query.path_query().is_empty() {
+                currently_opened_path
+                    .map(|found_path| PathMatch {
+                        score: 0.,
+                        positions: Vec::new(),
+                        worktree_id: found_path.project.worktree_id.to_usize(),
+                        path: found_path.project.path,
+                        path_prefix: "".into(),
+                        is_dir: false,
+                        distance_to_relative_ancestor: 0,
+                    })
+                    .into_iter()
+                    .collect()
+            } else {
+                fuzzy::match_path_sets(
+                    candidate_sets.as_slice(),
+                    query.path_query(),
+                    relative_to,
+                    false,
+                    MAX_SYMBOL_SEARCH_FILES,
+                    &cancel_flag,
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            if file_matches.is_empty() {
+                picker
+                    .update(&mut cx, |picker, cx| {
+                        picker
+                            .delegate
+                            .set_symbol_matches(search_id, query, Vec::new(), cx)
+                    })
+                    .log_err();
+                return;
+            }
+
+            let symbol_query = query.symbol_query.as_ref().map_or("", SymbolQuery::text);
+            let mut matches = Vec::new();
+            let mut positions = Vec::new();
+            for file_match in file_matches {
+                if cancel_flag.load(atomic::Ordering::Relaxed) {
+                    return;
+                }
+                let project_path = ProjectPath {
+                    worktree_id: WorktreeId::from_usize(file_match.worktree_id),
+                    path: file_match.path.clone(),
+                };
+                let Ok(snapshot) = project.update(&mut cx, |project, cx| {
+                    project.open_parsed_buffer(project_path, cx)
+                }) else {
+                    return;
+                };
+                let Some(snapshot) = snapshot.await.log_err() else {
+                    continue;
+                };
+                let Some(outline) = snapshot.outline(None) else {
+                    continue;
+                };
+                for mut symbol_match in outline
+                    .search(symbol_query, cx.background_executor().clone())
+                    .await
+                {
+                    // Outline searches include the ancestors of the matched items, unscored.
+                    if symbol_query.is_empty() || symbol_match.score > 0. {
+                        let item = &outline.items[symbol_match.candidate_id];
+                        symbol_match.candidate_id = positions.len();
+                        positions.push(item.range.start.to_point(&snapshot));
+                        matches.push((file_match.clone(), symbol_match));
+                    }
+                }
+            }
+
+            let matches = fuzzy::match_path_symbols(matches, 100)
+                .into_iter()
+                .map(|path_symbol_match| {
+                    let position = positions[path_symbol_match.symbol_match.candidate_id];
+                    SymbolMatch(path_symbol_match, SymbolPosition::Outline(position))
+                })
+                .collect();
+            picker
+                .update(&mut cx, |picker, cx| {
+                    picker
+                        .delegate
+                        .set_symbol_matches(search_id, query, matches, cx)
+                })
+                .log_err();
+        })
+    }
+
+    /// Searches the symbols of the workspace for a `#symbol` query.
+    fn spawn_workspace_symbol_search(
+        &mut self,
+        query: FileSearchQuery,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Task<()> {
+        let symbol_query = query
+            .symbol_query
+            .as_ref()
+            .map_or("", SymbolQuery::text)
+            .to_owned();
+        let project = self.project.clone();
+        let (search_id, cancel_flag) = self.start_search();
+        cx.spawn(|picker, mut cx| async move {
+            cx.background_executor().timer(SYMBOL_SEARCH_DEBOUNCE).await;
+            if cancel_flag.load(atomic::Ordering::Relaxed) {
+                return;
+            }
+            let Ok(symbols) =
+                project.update(&mut cx, |project, cx| project.symbols(&symbol_query, cx))
+            else {
+                return;
+            };
+            let symbols = symbols.await.log_err().unwrap_or_default();
+            let candidates = symbols
+                .iter()
+                .enumerate()
+                .map(|(id, symbol)| StringMatchCandidate::new(id, symbol.label.filter_text()))
+                .collect::<Vec<_>>();
+            let symbol_matches = fuzzy::match_strings(
+                &candidates,
+                &symbol_query,
+                false,
+                100,
+                &cancel_flag,
+                cx.background_executor().clone(),
+            )
+            .await;
+
+            picker
+                .update(&mut cx, |picker, cx| {
+                    let project = picker.delegate.project.read(cx);
+                    let matches = fuzzy::match_path_symbols(
+                        symbol_matches
+                            .into_iter()
+                            .filter(|symbol_match| {
+                                let symbol = &symbols[symbol_match.candidate_id];
+                                project
+                                    .worktree_for_id(symbol.path.worktree_id, cx)
+                                    .is_some()
+                            })
+                            .map(|symbol_match| {
+                                let symbol = &symbols[symbol_match.candidate_id];
+                                let path_match = PathMatch {
+                                    score: 0.,
+                                    positions: Vec::new(),
+                                    worktree_id: symbol.path.worktree_id.to_usize(),
+                                    path: symbol.path.path.clone(),
+                                    path_prefix: "".into(),
+                                    is_dir: false,
+                                    distance_to_relative_ancestor: usize::MAX,
+                                };
+                                (path_match, symbol_match)
+                            }),
+                        100,
+                    )
+                    .into_iter()
+                    .map(|path_symbol_match| {
+                        let symbol = &symbols[path_symbol_match.symbol_match.candidate_id];
+                        let position = SymbolPosition::LanguageServer(symbol.range.start);
+                        SymbolMatch(path_symbol_match, position)
+                    })
+                    .collect();
+                    picker
+                        .delegate
+                        .set_symbol_matches(search_id, query, matches, cx)
+                })
+                .log_err();
+        })
+    }
+
+    fn set_symbol_matches(
+        &mut self,
+        search_id: usize,
+        query: FileSearchQuery,
+        matches: Vec<SymbolMatch>,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) {
+        if search_id >= self.latest_search_id {
+            self.latest_search_id = search_id;
+            self.matches.matches = matches.into_iter().map(Match::Symbol).collect();
+            self.selected_index = 0;
+            self.latest_search_query = Some(query);
+            self.latest_search_did_cancel = false;
+            cx.notify();
+        }
+    }
+
     fn set_search_matches(
         &mut self,
         search_id: usize,
@@ -726,11 +1094,11 @@ impl FileFinderDelegate {
     ) {
         if search_id >= self.latest_search_id {
             self.latest_search_id = search_id;
-            let query_changed = Some(query.path_query())
+            let query_changed = Some((query.path_query(), &query.symbol_query))
                 != self
                     .latest_search_query
                     .as_ref()
-                    .map(|query| query.path_query());
+                    .map(|query| (query.path_query(), &query.symbol_query));
             let extend_old_matches = self.latest_search_did_cancel && !query_changed;
 
             let selected_match = if query_changed {
@@ -824,6 +1192,20 @@ impl FileFinderDelegate {
                 self.labels_for_path_match(&path_match)
             }
             Match::Search(path_match) => self.labels_for_path_match(&path_match.0),
+            Match::Symbol(SymbolMatch(path_symbol_match, _)) => {
+                let path_match = &path_symbol_match.path_match;
+                let full_path = [
+                    path_match.path_prefix.as_ref(),
+                    path_match.path.to_string_lossy().as_ref(),
+                ]
+                .join("");
+                (
+                    path_symbol_match.symbol_match.string.clone(),
+                    path_symbol_match.symbol_match.positions.clone(),
+                    full_path,
+                    path_match.positions.clone(),
+                )
+            }
         };
 
         if file_name_positions.is_empty() {
@@ -968,7 +1350,7 @@ impl PickerDelegate for FileFinderDelegate {
     type ListItem = ListItem;
 
     fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
-        "Search project files...".into()
+        "Search project files, file@symbol or #symbol...".into()
     }
 
     fn match_count(&self) -> usize {
@@ -1043,23 +1425,14 @@ impl PickerDelegate for FileFinderDelegate {
             cx.notify();
             Task::ready(())
         } else {
-            let path_position = PathWithPosition::parse_str(&raw_query);
-
-            let query = FileSearchQuery {
-                raw_query: raw_query.trim().to_owned(),
-                file_query_end: if path_position.path.to_str().unwrap_or(raw_query) == raw_query {
-                    None
-                } else {
-                    // Safe to unwrap as we won't get here when the unwrap in if fails
-                    Some(path_position.path.to_str().unwrap().len())
-                },
-                path_position,
-            };
-
-            if Path::new(query.path_query()).is_absolute() {
-                self.lookup_absolute_path(query, cx)
-            } else {
-                self.spawn_search(query, cx)
+            let query = FileSearchQuery::parse(raw_query);
+            match query.symbol_query {
+                Some(SymbolQuery::InFiles(_)) => self.spawn_file_symbol_search(query, cx),
+                Some(SymbolQuery::InWorkspace(_)) => self.spawn_workspace_symbol_search(query, cx),
+                None if Path::new(query.path_query()).is_absolute() => {
+                    self.lookup_absolute_path(query, cx)
+                }
+                None => self.spawn_search(query, cx),
             }
         }
     }
@@ -1139,9 +1512,14 @@ impl PickerDelegate for FileFinderDelegate {
                             },
                             cx,
                         ),
+                        Match::Symbol(m) => split_or_open(workspace, m.project_path(), cx),
                     }
                 });
 
+                let symbol_position = match m {
+                    Match::Symbol(SymbolMatch(_, position)) => Some(*position),
+                    _ => None,
+                };
                 let row = self
                     .latest_search_query
                     .as_ref()
@@ -1157,15 +1535,26 @@ impl PickerDelegate for FileFinderDelegate {
 
                 cx.spawn(|_, mut cx| async move {
                     let item = open_task.await.notify_async_err(&mut cx)?;
-                    if let Some(row) = row {
+                    if symbol_position.is_some() || row.is_some() {
                         if let Some(active_editor) = item.downcast::<Editor>() {
                             active_editor
                                 .downgrade()
                                 .update(&mut cx, |editor, cx| {
                                     let snapshot = editor.snapshot(cx).display_snapshot;
-                                    let point = snapshot
-                                        .buffer_snapshot
-                                        .clip_point(Point::new(row, col), Bias::Left);
+                                    let buffer = &snapshot.buffer_snapshot;
+                                    let point = match symbol_position {
+                                        Some(SymbolPosition::Outline(point)) => {
+                                            buffer.clip_point(point, Bias::Left)
+                                        }
+                                        Some(SymbolPosition::LanguageServer(point)) => buffer
+                                            .offset_to_point(buffer.point_utf16_to_offset(
+                                                buffer.clip_point_utf16(point, Bias::Left),
+                                            )),
+                                        None => buffer.clip_point(
+                                            Point::new(row.unwrap_or(0), col),
+                                            Bias::Left,
+                                        ),
+                                    };
                                     editor.change_selections(Some(Autoscroll::center()), cx, |s| {
                                         s.select_ranges([point..point])
                                     });
@@ -1188,6 +1577,57 @@ impl PickerDelegate for FileFinderDelegate {
             .log_err();
     }
 
+    fn preview_location(
+        &self,
+        ix: usize,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Task<anyhow::Result<PreviewLocation>>> {
+        if !PickerSettings::get_global(cx).preview {
+            return None;
+        }
+
+        let (project_path, symbol_position) = match self.matches.get(ix)? {
+            Match::History { path, .. } => {
+                let worktree_id = path.project.worktree_id;
+                self.project.read(cx).worktree_for_id(worktree_id, cx)?;
+                (path.project.clone(), None)
+            }
+            Match::Search(m) => (
+                ProjectPath {
+                    worktree_id: WorktreeId::from_usize(m.0.worktree_id),
+                    path: m.0.path.clone(),
+                },
+                None,
+            ),
+            Match::Symbol(m) => (m.project_path(), Some(m.1)),
+        };
+        let row = self
+            .latest_search_query
+            .as_ref()
+            .and_then(|query| query.path_position.row)
+            .map(|row| row.saturating_sub(1))
+            .unwrap_or(0);
+
+        let open_buffer = self
+            .project
+            .update(cx, |project, cx| project.open_buffer(project_path, cx));
+        Some(cx.spawn(|_, cx| async move {
+            let buffer = open_buffer.await?;
+            let point = buffer.read_with(&cx, |buffer, _| match symbol_position {
+                Some(SymbolPosition::Outline(point)) => buffer.clip_point(point, Bias::Left),
+                Some(SymbolPosition::LanguageServer(point)) => {
+                    buffer.clip_point_utf16(point, Bias::Left).to_point(buffer)
+                }
+                None => buffer.clip_point(Point::new(row, 0), Bias::Left),
+            })?;
+            let row_start = Point::new(point.row, 0);
+            Ok(PreviewLocation {
+                buffer,
+                range: row_start..row_start,
+            })
+        }))
+    }
+
     fn render_match(
         &self,
         ix: usize,
@@ -1210,12 +1650,20 @@ impl PickerDelegate for FileFinderDelegate {
                 .flex_none()
                 .size(IconSize::Small.rems())
                 .into_any_element(),
+            Match::Symbol(_) => Icon::new(IconName::Code)
+                .color(Color::Muted)
+                .size(IconSize::Small)
+                .into_any_element(),
         };
         let (file_name, file_name_positions, full_path, full_path_positions) =
             self.labels_for_match(path_match, cx, ix);
 
+        let icon_path = match path_match {
+            Match::Symbol(_) => path_match.path().as_ref(),
+            _ => Path::new(&file_name),
+        };
         let file_icon = if settings.file_icons {
-            FileIcons::get_icon(Path::new(&file_name), cx)
+            FileIcons::get_icon(icon_path, cx)
                 .map(Icon::from_path)
                 .map(|icon| icon.color(Color::Muted))
         } else {
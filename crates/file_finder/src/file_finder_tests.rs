@@ -2,10 +2,13 @@ use std::{assert_eq, future::IntoFuture, path::Path, time::Duration};
 
 use super::*;
 use editor::Editor;
+use futures::StreamExt as _;
 use gpui::{Entity, TestAppContext, VisualTestContext};
+use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
 use menu::{Confirm, SelectNext, SelectPrev};
-use project::{RemoveOptions, FS_WATCH_LATENCY};
+use project::{lsp_store::OpenLspBufferHandle, RemoveOptions, FS_WATCH_LATENCY};
 use serde_json::json;
+use settings::SettingsStore;
 use workspace::{AppState, ToggleFileFinder, Workspace};
 
 #[ctor::ctor]
@@ -2007,6 +2010,378 @@ async fn open_queried_buffer(
     history_items
 }
 
+#[gpui::test]
+async fn test_file_symbol_query(cx: &mut TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/src",
+            json!({
+                "editor.rs": "struct Editor;\nfn render() {}\nfn rename() {}\n",
+                "element.rs": "fn paint() {}\nfn render() {}\n",
+            }),
+        )
+        .await;
+
+    // File symbols come from outlines, so no language server is running.
+    let project = Project::test(app_state.fs.clone(), ["/src".as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+    let (picker, workspace, cx) = build_find_picker(project, cx);
+
+    search_symbols(&picker, "editor@rndr", cx).await;
+    picker.update(cx, |picker, _| {
+        assert_eq!(
+            collect_symbol_matches(picker),
+            [(PathBuf::from("editor.rs"), "fn render".to_string())]
+        );
+    });
+
+    search_symbols(&picker, "e@render", cx).await;
+    picker.update(cx, |picker, _| {
+        let symbol_matches = collect_symbol_matches(picker);
+        assert!(
+            symbol_matches
+                .iter()
+                .all(|(_, symbol)| symbol == "fn render"),
+            "Only `render` functions should match, but got: {symbol_matches:?}"
+        );
+        assert_eq!(symbol_matches.len(), 2, "{symbol_matches:?}");
+    });
+
+    search_symbols(&picker, "editor@", cx).await;
+    picker.update(cx, |picker, _| {
+        let mut symbol_matches = collect_symbol_matches(picker);
+        symbol_matches.sort();
+        assert_eq!(
+            symbol_matches,
+            [
+                (PathBuf::from("editor.rs"), "fn render".to_string()),
+                (PathBuf::from("editor.rs"), "fn rename".to_string()),
+                (PathBuf::from("editor.rs"), "struct Editor".to_string()),
+            ],
+            "An empty symbol query should list the whole outline"
+        );
+    });
+
+    search_symbols(&picker, "element@render", cx).await;
+    cx.dispatch_action(Confirm);
+
+    let editor = cx.update(|cx| workspace.read(cx).active_item_as::<Editor>(cx).unwrap());
+    cx.executor().advance_clock(Duration::from_secs(2));
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.title(cx), "element.rs");
+        let selections = editor.selections.all::<Point>(cx);
+        assert_eq!(
+            selections
+                .iter()
+                .map(|selection| selection.start..selection.end)
+                .collect::<Vec<_>>(),
+            [Point::new(1, 0)..Point::new(1, 0)]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_workspace_symbol_query(cx: &mut TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/src",
+            json!({
+                "editor.rs": "struct Editor;\nfn render() {}\n",
+                "element.rs": "fn paint() {}\n",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/src".as_ref()], cx).await;
+    let _handle = start_fake_symbol_server(
+        &project,
+        vec![
+            symbol("Editor", "/src/editor.rs", 0),
+            symbol("render", "/src/editor.rs", 1),
+            symbol("paint", "/src/element.rs", 0),
+        ],
+        cx,
+    )
+    .await;
+    let (picker, workspace, cx) = build_find_picker(project, cx);
+
+    search_symbols(&picker, "#Editor", cx).await;
+    picker.update(cx, |picker, _| {
+        assert_eq!(
+            collect_symbol_matches(picker),
+            [(PathBuf::from("editor.rs"), "Editor".to_string())]
+        );
+    });
+
+    search_symbols(&picker, "#paint", cx).await;
+    cx.dispatch_action(Confirm);
+
+    let editor = cx.update(|cx| workspace.read(cx).active_item_as::<Editor>(cx).unwrap());
+    cx.executor().advance_clock(Duration::from_secs(2));
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.title(cx), "element.rs");
+    });
+}
+
+#[gpui::test]
+async fn test_at_sign_in_paths(cx: &mut TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/src",
+            json!({
+                "icon@2x.png": "",
+                "main.rs": "",
+                "node_modules": {
+                    "@types": {
+                        "node": {
+                            "index.d.ts": "",
+                        },
+                    },
+                },
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/src".as_ref()], cx).await;
+    let (picker, _, cx) = build_find_picker(project, cx);
+
+    picker
+        .update(cx, |picker, cx| {
+            picker
+                .delegate
+                .update_matches("icon@2x.png".to_string(), cx)
+        })
+        .await;
+    picker.update(cx, |picker, _| {
+        assert_eq!(
+            collect_search_matches(picker).search,
+            [PathBuf::from("icon@2x.png")],
+            "Queries matching paths should search files rather than symbols"
+        );
+    });
+
+    picker
+        .update(cx, |picker, cx| {
+            picker.delegate.update_matches("@types".to_string(), cx)
+        })
+        .await;
+    picker.update(cx, |picker, _| {
+        assert_eq!(
+            collect_search_matches(picker).search,
+            [PathBuf::from("node_modules/@types/node/index.d.ts")],
+            "A leading `@` followed by a path should search files rather than symbols"
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_preview_follows_selection(cx: &mut TestAppContext) {
+    let app_state = init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<PickerSettings>(cx, |settings| {
+                settings.preview = Some(true)
+            });
+        });
+    });
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/src",
+            json!({
+                "first.txt": "first",
+                "second.txt": "second",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/src".as_ref()], cx).await;
+    let (picker, _, cx) = build_find_picker(project, cx);
+    picker.update(cx, |picker, cx| {
+        picker.update_matches("txt".to_string(), cx)
+    });
+
+    let previewed_text = |cx: &mut VisualTestContext| {
+        cx.run_until_parked();
+        picker.update(cx, |picker, cx| {
+            let selected_path = picker
+                .delegate
+                .matches
+                .get(picker.delegate.selected_index())
+                .unwrap()
+                .path()
+                .clone();
+            let location = picker.previewed_location(cx).unwrap();
+            let buffer = location.buffer.read(cx);
+            assert_eq!(buffer.file().unwrap().path(), &selected_path);
+            buffer.text()
+        })
+    };
+
+    let first_preview = previewed_text(cx);
+    cx.dispatch_action(SelectNext);
+    let second_preview = previewed_text(cx);
+    assert_ne!(
+        first_preview, second_preview,
+        "Moving the selection should preview the newly selected file"
+    );
+}
+
+#[test]
+fn test_symbol_query_parsing() {
+    let query = FileSearchQuery::parse("editor@render");
+    assert_eq!(query.path_query(), "editor");
+    assert_eq!(
+        query.symbol_query,
+        Some(SymbolQuery::InFiles("render".to_string()))
+    );
+
+    let query = FileSearchQuery::parse("@render");
+    assert_eq!(query.path_query(), "");
+    assert_eq!(
+        query.symbol_query,
+        Some(SymbolQuery::InFiles("render".to_string()))
+    );
+
+    let query = FileSearchQuery::parse("#Editor");
+    assert_eq!(query.path_query(), "");
+    assert_eq!(
+        query.symbol_query,
+        Some(SymbolQuery::InWorkspace("Editor".to_string()))
+    );
+
+    let query = FileSearchQuery::parse("node_modules/@types/node");
+    assert_eq!(query.path_query(), "node_modules/@types/node");
+    assert_eq!(query.symbol_query, None);
+
+    let query = FileSearchQuery::parse("editor.rs:10");
+    assert_eq!(query.path_query(), "editor.rs");
+    assert_eq!(query.path_position.row, Some(10));
+    assert_eq!(query.symbol_query, None);
+}
+
+fn rust_lang() -> Arc<Language> {
+    Arc::new(
+        Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_outline_query(
+            r#"(struct_item
+                "struct" @context
+                name: (_) @name) @item
+            (function_item
+                "fn" @context
+                name: (_) @name) @item"#,
+        )
+        .unwrap(),
+    )
+}
+
+/// Starts a fake language server for Rust files that reports the given workspace symbols. It runs
+/// as long as the returned handle is held.
+async fn start_fake_symbol_server(
+    project: &Model<Project>,
+    symbols: Vec<lsp::SymbolInformation>,
+    cx: &mut TestAppContext,
+) -> OpenLspBufferHandle {
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(Arc::new(Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    )));
+    let mut fake_servers = language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let symbol_path = symbols[0].location.uri.to_file_path().unwrap();
+    let (_, handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(symbol_path, cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::WorkspaceSymbolRequest, _, _>(move |params, cx| {
+        let executor = cx.background_executor().clone();
+        let symbols = symbols.clone();
+        async move {
+            let candidates = symbols
+                .iter()
+                .enumerate()
+                .map(|(id, symbol)| StringMatchCandidate::new(id, &symbol.name))
+                .collect::<Vec<_>>();
+            let matches = fuzzy::match_strings(
+                &candidates,
+                &params.query,
+                false,
+                100,
+                &Default::default(),
+                executor,
+            )
+            .await;
+            Ok(Some(lsp::WorkspaceSymbolResponse::Flat(
+                matches
+                    .into_iter()
+                    .map(|mat| symbols[mat.candidate_id].clone())
+                    .collect(),
+            )))
+        }
+    });
+    handle
+}
+
+fn symbol(name: &str, path: &str, row: u32) -> lsp::SymbolInformation {
+    #[allow(deprecated)]
+    lsp::SymbolInformation {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        deprecated: None,
+        container_name: None,
+        location: lsp::Location::new(
+            lsp::Url::from_file_path(path).unwrap(),
+            lsp::Range::new(lsp::Position::new(row, 0), lsp::Position::new(row, 0)),
+        ),
+    }
+}
+
+/// Updates the matches of a symbol query, which are only searched once the user stops typing.
+async fn search_symbols(
+    picker: &View<Picker<FileFinderDelegate>>,
+    query: &str,
+    cx: &mut VisualTestContext,
+) {
+    let search = picker.update(cx, |picker, cx| {
+        picker.delegate.update_matches(query.to_string(), cx)
+    });
+    cx.run_until_parked();
+    cx.executor().advance_clock(SYMBOL_SEARCH_DEBOUNCE);
+    search.await;
+}
+
 fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
     cx.update(|cx| {
         let state = AppState::test(cx);
@@ -2031,6 +2406,7 @@ fn test_path_position(test_str: &str) -> FileSearchQuery {
             Some(path_position.path.to_str().unwrap().len())
         },
         path_position,
+        symbol_query: None,
     }
 }
 
@@ -2135,11 +2511,28 @@ fn collect_search_matches(picker: &Picker<FileFinderDelegate>) -> SearchEntries
                     .push(Path::new(path_match.0.path_prefix.as_ref()).join(&path_match.0.path));
                 search_entries.search_matches.push(path_match.0.clone());
             }
+            Match::Symbol(_) => panic!("Should have no symbol matches, but got: {m:?}"),
         }
     }
     search_entries
 }
 
+fn collect_symbol_matches(picker: &Picker<FileFinderDelegate>) -> Vec<(PathBuf, String)> {
+    picker
+        .delegate
+        .matches
+        .matches
+        .iter()
+        .map(|m| match m {
+            Match::Symbol(SymbolMatch(path_symbol_match, _)) => (
+                path_symbol_match.path_match.path.to_path_buf(),
+                path_symbol_match.symbol_match.string.clone(),
+            ),
+            _ => panic!("Should have only symbol matches, but got: {m:?}"),
+        })
+        .collect()
+}
+
 #[track_caller]
 fn assert_match_selection(
     finder: &Picker<FileFinderDelegate>,
@@ -2168,6 +2561,7 @@ fn assert_match_at_position(
     let match_file_name = match &match_item {
         Match::History { path, .. } => path.absolute.as_deref().unwrap().file_name(),
         Match::Search(path_match) => path_match.0.path.file_name(),
+        Match::Symbol(symbol_match) => symbol_match.0.path_match.path.file_name(),
     }
     .unwrap()
     .to_string_lossy();
@@ -0,0 +1,250 @@
+//! Ranks files by how often and how recently they were opened in a workspace. Unlike the
+//! navigation history, which only lives as long as the window, open counts are persisted, so
+//! frequently used files are ranked higher right after a restart too.
+
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use collections::HashMap;
+use gpui::{AppContext, ViewContext};
+use project::{PathMatchCandidateSetIter, WorktreeId};
+use util::ResultExt;
+use workspace::Workspace;
+
+use persistence::FILE_FINDER_FRECENCY;
+
+/// The frecency at which a file's matches get half of the maximum boost.
+const HALF_BOOST_FRECENCY: f64 = 100.;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|_: &mut Workspace, cx: &mut ViewContext<Workspace>| {
+        cx.subscribe(&cx.view().clone(), |workspace, _, event, cx| {
+            if let workspace::Event::ItemAdded { item } = event {
+                let Some(workspace_id) = workspace.database_id() else {
+                    return;
+                };
+                let Some(abs_path) = item.project_path(cx).and_then(|project_path| {
+                    workspace
+                        .project()
+                        .read(cx)
+                        .absolute_path(&project_path, cx)
+                }) else {
+                    return;
+                };
+                let opened_at = unix_timestamp(SystemTime::now());
+                db::write_and_log(cx, move || {
+                    FILE_FINDER_FRECENCY.record_open(workspace_id, abs_path, opened_at)
+                });
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// The frecency of the files of a workspace, by worktree, as factors to apply to the scores of
+/// their matches.
+#[derive(Debug, Default)]
+pub(crate) struct Frecency {
+    score_factors: HashMap<WorktreeId, HashMap<Arc<Path>, f64>>,
+}
+
+impl Frecency {
+    pub(crate) fn load(workspace: &Workspace, cx: &AppContext) -> Self {
+        let Some(workspace_id) = workspace.database_id() else {
+            return Self::default();
+        };
+        let opened_files = FILE_FINDER_FRECENCY
+            .opened_files(workspace_id)
+            .log_err()
+            .unwrap_or_default();
+        let now = SystemTime::now();
+        let project = workspace.project().read(cx);
+
+        let mut score_factors = HashMap::<WorktreeId, HashMap<Arc<Path>, f64>>::default();
+        for (abs_path, open_count, last_opened) in opened_files {
+            let Some((worktree, path)) = project.find_worktree(&abs_path, cx) else {
+                continue;
+            };
+            let last_opened = UNIX_EPOCH + Duration::from_secs(last_opened.max(0) as u64);
+            let frecency = frecency(open_count, last_opened, now);
+            score_factors
+                .entry(worktree.read(cx).id())
+                .or_default()
+                .insert(Arc::from(path), score_factor(frecency));
+        }
+        Self { score_factors }
+    }
+
+    pub(crate) fn for_worktree(&self, worktree_id: WorktreeId) -> Arc<HashMap<Arc<Path>, f64>> {
+        Arc::new(
+            self.score_factors
+                .get(&worktree_id)
+                .cloned()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+/// A set of project files whose matches are boosted according to their frecency.
+pub(crate) struct FrecentPathMatchCandidateSet {
+    pub(crate) candidate_set: project::PathMatchCandidateSet,
+    pub(crate) score_factors: Arc<HashMap<Arc<Path>, f64>>,
+}
+
+impl<'a> fuzzy::PathMatchCandidateSet<'a> for FrecentPathMatchCandidateSet {
+    type Candidates = PathMatchCandidateSetIter<'a>;
+
+    fn id(&self) -> usize {
+        self.candidate_set.id()
+    }
+
+    fn len(&self) -> usize {
+        self.candidate_set.len()
+    }
+
+    fn prefix(&self) -> Arc<str> {
+        self.candidate_set.prefix()
+    }
+
+    fn candidates(&'a self, start: usize) -> Self::Candidates {
+        self.candidate_set.candidates(start)
+    }
+
+    fn score_factor(&self, path: &Path) -> f64 {
+        self.score_factors.get(path).copied().unwrap_or(1.)
+    }
+}
+
+/// Weighs the number of times a file was opened by how recently it was last opened.
+fn frecency(open_count: u32, last_opened: SystemTime, now: SystemTime) -> f64 {
+    const HOUR: u64 = 60 * 60;
+    const DAY: u64 = 24 * HOUR;
+
+    let age = now
+        .duration_since(last_opened)
+        .unwrap_or_default()
+        .as_secs();
+    let recency_weight = if age < 4 * HOUR {
+        100.
+    } else if age < DAY {
+        80.
+    } else if age < 3 * DAY {
+        60.
+    } else if age < 7 * DAY {
+        40.
+    } else if age < 30 * DAY {
+        20.
+    } else {
+        10.
+    };
+    open_count as f64 * recency_weight
+}
+
+/// Maps frecencies to score factors between 1 and 2, so that frecency breaks ties between matches
+/// of similar quality without letting frequently opened files outrank much better matches.
+fn score_factor(frecency: f64) -> f64 {
+    1. + frecency / (frecency + HALF_BOOST_FRECENCY)
+}
+
+fn unix_timestamp(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+mod persistence {
+    use anyhow::Result;
+    use std::path::PathBuf;
+
+    use db::{define_connection, query, sqlez_macros::sql};
+    use workspace::{WorkspaceDb, WorkspaceId};
+
+    define_connection! {
+        pub static ref FILE_FINDER_FRECENCY: FileFinderFrecencyDb<WorkspaceDb> =
+            &[sql!(
+                CREATE TABLE file_finder_frecency (
+                    workspace_id INTEGER NOT NULL,
+                    abs_path BLOB NOT NULL,
+                    open_count INTEGER NOT NULL,
+                    last_opened INTEGER NOT NULL,
+
+                    PRIMARY KEY(workspace_id, abs_path),
+                    FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                    ON DELETE CASCADE
+                ) STRICT;
+            )];
+    }
+
+    impl FileFinderFrecencyDb {
+        query! {
+            pub async fn record_open(
+                workspace_id: WorkspaceId,
+                abs_path: PathBuf,
+                opened_at: i64
+            ) -> Result<()> {
+                INSERT INTO file_finder_frecency(workspace_id, abs_path, open_count, last_opened)
+                VALUES (?1, ?2, 1, ?3)
+                ON CONFLICT(workspace_id, abs_path) DO UPDATE SET
+                    open_count = open_count + 1,
+                    last_opened = ?3
+            }
+        }
+
+        query! {
+            pub fn opened_files(workspace_id: WorkspaceId) -> Result<Vec<(PathBuf, u32, i64)>> {
+                SELECT abs_path, open_count, last_opened
+                FROM file_finder_frecency
+                WHERE workspace_id = ?
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_recorded_opens() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let main_rs = PathBuf::from("/project/src/main.rs");
+        let lib_rs = PathBuf::from("/project/src/lib.rs");
+
+        FILE_FINDER_FRECENCY
+            .record_open(workspace_id, main_rs.clone(), 10)
+            .await
+            .unwrap();
+        FILE_FINDER_FRECENCY
+            .record_open(workspace_id, lib_rs.clone(), 20)
+            .await
+            .unwrap();
+        FILE_FINDER_FRECENCY
+            .record_open(workspace_id, main_rs.clone(), 30)
+            .await
+            .unwrap();
+
+        let mut opened_files = FILE_FINDER_FRECENCY.opened_files(workspace_id).unwrap();
+        opened_files.sort();
+        assert_eq!(opened_files, [(lib_rs, 1, 20), (main_rs, 2, 30)]);
+    }
+
+    #[test]
+    fn test_frecency_score_factors() {
+        let now = SystemTime::now();
+        let hours_ago = |hours: u64| now - Duration::from_secs(hours * 60 * 60);
+
+        assert!(frecency(3, hours_ago(1), now) > frecency(2, hours_ago(1), now));
+        assert!(frecency(2, hours_ago(1), now) > frecency(2, hours_ago(30), now));
+        assert!(frecency(2, hours_ago(1), now) > frecency(4, hours_ago(24 * 60), now));
+
+        assert_eq!(score_factor(0.), 1.);
+        assert_eq!(score_factor(HALF_BOOST_FRECENCY), 1.5);
+        assert!(score_factor(1_000_000.) < 2.);
+    }
+}
@@ -25,7 +25,7 @@ use std::os::unix::fs::MetadataExt;
 use std::os::unix::fs::FileTypeExt;
 
 use async_tar::Archive;
-use futures::{future::BoxFuture, AsyncRead, Stream, StreamExt};
+use futures::{future::BoxFuture, AsyncRead, AsyncWrite, Stream, StreamExt};
 use git::repository::{GitRepository, RealGitRepository};
 use gpui::{AppContext, Global, ReadGlobal};
 use rope::Rope;
@@ -104,6 +104,8 @@ pub trait Fs: Send + Sync {
     }
     async fn open_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>>;
     async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read>>;
+    /// Opens the file at the given path for appending to it, creating it if it doesn't exist.
+    async fn open_append(&self, path: &Path) -> Result<Pin<Box<dyn AsyncWrite + Send>>>;
     async fn load(&self, path: &Path) -> Result<String> {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
@@ -503,6 +505,15 @@ impl Fs for RealFs {
         Ok(Arc::new(std::fs::File::open(path)?))
     }
 
+    async fn open_append(&self, path: &Path) -> Result<Pin<Box<dyn AsyncWrite + Send>>> {
+        let file = smol::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        Ok(Box::pin(file))
+    }
+
     async fn load(&self, path: &Path) -> Result<String> {
         let path = path.to_path_buf();
         let text = smol::unblock(|| std::fs::read_to_string(path)).await?;
@@ -1173,6 +1184,28 @@ impl FakeFs {
         Ok(())
     }
 
+    fn append_file_internal(&self, path: &Path, bytes: &[u8]) -> Result<()> {
+        let mut state = self.state.lock();
+        let new_mtime = state.get_and_increment_mtime();
+        let entry = state.read_path(path)?;
+        let mut entry = entry.lock();
+        let FakeFsEntry::File {
+            mtime,
+            len,
+            content,
+            ..
+        } = &mut *entry
+        else {
+            return Err(anyhow!("not a file: {path:?}"));
+        };
+        content.extend_from_slice(bytes);
+        *len = content.len() as u64;
+        *mtime = new_mtime;
+        drop(entry);
+        state.emit_event([(path, Some(PathEventKind::Changed))]);
+        Ok(())
+    }
+
     pub fn read_file_sync(&self, path: impl AsRef<Path>) -> Result<Vec<u8>> {
         let path = path.as_ref();
         let path = normalize_path(path);
@@ -1444,6 +1477,43 @@ impl FakeFs {
     }
 }
 
+/// A file of a [`FakeFs`] opened for appending, whose writes are applied immediately.
+#[cfg(any(test, feature = "test-support"))]
+struct FakeAppendFile {
+    fs: Arc<FakeFs>,
+    path: PathBuf,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl AsyncWrite for FakeAppendFile {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<io::Result<usize>> {
+        std::task::Poll::Ready(
+            self.fs
+                .append_file_internal(&self.path, buf)
+                .map(|_| buf.len())
+                .map_err(|error| io::Error::new(io::ErrorKind::Other, error)),
+        )
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(
+        self: Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
 #[cfg(any(test, feature = "test-support"))]
 impl FakeFsEntry {
     fn is_file(&self) -> bool {
@@ -1792,6 +1862,18 @@ impl Fs for FakeFs {
         Ok(Box::new(io::Cursor::new(bytes)))
     }
 
+    async fn open_append(&self, path: &Path) -> Result<Pin<Box<dyn AsyncWrite + Send>>> {
+        self.simulate_random_delay().await;
+        let path = normalize_path(path);
+        if self.state.lock().read_path(&path).is_err() {
+            self.write_file_internal(&path, Vec::new())?;
+        }
+        Ok(Box::pin(FakeAppendFile {
+            fs: self.this.upgrade().unwrap(),
+            path,
+        }))
+    }
+
     async fn open_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>> {
         self.simulate_random_delay().await;
         let state = self.state.lock();
@@ -1,9 +1,11 @@
 mod char_bag;
 mod matcher;
+mod path_symbols;
 mod paths;
 mod strings;
 
 pub use char_bag::CharBag;
+pub use path_symbols::{match_path_symbols, PathSymbolMatch};
 pub use paths::{
     match_fixed_path_set, match_path_sets, PathMatch, PathMatchCandidate, PathMatchCandidateSet,
 };
@@ -0,0 +1,122 @@
+use std::cmp::Ordering;
+
+use crate::{PathMatch, StringMatch};
+
+/// A symbol matched inside of a file that was itself matched by its path, as for queries like
+/// `editor@render`, where `editor` is matched against paths and `render` against symbol names.
+#[derive(Clone, Debug)]
+pub struct PathSymbolMatch {
+    pub score: f64,
+    pub path_match: PathMatch,
+    pub symbol_match: StringMatch,
+}
+
+impl PartialEq for PathSymbolMatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for PathSymbolMatch {}
+
+impl PartialOrd for PathSymbolMatch {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PathSymbolMatch {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .partial_cmp(&other.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.path_match.cmp(&other.path_match))
+            .then_with(|| {
+                other
+                    .symbol_match
+                    .candidate_id
+                    .cmp(&self.symbol_match.candidate_id)
+            })
+    }
+}
+
+/// Ranks symbol matches together with the path matches of the files containing them, keeping the
+/// `max_results` best ones, best first.
+///
+/// The scores are multiplied, so that an excellent symbol match in a poorly matching file doesn't
+/// outrank a good symbol match in the file the query most likely refers to. Path matches with a
+/// score of zero, e.g. for queries with an empty path, rank symbols by their own score only.
+pub fn match_path_symbols(
+    matches: impl IntoIterator<Item = (PathMatch, StringMatch)>,
+    max_results: usize,
+) -> Vec<PathSymbolMatch> {
+    let mut results = matches
+        .into_iter()
+        .map(|(path_match, symbol_match)| PathSymbolMatch {
+            score: combined_score(path_match.score, symbol_match.score),
+            path_match,
+            symbol_match,
+        })
+        .collect::<Vec<_>>();
+    util::truncate_to_bottom_n_sorted_by(&mut results, max_results, &|a, b| b.cmp(a));
+    results
+}
+
+fn combined_score(path_score: f64, symbol_score: f64) -> f64 {
+    if path_score > 0. {
+        path_score * symbol_score
+    } else {
+        symbol_score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc};
+
+    use super::*;
+
+    #[test]
+    fn test_match_path_symbols() {
+        let path_match = |path: &str, score| PathMatch {
+            score,
+            positions: Vec::new(),
+            worktree_id: 0,
+            path: Arc::from(Path::new(path)),
+            path_prefix: Arc::default(),
+            is_dir: false,
+            distance_to_relative_ancestor: usize::MAX,
+        };
+        let symbol_match = |candidate_id, symbol: &str, score| StringMatch {
+            candidate_id,
+            score,
+            positions: Vec::new(),
+            string: symbol.to_string(),
+        };
+
+        let matches = match_path_symbols(
+            [
+                (path_match("editor.rs", 0.9), symbol_match(0, "render", 0.5)),
+                (path_match("editor.rs", 0.9), symbol_match(1, "rename", 0.2)),
+                (path_match("element.rs", 0.3), symbol_match(0, "render", 1.)),
+                (path_match("element.rs", 0.3), symbol_match(1, "paint", 0.)),
+                (path_match("edit.rs", 0.9), symbol_match(3, "render", 0.5)),
+            ],
+            3,
+        );
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (
+                    m.path_match.path.to_str().unwrap(),
+                    m.symbol_match.string.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("editor.rs", "render"),
+                ("edit.rs", "render"),
+                ("element.rs", "render"),
+            ]
+        );
+    }
+}
@@ -43,6 +43,12 @@ pub trait PathMatchCandidateSet<'a>: Send + Sync {
     }
     fn prefix(&self) -> Arc<str>;
     fn candidates(&'a self, start: usize) -> Self::Candidates;
+    /// A factor applied to the score of the given path's matches, allowing sets to rank some of
+    /// their paths higher than others that match the query equally well, e.g. frequently opened
+    /// files.
+    fn score_factor(&self, _path: &Path) -> f64 {
+        1.
+    }
 }
 
 impl<'a> MatchCandidate for PathMatchCandidate<'a> {
@@ -181,7 +187,7 @@ pub async fn match_path_sets<'a, Set: PathMatchCandidateSet<'a>>(
                                 results,
                                 cancel_flag,
                                 |candidate, score, positions| PathMatch {
-                                    score,
+                                    score: score * candidate_set.score_factor(candidate.path),
                                     worktree_id,
                                     positions: positions.clone(),
                                     path: Arc::from(candidate.path),
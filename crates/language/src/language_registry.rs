@@ -640,6 +640,11 @@ impl LanguageRegistry {
         }
     }
 
+    /// Returns the language files at the given path are detected as, without loading it.
+    pub fn available_language_for_path(self: &Arc<Self>, path: &Path) -> Option<AvailableLanguage> {
+        self.language_for_file_internal(path, None, None)
+    }
+
     fn language_for_file_internal(
         self: &Arc<Self>,
         path: &Path,
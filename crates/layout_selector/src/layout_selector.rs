@@ -0,0 +1,281 @@
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Task,
+    View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{notifications::DetachAndPromptErr, ModalView, Workspace};
+
+actions!(layout_selector, [Toggle, SaveLayout]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(LayoutSelector::register).detach();
+}
+
+/// A picker listing the layouts saved for the workspace, which either switches to the selected
+/// layout, or saves the current layout under a new or existing name.
+pub struct LayoutSelector {
+    picker: View<Picker<LayoutSelectorDelegate>>,
+}
+
+impl LayoutSelector {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Toggle, cx| {
+            Self::toggle(workspace, false, cx);
+        });
+        workspace.register_action(|workspace, _: &SaveLayout, cx| {
+            Self::toggle(workspace, true, cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, save: bool, cx: &mut ViewContext<Workspace>) {
+        let layout_names = workspace.layout_names(cx);
+        cx.spawn(|workspace, mut cx| async move {
+            let layout_names = layout_names.await.log_err().unwrap_or_default();
+            workspace.update(&mut cx, |workspace, cx| {
+                let weak_workspace = cx.view().downgrade();
+                workspace.toggle_modal(cx, move |cx| {
+                    let delegate = LayoutSelectorDelegate {
+                        layout_selector: cx.view().downgrade(),
+                        workspace: weak_workspace,
+                        layout_names,
+                        save,
+                        entries: Vec::new(),
+                        selected_index: 0,
+                    };
+                    Self {
+                        picker: cx.new_view(|cx| Picker::uniform_list(delegate, cx)),
+                    }
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl Render for LayoutSelector {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for LayoutSelector {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for LayoutSelector {}
+impl ModalView for LayoutSelector {}
+
+enum LayoutEntry {
+    /// Saves the current layout under the name typed in the picker.
+    SaveAs(String),
+    Saved(StringMatch),
+}
+
+pub struct LayoutSelectorDelegate {
+    layout_selector: WeakView<LayoutSelector>,
+    workspace: WeakView<Workspace>,
+    layout_names: Vec<String>,
+    /// Whether the picker was opened to save the current layout rather than to switch to one.
+    save: bool,
+    entries: Vec<LayoutEntry>,
+    selected_index: usize,
+}
+
+impl LayoutSelectorDelegate {
+    fn delete_layout(&self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(LayoutEntry::Saved(mat)) = self.entries.get(ix) else {
+            return;
+        };
+        let name = self.layout_names[mat.candidate_id].clone();
+        let Some(delete) = self
+            .workspace
+            .update(cx, |workspace, cx| workspace.delete_layout(name, cx))
+            .log_err()
+        else {
+            return;
+        };
+        let workspace = self.workspace.clone();
+        cx.spawn(move |this, mut cx| async move {
+            delete.await.log_err();
+            let layout_names = workspace
+                .update(&mut cx, |workspace, cx| workspace.layout_names(cx))?
+                .await
+                .log_err()
+                .unwrap_or_default();
+            this.update(&mut cx, move |picker, cx| {
+                picker.delegate.layout_names = layout_names;
+                picker.delegate.selected_index = ix.saturating_sub(1);
+                picker.update_matches(picker.query(cx), cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for LayoutSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        if self.save {
+            "Name the layout to save…".into()
+        } else {
+            "Switch to a layout…".into()
+        }
+    }
+
+    fn no_matches_text(&self, _: &mut WindowContext) -> SharedString {
+        if self.save {
+            "Type a name for the layout".into()
+        } else {
+            "No saved layouts".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .layout_names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| StringMatchCandidate::new(id, name))
+            .collect::<Vec<_>>();
+        let background = cx.background_executor().clone();
+        cx.spawn(|picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    let name = query.trim();
+                    let is_new_name = !name.is_empty()
+                        && delegate
+                            .layout_names
+                            .iter()
+                            .all(|layout_name| layout_name != name);
+                    delegate.entries = (delegate.save && is_new_name)
+                        .then(|| LayoutEntry::SaveAs(name.to_string()))
+                        .into_iter()
+                        .chain(matches.into_iter().map(LayoutEntry::Saved))
+                        .collect();
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.entries.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(entry) = self.entries.get(self.selected_index) else {
+            return;
+        };
+        let name = match entry {
+            LayoutEntry::SaveAs(name) => name.clone(),
+            LayoutEntry::Saved(mat) => self.layout_names[mat.candidate_id].clone(),
+        };
+
+        let save = self.save;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                if save {
+                    workspace.save_layout(name, cx).detach_and_prompt_err(
+                        "Failed to save layout",
+                        cx,
+                        |_, _| None,
+                    );
+                } else {
+                    workspace.switch_layout(name, cx).detach_and_prompt_err(
+                        "Failed to switch layout",
+                        cx,
+                        |_, _| None,
+                    );
+                }
+            })
+            .log_err();
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.layout_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected);
+        match self.entries.get(ix)? {
+            LayoutEntry::SaveAs(name) => Some(
+                item.start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                    .child(Label::new(format!("Save as “{name}”"))),
+            ),
+            LayoutEntry::Saved(mat) => {
+                let delete_button = IconButton::new("delete", IconName::Close)
+                    .icon_size(IconSize::Small)
+                    .on_click(cx.listener(move |picker, _, cx| {
+                        cx.stop_propagation();
+                        cx.prevent_default();
+                        picker.delegate.delete_layout(ix, cx);
+                    }))
+                    .tooltip(|cx| Tooltip::text("Delete Layout", cx));
+                Some(
+                    item.child(HighlightedLabel::new(
+                        mat.string.clone(),
+                        mat.positions.clone(),
+                    ))
+                    .map(|item| {
+                        if selected {
+                            item.end_slot(delete_button)
+                        } else {
+                            item.end_hover_slot(delete_button)
+                        }
+                    }),
+                )
+            }
+        }
+    }
+}
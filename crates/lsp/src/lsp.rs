@@ -645,9 +645,12 @@ impl LanguageServer {
                     }),
                     file_operations: Some(WorkspaceFileOperationsClientCapabilities {
                         dynamic_registration: Some(false),
+                        did_create: Some(true),
+                        will_create: Some(true),
                         did_rename: Some(true),
                         will_rename: Some(true),
-                        ..Default::default()
+                        did_delete: Some(true),
+                        will_delete: Some(true),
                     }),
                     apply_edit: Some(true),
                     ..Default::default()
@@ -758,6 +761,12 @@ impl LanguageServer {
                         did_save: Some(true),
                         ..TextDocumentSyncClientCapabilities::default()
                     }),
+                    selection_range: Some(SelectionRangeClientCapabilities {
+                        dynamic_registration: None,
+                    }),
+                    inline_value: Some(InlineValueClientCapabilities {
+                        dynamic_registration: None,
+                    }),
                     ..TextDocumentClientCapabilities::default()
                 }),
                 experimental: Some(json!({
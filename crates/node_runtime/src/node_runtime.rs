@@ -1,10 +1,11 @@
 mod archive;
 
+use ::fs::{Fs, RemoveOptions};
 use anyhow::{anyhow, bail, Context, Result};
 pub use archive::extract_zip;
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
-use futures::AsyncReadExt;
+use futures::{AsyncRead, AsyncReadExt};
 use http_client::{HttpClient, Uri};
 use semver::Version;
 use serde::Deserialize;
@@ -16,6 +17,7 @@ use std::process::{Output, Stdio};
 use std::{
     env::consts,
     path::{Path, PathBuf},
+    pin::Pin,
     sync::Arc,
 };
 use util::ResultExt;
@@ -96,6 +98,18 @@ impl NodeRuntime {
         return Ok(instance);
     }
 
+    /// Whether a Node runtime installed on the system can be used, either the one configured or
+    /// one found on the `PATH`, rather than the one managed by Editsync.
+    pub async fn has_system_node(&self) -> bool {
+        let options = self.0.lock().await.options.borrow().clone();
+        let options = options.unwrap_or_default();
+        if let Some((node, npm)) = options.use_paths {
+            SystemNodeRuntime::new(node, npm).await.is_ok()
+        } else {
+            options.allow_path_lookup && SystemNodeRuntime::detect().await.is_some()
+        }
+    }
+
     pub async fn binary_path(&self) -> Result<PathBuf> {
         self.instance().await?.binary_path()
     }
@@ -218,6 +232,65 @@ impl NodeRuntime {
     }
 }
 
+/// Returns the directory in which Editsync installs the Node runtime it manages, when no other
+/// Node runtime can be used.
+pub fn managed_node_dir() -> Result<PathBuf> {
+    let (os, arch) = ManagedNodeRuntime::platform(consts::OS, consts::ARCH)?;
+    let folder_name = format!("node-{}-{os}-{arch}", ManagedNodeRuntime::VERSION);
+    Ok(paths::support_dir().join("node").join(folder_name))
+}
+
+/// Downloads the Node runtime that Editsync manages for another platform, named as in
+/// [`std::env::consts`], into the given directory. Returns the path of the downloaded archive,
+/// which is installed on that platform by [`install_managed_node_archive`].
+pub async fn download_managed_node_archive(
+    http: &Arc<dyn HttpClient>,
+    os: &str,
+    arch: &str,
+    directory: &Path,
+) -> Result<PathBuf> {
+    let (file_name, url, archive_type) = ManagedNodeRuntime::archive(os, arch)?;
+    if !matches!(archive_type, ArchiveType::TarGz) {
+        bail!("Node archives can't be installed on {os}");
+    }
+
+    let mut response = http
+        .get(&url, Default::default(), true)
+        .await
+        .context("error downloading Node binary tarball")?;
+    let path = directory.join(file_name);
+    let mut file = fs::File::create(&path).await?;
+    futures::io::copy(response.body_mut(), &mut file).await?;
+    Ok(path)
+}
+
+/// Installs the Node runtime that Editsync manages from an archive downloaded by
+/// [`download_managed_node_archive`], replacing any previous installation.
+pub async fn install_managed_node_archive(fs: &dyn Fs, archive: &Path) -> Result<()> {
+    let node_containing_dir = paths::support_dir().join("node");
+    fs.remove_dir(
+        &node_containing_dir,
+        RemoveOptions {
+            recursive: true,
+            ignore_if_not_exists: true,
+        },
+    )
+    .await?;
+    fs.create_dir(&node_containing_dir)
+        .await
+        .context("error creating node containing dir")?;
+
+    let bytes = fs
+        .load_bytes(archive)
+        .await
+        .with_context(|| format!("error opening Node archive {archive:?}"))?;
+    let mut decompressed_bytes = GzipDecoder::new(bytes.as_slice());
+    let archive =
+        Archive::new(Pin::new(&mut decompressed_bytes) as Pin<&mut (dyn AsyncRead + Send)>);
+    fs.extract_tar_file(&node_containing_dir, archive).await?;
+    Ok(())
+}
+
 enum ArchiveType {
     TarGz,
     Zip,
@@ -289,26 +362,51 @@ impl ManagedNodeRuntime {
         std::env::join_paths(env_path).context("failed to create PATH env variable")
     }
 
-    async fn install_if_needed(http: &Arc<dyn HttpClient>) -> Result<Box<dyn NodeRuntimeTrait>> {
-        log::info!("Node runtime install_if_needed");
-
-        let os = match consts::OS {
+    /// Returns the names by which Node's distributions refer to the given platform, named as in
+    /// [`std::env::consts`].
+    fn platform(os: &str, arch: &str) -> Result<(&'static str, &'static str)> {
+        let os = match os {
             "macos" => "darwin",
             "linux" => "linux",
             "windows" => "win",
             other => bail!("Running on unsupported os: {other}"),
         };
 
-        let arch = match consts::ARCH {
+        let arch = match arch {
             "x86_64" => "x64",
             "aarch64" => "arm64",
             other => bail!("Running on unsupported architecture: {other}"),
         };
 
+        Ok((os, arch))
+    }
+
+    /// Returns the file name and the URL of the Node distribution for the given platform.
+    fn archive(os: &str, arch: &str) -> Result<(String, String, ArchiveType)> {
+        let archive_type = match os {
+            "macos" | "linux" => ArchiveType::TarGz,
+            "windows" => ArchiveType::Zip,
+            other => bail!("Running on unsupported os: {other}"),
+        };
+        let (os, arch) = Self::platform(os, arch)?;
+
         let version = Self::VERSION;
-        let folder_name = format!("node-{version}-{os}-{arch}");
+        let file_name = format!(
+            "node-{version}-{os}-{arch}.{extension}",
+            extension = match archive_type {
+                ArchiveType::TarGz => "tar.gz",
+                ArchiveType::Zip => "zip",
+            }
+        );
+        let url = format!("https://nodejs.org/dist/{version}/{file_name}");
+        Ok((file_name, url, archive_type))
+    }
+
+    async fn install_if_needed(http: &Arc<dyn HttpClient>) -> Result<Box<dyn NodeRuntimeTrait>> {
+        log::info!("Node runtime install_if_needed");
+
         let node_containing_dir = paths::support_dir().join("node");
-        let node_dir = node_containing_dir.join(folder_name);
+        let node_dir = managed_node_dir()?;
         let node_binary = node_dir.join(Self::NODE_PATH);
         let npm_file = node_dir.join(Self::NPM_PATH);
 
@@ -332,21 +430,7 @@ impl ManagedNodeRuntime {
                 .await
                 .context("error creating node containing dir")?;
 
-            let archive_type = match consts::OS {
-                "macos" | "linux" => ArchiveType::TarGz,
-                "windows" => ArchiveType::Zip,
-                other => bail!("Running on unsupported os: {other}"),
-            };
-
-            let version = Self::VERSION;
-            let file_name = format!(
-                "node-{version}-{os}-{arch}.{extension}",
-                extension = match archive_type {
-                    ArchiveType::TarGz => "tar.gz",
-                    ArchiveType::Zip => "zip",
-                }
-            );
-            let url = format!("https://nodejs.org/dist/{version}/{file_name}");
+            let (_, url, archive_type) = Self::archive(consts::OS, consts::ARCH)?;
             let mut response = http
                 .get(&url, Default::default(), true)
                 .await
@@ -672,3 +756,92 @@ fn configure_npm_command(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fs::FakeFs;
+    use async_compression::futures::write::GzipEncoder;
+    use futures::AsyncWriteExt;
+    use serde_json::json;
+
+    #[test]
+    fn test_managed_node_platform() {
+        assert_eq!(
+            ManagedNodeRuntime::platform("linux", "x86_64").unwrap(),
+            ("linux", "x64")
+        );
+        assert_eq!(
+            ManagedNodeRuntime::platform("macos", "aarch64").unwrap(),
+            ("darwin", "arm64")
+        );
+        assert_eq!(
+            ManagedNodeRuntime::platform("windows", "x86_64").unwrap(),
+            ("win", "x64")
+        );
+        assert!(ManagedNodeRuntime::platform("freebsd", "x86_64").is_err());
+        assert!(ManagedNodeRuntime::platform("linux", "riscv64").is_err());
+    }
+
+    #[test]
+    fn test_managed_node_archive() {
+        let version = ManagedNodeRuntime::VERSION;
+
+        let (file_name, url, archive_type) =
+            ManagedNodeRuntime::archive("linux", "aarch64").unwrap();
+        assert_eq!(file_name, format!("node-{version}-linux-arm64.tar.gz"));
+        assert_eq!(
+            url,
+            format!("https://nodejs.org/dist/{version}/{file_name}")
+        );
+        assert!(matches!(archive_type, ArchiveType::TarGz));
+
+        let (file_name, _, archive_type) =
+            ManagedNodeRuntime::archive("windows", "x86_64").unwrap();
+        assert_eq!(file_name, format!("node-{version}-win-x64.zip"));
+        assert!(matches!(archive_type, ArchiveType::Zip));
+
+        assert!(ManagedNodeRuntime::archive("freebsd", "x86_64").is_err());
+    }
+
+    #[gpui::test]
+    async fn test_install_managed_node_archive(cx: &mut gpui::TestAppContext) {
+        let mut builder = async_tar::Builder::new(Vec::new());
+        let mut header = async_tar::Header::new_gnu();
+        header.set_size(4);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "node-v1/bin/node", b"node".as_slice())
+            .await
+            .unwrap();
+        let mut encoder = GzipEncoder::new(Vec::new());
+        encoder
+            .write_all(&builder.into_inner().await.unwrap())
+            .await
+            .unwrap();
+        encoder.close().await.unwrap();
+
+        let fs = FakeFs::new(cx.executor());
+        let archive = Path::new("/uploads/node.tar.gz");
+        fs.insert_tree("/uploads", json!({})).await;
+        fs.insert_file(archive, encoder.into_inner()).await;
+        let node_containing_dir = paths::support_dir().join("node");
+        fs.insert_tree(
+            &node_containing_dir,
+            json!({ "node-v0": { "bin": { "node": "" } } }),
+        )
+        .await;
+
+        install_managed_node_archive(fs.as_ref(), archive)
+            .await
+            .unwrap();
+        assert_eq!(
+            fs.load(&node_containing_dir.join("node-v1/bin/node"))
+                .await
+                .unwrap(),
+            "node"
+        );
+        assert!(!fs.is_dir(&node_containing_dir.join("node-v0")).await);
+    }
+}
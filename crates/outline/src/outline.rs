@@ -11,9 +11,9 @@ use gpui::{
     ParentElement, Point, Render, Styled, StyledText, Task, TextStyle, View, ViewContext,
     VisualContext, WeakView, WindowContext,
 };
-use language::{Outline, OutlineItem};
+use language::{Outline, OutlineItem, ToPoint as _};
 use ordered_float::OrderedFloat;
-use picker::{Picker, PickerDelegate};
+use picker::{Picker, PickerDelegate, PickerSettings, PreviewLocation};
 use settings::Settings;
 use theme::{color_alpha, ActiveTheme, ThemeSettings};
 use ui::{prelude::*, ListItem, ListItemSpacing};
@@ -21,6 +21,7 @@ use util::ResultExt;
 use workspace::{DismissDecision, ModalView};
 
 pub fn init(cx: &mut AppContext) {
+    PickerSettings::register(cx);
     cx.observe_new_views(OutlineView::register).detach();
     editsync_actions::outline::TOGGLE_OUTLINE
         .set(|view, cx| {
@@ -252,6 +253,30 @@ impl PickerDelegate for OutlineViewDelegate {
         Task::ready(())
     }
 
+    fn preview_location(
+        &self,
+        ix: usize,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Task<anyhow::Result<PreviewLocation>>> {
+        if !PickerSettings::get_global(cx).preview {
+            return None;
+        }
+
+        let outline_item = &self.outline.items[self.matches.get(ix)?.candidate_id];
+        let buffer = self
+            .active_editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .buffer(outline_item.range.start.buffer_id?)?;
+        let range = {
+            let buffer = buffer.read(cx);
+            outline_item.range.start.text_anchor.to_point(buffer)
+                ..outline_item.range.end.text_anchor.to_point(buffer)
+        };
+        Some(Task::ready(Ok(PreviewLocation { buffer, range })))
+    }
+
     fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<OutlineViewDelegate>>) {
         self.prev_scroll_position.take();
 
@@ -346,6 +371,7 @@ mod tests {
     use language::{Language, LanguageConfig, LanguageMatcher};
     use project::{FakeFs, Project};
     use serde_json::json;
+    use settings::SettingsStore;
     use workspace::{AppState, Workspace};
 
     #[gpui::test]
@@ -466,6 +492,76 @@ mod tests {
         assert_single_caret_at_row(&editor, expected_first_highlighted_row, cx);
     }
 
+    #[gpui::test]
+    async fn test_outline_view_preview(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<PickerSettings>(cx, |settings| {
+                    settings.preview = Some(true)
+                });
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": indoc!{"
+                    struct SingleLine;
+
+                    struct MultiLine {
+                        field_1: i32,
+                    }
+                "}
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        });
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+            .await
+            .unwrap();
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "a.rs"), None, true, cx)
+            })
+            .await
+            .unwrap();
+
+        let outline_view = open_outline_view(&workspace, cx);
+        let previewed_rows = |cx: &mut VisualTestContext| {
+            cx.run_until_parked();
+            outline_view.update(cx, |outline_view, cx| {
+                let location = outline_view.previewed_location(cx).unwrap();
+                assert_eq!(location.buffer, buffer);
+                location.range.start.row..location.range.end.row
+            })
+        };
+
+        cx.dispatch_action(menu::SelectNext);
+        assert_eq!(
+            previewed_rows(cx),
+            2..4,
+            "Selecting the second struct should preview it"
+        );
+
+        cx.dispatch_action(menu::SelectPrev);
+        assert_eq!(
+            previewed_rows(cx),
+            0..0,
+            "Selecting the first struct should preview it"
+        );
+    }
+
     fn open_outline_view(
         workspace: &View<Workspace>,
         cx: &mut VisualTestContext,
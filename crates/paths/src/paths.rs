@@ -266,6 +266,23 @@ pub fn languages_dir() -> &'static PathBuf {
     LANGUAGES_DIR.get_or_init(|| support_dir().join("languages"))
 }
 
+/// Returns the path to the language server uploads directory.
+///
+/// This is where language servers and the Node runtime uploaded from a client are stored on a
+/// remote, before they are installed.
+pub fn remote_language_server_uploads_dir() -> &'static PathBuf {
+    static UPLOAD_DIR: OnceLock<PathBuf> = OnceLock::new();
+    UPLOAD_DIR.get_or_init(|| support_dir().join("language_server_uploads"))
+}
+
+/// Returns the path to the session recordings directory.
+///
+/// This is where recordings of collaboration sessions are saved.
+pub fn session_recordings_dir() -> &'static PathBuf {
+    static SESSION_RECORDINGS_DIR: OnceLock<PathBuf> = OnceLock::new();
+    SESSION_RECORDINGS_DIR.get_or_init(|| support_dir().join("session_recordings"))
+}
+
 /// Returns the path to the Copilot directory.
 pub fn copilot_dir() -> &'static PathBuf {
     static COPILOT_DIR: OnceLock<PathBuf> = OnceLock::new();
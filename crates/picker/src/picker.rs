@@ -10,10 +10,16 @@ use head::Head;
 use serde::Deserialize;
 use std::{sync::Arc, time::Duration};
 use ui::{prelude::*, v_flex, Color, Divider, Label, ListItem, ListItemSpacing};
+use util::ResultExt;
 use workspace::ModalView;
 
 mod head;
 pub mod highlighted_match_with_paths;
+mod picker_settings;
+mod preview;
+
+pub use picker_settings::PickerSettings;
+pub use preview::PreviewLocation;
 
 enum ElementContainer {
     List(ListState),
@@ -44,6 +50,8 @@ pub struct Picker<D: PickerDelegate> {
     confirm_on_update: Option<bool>,
     width: Option<Length>,
     max_height: Option<Length>,
+    preview_editor: Option<View<Editor>>,
+    pending_preview: Option<Task<()>>,
 
     /// Whether the `Picker` is rendered as a self-contained modal.
     ///
@@ -149,6 +157,15 @@ pub trait PickerDelegate: Sized + 'static {
     fn render_header(&self, _: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
         None
     }
+    /// Override to show a preview of the text around the selected match below the list of
+    /// matches. Implementations should return `None` unless [`PickerSettings::preview`] is set.
+    fn preview_location(
+        &self,
+        _ix: usize,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Task<Result<PreviewLocation>>> {
+        None
+    }
     fn render_footer(&self, _: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
         None
     }
@@ -213,6 +230,8 @@ impl<D: PickerDelegate> Picker<D> {
             confirm_on_update: None,
             width: None,
             max_height: Some(rems(18.).into()),
+            preview_editor: None,
+            pending_preview: None,
             is_modal: true,
         };
         this.update_matches("".to_string(), cx);
@@ -290,9 +309,41 @@ impl<D: PickerDelegate> Picker<D> {
             if scroll_to_index {
                 self.scroll_to_item_index(ix);
             }
+            self.update_preview(cx);
         }
     }
 
+    /// The location previewed for the selected match, if any.
+    pub fn previewed_location(&self, cx: &AppContext) -> Option<PreviewLocation> {
+        preview::previewed_location(self.preview_editor.as_ref()?, cx)
+    }
+
+    fn update_preview(&mut self, cx: &mut ViewContext<Self>) {
+        let location = if self.delegate.match_count() > 0 {
+            self.delegate
+                .preview_location(self.delegate.selected_index(), cx)
+        } else {
+            None
+        };
+        let Some(location) = location else {
+            self.pending_preview = None;
+            self.preview_editor = None;
+            return;
+        };
+
+        self.pending_preview = Some(cx.spawn(|this, mut cx| async move {
+            let Some(location) = location.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                let editor = preview::preview_editor(this.preview_editor.as_ref(), location, cx);
+                this.preview_editor = Some(editor);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
     pub fn select_next(&mut self, _: &menu::SelectNext, cx: &mut ViewContext<Self>) {
         let count = self.delegate.match_count();
         if count > 0 {
@@ -479,6 +530,7 @@ impl<D: PickerDelegate> Picker<D> {
 
         let index = self.delegate.selected_index();
         self.scroll_to_item_index(index);
+        self.update_preview(cx);
         self.pending_update_matches = None;
         if let Some(secondary) = self.confirm_on_update.take() {
             self.do_confirm(secondary, cx);
@@ -648,6 +700,15 @@ impl<D: PickerDelegate> Render for Picker<D> {
                     ),
                 )
             })
+            .when_some(self.preview_editor.clone(), |el, editor| {
+                el.child(Divider::horizontal()).child(
+                    div()
+                        .flex_none()
+                        .h(rems(16.))
+                        .overflow_hidden()
+                        .child(editor),
+                )
+            })
             .children(self.delegate.render_footer(cx))
             .children(match &self.head {
                 Head::Editor(editor) => {
@@ -0,0 +1,29 @@
+use anyhow::Result;
+use gpui::AppContext;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PickerSettings {
+    pub preview: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct PickerSettingsContent {
+    /// Whether to show a preview of the file of the selected match below the list of matches, in
+    /// the file finder, the outline and the project symbols.
+    ///
+    /// Default: false
+    pub preview: Option<bool>,
+}
+
+impl Settings for PickerSettings {
+    const KEY: Option<&'static str> = Some("picker");
+
+    type FileContent = PickerSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
@@ -0,0 +1,68 @@
+use std::ops::Range;
+
+use editor::{scroll::Autoscroll, Editor, ToPoint};
+use gpui::{AppContext, Model, View, ViewContext, VisualContext};
+use language::{Buffer, Point};
+use ui::prelude::*;
+
+/// The text to preview for a picker's selected match.
+pub struct PreviewLocation {
+    pub buffer: Model<Buffer>,
+    /// The range to highlight and to scroll to.
+    pub range: Range<Point>,
+}
+
+enum PreviewHighlight {}
+
+/// Returns the read-only editor previewing the given location, reusing the current preview
+/// editor when it already shows the location's buffer.
+pub(crate) fn preview_editor<V: 'static>(
+    current_editor: Option<&View<Editor>>,
+    location: PreviewLocation,
+    cx: &mut ViewContext<V>,
+) -> View<Editor> {
+    let editor = current_editor
+        .filter(|editor| {
+            editor.read(cx).buffer().read(cx).as_singleton().as_ref() == Some(&location.buffer)
+        })
+        .cloned()
+        .unwrap_or_else(|| {
+            let buffer = location.buffer.clone();
+            cx.new_view(|cx| {
+                let mut editor = Editor::for_buffer(buffer, None, cx);
+                editor.set_read_only(true);
+                editor.set_show_inline_completions(Some(false), cx);
+                editor
+            })
+        });
+
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let range =
+            snapshot.anchor_before(location.range.start)..snapshot.anchor_after(location.range.end);
+        editor.clear_row_highlights::<PreviewHighlight>();
+        editor.highlight_rows::<PreviewHighlight>(
+            range,
+            cx.theme().colors().editor_highlighted_line_background,
+            true,
+            cx,
+        );
+        editor.request_autoscroll(Autoscroll::center(), cx);
+    });
+    editor
+}
+
+/// Returns the location shown by the given preview editor.
+pub(crate) fn previewed_location(
+    editor: &View<Editor>,
+    cx: &AppContext,
+) -> Option<PreviewLocation> {
+    let editor = editor.read(cx);
+    let buffer = editor.buffer().read(cx);
+    let (range, _) = editor.highlighted_rows::<PreviewHighlight>().next()?;
+    let snapshot = buffer.snapshot(cx);
+    Some(PreviewLocation {
+        buffer: buffer.as_singleton()?,
+        range: range.start.to_point(&snapshot)..range.end.to_point(&snapshot),
+    })
+}
@@ -1711,6 +1711,55 @@ impl BufferStore {
         Ok(response)
     }
 
+    /// Returns the versions of the given buffers, and sends the downstream client the operations
+    /// it hasn't observed. Used by a remote server when its client reconnects.
+    pub fn synchronize_downstream_buffers(
+        &self,
+        buffers: Vec<proto::BufferVersion>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<proto::SynchronizeBuffersResponse> {
+        let Some((client, project_id)) = self.downstream_client.clone() else {
+            bail!("buffers are not shared with a downstream client");
+        };
+        let mut response = proto::SynchronizeBuffersResponse {
+            buffers: Default::default(),
+        };
+        for buffer in buffers {
+            let buffer_id = BufferId::new(buffer.id)?;
+            let remote_version = deserialize_version(&buffer.version);
+            let Some(buffer) = self.get(buffer_id) else {
+                continue;
+            };
+            let buffer = buffer.read(cx);
+            response.buffers.push(proto::BufferVersion {
+                id: buffer_id.into(),
+                version: serialize_version(&buffer.version),
+            });
+
+            let operations = buffer.serialize_ops(Some(remote_version), cx);
+            let client = client.clone();
+            cx.background_executor()
+                .spawn(
+                    async move {
+                        let operations = operations.await;
+                        for chunk in split_operations(operations) {
+                            client
+                                .request(proto::UpdateBuffer {
+                                    project_id,
+                                    buffer_id: buffer_id.into(),
+                                    operations: chunk,
+                                })
+                                .await?;
+                        }
+                        anyhow::Ok(())
+                    }
+                    .log_err(),
+                )
+                .detach();
+        }
+        Ok(response)
+    }
+
     pub fn handle_create_buffer_for_peer(
         &mut self,
         envelope: TypedEnvelope<proto::CreateBufferForPeer>,
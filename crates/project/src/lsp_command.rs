@@ -3,8 +3,8 @@ mod signature_help;
 use crate::{
     lsp_store::{LocalLspStore, LspStore},
     CodeAction, CoreCompletion, DocumentHighlight, Hover, HoverBlock, HoverBlockKind, InlayHint,
-    InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
-    LocationLink, MarkupContent, ProjectTransaction, ResolveState,
+    InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, InlineValue,
+    InlineValueKind, Location, LocationLink, MarkupContent, ProjectTransaction, ResolveState,
 };
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
@@ -24,7 +24,7 @@ use lsp::{
     AdapterServerCapabilities, CodeActionKind, CodeActionOptions, CompletionContext,
     CompletionListItemDefaultsEditRange, CompletionTriggerKind, DocumentHighlightKind,
     LanguageServer, LanguageServerId, LinkedEditingRangeServerCapabilities, OneOf,
-    ServerCapabilities,
+    SelectionRangeProviderCapability, ServerCapabilities,
 };
 use signature_help::{lsp_to_proto_signature, proto_to_lsp_signature};
 use std::{cmp::Reverse, ops::Range, path::Path, sync::Arc};
@@ -182,6 +182,17 @@ pub(crate) struct LinkedEditingRange {
     pub position: Anchor,
 }
 
+#[derive(Debug)]
+pub(crate) struct GetSelectionRanges {
+    pub positions: Vec<Anchor>,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetInlineValues {
+    pub range: Range<Anchor>,
+    pub stopped_location: Range<Anchor>,
+}
+
 #[async_trait(?Send)]
 impl LspCommand for PrepareRename {
     type Response = Option<Range<Anchor>>;
@@ -3071,3 +3082,414 @@ impl LspCommand for LinkedEditingRange {
         BufferId::new(message.buffer_id)
     }
 }
+
+#[async_trait(?Send)]
+impl LspCommand for GetSelectionRanges {
+    type Response = Vec<Vec<Range<Anchor>>>;
+    type LspRequest = lsp::request::SelectionRangeRequest;
+    type ProtoRequest = proto::GetSelectionRanges;
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match &capabilities.server_capabilities.selection_range_provider {
+            Some(SelectionRangeProviderCapability::Simple(enabled)) => *enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        buffer: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::SelectionRangeParams {
+        let snapshot = buffer.snapshot();
+        lsp::SelectionRangeParams {
+            text_document: lsp::TextDocumentIdentifier {
+                uri: lsp::Url::from_file_path(path).unwrap(),
+            },
+            positions: self
+                .positions
+                .iter()
+                .map(|position| point_to_lsp(position.to_point_utf16(&snapshot)))
+                .collect(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::SelectionRange>>,
+        _: Model<LspStore>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<Vec<Range<Anchor>>>> {
+        let Some(selection_ranges) = message else {
+            return Ok(Vec::new());
+        };
+
+        buffer.read_with(&cx, |buffer, _| {
+            selection_ranges
+                .into_iter()
+                .map(|selection_range| {
+                    // Flatten the linked list of parents, innermost range first.
+                    let mut chain = Vec::new();
+                    let mut next = Some(selection_range);
+                    while let Some(selection_range) = next {
+                        let start = buffer.clip_point_utf16(
+                            point_from_lsp(selection_range.range.start),
+                            Bias::Left,
+                        );
+                        let end = buffer.clip_point_utf16(
+                            point_from_lsp(selection_range.range.end),
+                            Bias::Left,
+                        );
+                        chain.push(buffer.anchor_before(start)..buffer.anchor_after(end));
+                        next = selection_range.parent.map(|parent| *parent);
+                    }
+                    chain
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSelectionRanges {
+        proto::GetSelectionRanges {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            positions: self.positions.iter().map(serialize_anchor).collect(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSelectionRanges,
+        _: Model<LspStore>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let positions = message
+            .positions
+            .into_iter()
+            .map(|position| deserialize_anchor(position).ok_or_else(|| anyhow!("invalid position")))
+            .collect::<Result<Vec<_>>>()?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors(positions.iter().copied())
+            })?
+            .await?;
+        Ok(Self { positions })
+    }
+
+    fn response_to_proto(
+        response: Vec<Vec<Range<Anchor>>>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetSelectionRangesResponse {
+        proto::GetSelectionRangesResponse {
+            chains: response
+                .into_iter()
+                .map(|chain| proto::SelectionRangeChain {
+                    ranges: chain
+                        .into_iter()
+                        .map(|range| proto::AnchorRange {
+                            start: Some(serialize_anchor(&range.start)),
+                            end: Some(serialize_anchor(&range.end)),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSelectionRangesResponse,
+        _: Model<LspStore>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Vec<Range<Anchor>>>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let chains = message
+            .chains
+            .into_iter()
+            .map(|chain| {
+                chain
+                    .ranges
+                    .into_iter()
+                    .filter_map(|range| {
+                        let start = deserialize_anchor(range.start?)?;
+                        let end = deserialize_anchor(range.end?)?;
+                        Some(start..end)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors(
+                    chains
+                        .iter()
+                        .flatten()
+                        .flat_map(|range| [range.start, range.end]),
+                )
+            })?
+            .await?;
+        Ok(chains)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSelectionRanges) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetInlineValues {
+    type Response = Vec<InlineValue>;
+    type LspRequest = lsp::request::InlineValueRequest;
+    type ProtoRequest = proto::GetInlineValues;
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match &capabilities.server_capabilities.inline_value_provider {
+            Some(OneOf::Left(enabled)) => *enabled,
+            Some(OneOf::Right(_)) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        buffer: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::InlineValueParams {
+        lsp::InlineValueParams {
+            text_document: lsp::TextDocumentIdentifier {
+                uri: lsp::Url::from_file_path(path).unwrap(),
+            },
+            range: range_to_lsp(self.range.to_point_utf16(buffer)),
+            context: lsp::InlineValueContext {
+                // There is no debug adapter to report stack frames, so every request refers to
+                // the same frame.
+                frame_id: 0,
+                stopped_location: range_to_lsp(self.stopped_location.to_point_utf16(buffer)),
+            },
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::InlineValue>>,
+        _: Model<LspStore>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<InlineValue>> {
+        let Some(inline_values) = message else {
+            return Ok(Vec::new());
+        };
+
+        buffer.read_with(&cx, |buffer, _| {
+            inline_values
+                .into_iter()
+                .map(|inline_value| {
+                    let (range, kind) = match inline_value {
+                        lsp::InlineValue::Text(text) => {
+                            (text.range, InlineValueKind::Text(text.text))
+                        }
+                        lsp::InlineValue::VariableLookup(lookup) => (
+                            lookup.range,
+                            InlineValueKind::VariableLookup {
+                                variable_name: lookup.variable_name,
+                                case_sensitive: lookup.case_sensitive_lookup,
+                            },
+                        ),
+                        lsp::InlineValue::EvaluatableExpression(expression) => (
+                            expression.range,
+                            InlineValueKind::EvaluatableExpression {
+                                expression: expression.expression,
+                            },
+                        ),
+                    };
+                    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                    InlineValue {
+                        range: buffer.anchor_before(start)..buffer.anchor_after(end),
+                        kind,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetInlineValues {
+        proto::GetInlineValues {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            range: Some(proto::AnchorRange {
+                start: Some(serialize_anchor(&self.range.start)),
+                end: Some(serialize_anchor(&self.range.end)),
+            }),
+            stopped_location: Some(proto::AnchorRange {
+                start: Some(serialize_anchor(&self.stopped_location.start)),
+                end: Some(serialize_anchor(&self.stopped_location.end)),
+            }),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetInlineValues,
+        _: Model<LspStore>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        fn deserialize_range(range: Option<proto::AnchorRange>) -> Result<Range<Anchor>> {
+            let range = range.context("invalid range")?;
+            let start = range
+                .start
+                .and_then(deserialize_anchor)
+                .context("invalid start")?;
+            let end = range
+                .end
+                .and_then(deserialize_anchor)
+                .context("invalid end")?;
+            Ok(start..end)
+        }
+
+        let range = deserialize_range(message.range)?;
+        let stopped_location = deserialize_range(message.stopped_location)?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors([
+                    range.start,
+                    range.end,
+                    stopped_location.start,
+                    stopped_location.end,
+                ])
+            })?
+            .await?;
+        Ok(Self {
+            range,
+            stopped_location,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<InlineValue>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetInlineValuesResponse {
+        proto::GetInlineValuesResponse {
+            inline_values: response
+                .into_iter()
+                .map(|inline_value| {
+                    let kind = match inline_value.kind {
+                        InlineValueKind::Text(text) => proto::inline_value::Kind::Text(text),
+                        InlineValueKind::VariableLookup {
+                            variable_name,
+                            case_sensitive,
+                        } => proto::inline_value::Kind::VariableLookup(
+                            proto::InlineValueVariableLookup {
+                                variable_name,
+                                case_sensitive,
+                            },
+                        ),
+                        InlineValueKind::EvaluatableExpression { expression } => {
+                            proto::inline_value::Kind::EvaluatableExpression(
+                                proto::InlineValueEvaluatableExpression { expression },
+                            )
+                        }
+                    };
+                    proto::InlineValue {
+                        range: Some(proto::AnchorRange {
+                            start: Some(serialize_anchor(&inline_value.range.start)),
+                            end: Some(serialize_anchor(&inline_value.range.end)),
+                        }),
+                        kind: Some(kind),
+                    }
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetInlineValuesResponse,
+        _: Model<LspStore>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<InlineValue>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let inline_values = message
+            .inline_values
+            .into_iter()
+            .filter_map(|inline_value| {
+                let range = inline_value.range?;
+                let start = deserialize_anchor(range.start?)?;
+                let end = deserialize_anchor(range.end?)?;
+                let kind = match inline_value.kind? {
+                    proto::inline_value::Kind::Text(text) => InlineValueKind::Text(text),
+                    proto::inline_value::Kind::VariableLookup(lookup) => {
+                        InlineValueKind::VariableLookup {
+                            variable_name: lookup.variable_name,
+                            case_sensitive: lookup.case_sensitive,
+                        }
+                    }
+                    proto::inline_value::Kind::EvaluatableExpression(expression) => {
+                        InlineValueKind::EvaluatableExpression {
+                            expression: expression.expression,
+                        }
+                    }
+                };
+                Some(InlineValue {
+                    range: start..end,
+                    kind,
+                })
+            })
+            .collect::<Vec<_>>();
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors(
+                    inline_values.iter().flat_map(|inline_value| {
+                        [inline_value.range.start, inline_value.range.end]
+                    }),
+                )
+            })?
+            .await?;
+        Ok(inline_values)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetInlineValues) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
@@ -43,13 +43,15 @@ use language::{
     Unclipped,
 };
 use lsp::{
-    notification::DidRenameFiles, CodeActionKind, CompletionContext, DiagnosticSeverity,
-    DiagnosticTag, DidChangeWatchedFilesRegistrationOptions, Edit, FileOperationFilter,
-    FileOperationPatternKind, FileOperationRegistrationOptions, FileRename, FileSystemWatcher,
-    InsertTextFormat, LanguageServer, LanguageServerBinary, LanguageServerBinaryOptions,
-    LanguageServerId, LanguageServerName, LspRequestFuture, MessageActionItem, MessageType, OneOf,
-    RenameFilesParams, ServerHealthStatus, ServerStatus, SymbolKind, TextEdit, Url,
-    WillRenameFiles, WorkDoneProgressCancelParams, WorkspaceFolder,
+    notification::{DidCreateFiles, DidDeleteFiles, DidRenameFiles},
+    CodeActionKind, CompletionContext, CreateFilesParams, DeleteFilesParams, DiagnosticSeverity,
+    DiagnosticTag, DidChangeWatchedFilesRegistrationOptions, Edit, FileCreate, FileDelete,
+    FileOperationFilter, FileOperationPatternKind, FileOperationRegistrationOptions, FileRename,
+    FileSystemWatcher, InsertTextFormat, LanguageServer, LanguageServerBinary,
+    LanguageServerBinaryOptions, LanguageServerId, LanguageServerName, LspRequestFuture,
+    MessageActionItem, MessageType, OneOf, RenameFilesParams, ServerHealthStatus, ServerStatus,
+    SymbolKind, TextEdit, Url, WillCreateFiles, WillDeleteFiles, WillRenameFiles,
+    WorkDoneProgressCancelParams, WorkspaceFolder,
 };
 use node_runtime::read_package_installed_version;
 use parking_lot::Mutex;
@@ -151,8 +153,8 @@ pub struct LocalLspStore {
     buffers_being_formatted: HashSet<BufferId>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
     language_server_watched_paths: HashMap<LanguageServerId, LanguageServerWatchedPaths>,
-    language_server_paths_watched_for_rename:
-        HashMap<LanguageServerId, RenamePathsWatchedForServer>,
+    language_server_paths_watched_for_file_operations:
+        HashMap<LanguageServerId, FileOperationsWatchedForServer>,
     language_server_watcher_registrations:
         HashMap<LanguageServerId, HashMap<String, Vec<FileSystemWatcher>>>,
     supplementary_language_servers:
@@ -2227,16 +2229,7 @@ impl LocalLspStore {
         })
     }
 
-    pub(crate) async fn deserialize_workspace_edit(
-        this: Model<LspStore>,
-        edit: lsp::WorkspaceEdit,
-        push_to_history: bool,
-        lsp_adapter: Arc<CachedLspAdapter>,
-        language_server: Arc<LanguageServer>,
-        cx: &mut AsyncAppContext,
-    ) -> Result<ProjectTransaction> {
-        let fs = this.read_with(cx, |this, _| this.as_local().unwrap().fs.clone())?;
-
+    fn workspace_edit_operations(edit: lsp::WorkspaceEdit) -> Vec<lsp::DocumentChangeOperation> {
         let mut operations = Vec::new();
         if let Some(document_changes) = edit.document_changes {
             match document_changes {
@@ -2256,49 +2249,27 @@ impl LocalLspStore {
                 })
             }));
         }
+        operations
+    }
 
-        let mut project_transaction = ProjectTransaction::default();
-        for operation in operations {
-            match operation {
-                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(op)) => {
-                    let abs_path = op
-                        .uri
-                        .to_file_path()
-                        .map_err(|_| anyhow!("can't convert URI to path"))?;
+    async fn apply_resource_operation(fs: &dyn Fs, operation: lsp::ResourceOp) -> Result<()> {
+        match operation {
+            lsp::ResourceOp::Create(op) => {
+                let abs_path = op
+                    .uri
+                    .to_file_path()
+                    .map_err(|_| anyhow!("can't convert URI to path"))?;
 
-                    if let Some(parent_path) = abs_path.parent() {
-                        fs.create_dir(parent_path).await?;
-                    }
-                    if abs_path.ends_with("/") {
-                        fs.create_dir(&abs_path).await?;
-                    } else {
-                        fs.create_file(
-                            &abs_path,
-                            op.options
-                                .map(|options| fs::CreateOptions {
-                                    overwrite: options.overwrite.unwrap_or(false),
-                                    ignore_if_exists: options.ignore_if_exists.unwrap_or(false),
-                                })
-                                .unwrap_or_default(),
-                        )
-                        .await?;
-                    }
+                if let Some(parent_path) = abs_path.parent() {
+                    fs.create_dir(parent_path).await?;
                 }
-
-                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(op)) => {
-                    let source_abs_path = op
-                        .old_uri
-                        .to_file_path()
-                        .map_err(|_| anyhow!("can't convert URI to path"))?;
-                    let target_abs_path = op
-                        .new_uri
-                        .to_file_path()
-                        .map_err(|_| anyhow!("can't convert URI to path"))?;
-                    fs.rename(
-                        &source_abs_path,
-                        &target_abs_path,
+                if abs_path.ends_with("/") {
+                    fs.create_dir(&abs_path).await?;
+                } else {
+                    fs.create_file(
+                        &abs_path,
                         op.options
-                            .map(|options| fs::RenameOptions {
+                            .map(|options| fs::CreateOptions {
                                 overwrite: options.overwrite.unwrap_or(false),
                                 ignore_if_exists: options.ignore_if_exists.unwrap_or(false),
                             })
@@ -2306,24 +2277,144 @@ impl LocalLspStore {
                     )
                     .await?;
                 }
+            }
 
-                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Delete(op)) => {
-                    let abs_path = op
-                        .uri
-                        .to_file_path()
-                        .map_err(|_| anyhow!("can't convert URI to path"))?;
-                    let options = op
-                        .options
-                        .map(|options| fs::RemoveOptions {
-                            recursive: options.recursive.unwrap_or(false),
-                            ignore_if_not_exists: options.ignore_if_not_exists.unwrap_or(false),
+            lsp::ResourceOp::Rename(op) => {
+                let source_abs_path = op
+                    .old_uri
+                    .to_file_path()
+                    .map_err(|_| anyhow!("can't convert URI to path"))?;
+                let target_abs_path = op
+                    .new_uri
+                    .to_file_path()
+                    .map_err(|_| anyhow!("can't convert URI to path"))?;
+                fs.rename(
+                    &source_abs_path,
+                    &target_abs_path,
+                    op.options
+                        .map(|options| fs::RenameOptions {
+                            overwrite: options.overwrite.unwrap_or(false),
+                            ignore_if_exists: options.ignore_if_exists.unwrap_or(false),
                         })
-                        .unwrap_or_default();
-                    if abs_path.ends_with("/") {
-                        fs.remove_dir(&abs_path, options).await?;
-                    } else {
-                        fs.remove_file(&abs_path, options).await?;
+                        .unwrap_or_default(),
+                )
+                .await?;
+            }
+
+            lsp::ResourceOp::Delete(op) => {
+                let abs_path = op
+                    .uri
+                    .to_file_path()
+                    .map_err(|_| anyhow!("can't convert URI to path"))?;
+                let options = op
+                    .options
+                    .map(|options| fs::RemoveOptions {
+                        recursive: options.recursive.unwrap_or(false),
+                        ignore_if_not_exists: options.ignore_if_not_exists.unwrap_or(false),
+                    })
+                    .unwrap_or_default();
+                if abs_path.ends_with("/") {
+                    fs.remove_dir(&abs_path, options).await?;
+                } else {
+                    fs.remove_file(&abs_path, options).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves the text edits of a workspace edit against their buffers, without applying them.
+    ///
+    /// Resource operations are collected rather than performed, so that they wait for the edits
+    /// to be reviewed too.
+    pub(crate) async fn resolve_workspace_edit(
+        this: Model<LspStore>,
+        edit: lsp::WorkspaceEdit,
+        lsp_adapter: Arc<CachedLspAdapter>,
+        language_server: Arc<LanguageServer>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<FileOperationEdits> {
+        let mut resolved_edits = FileOperationEdits::default();
+        for operation in Self::workspace_edit_operations(edit) {
+            match operation {
+                lsp::DocumentChangeOperation::Op(op) => {
+                    resolved_edits.resource_operations.push(op);
+                }
+                lsp::DocumentChangeOperation::Edit(op) => {
+                    // A file that one of the deferred operations creates can't be opened yet.
+                    if resolved_edits.creates(&op.text_document.uri) {
+                        log::warn!(
+                            "skipping edits to {}, which doesn't exist yet",
+                            op.text_document.uri
+                        );
+                        continue;
+                    }
+
+                    let buffer_to_edit = this
+                        .update(cx, |this, cx| {
+                            this.open_local_buffer_via_lsp(
+                                op.text_document.uri.clone(),
+                                language_server.server_id(),
+                                lsp_adapter.name.clone(),
+                                cx,
+                            )
+                        })?
+                        .await?;
+
+                    let mut edits = Vec::new();
+                    for edit in op.edits {
+                        let edit = match edit {
+                            Edit::Plain(edit) => edit,
+                            Edit::Annotated(edit) => edit.text_edit,
+                            Edit::Snippet(edit) => {
+                                let Ok(snippet) = Snippet::parse(&edit.snippet.value) else {
+                                    continue;
+                                };
+                                TextEdit {
+                                    range: edit.range,
+                                    new_text: snippet.text,
+                                }
+                            }
+                        };
+                        if !edits.contains(&edit) {
+                            edits.push(edit);
+                        }
                     }
+
+                    let edits = this
+                        .update(cx, |this, cx| {
+                            this.as_local_mut().unwrap().edits_from_lsp(
+                                &buffer_to_edit,
+                                edits,
+                                language_server.server_id(),
+                                op.text_document.version,
+                                cx,
+                            )
+                        })?
+                        .await?;
+                    resolved_edits.buffer_edits.push((buffer_to_edit, edits));
+                }
+            }
+        }
+
+        Ok(resolved_edits)
+    }
+
+    pub(crate) async fn deserialize_workspace_edit(
+        this: Model<LspStore>,
+        edit: lsp::WorkspaceEdit,
+        push_to_history: bool,
+        lsp_adapter: Arc<CachedLspAdapter>,
+        language_server: Arc<LanguageServer>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<ProjectTransaction> {
+        let fs = this.read_with(cx, |this, _| this.as_local().unwrap().fs.clone())?;
+
+        let mut project_transaction = ProjectTransaction::default();
+        for operation in Self::workspace_edit_operations(edit) {
+            match operation {
+                lsp::DocumentChangeOperation::Op(op) => {
+                    Self::apply_resource_operation(fs.as_ref(), op).await?;
                 }
 
                 lsp::DocumentChangeOperation::Edit(op) => {
@@ -2845,6 +2936,11 @@ impl LspStore {
         client.add_model_request_handler(Self::handle_on_type_formatting);
         client.add_model_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_model_request_handler(Self::handle_register_buffer_with_language_servers);
+        client.add_model_request_handler(Self::handle_will_perform_file_operation);
+        client.add_model_request_handler(Self::handle_apply_resource_operations);
+        client.add_model_request_handler(Self::handle_create_project_entry);
+        client.add_model_request_handler(Self::handle_rename_project_entry);
+        client.add_model_request_handler(Self::handle_delete_project_entry);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCodeActions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetHover>);
@@ -2857,6 +2953,8 @@ impl LspStore {
         client.add_model_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_model_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
         client.add_model_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetInlineValues>);
     }
 
     pub fn as_remote(&self) -> Option<&RemoteLspStore> {
@@ -2951,7 +3049,7 @@ impl LspStore {
                 language_servers: Default::default(),
                 last_workspace_edits_by_language_server: Default::default(),
                 language_server_watched_paths: Default::default(),
-                language_server_paths_watched_for_rename: Default::default(),
+                language_server_paths_watched_for_file_operations: Default::default(),
                 language_server_watcher_registrations: Default::default(),
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
                 buffers_being_formatted: Default::default(),
@@ -6160,61 +6258,87 @@ impl LspStore {
             .map(|(key, value)| (*key, value))
     }
 
-    pub(super) fn did_rename_entry(
+    pub(super) fn did_perform_file_operation(
         &self,
         worktree_id: WorktreeId,
-        old_path: &Path,
-        new_path: &Path,
+        operation: &FileOperation,
         is_dir: bool,
     ) {
         maybe!({
             let local_store = self.as_local()?;
-
-            let old_uri = lsp::Url::from_file_path(old_path).ok().map(String::from)?;
-            let new_uri = lsp::Url::from_file_path(new_path).ok().map(String::from)?;
+            let filtered_uri = file_operation_uri(operation.filtered_path())?;
 
             for language_server in local_store.language_servers_for_worktree(worktree_id) {
                 let Some(filter) = local_store
-                    .language_server_paths_watched_for_rename
+                    .language_server_paths_watched_for_file_operations
                     .get(&language_server.server_id())
                 else {
                     continue;
                 };
 
-                if filter.should_send_did_rename(&old_uri, is_dir) {
-                    language_server
-                        .notify::<DidRenameFiles>(RenameFilesParams {
-                            files: vec![FileRename {
-                                old_uri: old_uri.clone(),
-                                new_uri: new_uri.clone(),
-                            }],
-                        })
-                        .log_err();
+                if filter.should_send_did(operation, &filtered_uri, is_dir) {
+                    match operation {
+                        FileOperation::Create { path } => {
+                            language_server.notify::<DidCreateFiles>(CreateFilesParams {
+                                files: vec![FileCreate {
+                                    uri: file_operation_uri(path)?,
+                                }],
+                            })
+                        }
+                        FileOperation::Rename { old_path, new_path } => {
+                            language_server.notify::<DidRenameFiles>(RenameFilesParams {
+                                files: vec![FileRename {
+                                    old_uri: file_operation_uri(old_path)?,
+                                    new_uri: file_operation_uri(new_path)?,
+                                }],
+                            })
+                        }
+                        FileOperation::Delete { path } => {
+                            language_server.notify::<DidDeleteFiles>(DeleteFilesParams {
+                                files: vec![FileDelete {
+                                    uri: file_operation_uri(path)?,
+                                }],
+                            })
+                        }
+                    }
+                    .log_err();
                 }
             }
             Some(())
         });
     }
 
-    pub(super) fn will_rename_entry(
+    /// Asks the language servers interested in the given file operation for the edits that should
+    /// accompany it, and resolves those edits against their buffers without applying them.
+    pub(super) fn will_perform_file_operation(
         this: WeakModel<Self>,
         worktree_id: WorktreeId,
-        old_path: &Path,
-        new_path: &Path,
+        operation: FileOperation,
         is_dir: bool,
         cx: AsyncAppContext,
-    ) -> Task<()> {
-        let old_uri = lsp::Url::from_file_path(old_path).ok().map(String::from);
-        let new_uri = lsp::Url::from_file_path(new_path).ok().map(String::from);
+    ) -> Task<FileOperationEdits> {
         cx.spawn(move |mut cx| async move {
+            let upstream_client = this.update(&mut cx, |this, _| this.upstream_client());
+            if let Ok(Some((client, project_id))) = upstream_client {
+                let request = client.request(proto::WillPerformFileOperation {
+                    project_id,
+                    worktree_id: worktree_id.to_proto(),
+                    operation: Some(operation.to_proto()),
+                    is_dir,
+                });
+                return Self::deserialize_file_operation_edits(this, request, &mut cx)
+                    .await
+                    .log_err()
+                    .unwrap_or_default();
+            }
+
             let mut tasks = vec![];
             this.update(&mut cx, |this, cx| {
                 let local_store = this.as_local()?;
-                let old_uri = old_uri?;
-                let new_uri = new_uri?;
+                let filtered_uri = file_operation_uri(operation.filtered_path())?;
                 for language_server in local_store.language_servers_for_worktree(worktree_id) {
                     let Some(filter) = local_store
-                        .language_server_paths_watched_for_rename
+                        .language_server_paths_watched_for_file_operations
                         .get(&language_server.server_id())
                     else {
                         continue;
@@ -6224,48 +6348,331 @@ impl LspStore {
                     else {
                         continue;
                     };
-                    if filter.should_send_will_rename(&old_uri, is_dir) {
-                        let apply_edit = cx.spawn({
-                            let old_uri = old_uri.clone();
-                            let new_uri = new_uri.clone();
-                            let language_server = language_server.clone();
-                            |this, mut cx| async move {
-                                let edit = language_server
-                                    .request::<WillRenameFiles>(RenameFilesParams {
-                                        files: vec![FileRename { old_uri, new_uri }],
-                                    })
-                                    .log_err()
-                                    .await
-                                    .flatten()?;
-
-                                LocalLspStore::deserialize_workspace_edit(
-                                    this.upgrade()?,
-                                    edit,
-                                    false,
-                                    adapter.clone(),
-                                    language_server.clone(),
-                                    &mut cx,
-                                )
-                                .await
-                                .ok();
-                                Some(())
-                            }
-                        });
-                        tasks.push(apply_edit);
+                    if !filter.should_send_will(&operation, &filtered_uri, is_dir) {
+                        continue;
                     }
+
+                    let request = match &operation {
+                        FileOperation::Create { path } => language_server
+                            .request::<WillCreateFiles>(CreateFilesParams {
+                                files: vec![FileCreate {
+                                    uri: file_operation_uri(path)?,
+                                }],
+                            })
+                            .boxed_local(),
+                        FileOperation::Rename { old_path, new_path } => language_server
+                            .request::<WillRenameFiles>(RenameFilesParams {
+                                files: vec![FileRename {
+                                    old_uri: file_operation_uri(old_path)?,
+                                    new_uri: file_operation_uri(new_path)?,
+                                }],
+                            })
+                            .boxed_local(),
+                        FileOperation::Delete { path } => language_server
+                            .request::<WillDeleteFiles>(DeleteFilesParams {
+                                files: vec![FileDelete {
+                                    uri: file_operation_uri(path)?,
+                                }],
+                            })
+                            .boxed_local(),
+                    };
+                    let language_server = language_server.clone();
+                    tasks.push(cx.spawn(|this, mut cx| async move {
+                        let edit = request.log_err().await.flatten()?;
+                        LocalLspStore::resolve_workspace_edit(
+                            this.upgrade()?,
+                            edit,
+                            adapter,
+                            language_server,
+                            &mut cx,
+                        )
+                        .await
+                        .log_err()
+                    }));
                 }
                 Some(())
             })
             .ok()
             .flatten();
+
+            let mut edits = FileOperationEdits::default();
             for task in tasks {
-                // Await on tasks sequentially so that the order of application of edits is deterministic
+                // Await on tasks sequentially so that the order of the edits is deterministic
                 // (at least with regards to the order of registration of language servers)
-                task.await;
+                if let Some(server_edits) = task.await {
+                    edits.buffer_edits.extend(server_edits.buffer_edits);
+                    edits
+                        .resource_operations
+                        .extend(server_edits.resource_operations);
+                }
             }
+            edits
         })
     }
 
+    async fn deserialize_file_operation_edits(
+        this: WeakModel<Self>,
+        request: impl Future<Output = Result<proto::WillPerformFileOperationResponse>>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<FileOperationEdits> {
+        let response = request.await?;
+        let mut edits = FileOperationEdits::default();
+        for buffer_edits in response.buffer_edits {
+            let buffer_id = BufferId::new(buffer_edits.buffer_id)?;
+            let buffer = this
+                .update(cx, |this, cx| this.wait_for_remote_buffer(buffer_id, cx))?
+                .await?;
+            buffer
+                .update(cx, |buffer, _| {
+                    buffer.wait_for_version(deserialize_version(&buffer_edits.version))
+                })?
+                .await?;
+            let ranges = buffer_edits
+                .edits
+                .into_iter()
+                .map(|edit| {
+                    let start = deserialize_anchor(edit.start?)?;
+                    let end = deserialize_anchor(edit.end?)?;
+                    Some((start..end, edit.new_text))
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| anyhow!("invalid file operation edit"))?;
+            edits.buffer_edits.push((buffer, ranges));
+        }
+        edits.resource_operations = response
+            .resource_operations
+            .iter()
+            .map(|operation| serde_json::from_slice(operation))
+            .collect::<Result<_, _>>()?;
+        Ok(edits)
+    }
+
+    /// Performs the resource operations that language servers requested along with the edits of
+    /// a file operation.
+    pub fn apply_resource_operations(
+        &self,
+        operations: Vec<lsp::ResourceOp>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let operations = operations
+                .iter()
+                .map(serde_json::to_vec)
+                .collect::<Result<Vec<_>, _>>();
+            cx.background_executor().spawn(async move {
+                client
+                    .request(proto::ApplyResourceOperations {
+                        project_id,
+                        operations: operations?,
+                    })
+                    .await?;
+                Ok(())
+            })
+        } else if let Some(local) = self.as_local() {
+            let fs = local.fs.clone();
+            cx.background_executor().spawn(async move {
+                for operation in operations {
+                    LocalLspStore::apply_resource_operation(fs.as_ref(), operation).await?;
+                }
+                Ok(())
+            })
+        } else {
+            Task::ready(Err(anyhow!("no upstream client and not local")))
+        }
+    }
+
+    async fn handle_will_perform_file_operation(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::WillPerformFileOperation>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::WillPerformFileOperationResponse> {
+        let peer_id = envelope.original_sender_id().unwrap_or_default();
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let operation = FileOperation::from_proto(
+            envelope
+                .payload
+                .operation
+                .ok_or_else(|| anyhow!("invalid file operation"))?,
+        )?;
+        this.update(&mut cx, |this, cx| {
+            let worktree = this
+                .worktree_store
+                .read(cx)
+                .worktree_for_id(worktree_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))?;
+            let worktree_abs_path = worktree.read(cx).abs_path();
+            if operation
+                .paths()
+                .all(|path| is_path_within(path, &worktree_abs_path))
+            {
+                Ok(())
+            } else {
+                Err(anyhow!("file operation outside of its worktree"))
+            }
+        })??;
+
+        let edits = Self::will_perform_file_operation(
+            this.downgrade(),
+            worktree_id,
+            operation,
+            envelope.payload.is_dir,
+            cx.clone(),
+        )
+        .await;
+
+        this.update(&mut cx, |this, cx| {
+            let mut buffer_edits = Vec::new();
+            for (buffer, edits) in edits.buffer_edits {
+                this.buffer_store
+                    .update(cx, |buffer_store, cx| {
+                        buffer_store.create_buffer_for_peer(&buffer, peer_id, cx)
+                    })
+                    .detach_and_log_err(cx);
+                let buffer = buffer.read(cx);
+                buffer_edits.push(proto::FileOperationBufferEdits {
+                    buffer_id: buffer.remote_id().into(),
+                    version: serialize_version(&buffer.version()),
+                    edits: edits
+                        .into_iter()
+                        .map(|(range, new_text)| proto::FileOperationEdit {
+                            start: Some(serialize_anchor(&range.start)),
+                            end: Some(serialize_anchor(&range.end)),
+                            new_text,
+                        })
+                        .collect(),
+                });
+            }
+            Ok(proto::WillPerformFileOperationResponse {
+                buffer_edits,
+                resource_operations: edits
+                    .resource_operations
+                    .iter()
+                    .map(serde_json::to_vec)
+                    .collect::<Result<_, _>>()?,
+            })
+        })?
+    }
+
+    async fn handle_apply_resource_operations(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ApplyResourceOperations>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let operations = envelope
+            .payload
+            .operations
+            .iter()
+            .map(|operation| serde_json::from_slice::<lsp::ResourceOp>(operation))
+            .collect::<Result<Vec<_>, _>>()?;
+        this.update(&mut cx, |this, cx| {
+            // Peers can only touch the files of the project.
+            let worktree_store = this.worktree_store.read(cx);
+            for operation in &operations {
+                let uris = match operation {
+                    lsp::ResourceOp::Create(create) => vec![&create.uri],
+                    lsp::ResourceOp::Rename(rename) => vec![&rename.old_uri, &rename.new_uri],
+                    lsp::ResourceOp::Delete(delete) => vec![&delete.uri],
+                };
+                for uri in uris {
+                    let path = uri
+                        .to_file_path()
+                        .map_err(|_| anyhow!("can't convert URI to path"))?;
+                    let is_in_project = worktree_store
+                        .visible_worktrees(cx)
+                        .any(|worktree| is_path_within(&path, &worktree.read(cx).abs_path()));
+                    if !is_in_project {
+                        return Err(anyhow!("resource operation outside of the project"));
+                    }
+                }
+            }
+            Ok(this.apply_resource_operations(operations, cx))
+        })??
+        .await?;
+        Ok(proto::Ack {})
+    }
+
+    // The requesting peer asks for the edits accompanying these operations beforehand, through
+    // `WillPerformFileOperation`, so that it can review them. Only the notifications that follow
+    // the operations are left to send here.
+
+    pub async fn handle_create_project_entry(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::CreateProjectEntry>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ProjectEntryResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let is_dir = envelope.payload.is_directory;
+        let (worktree, operation) = this.update(&mut cx, |this, cx| {
+            let worktree = this
+                .worktree_store
+                .read(cx)
+                .worktree_for_id(worktree_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))?;
+            let path = worktree
+                .read(cx)
+                .absolutize(Path::new(&envelope.payload.path))?;
+            anyhow::Ok((worktree, FileOperation::Create { path }))
+        })??;
+        let response =
+            Worktree::handle_create_entry(worktree, envelope.payload, cx.clone()).await?;
+        this.update(&mut cx, |this, _| {
+            this.did_perform_file_operation(worktree_id, &operation, is_dir)
+        })?;
+        Ok(response)
+    }
+
+    pub async fn handle_rename_project_entry(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::RenameProjectEntry>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ProjectEntryResponse> {
+        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
+        let (worktree_id, worktree, operation, is_dir) = this.update(&mut cx, |this, cx| {
+            let (worktree, entry) = this
+                .worktree_store
+                .read(cx)
+                .worktree_and_entry_for_id(entry_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))?;
+            let root_path = worktree.read(cx).abs_path();
+            let operation = FileOperation::Rename {
+                old_path: root_path.join(&entry.path),
+                new_path: root_path.join(&envelope.payload.new_path),
+            };
+            anyhow::Ok((worktree.read(cx).id(), worktree, operation, entry.is_dir()))
+        })??;
+        let response =
+            Worktree::handle_rename_entry(worktree, envelope.payload, cx.clone()).await?;
+        this.update(&mut cx, |this, _| {
+            this.did_perform_file_operation(worktree_id, &operation, is_dir)
+        })?;
+        Ok(response)
+    }
+
+    pub async fn handle_delete_project_entry(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::DeleteProjectEntry>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ProjectEntryResponse> {
+        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
+        let (worktree_id, worktree, operation, is_dir) = this.update(&mut cx, |this, cx| {
+            let (worktree, entry) = this
+                .worktree_store
+                .read(cx)
+                .worktree_and_entry_for_id(entry_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))?;
+            let operation = FileOperation::Delete {
+                path: worktree.read(cx).abs_path().join(&entry.path),
+            };
+            anyhow::Ok((worktree.read(cx).id(), worktree, operation, entry.is_dir()))
+        })??;
+        let response =
+            Worktree::handle_delete_entry(worktree, envelope.payload, cx.clone()).await?;
+        this.update(&mut cx, |this, _| {
+            this.did_perform_file_operation(worktree_id, &operation, is_dir)
+        })?;
+        Ok(response)
+    }
+
     fn lsp_notify_abs_paths_changed(
         &mut self,
         server_id: LanguageServerId,
@@ -7402,14 +7809,10 @@ impl LspStore {
             .as_ref()
             .and_then(|ws| ws.file_operations.as_ref())
         {
-            let did_rename_caps = file_ops_caps.did_rename.as_ref();
-            let will_rename_caps = file_ops_caps.will_rename.as_ref();
-            if did_rename_caps.or(will_rename_caps).is_some() {
-                let watcher = RenamePathsWatchedForServer::default()
-                    .with_did_rename_patterns(did_rename_caps)
-                    .with_will_rename_patterns(will_rename_caps);
+            let watcher = FileOperationsWatchedForServer::new(file_ops_caps);
+            if !watcher.is_empty() {
                 local
-                    .language_server_paths_watched_for_rename
+                    .language_server_paths_watched_for_file_operations
                     .insert(server_id, watcher);
             }
         }
@@ -7953,49 +8356,230 @@ pub enum LanguageServerToQuery {
     Other(LanguageServerId),
 }
 
-#[derive(Default)]
-struct RenamePathsWatchedForServer {
-    did_rename: Vec<RenameActionPredicate>,
-    will_rename: Vec<RenameActionPredicate>,
+/// A change to the file system that language servers can react to, through the
+/// `workspace/will{Create,Rename,Delete}Files` requests and the matching `did` notifications.
+#[derive(Clone, Debug)]
+pub(crate) enum FileOperation {
+    Create {
+        path: PathBuf,
+    },
+    Rename {
+        old_path: PathBuf,
+        new_path: PathBuf,
+    },
+    Delete {
+        path: PathBuf,
+    },
 }
 
-impl RenamePathsWatchedForServer {
-    fn with_did_rename_patterns(
-        mut self,
-        did_rename: Option<&FileOperationRegistrationOptions>,
-    ) -> Self {
-        if let Some(did_rename) = did_rename {
-            self.did_rename = did_rename
-                .filters
-                .iter()
-                .filter_map(|filter| filter.try_into().log_err())
-                .collect();
+impl FileOperation {
+    /// The path matched against the file operation filters registered by language servers.
+    fn filtered_path(&self) -> &Path {
+        match self {
+            FileOperation::Create { path } | FileOperation::Delete { path } => path,
+            FileOperation::Rename { old_path, .. } => old_path,
         }
-        self
     }
-    fn with_will_rename_patterns(
-        mut self,
-        will_rename: Option<&FileOperationRegistrationOptions>,
-    ) -> Self {
-        if let Some(will_rename) = will_rename {
-            self.will_rename = will_rename
-                .filters
-                .iter()
-                .filter_map(|filter| filter.try_into().log_err())
-                .collect();
+
+    /// A short description of the operation, used to title the review of its edits.
+    pub(crate) fn title(&self) -> String {
+        fn file_name(path: &Path) -> String {
+            path.file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .into_owned()
+        }
+
+        match self {
+            FileOperation::Create { path } => format!("Create {}", file_name(path)),
+            FileOperation::Rename { old_path, new_path } => {
+                format!("Rename {} to {}", file_name(old_path), file_name(new_path))
+            }
+            FileOperation::Delete { path } => format!("Delete {}", file_name(path)),
+        }
+    }
+
+    /// The paths that the operation touches.
+    fn paths(&self) -> impl Iterator<Item = &Path> {
+        let (first, second) = match self {
+            FileOperation::Create { path } | FileOperation::Delete { path } => (path, None),
+            FileOperation::Rename { old_path, new_path } => (old_path, Some(new_path)),
+        };
+        iter::once(first.as_path()).chain(second.map(PathBuf::as_path))
+    }
+
+    fn to_proto(&self) -> proto::FileOperation {
+        let variant = match self {
+            FileOperation::Create { path } => {
+                proto::file_operation::Variant::Create(proto::file_operation::Create {
+                    path: path.to_string_lossy().into(),
+                })
+            }
+            FileOperation::Rename { old_path, new_path } => {
+                proto::file_operation::Variant::Rename(proto::file_operation::Rename {
+                    old_path: old_path.to_string_lossy().into(),
+                    new_path: new_path.to_string_lossy().into(),
+                })
+            }
+            FileOperation::Delete { path } => {
+                proto::file_operation::Variant::Delete(proto::file_operation::Delete {
+                    path: path.to_string_lossy().into(),
+                })
+            }
+        };
+        proto::FileOperation {
+            variant: Some(variant),
+        }
+    }
+
+    fn from_proto(operation: proto::FileOperation) -> Result<Self> {
+        Ok(
+            match operation
+                .variant
+                .ok_or_else(|| anyhow!("invalid file operation"))?
+            {
+                proto::file_operation::Variant::Create(create) => FileOperation::Create {
+                    path: create.path.into(),
+                },
+                proto::file_operation::Variant::Rename(rename) => FileOperation::Rename {
+                    old_path: rename.old_path.into(),
+                    new_path: rename.new_path.into(),
+                },
+                proto::file_operation::Variant::Delete(delete) => FileOperation::Delete {
+                    path: delete.path.into(),
+                },
+            },
+        )
+    }
+}
+
+/// Whether the path is within the given directory, without leaving it through `..` components.
+fn is_path_within(path: &Path, dir: &Path) -> bool {
+    path.strip_prefix(dir).map_or(false, |relative_path| {
+        relative_path
+            .components()
+            .all(|component| matches!(component, path::Component::Normal(_)))
+    })
+}
+
+fn file_operation_uri(path: &Path) -> Option<String> {
+    lsp::Url::from_file_path(path).ok().map(String::from)
+}
+
+/// Edits that language servers requested in response to a file operation.
+///
+/// The edits are resolved against their buffers but not applied, so that they can be reviewed first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FileOperationEdits {
+    pub buffer_edits: Vec<(Model<Buffer>, Vec<(Range<Anchor>, String)>)>,
+    /// Files to create, rename or delete along with the buffer edits. They are only performed
+    /// through [`LspStore::apply_resource_operations`], once the user agreed to them.
+    pub resource_operations: Vec<lsp::ResourceOp>,
+}
+
+impl FileOperationEdits {
+    pub fn is_empty(&self) -> bool {
+        self.buffer_edits.iter().all(|(_, edits)| edits.is_empty())
+            && self.resource_operations.is_empty()
+    }
+
+    /// Whether one of the resource operations creates the file at the given URI.
+    fn creates(&self, uri: &lsp::Url) -> bool {
+        self.resource_operations
+            .iter()
+            .any(|operation| match operation {
+                lsp::ResourceOp::Create(create) => &create.uri == uri,
+                lsp::ResourceOp::Rename(rename) => &rename.new_uri == uri,
+                lsp::ResourceOp::Delete(_) => false,
+            })
+    }
+
+    /// Applies the buffer edits, grouping the edits of each buffer into a single transaction.
+    pub fn apply(&self, cx: &mut AppContext) -> ProjectTransaction {
+        let mut project_transaction = ProjectTransaction::default();
+        for (buffer, edits) in &self.buffer_edits {
+            let transaction = buffer.update(cx, |buffer, cx| {
+                buffer.finalize_last_transaction();
+                buffer.start_transaction();
+                for (range, text) in edits {
+                    buffer.edit([(range.clone(), text.clone())], None, cx);
+                }
+                buffer.end_transaction(cx)?;
+                buffer.finalize_last_transaction().cloned()
+            });
+            if let Some(transaction) = transaction {
+                project_transaction.0.insert(buffer.clone(), transaction);
+            }
         }
-        self
+        project_transaction
     }
+}
 
-    fn should_send_did_rename(&self, path: &str, is_dir: bool) -> bool {
-        self.did_rename.iter().any(|pred| pred.eval(path, is_dir))
+#[derive(Default)]
+struct FileOperationsWatchedForServer {
+    did_create: Vec<FileOperationPredicate>,
+    will_create: Vec<FileOperationPredicate>,
+    did_rename: Vec<FileOperationPredicate>,
+    will_rename: Vec<FileOperationPredicate>,
+    did_delete: Vec<FileOperationPredicate>,
+    will_delete: Vec<FileOperationPredicate>,
+}
+
+impl FileOperationsWatchedForServer {
+    fn new(capabilities: &lsp::WorkspaceFileOperationsServerCapabilities) -> Self {
+        fn predicates(
+            registration: Option<&FileOperationRegistrationOptions>,
+        ) -> Vec<FileOperationPredicate> {
+            registration
+                .map(|registration| {
+                    registration
+                        .filters
+                        .iter()
+                        .filter_map(|filter| filter.try_into().log_err())
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        Self {
+            did_create: predicates(capabilities.did_create.as_ref()),
+            will_create: predicates(capabilities.will_create.as_ref()),
+            did_rename: predicates(capabilities.did_rename.as_ref()),
+            will_rename: predicates(capabilities.will_rename.as_ref()),
+            did_delete: predicates(capabilities.did_delete.as_ref()),
+            will_delete: predicates(capabilities.will_delete.as_ref()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.did_create.is_empty()
+            && self.will_create.is_empty()
+            && self.did_rename.is_empty()
+            && self.will_rename.is_empty()
+            && self.did_delete.is_empty()
+            && self.will_delete.is_empty()
     }
-    fn should_send_will_rename(&self, path: &str, is_dir: bool) -> bool {
-        self.will_rename.iter().any(|pred| pred.eval(path, is_dir))
+
+    fn should_send_did(&self, operation: &FileOperation, path: &str, is_dir: bool) -> bool {
+        let predicates = match operation {
+            FileOperation::Create { .. } => &self.did_create,
+            FileOperation::Rename { .. } => &self.did_rename,
+            FileOperation::Delete { .. } => &self.did_delete,
+        };
+        predicates.iter().any(|pred| pred.eval(path, is_dir))
+    }
+
+    fn should_send_will(&self, operation: &FileOperation, path: &str, is_dir: bool) -> bool {
+        let predicates = match operation {
+            FileOperation::Create { .. } => &self.will_create,
+            FileOperation::Rename { .. } => &self.will_rename,
+            FileOperation::Delete { .. } => &self.will_delete,
+        };
+        predicates.iter().any(|pred| pred.eval(path, is_dir))
     }
 }
 
-impl TryFrom<&FileOperationFilter> for RenameActionPredicate {
+impl TryFrom<&FileOperationFilter> for FileOperationPredicate {
     type Error = globset::Error;
     fn try_from(ops: &FileOperationFilter) -> Result<Self, globset::Error> {
         Ok(Self {
@@ -8012,12 +8596,12 @@ impl TryFrom<&FileOperationFilter> for RenameActionPredicate {
         })
     }
 }
-struct RenameActionPredicate {
+struct FileOperationPredicate {
     glob: GlobMatcher,
     kind: Option<FileOperationPatternKind>,
 }
 
-impl RenameActionPredicate {
+impl FileOperationPredicate {
     // Returns true if language server should be notified
     fn eval(&self, path: &str, is_dir: bool) -> bool {
         self.kind.as_ref().map_or(true, |kind| {
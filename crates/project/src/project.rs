@@ -46,16 +46,17 @@ use gpui::{
 };
 use itertools::Itertools;
 use language::{
-    language_settings::InlayHintKind, proto::split_operations, Buffer, BufferEvent,
-    CachedLspAdapter, Capability, CodeLabel, Documentation, File as _, Language, LanguageName,
-    LanguageRegistry, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList, Transaction,
-    Unclipped,
+    language_settings::InlayHintKind, proto::split_operations, AvailableLanguage, Buffer,
+    BufferEvent, BufferSnapshot, CachedLspAdapter, Capability, CodeLabel, Documentation, File as _,
+    Language, LanguageName, LanguageRegistry, ParseStatus, PointUtf16, ToOffset, ToPointUtf16,
+    Toolchain, ToolchainList, Transaction, Unclipped,
 };
 use lsp::{
     CodeActionKind, CompletionContext, CompletionItemKind, DocumentHighlightKind, LanguageServer,
     LanguageServerId, LanguageServerName, MessageActionItem,
 };
 use lsp_command::*;
+use lsp_store::FileOperation;
 use node_runtime::NodeRuntime;
 use parking_lot::Mutex;
 pub use prettier_store::PrettierStore;
@@ -102,9 +103,9 @@ pub use worktree::{
 
 pub use buffer_store::ProjectTransaction;
 pub use lsp_store::{
-    DiagnosticSummary, LanguageServerLogType, LanguageServerProgress, LanguageServerPromptRequest,
-    LanguageServerStatus, LanguageServerToQuery, LspStore, LspStoreEvent,
-    SERVER_PROGRESS_THROTTLE_TIMEOUT,
+    DiagnosticSummary, FileOperationEdits, LanguageServerLogType, LanguageServerProgress,
+    LanguageServerPromptRequest, LanguageServerStatus, LanguageServerToQuery, LspStore,
+    LspStoreEvent, SERVER_PROGRESS_THROTTLE_TIMEOUT,
 };
 pub use toolchain_store::ToolchainStore;
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
@@ -146,6 +147,9 @@ pub struct Project {
     user_store: Model<UserStore>,
     fs: Arc<dyn Fs>,
     ssh_client: Option<Model<SshRemoteClient>>,
+    /// The versions of the open buffers when the connection to the remote server was lost, while
+    /// it's being reestablished. Local edits made in the meantime are sent once reconnected.
+    ssh_offline_buffer_versions: Option<HashMap<BufferId, clock::Global>>,
     client_state: ProjectClientState,
     collaborators: HashMap<proto::PeerId, Collaborator>,
     client_subscriptions: Vec<client::Subscription>,
@@ -260,6 +264,12 @@ pub enum Event {
     DisconnectedFromSshRemote,
     Closed,
     DeletedEntry(WorktreeId, ProjectEntryId),
+    /// Language servers requested edits in response to a file being created, renamed or deleted.
+    /// The edits are not applied, so that they can be reviewed first.
+    FileOperationEditsProposed {
+        title: String,
+        edits: FileOperationEdits,
+    },
     CollaboratorUpdated {
         old_peer_id: proto::PeerId,
         new_peer_id: proto::PeerId,
@@ -410,6 +420,28 @@ impl InlayHint {
     }
 }
 
+/// A value to display next to a range of a buffer, as reported by a language server's
+/// `textDocument/inlineValue`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineValue {
+    pub range: Range<language::Anchor>,
+    pub kind: InlineValueKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InlineValueKind {
+    /// Text to display as is.
+    Text(String),
+    /// A variable whose value has to be looked up, either by the given name or by the text of
+    /// the range.
+    VariableLookup {
+        variable_name: Option<String>,
+        case_sensitive: bool,
+    },
+    /// An expression to evaluate, either the given one or the text of the range.
+    EvaluatableExpression { expression: Option<String> },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InlayHintLabel {
     String(String),
@@ -579,6 +611,9 @@ impl Project {
         client.add_model_request_handler(Self::handle_update_buffer);
         client.add_model_message_handler(Self::handle_update_worktree);
         client.add_model_request_handler(Self::handle_synchronize_buffers);
+        client.add_model_message_handler(Self::handle_update_shared_terminal);
+        client.add_model_message_handler(Self::handle_unshare_terminal);
+        client.add_model_request_handler(Self::handle_shared_terminal_input);
 
         client.add_model_request_handler(Self::handle_search_candidate_buffers);
         client.add_model_request_handler(Self::handle_open_buffer_by_id);
@@ -586,8 +621,6 @@ impl Project {
         client.add_model_request_handler(Self::handle_open_new_buffer);
         client.add_model_message_handler(Self::handle_create_buffer_for_peer);
 
-        client.add_model_request_handler(WorktreeStore::handle_rename_project_entry);
-
         WorktreeStore::init(&client);
         BufferStore::init(&client);
         LspStore::init(&client);
@@ -698,11 +731,10 @@ impl Project {
                 settings_observer,
                 fs,
                 ssh_client: None,
+                ssh_offline_buffer_versions: None,
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
                 node: Some(node),
                 search_history: Self::new_search_history(),
                 environment,
@@ -794,7 +826,11 @@ impl Project {
             cx.subscribe(&lsp_store, Self::on_lsp_store_event).detach();
 
             cx.subscribe(&ssh, Self::on_ssh_event).detach();
-            cx.observe(&ssh, |_, _, cx| cx.notify()).detach();
+            cx.observe(&ssh, |this, ssh, cx| {
+                this.on_ssh_connection_state_changed(ssh.read(cx).connection_state(), cx);
+                cx.notify();
+            })
+            .detach();
 
             let this = Self {
                 buffer_ordered_messages_tx: tx,
@@ -831,11 +867,10 @@ impl Project {
                 settings_observer,
                 fs,
                 ssh_client: Some(ssh.clone()),
+                ssh_offline_buffer_versions: None,
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
                 node: Some(node),
                 search_history: Self::new_search_history(),
                 environment,
@@ -1026,6 +1061,7 @@ impl Project {
                 snippets,
                 fs,
                 ssh_client: None,
+                ssh_offline_buffer_versions: None,
                 settings_observer: settings_observer.clone(),
                 client_subscriptions: Default::default(),
                 _subscriptions: vec![cx.on_release(Self::release)],
@@ -1038,9 +1074,7 @@ impl Project {
                 },
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
                 node: None,
                 search_history: Self::new_search_history(),
                 search_included_history: Self::new_search_history(),
@@ -1465,8 +1499,33 @@ impl Project {
                 "No worktree for path {project_path:?}"
             ))));
         };
-        worktree.update(cx, |worktree, cx| {
-            worktree.create_entry(project_path.path, is_directory, cx)
+
+        let worktree_id = project_path.worktree_id;
+        let operation = FileOperation::Create {
+            path: worktree.read(cx).abs_path().join(&project_path.path),
+        };
+        let lsp_store = self.lsp_store().downgrade();
+        cx.spawn(|this, mut cx| async move {
+            let edits = LspStore::will_perform_file_operation(
+                lsp_store,
+                worktree_id,
+                operation.clone(),
+                is_directory,
+                cx.clone(),
+            )
+            .await;
+
+            let entry = worktree
+                .update(&mut cx, |worktree, cx| {
+                    worktree.create_entry(project_path.path, is_directory, cx)
+                })?
+                .await?;
+
+            this.update(&mut cx, |this, cx| {
+                this.did_perform_file_operation(worktree_id, operation, is_directory, edits, cx);
+            })
+            .ok();
+            Ok(entry)
         })
     }
 
@@ -1502,17 +1561,17 @@ impl Project {
 
         let worktree_id = worktree.read(cx).id();
 
+        let root_path = worktree.read(cx).abs_path();
+        let operation = FileOperation::Rename {
+            old_path: root_path.join(&old_path),
+            new_path: root_path.join(&new_path),
+        };
         let lsp_store = self.lsp_store().downgrade();
-        cx.spawn(|_, mut cx| async move {
-            let (old_abs_path, new_abs_path) = {
-                let root_path = worktree.update(&mut cx, |this, _| this.abs_path())?;
-                (root_path.join(&old_path), root_path.join(&new_path))
-            };
-            LspStore::will_rename_entry(
-                lsp_store.clone(),
+        cx.spawn(|this, mut cx| async move {
+            let edits = LspStore::will_perform_file_operation(
+                lsp_store,
                 worktree_id,
-                &old_abs_path,
-                &new_abs_path,
+                operation.clone(),
                 is_dir,
                 cx.clone(),
             )
@@ -1524,11 +1583,10 @@ impl Project {
                 })?
                 .await?;
 
-            lsp_store
-                .update(&mut cx, |this, _| {
-                    this.did_rename_entry(worktree_id, &old_abs_path, &new_abs_path, is_dir);
-                })
-                .ok();
+            this.update(&mut cx, |this, cx| {
+                this.did_perform_file_operation(worktree_id, operation, is_dir, edits, cx);
+            })
+            .ok();
             Ok(entry)
         })
     }
@@ -1539,11 +1597,62 @@ impl Project {
         trash: bool,
         cx: &mut ModelContext<Self>,
     ) -> Option<Task<Result<()>>> {
-        let worktree = self.worktree_for_entry(entry_id, cx)?;
-        cx.emit(Event::DeletedEntry(worktree.read(cx).id(), entry_id));
-        worktree.update(cx, |worktree, cx| {
-            worktree.delete_entry(entry_id, trash, cx)
-        })
+        let (worktree, entry) = self
+            .worktree_store
+            .read(cx)
+            .worktree_and_entry_for_id(entry_id, cx)?;
+        let worktree_id = worktree.read(cx).id();
+        let is_dir = entry.is_dir();
+        let operation = FileOperation::Delete {
+            path: worktree.read(cx).abs_path().join(&entry.path),
+        };
+        cx.emit(Event::DeletedEntry(worktree_id, entry_id));
+
+        let lsp_store = self.lsp_store().downgrade();
+        Some(cx.spawn(|this, mut cx| async move {
+            let edits = LspStore::will_perform_file_operation(
+                lsp_store,
+                worktree_id,
+                operation.clone(),
+                is_dir,
+                cx.clone(),
+            )
+            .await;
+
+            worktree
+                .update(&mut cx, |worktree, cx| {
+                    worktree.delete_entry(entry_id, trash, cx)
+                })?
+                .ok_or_else(|| anyhow!("no entry to delete"))?
+                .await?;
+
+            this.update(&mut cx, |this, cx| {
+                this.did_perform_file_operation(worktree_id, operation, is_dir, edits, cx);
+            })
+            .ok();
+            Ok(())
+        }))
+    }
+
+    /// Notifies the language servers about a completed file operation, and proposes the edits
+    /// they requested ahead of it for review.
+    fn did_perform_file_operation(
+        &mut self,
+        worktree_id: WorktreeId,
+        operation: FileOperation,
+        is_dir: bool,
+        edits: FileOperationEdits,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.lsp_store
+            .read(cx)
+            .did_perform_file_operation(worktree_id, &operation, is_dir);
+        if !edits.is_empty() {
+            cx.emit(Event::FileOperationEditsProposed {
+                title: operation.title(),
+                edits,
+            });
+        }
     }
 
     pub fn expand_entry(
@@ -1616,6 +1725,7 @@ impl Project {
         self.worktree_store.update(cx, |worktree_store, cx| {
             worktree_store.send_project_updates(cx);
         });
+        self.send_shared_terminal_updates(cx);
         cx.notify();
         cx.emit(Event::Reshared);
         Ok(())
@@ -1678,6 +1788,7 @@ impl Project {
             self.settings_observer.update(cx, |settings_observer, cx| {
                 settings_observer.unshared(cx);
             });
+            self.forget_shared_terminals(cx);
 
             self.client
                 .send(proto::UnshareProject {
@@ -1734,6 +1845,7 @@ impl Project {
             });
             self.lsp_store
                 .update(cx, |lsp_store, _cx| lsp_store.disconnected_from_host());
+            self.forget_shared_terminals(cx);
         }
     }
 
@@ -2252,6 +2364,130 @@ impl Project {
         }
     }
 
+    fn on_ssh_connection_state_changed(
+        &mut self,
+        state: remote::ConnectionState,
+        cx: &mut ModelContext<Self>,
+    ) {
+        match state {
+            remote::ConnectionState::HeartbeatMissed | remote::ConnectionState::Reconnecting => {
+                if self.ssh_offline_buffer_versions.is_none() {
+                    let versions = self
+                        .buffer_store
+                        .read(cx)
+                        .buffers()
+                        .map(|buffer| {
+                            let buffer = buffer.read(cx);
+                            (buffer.remote_id(), buffer.version())
+                        })
+                        .collect();
+                    self.ssh_offline_buffer_versions = Some(versions);
+                }
+            }
+            remote::ConnectionState::Connected => {
+                if let Some(offline_versions) = self.ssh_offline_buffer_versions.take() {
+                    self.synchronize_ssh_buffers(offline_versions, cx)
+                        .detach_and_log_err(cx);
+                }
+            }
+            remote::ConnectionState::Connecting | remote::ConnectionState::Disconnected => {}
+        }
+    }
+
+    /// Sends the remote server the operations it's missing after a reconnect, including those of
+    /// the edits made while reconnecting, and warns about the buffers that were also edited on the
+    /// server in the meantime. Concurrent edits are merged, but may not make sense together.
+    fn synchronize_ssh_buffers(
+        &mut self,
+        offline_versions: HashMap<BufferId, clock::Global>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(ssh_client) = self.ssh_client.as_ref() else {
+            return Task::ready(Ok(()));
+        };
+        let client = ssh_client.read(cx).proto_client();
+        let host = ssh_client.read(cx).connection_options().host;
+        cx.spawn(move |this, mut cx| async move {
+            let (buffers, _) = this.update(&mut cx, |this, cx| {
+                this.buffer_store.read(cx).buffer_version_info(cx)
+            })?;
+            let response = client
+                .request(proto::SynchronizeBuffers {
+                    project_id: SSH_PROJECT_ID,
+                    buffers,
+                })
+                .await?;
+
+            let (send_updates, conflicting_paths) = this.update(&mut cx, |this, cx| {
+                let mut conflicting_paths = Vec::new();
+                let send_updates = response
+                    .buffers
+                    .into_iter()
+                    .filter_map(|remote_buffer| {
+                        let buffer_id = BufferId::new(remote_buffer.id).log_err()?;
+                        let buffer = this.buffer_for_id(buffer_id, cx)?;
+                        let buffer = buffer.read(cx);
+                        let remote_version =
+                            language::proto::deserialize_version(&remote_buffer.version);
+
+                        if let Some(offline_version) = offline_versions.get(&buffer_id) {
+                            let replica_id = buffer.replica_id();
+                            let edited_locally =
+                                buffer.version().get(replica_id) > offline_version.get(replica_id);
+                            let edited_remotely = remote_version.iter().any(|timestamp| {
+                                timestamp.replica_id != replica_id
+                                    && timestamp.value > offline_version.get(timestamp.replica_id)
+                            });
+                            if edited_locally && edited_remotely {
+                                if let Some(file) = buffer.file() {
+                                    conflicting_paths.push(file.full_path(cx));
+                                }
+                            }
+                        }
+
+                        let operations = buffer.serialize_ops(Some(remote_version), cx);
+                        let client = client.clone();
+                        Some(cx.background_executor().spawn(async move {
+                            let operations = operations.await;
+                            for chunk in split_operations(operations) {
+                                client
+                                    .request(proto::UpdateBuffer {
+                                        project_id: SSH_PROJECT_ID,
+                                        buffer_id: buffer_id.into(),
+                                        operations: chunk,
+                                    })
+                                    .await?;
+                            }
+                            anyhow::Ok(())
+                        }))
+                    })
+                    .collect::<Vec<_>>();
+                (send_updates, conflicting_paths)
+            })?;
+
+            if !conflicting_paths.is_empty() {
+                let paths = conflicting_paths
+                    .iter()
+                    .map(|path| path.to_string_lossy())
+                    .join(", ");
+                let message = format!(
+                    "Edits made while reconnecting to {host} were merged with server changes to {paths}"
+                );
+                this.update(&mut cx, |_, cx| {
+                    cx.emit(Event::Toast {
+                        notification_id: "ssh-offline-edits".into(),
+                        message,
+                    })
+                })?;
+            }
+
+            futures::future::join_all(send_updates)
+                .await
+                .into_iter()
+                .collect()
+        })
+    }
+
     fn on_settings_observer_event(
         &mut self,
         _: Model<SettingsObserver>,
@@ -2367,7 +2603,13 @@ impl Project {
             } => {
                 let operation = language::proto::serialize_operation(operation);
 
-                if let Some(ssh) = &self.ssh_client {
+                // While reconnecting, the operations are only kept in the buffer's history, and
+                // sent once the buffers are resynchronized.
+                if let Some(ssh) = self
+                    .ssh_client
+                    .as_ref()
+                    .filter(|_| self.ssh_offline_buffer_versions.is_none())
+                {
                     ssh.read(cx)
                         .proto_client()
                         .send(proto::UpdateBuffer {
@@ -2757,6 +2999,42 @@ impl Project {
         self.document_highlights_impl(buffer, position, cx)
     }
 
+    /// Returns the chain of selection ranges that the primary language server reports for each
+    /// of the given positions, ordered from the innermost range to the outermost one.
+    pub fn selection_ranges(
+        &mut self,
+        buffer: &Model<Buffer>,
+        positions: Vec<Anchor>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Vec<Range<Anchor>>>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
+    /// Returns the inline values that the primary language server reports for the given range
+    /// of the buffer, for execution stopped at `stopped_location`.
+    pub fn inline_values(
+        &mut self,
+        buffer: &Model<Buffer>,
+        range: Range<Anchor>,
+        stopped_location: Range<Anchor>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<InlineValue>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetInlineValues {
+                range,
+                stopped_location,
+            },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut ModelContext<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -2996,6 +3274,8 @@ impl Project {
             self.find_search_candidate_buffers(&query, MAX_SEARCH_RESULT_FILES + 1, cx)
         };
 
+        let languages = self.languages.clone();
+        let lsp_store = self.lsp_store.downgrade();
         cx.spawn(|_, cx| async move {
             let mut range_count = 0;
             let mut buffer_count = 0;
@@ -3012,19 +3292,31 @@ impl Project {
                 for buffer in matching_buffer_chunk {
                     let buffer = buffer.clone();
                     let query = query.clone();
-                    let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
-                    chunk_results.push(cx.background_executor().spawn(async move {
-                        let ranges = query
-                            .search(&snapshot, None)
-                            .await
-                            .iter()
-                            .map(|range| {
-                                snapshot.anchor_before(range.start)
-                                    ..snapshot.anchor_after(range.end)
+                    let languages = languages.clone();
+                    let lsp_store = lsp_store.clone();
+                    let cx = cx.clone();
+                    chunk_results.push(async move {
+                        let snapshot = if query.is_structural() {
+                            Self::parsed_buffer_snapshot(&buffer, &languages, &lsp_store, &cx)
+                                .await?
+                        } else {
+                            buffer.read_with(&cx, |buffer, _| buffer.snapshot())?
+                        };
+                        cx.background_executor()
+                            .spawn(async move {
+                                let ranges = query
+                                    .search(&snapshot, None)
+                                    .await
+                                    .iter()
+                                    .map(|range| {
+                                        snapshot.anchor_before(range.start)
+                                            ..snapshot.anchor_after(range.end)
+                                    })
+                                    .collect::<Vec<_>>();
+                                anyhow::Ok((buffer, ranges))
                             })
-                            .collect::<Vec<_>>();
-                        anyhow::Ok((buffer, ranges))
-                    }));
+                            .await
+                    });
                 }
 
                 let chunk_results = futures::future::join_all(chunk_results).await;
@@ -3056,6 +3348,131 @@ impl Project {
         result_rx
     }
 
+    /// Opens the buffer for the given path and returns its snapshot once its syntax tree is up to
+    /// date, e.g. to list the symbols of its outline.
+    pub fn open_parsed_buffer(
+        &mut self,
+        path: impl Into<ProjectPath>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<BufferSnapshot>> {
+        let open_buffer = self.open_buffer(path, cx);
+        let languages = self.languages.clone();
+        let lsp_store = self.lsp_store.downgrade();
+        cx.spawn(|_, cx| async move {
+            let buffer = open_buffer.await?;
+            Self::parsed_buffer_snapshot(&buffer, &languages, &lsp_store, &cx).await
+        })
+    }
+
+    /// Loads the languages of the files searched by the query, which structural search patterns
+    /// are validated against. Files that aren't open are detected by their path.
+    pub fn searched_languages(
+        &self,
+        query: &SearchQuery,
+        cx: &AppContext,
+    ) -> Task<Vec<Arc<Language>>> {
+        let mut languages = Vec::<Arc<Language>>::new();
+        let mut available_languages = Vec::<AvailableLanguage>::new();
+        let buffers = match query.buffers() {
+            Some(buffers) => buffers.clone(),
+            None => self.opened_buffers(cx),
+        };
+        for buffer in buffers {
+            let buffer = buffer.read(cx);
+            if buffer
+                .file()
+                .map_or(false, |file| !query.file_matches(file.path()))
+            {
+                continue;
+            }
+            if let Some(language) = buffer.language() {
+                if !languages
+                    .iter()
+                    .any(|known| known.name() == language.name())
+                {
+                    languages.push(language.clone());
+                }
+            }
+        }
+
+        if !query.is_opened_only() {
+            let worktrees = self.visible_worktrees(cx).collect::<Vec<_>>();
+            let include_root = worktrees.len() > 1;
+            for worktree in worktrees {
+                let snapshot = worktree.read(cx).snapshot();
+                for entry in snapshot.files(query.include_ignored(), 0) {
+                    if query.filters_path() {
+                        let matched_path = if include_root {
+                            let mut full_path = PathBuf::from(snapshot.root_name());
+                            full_path.push(&entry.path);
+                            query.file_matches(&full_path)
+                        } else {
+                            query.file_matches(&entry.path)
+                        };
+                        if !matched_path {
+                            continue;
+                        }
+                    }
+                    let Some(available_language) =
+                        self.languages.available_language_for_path(&entry.path)
+                    else {
+                        continue;
+                    };
+                    let name = available_language.name();
+                    if !languages.iter().any(|known| known.name() == name)
+                        && !available_languages.iter().any(|known| known.name() == name)
+                    {
+                        available_languages.push(available_language);
+                    }
+                }
+            }
+        }
+
+        let registry = self.languages.clone();
+        cx.background_executor().spawn(async move {
+            for available_language in available_languages {
+                if let Ok(Ok(language)) = registry.load_language(&available_language).await {
+                    languages.push(language);
+                }
+            }
+            languages.retain(|language| language.grammar().is_some());
+            languages
+        })
+    }
+
+    /// Returns a snapshot of the buffer once its language is known and its syntax tree is up to
+    /// date, as structural search and outlines are computed from syntax trees.
+    async fn parsed_buffer_snapshot(
+        buffer: &Model<Buffer>,
+        languages: &Arc<LanguageRegistry>,
+        lsp_store: &WeakModel<LspStore>,
+        cx: &AsyncAppContext,
+    ) -> Result<BufferSnapshot> {
+        let available_language = cx.update(|cx| {
+            let buffer = buffer.read(cx);
+            if buffer.language().is_some() {
+                return None;
+            }
+            languages.language_for_file(buffer.file()?, Some(buffer.as_rope()), cx)
+        })?;
+        if let Some(available_language) = available_language {
+            let language = languages.load_language(&available_language).await??;
+            lsp_store.update(&mut cx.clone(), |lsp_store, cx| {
+                if buffer.read(cx).language().is_none() {
+                    lsp_store.set_language_for_buffer(buffer, language, cx);
+                }
+            })?;
+        }
+
+        let mut parse_status = buffer.read_with(cx, |buffer, _| buffer.parse_status())?;
+        while *parse_status.borrow() != ParseStatus::Idle {
+            if parse_status.changed().await.is_err() {
+                break;
+            }
+        }
+        buffer.read_with(cx, |buffer, _| buffer.snapshot())
+    }
+
     fn find_search_candidate_buffers(
         &mut self,
         query: &SearchQuery,
@@ -3608,6 +4025,7 @@ impl Project {
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
+            this.send_shared_terminal_updates(cx);
             cx.notify();
         })?;
 
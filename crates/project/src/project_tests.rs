@@ -11,8 +11,9 @@ use language::{
     OffsetRangeExt, Point, ToPoint,
 };
 use lsp::{
-    notification::DidRenameFiles, DiagnosticSeverity, DocumentChanges, FileOperationFilter,
-    NumberOrString, TextDocumentEdit, WillRenameFiles,
+    notification::{DidCreateFiles, DidDeleteFiles, DidRenameFiles},
+    DiagnosticSeverity, DocumentChanges, FileOperationFilter, NumberOrString, TextDocumentEdit,
+    WillCreateFiles, WillDeleteFiles, WillRenameFiles,
 };
 use parking_lot::Mutex;
 use pretty_assertions::{assert_eq, assert_matches};
@@ -4078,6 +4079,272 @@ async fn test_lsp_rename_notifications(cx: &mut gpui::TestAppContext) {
     assert_eq!(resolved_workspace_edit.get(), Some(&expected_edit));
 }
 
+#[gpui::test]
+async fn test_lsp_delete_notifications(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "use one::ONE;\nconst TWO: usize = ONE + ONE;"
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let watched_paths = lsp::FileOperationRegistrationOptions {
+        filters: vec![FileOperationFilter {
+            scheme: Some("file".to_owned()),
+            pattern: lsp::FileOperationPattern {
+                glob: "**/*.rs".to_owned(),
+                matches: Some(lsp::FileOperationPatternKind::File),
+                options: None,
+            },
+        }],
+    };
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                workspace: Some(lsp::WorkspaceServerCapabilities {
+                    workspace_folders: None,
+                    file_operations: Some(lsp::WorkspaceFileOperationsServerCapabilities {
+                        did_delete: Some(watched_paths.clone()),
+                        will_delete: Some(watched_paths),
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let _ = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/dir/two.rs", cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let proposed_edits = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let proposed_edits = proposed_edits.clone();
+        cx.subscribe(&project, move |_, event, _| {
+            if let Event::FileOperationEditsProposed { title, edits } = event {
+                proposed_edits.lock().push((title.clone(), edits.clone()));
+            }
+        })
+        .detach();
+    });
+
+    let response = project.update(cx, |project, cx| {
+        let worktree = project.worktrees(cx).next().unwrap();
+        let entry = worktree.read(cx).entry_for_path("one.rs").unwrap();
+        project.delete_entry(entry.id, false, cx).unwrap()
+    });
+    fake_server
+        .handle_request::<WillDeleteFiles, _, _>(|params, _| async move {
+            assert_eq!(params.files.len(), 1);
+            assert_eq!(params.files[0].uri, "file:///dir/one.rs");
+            Ok(Some(lsp::WorkspaceEdit {
+                changes: None,
+                document_changes: Some(DocumentChanges::Edits(vec![TextDocumentEdit {
+                    edits: vec![lsp::Edit::Plain(lsp::TextEdit {
+                        range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(1, 0)),
+                        new_text: String::new(),
+                    })],
+                    text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                        uri: Url::from_str("file:///dir/two.rs").unwrap(),
+                        version: None,
+                    },
+                }])),
+                change_annotations: None,
+            }))
+        })
+        .next()
+        .await
+        .unwrap();
+    response.await.unwrap();
+    fake_server
+        .handle_notification::<DidDeleteFiles, _>(|params, _| {
+            assert_eq!(params.files.len(), 1);
+            assert_eq!(params.files[0].uri, "file:///dir/one.rs");
+        })
+        .next()
+        .await
+        .unwrap();
+
+    // The edits are proposed for review, but not applied.
+    let proposed_edits = proposed_edits.lock().clone();
+    assert_eq!(proposed_edits.len(), 1);
+    let (title, edits) = &proposed_edits[0];
+    assert_eq!(title, "Delete one.rs");
+    assert_eq!(edits.buffer_edits.len(), 1);
+    let (buffer, buffer_edits) = &edits.buffer_edits[0];
+    cx.read(|cx| {
+        let buffer = buffer.read(cx);
+        assert!(!buffer.is_dirty());
+        assert_eq!(
+            buffer_edits
+                .iter()
+                .map(|(range, text)| (range.to_point(buffer), text.as_str()))
+                .collect::<Vec<_>>(),
+            [(Point::new(0, 0)..Point::new(1, 0), "")]
+        );
+    });
+
+    cx.update(|cx| edits.apply(cx));
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "const TWO: usize = ONE + ONE;");
+    });
+}
+
+#[gpui::test]
+async fn test_lsp_create_notifications(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "lib.rs": "mod one;",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let watched_paths = lsp::FileOperationRegistrationOptions {
+        filters: vec![FileOperationFilter {
+            scheme: Some("file".to_owned()),
+            pattern: lsp::FileOperationPattern {
+                glob: "**/*.rs".to_owned(),
+                matches: Some(lsp::FileOperationPatternKind::File),
+                options: None,
+            },
+        }],
+    };
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                workspace: Some(lsp::WorkspaceServerCapabilities {
+                    workspace_folders: None,
+                    file_operations: Some(lsp::WorkspaceFileOperationsServerCapabilities {
+                        did_create: Some(watched_paths.clone()),
+                        will_create: Some(watched_paths),
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let _ = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/dir/lib.rs", cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let proposed_edits = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let proposed_edits = proposed_edits.clone();
+        cx.subscribe(&project, move |_, event, _| {
+            if let Event::FileOperationEditsProposed { title, edits } = event {
+                proposed_edits.lock().push((title.clone(), edits.clone()));
+            }
+        })
+        .detach();
+    });
+
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let response = project.update(cx, |project, cx| {
+        project.create_entry((worktree_id, "one.rs"), false, cx)
+    });
+    fake_server
+        .handle_request::<WillCreateFiles, _, _>(|params, _| async move {
+            assert_eq!(params.files.len(), 1);
+            assert_eq!(params.files[0].uri, "file:///dir/one.rs");
+            Ok(Some(lsp::WorkspaceEdit {
+                changes: None,
+                document_changes: Some(DocumentChanges::Operations(vec![
+                    lsp::DocumentChangeOperation::Edit(TextDocumentEdit {
+                        edits: vec![lsp::Edit::Plain(lsp::TextEdit {
+                            range: lsp::Range::new(
+                                lsp::Position::new(0, 0),
+                                lsp::Position::new(0, 0),
+                            ),
+                            new_text: "mod two;\n".into(),
+                        })],
+                        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                            uri: Url::from_str("file:///dir/lib.rs").unwrap(),
+                            version: None,
+                        },
+                    }),
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(lsp::CreateFile {
+                        uri: Url::from_str("file:///dir/two.rs").unwrap(),
+                        options: None,
+                        annotation_id: None,
+                    })),
+                ])),
+                change_annotations: None,
+            }))
+        })
+        .next()
+        .await
+        .unwrap();
+    response.await.unwrap();
+    fake_server
+        .handle_notification::<DidCreateFiles, _>(|params, _| {
+            assert_eq!(params.files.len(), 1);
+            assert_eq!(params.files[0].uri, "file:///dir/one.rs");
+        })
+        .next()
+        .await
+        .unwrap();
+
+    // Neither the edits nor the file creation happen before the user reviewed them.
+    let proposed_edits = proposed_edits.lock().clone();
+    assert_eq!(proposed_edits.len(), 1);
+    let (title, edits) = &proposed_edits[0];
+    assert_eq!(title, "Create one.rs");
+    assert_eq!(edits.buffer_edits.len(), 1);
+    assert_eq!(edits.resource_operations.len(), 1);
+    let (buffer, _) = &edits.buffer_edits[0];
+    buffer.read_with(cx, |buffer, _| {
+        assert!(!buffer.is_dirty());
+        assert_eq!(buffer.text(), "mod one;");
+    });
+    assert!(!fs.is_file("/dir/two.rs".as_ref()).await);
+
+    cx.update(|cx| edits.apply(cx));
+    project
+        .update(cx, |project, cx| {
+            project.lsp_store().update(cx, |lsp_store, cx| {
+                lsp_store.apply_resource_operations(edits.resource_operations.clone(), cx)
+            })
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "mod two;\nmod one;");
+    });
+    assert!(fs.is_file("/dir/two.rs".as_ref()).await);
+}
+
 #[gpui::test]
 async fn test_rename(cx: &mut gpui::TestAppContext) {
     // hi
@@ -4836,6 +5103,158 @@ async fn test_search_in_gitignored_dirs(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "main.rs": "fn main() {\n    foo(1, 2);\n    foo(x);\n    bar(3, 4);\n}\n",
+            "notes.txt": "foo(1, 2)",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::structural(
+                "foo($A, $B)",
+                false,
+                Default::default(),
+                Default::default(),
+                None,
+                &[],
+            )
+            .unwrap(),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("dir/main.rs".to_string(), vec![16..25])]),
+        "Code patterns should only match calls with the same shape, in files with a grammar"
+    );
+
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::structural(
+                "(call_expression function: (identifier) @callee) @match",
+                false,
+                Default::default(),
+                Default::default(),
+                None,
+                &[],
+            )
+            .unwrap(),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("dir/main.rs".to_string(), vec![16..25, 31..37, 43..52])]),
+        "Tree-sitter queries should match the range of their @match capture"
+    );
+
+    // Patterns are validated against the languages of all searched files.
+    let query = SearchQuery::structural(
+        "foo($A, $B)",
+        false,
+        Default::default(),
+        Default::default(),
+        None,
+        &[],
+    )
+    .unwrap();
+    let languages = project
+        .read_with(cx, |project, cx| project.searched_languages(&query, cx))
+        .await;
+    assert_eq!(
+        languages
+            .iter()
+            .map(|language| language.name())
+            .collect::<Vec<_>>(),
+        vec!["Rust".into()]
+    );
+    let text_files_query = SearchQuery::structural(
+        "foo($A, $B)",
+        false,
+        PathMatcher::new(&["*.txt".to_owned()]).unwrap(),
+        Default::default(),
+        None,
+        &[],
+    )
+    .unwrap();
+    let languages = project
+        .read_with(cx, |project, cx| {
+            project.searched_languages(&text_files_query, cx)
+        })
+        .await;
+    assert!(languages.is_empty());
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    let query = SearchQuery::structural(
+        "foo($A, $B)",
+        false,
+        Default::default(),
+        Default::default(),
+        None,
+        &[],
+    )
+    .unwrap()
+    .with_replacement("foo($B, $A)".to_string());
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(
+        query.structural_replacement_for(&snapshot, 16..25),
+        Some("foo(2, 1)".to_string())
+    );
+
+    // Captures are reused until the buffer changes.
+    buffer.update(cx, |buffer, cx| buffer.edit([(20..21, "5")], None, cx));
+    cx.executor().run_until_parked();
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(
+        query.structural_replacement_for(&snapshot, 16..25),
+        Some("foo(2, 5)".to_string())
+    );
+    assert_eq!(query.structural_replacement_for(&snapshot, 16..24), None);
+
+    // Files are filtered by the words of the pattern, even when they overlap or repeat.
+    let query = SearchQuery::structural(
+        "foo(foobar, foo)",
+        false,
+        Default::default(),
+        Default::default(),
+        None,
+        &[rust_lang()],
+    )
+    .unwrap();
+    assert!(query.detect("foo(foobar, foo)".as_bytes()).unwrap());
+    assert!(!query.detect("foo(bar)".as_bytes()).unwrap());
+
+    assert!(
+        SearchQuery::structural(
+            "fn (",
+            false,
+            Default::default(),
+            Default::default(),
+            None,
+            &[rust_lang()],
+        )
+        .is_err(),
+        "Patterns that don't parse in any of the languages should be rejected"
+    );
+}
+
 #[gpui::test]
 async fn test_create_entry(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1,9 +1,9 @@
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use client::proto;
 use fancy_regex::{Captures, Regex, RegexBuilder};
 use gpui::Model;
-use language::{Buffer, BufferSnapshot, CharKind};
+use language::{Buffer, BufferSnapshot, CharKind, Language};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
@@ -15,6 +15,12 @@ use std::{
 use text::Anchor;
 use util::paths::PathMatcher;
 
+mod preserve_case;
+mod structural;
+
+use preserve_case::preserve_case;
+pub use structural::{StructuralMatch, StructuralPattern};
+
 pub enum SearchResult {
     Buffer {
         buffer: Model<Buffer>,
@@ -57,6 +63,7 @@ pub enum SearchQuery {
     Text {
         search: Arc<AhoCorasick>,
         replacement: Option<String>,
+        preserve_case: bool,
         whole_word: bool,
         case_sensitive: bool,
         include_ignored: bool,
@@ -66,12 +73,24 @@ pub enum SearchQuery {
     Regex {
         regex: Regex,
         replacement: Option<String>,
+        preserve_case: bool,
         multiline: bool,
         whole_word: bool,
         case_sensitive: bool,
         include_ignored: bool,
         inner: SearchInputs,
     },
+
+    /// Matches code by its syntax tree rather than its text, in buffers whose language has a
+    /// tree-sitter grammar.
+    Structural {
+        pattern: Arc<StructuralPattern>,
+        /// Matches files containing every word of the pattern, before they are parsed.
+        prefilter: Option<Arc<AhoCorasick>>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 static WORD_MATCH_TEST: LazyLock<Regex> = LazyLock::new(|| {
@@ -103,6 +122,7 @@ impl SearchQuery {
         Ok(Self::Text {
             search: Arc::new(search),
             replacement: None,
+            preserve_case: false,
             whole_word,
             case_sensitive,
             include_ignored,
@@ -150,6 +170,7 @@ impl SearchQuery {
         Ok(Self::Regex {
             regex,
             replacement: None,
+            preserve_case: false,
             multiline,
             whole_word,
             case_sensitive,
@@ -158,8 +179,57 @@ impl SearchQuery {
         })
     }
 
+    /// Creates a syntax-aware query from either a code pattern, in which `$NAME` metavariables
+    /// match any syntax node, or a tree-sitter query.
+    ///
+    /// The pattern must be valid in at least one of the given `languages`, unless none are given.
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+        buffers: Option<Vec<Model<Buffer>>>,
+        languages: &[Arc<Language>],
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let pattern = StructuralPattern::new(query.as_str());
+        if !pattern.is_valid_in(languages) {
+            return Err(anyhow!("invalid structural search pattern: {query}"));
+        }
+        let mut required_words = pattern.required_words();
+        required_words.sort_unstable();
+        required_words.dedup();
+        let prefilter = if required_words.is_empty() {
+            None
+        } else {
+            Some(Arc::new(AhoCorasickBuilder::new().build(&required_words)?))
+        };
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            buffers,
+        };
+        Ok(Self::Structural {
+            pattern: Arc::new(pattern),
+            prefilter,
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchQuery) -> Result<Self> {
-        if message.regex {
+        if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+                None, // search opened only don't need search remote
+                &[],  // the pattern was validated by the peer
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -191,6 +261,10 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
@@ -198,10 +272,30 @@ impl SearchQuery {
         }
     }
 
+    /// Makes replacements follow the case of the text they replace. Structural replacements are
+    /// code, whose case is left untouched.
+    pub fn with_preserve_case(mut self, preserve: bool) -> Self {
+        match self {
+            Self::Text {
+                ref mut preserve_case,
+                ..
+            }
+            | Self::Regex {
+                ref mut preserve_case,
+                ..
+            } => {
+                *preserve_case = preserve;
+                self
+            }
+            Self::Structural { .. } => self,
+        }
+    }
+
     pub fn to_proto(&self) -> proto::SearchQuery {
         proto::SearchQuery {
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
@@ -245,22 +339,49 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { prefilter, .. } => {
+                let Some(prefilter) = prefilter else {
+                    return Ok(true);
+                };
+                // Words can overlap or be prefixes of each other, like `foo` and `foobar`, which
+                // a non-overlapping search would miss. Overlapping searches can't be streamed.
+                let mut text = Vec::new();
+                BufReader::new(stream).read_to_end(&mut text)?;
+                let mut found = vec![false; prefilter.patterns_len()];
+                for mat in prefilter.find_overlapping_iter(&text) {
+                    found[mat.pattern().as_usize()] = true;
+                    if found.iter().all(|found| *found) {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
+            SearchQuery::Text {
+                replacement,
+                preserve_case: true,
+                ..
+            } => replacement
+                .as_deref()
+                .map(|replacement| Cow::from(preserve_case(text, replacement))),
             SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
             SearchQuery::Regex {
-                regex, replacement, ..
+                regex,
+                replacement,
+                preserve_case: should_preserve_case,
+                ..
             } => {
                 if let Some(replacement) = replacement {
                     static TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX: LazyLock<Regex> =
@@ -274,14 +395,42 @@ impl SearchQuery {
                             x => unreachable!("Unexpected escape sequence: {}", x),
                         },
                     );
-                    Some(regex.replace(text, replacement))
+                    let replaced = regex.replace(text, replacement);
+                    if *should_preserve_case {
+                        Some(Cow::from(preserve_case(text, &replaced)))
+                    } else {
+                        Some(replaced)
+                    }
                 } else {
                     None
                 }
             }
+            // The replacement depends on the syntax tree around the match, see
+            // `structural_replacement_for`.
+            SearchQuery::Structural { .. } => None,
         }
     }
 
+    /// Returns the replacement for the structural match at the given range of the buffer, with the
+    /// pattern's metavariables expanded.
+    pub fn structural_replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        let SearchQuery::Structural {
+            pattern,
+            replacement,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let replacement = replacement.as_ref()?;
+        let mat = pattern.match_at(buffer, range)?;
+        Some(StructuralPattern::expand_replacement(replacement, &mat))
+    }
+
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
@@ -294,7 +443,7 @@ impl SearchQuery {
         }
 
         let range_offset = subrange.as_ref().map(|r| r.start).unwrap_or(0);
-        let rope = if let Some(range) = subrange {
+        let rope = if let Some(range) = subrange.clone() {
             buffer.as_rope().slice(range)
         } else {
             buffer.as_rope().clone()
@@ -374,6 +523,23 @@ impl SearchQuery {
                     }
                 }
             }
+
+            // Matches in whole buffers are kept by the pattern, to be reused when previewing and
+            // applying replacements.
+            Self::Structural { pattern, .. } => match subrange {
+                Some(range) if range != (0..buffer.len()) => matches.extend(
+                    pattern
+                        .matches(buffer, range)
+                        .into_iter()
+                        .map(|mat| mat.range.start - range_offset..mat.range.end - range_offset),
+                ),
+                _ => matches.extend(
+                    pattern
+                        .buffer_matches(buffer)
+                        .iter()
+                        .map(|mat| mat.range.clone()),
+                ),
+            },
         }
 
         matches
@@ -391,6 +557,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -398,6 +565,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
         }
     }
 
@@ -409,6 +577,16 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
+        }
+    }
+
+    pub fn preserve_case(&self) -> bool {
+        match self {
+            Self::Text { preserve_case, .. } | Self::Regex { preserve_case, .. } => *preserve_case,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -416,6 +594,19 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
+    /// Whether the query can match in files of the given languages. Only structural patterns
+    /// depend on the language, and are considered valid when no languages are given.
+    pub fn is_valid_in(&self, languages: &[Arc<Language>]) -> bool {
+        match self {
+            Self::Structural { pattern, .. } => pattern.is_valid_in(languages),
+            Self::Text { .. } | Self::Regex { .. } => true,
+        }
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -453,7 +644,9 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 }
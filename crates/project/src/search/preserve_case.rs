@@ -0,0 +1,163 @@
+//! Adapting the case of replacements to the case of the text they replace, so that replacing
+//! `foo_bar` with `baz_qux` also turns `fooBar` into `bazQux` and `FOO_BAR` into `BAZ_QUX`.
+
+/// The way the words of an identifier or of a piece of text are capitalized and separated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CaseStyle {
+    Lower,
+    Upper,
+    Capitalized,
+    Camel,
+    Pascal,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl CaseStyle {
+    fn of(text: &str) -> Option<Self> {
+        let has_lowercase = text.chars().any(char::is_lowercase);
+        let has_uppercase = text.chars().any(char::is_uppercase);
+        if !has_lowercase && !has_uppercase {
+            return None;
+        }
+
+        let is_identifier =
+            |separator: char| text.chars().all(|c| c.is_alphanumeric() || c == separator);
+        for (separator, lower, upper) in [
+            ('_', CaseStyle::Snake, CaseStyle::ScreamingSnake),
+            ('-', CaseStyle::Kebab, CaseStyle::ScreamingKebab),
+        ] {
+            if text.contains(separator) && is_identifier(separator) {
+                return match (has_lowercase, has_uppercase) {
+                    (true, false) => Some(lower),
+                    (false, true) => Some(upper),
+                    _ => None,
+                };
+            }
+        }
+
+        let first = text.chars().find(|c| c.is_alphabetic())?;
+        match (has_lowercase, has_uppercase) {
+            (true, false) => Some(CaseStyle::Lower),
+            (false, true) => Some(CaseStyle::Upper),
+            _ if !is_identifier('_') => {
+                let mut rest = text.chars().skip_while(|c| !c.is_alphabetic()).skip(1);
+                (first.is_uppercase() && !rest.any(char::is_uppercase))
+                    .then_some(CaseStyle::Capitalized)
+            }
+            _ if first.is_lowercase() => Some(CaseStyle::Camel),
+            _ if text.chars().skip(1).any(char::is_uppercase) => Some(CaseStyle::Pascal),
+            _ => Some(CaseStyle::Capitalized),
+        }
+    }
+
+    fn apply(self, text: &str) -> String {
+        match self {
+            CaseStyle::Lower => text.to_lowercase(),
+            CaseStyle::Upper => text.to_uppercase(),
+            CaseStyle::Capitalized => capitalize(text, false),
+            CaseStyle::Camel => {
+                let mut words = words(text).into_iter();
+                let first = words.next().map(str::to_lowercase).unwrap_or_default();
+                words.fold(first, |mut result, word| {
+                    result.push_str(&capitalize(word, true));
+                    result
+                })
+            }
+            CaseStyle::Pascal => words(text)
+                .into_iter()
+                .map(|word| capitalize(word, true))
+                .collect(),
+            CaseStyle::Snake => join_words(text, "_", str::to_lowercase),
+            CaseStyle::ScreamingSnake => join_words(text, "_", str::to_uppercase),
+            CaseStyle::Kebab => join_words(text, "-", str::to_lowercase),
+            CaseStyle::ScreamingKebab => join_words(text, "-", str::to_uppercase),
+        }
+    }
+}
+
+/// Returns `replacement` with its case adapted to the case of `replaced`. Replacements are kept
+/// as they are when the case of the replaced text doesn't follow a recognizable style.
+pub fn preserve_case(replaced: &str, replacement: &str) -> String {
+    match CaseStyle::of(replaced) {
+        Some(style) if !words(replacement).is_empty() => style.apply(replacement),
+        _ => replacement.to_string(),
+    }
+}
+
+fn capitalize(word: &str, lowercase_rest: bool) -> String {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    let rest = chars.as_str();
+    let mut result = first.to_uppercase().collect::<String>();
+    if lowercase_rest {
+        result.push_str(&rest.to_lowercase());
+    } else {
+        result.push_str(rest);
+    }
+    result
+}
+
+fn join_words(text: &str, separator: &str, convert: fn(&str) -> String) -> String {
+    words(text)
+        .into_iter()
+        .map(convert)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Splits text into words at separators and at case changes, keeping acronyms together, so that
+/// `HTTPServer_config` is split into `HTTP`, `Server` and `config`.
+fn words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for part in text.split(|c: char| !c.is_alphanumeric()) {
+        let chars = part.char_indices().collect::<Vec<_>>();
+        let mut start = 0;
+        for ix in 1..chars.len() {
+            let (offset, c) = chars[ix];
+            let previous = chars[ix - 1].1;
+            let next_is_lowercase = chars.get(ix + 1).is_some_and(|(_, c)| c.is_lowercase());
+            let is_boundary = c.is_uppercase()
+                && (previous.is_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_uppercase() && next_is_lowercase));
+            if is_boundary {
+                words.push(&part[start..offset]);
+                start = offset;
+            }
+        }
+        if start < part.len() {
+            words.push(&part[start..]);
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preserve_case() {
+        assert_eq!(preserve_case("foo_bar", "baz_qux"), "baz_qux");
+        assert_eq!(preserve_case("fooBar", "baz_qux"), "bazQux");
+        assert_eq!(preserve_case("FooBar", "baz_qux"), "BazQux");
+        assert_eq!(preserve_case("FOO_BAR", "bazQux"), "BAZ_QUX");
+        assert_eq!(preserve_case("foo-bar", "BazQux"), "baz-qux");
+        assert_eq!(preserve_case("FOO", "baz"), "BAZ");
+        assert_eq!(preserve_case("Foo", "baz"), "Baz");
+        assert_eq!(preserve_case("Foo bar", "baz Qux"), "Baz Qux");
+        assert_eq!(preserve_case("foo", "Baz"), "baz");
+        assert_eq!(preserve_case("fOo bAr", "baz"), "baz");
+        assert_eq!(preserve_case("123", "Baz"), "Baz");
+        assert_eq!(preserve_case("FOO", ""), "");
+        assert_eq!(
+            words("HTTPServer_config2Value"),
+            ["HTTP", "Server", "config2", "Value"]
+        );
+    }
+}
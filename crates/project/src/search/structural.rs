@@ -0,0 +1,373 @@
+//! Syntax-aware search, matching either code patterns with metavariables, such as
+//! `foo($A, $B)`, or tree-sitter queries, such as `(call_expression function: (_) @callee)`,
+//! against the syntax trees of buffers.
+
+use collections::HashMap;
+use language::{with_parser, with_query_cursor, BufferSnapshot, Language, LanguageName, Node};
+use parking_lot::Mutex;
+use regex::Regex;
+use std::{
+    ops::Range,
+    sync::{Arc, LazyLock},
+};
+use text::BufferId;
+use tree_sitter::Query;
+
+/// Metavariables are written as a `$` followed by an uppercase name, like `$A` or `$ARGS`.
+static METAVARIABLE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$([A-Z_][A-Z0-9_]*)").unwrap());
+
+/// Metavariables are replaced by identifiers starting with this prefix before a pattern is
+/// parsed, so that it parses like regular code in every language.
+const METAVARIABLE_PREFIX: &str = "EDITSYNC_METAVARIABLE_";
+
+/// The capture that designates the matched range in a tree-sitter query. When a query doesn't use
+/// it, the widest capture of each match is used instead.
+const MATCH_CAPTURE_NAME: &str = "match";
+
+/// A structural search pattern, compiled lazily for each language it is matched against.
+pub struct StructuralPattern {
+    source: Arc<str>,
+    compiled: Mutex<HashMap<LanguageName, Option<Arc<CompiledPattern>>>>,
+    /// The matches in each buffer the pattern was matched against in full, along with the version
+    /// of the buffer they were computed for.
+    buffer_matches: Mutex<HashMap<BufferId, (clock::Global, Arc<[StructuralMatch]>)>>,
+}
+
+impl std::fmt::Debug for StructuralPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StructuralPattern")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A match of a [`StructuralPattern`], along with the text bound to each of its metavariables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuralMatch {
+    pub range: Range<usize>,
+    pub captures: HashMap<String, String>,
+}
+
+enum CompiledPattern {
+    Code(PatternNode),
+    Query(Query),
+}
+
+/// An owned copy of the syntax tree of a code pattern.
+struct PatternNode {
+    kind_id: u16,
+    /// The text of leaf nodes, which must appear verbatim in matches.
+    text: Option<String>,
+    metavariable: Option<String>,
+    children: Vec<PatternNode>,
+}
+
+impl StructuralPattern {
+    pub fn new(source: impl Into<Arc<str>>) -> Self {
+        Self {
+            source: source.into(),
+            compiled: Mutex::default(),
+            buffer_matches: Mutex::default(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the words that must appear in a file for it to contain a match, so that files can be
+    /// filtered without being parsed. Tree-sitter queries don't contain such words.
+    pub fn required_words(&self) -> Vec<String> {
+        if looks_like_query(&self.source) {
+            return Vec::new();
+        }
+        let source = METAVARIABLE_REGEX.replace_all(&self.source, " ");
+        source
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|word| !word.is_empty())
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// Returns the non-overlapping matches of this pattern in the buffer that lie within `range`,
+    /// in the order in which they appear. Buffers without a tree-sitter grammar never match.
+    pub fn matches(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<StructuralMatch> {
+        let mut text = None;
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let Some(pattern) = self.compiled_for(layer.language) else {
+                continue;
+            };
+            let root = layer.node();
+            if root.end_byte() <= range.start || root.start_byte() >= range.end {
+                continue;
+            }
+
+            let text = text.get_or_insert_with(|| buffer.text());
+            match pattern.as_ref() {
+                CompiledPattern::Code(pattern) => {
+                    collect_code_matches(pattern, root, text, &range, &mut matches)
+                }
+                CompiledPattern::Query(query) => {
+                    collect_query_matches(query, root, text, &range, &mut matches)
+                }
+            }
+        }
+
+        matches.sort_by_key(|mat| (mat.range.start, usize::MAX - mat.range.end));
+        let mut last_end = 0;
+        matches.retain(|mat| {
+            if mat.range.start < last_end {
+                false
+            } else {
+                last_end = mat.range.end;
+                true
+            }
+        });
+        matches
+    }
+
+    /// Returns all matches of this pattern in the buffer, reusing the ones computed for the same
+    /// version of the buffer.
+    pub fn buffer_matches(&self, buffer: &BufferSnapshot) -> Arc<[StructuralMatch]> {
+        if let Some((version, matches)) = self.buffer_matches.lock().get(&buffer.remote_id()) {
+            if version == buffer.version() {
+                return matches.clone();
+            }
+        }
+
+        let matches: Arc<[StructuralMatch]> = self.matches(buffer, 0..buffer.len()).into();
+        self.buffer_matches.lock().insert(
+            buffer.remote_id(),
+            (buffer.version().clone(), matches.clone()),
+        );
+        matches
+    }
+
+    /// Returns the match spanning exactly `range` in the buffer, if any.
+    pub fn match_at(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<StructuralMatch> {
+        let matches = self.buffer_matches(buffer);
+        let ix = matches
+            .binary_search_by_key(&range.start, |mat| mat.range.start)
+            .ok()?;
+        let mat = &matches[ix];
+        (mat.range == range).then(|| mat.clone())
+    }
+
+    /// Expands the metavariables of a replacement template with the text they matched.
+    pub fn expand_replacement(template: &str, mat: &StructuralMatch) -> String {
+        METAVARIABLE_REGEX
+            .replace_all(template, |captures: &regex::Captures| {
+                let name = &captures[1];
+                mat.captures
+                    .get(name)
+                    .or_else(|| mat.captures.get(&name.to_lowercase()))
+                    .cloned()
+                    .unwrap_or_else(|| captures[0].to_string())
+            })
+            .into_owned()
+    }
+
+    /// Whether the pattern parses as code, or as a tree-sitter query, in the given language.
+    pub fn is_valid_for(&self, language: &Arc<Language>) -> bool {
+        self.compiled_for(language).is_some()
+    }
+
+    /// Whether the pattern is valid in at least one of the given languages, or none are given.
+    pub fn is_valid_in(&self, languages: &[Arc<Language>]) -> bool {
+        languages.is_empty() || languages.iter().any(|language| self.is_valid_for(language))
+    }
+
+    fn compiled_for(&self, language: &Arc<Language>) -> Option<Arc<CompiledPattern>> {
+        self.compiled
+            .lock()
+            .entry(language.name())
+            .or_insert_with(|| compile(&self.source, language).map(Arc::new))
+            .clone()
+    }
+}
+
+fn looks_like_query(source: &str) -> bool {
+    source.trim_start().starts_with('(')
+}
+
+fn compile(source: &str, language: &Language) -> Option<CompiledPattern> {
+    let grammar = language.grammar()?;
+    if looks_like_query(source) {
+        if let Ok(query) = Query::new(&grammar.ts_language, source) {
+            return Some(CompiledPattern::Query(query));
+        }
+    }
+
+    let source = METAVARIABLE_REGEX.replace_all(source.trim(), |captures: &regex::Captures| {
+        format!("{METAVARIABLE_PREFIX}{}", &captures[1])
+    });
+    let tree = with_parser(|parser| {
+        parser.set_language(&grammar.ts_language).ok()?;
+        parser.parse(source.as_bytes(), None)
+    })?;
+
+    // Skip the nodes wrapping the pattern, such as the source file and expression statements.
+    let mut node = tree.root_node();
+    while node.named_child_count() == 1 {
+        let child = node.named_child(0)?;
+        if source[child.byte_range()].trim() != source.trim() {
+            break;
+        }
+        node = child;
+    }
+    if node.has_error() || node.byte_range().is_empty() {
+        return None;
+    }
+
+    Some(CompiledPattern::Code(PatternNode::new(node, &source)))
+}
+
+impl PatternNode {
+    fn new(node: Node, source: &str) -> Self {
+        let mut cursor = node.walk();
+        let children = node
+            .children(&mut cursor)
+            .filter(|child| !child.is_extra())
+            .map(|child| PatternNode::new(child, source))
+            .collect::<Vec<_>>();
+        let node_text = &source[node.byte_range()];
+        let metavariable = node_text
+            .strip_prefix(METAVARIABLE_PREFIX)
+            .filter(|name| name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+            .map(ToString::to_string);
+        Self {
+            kind_id: node.kind_id(),
+            text: children.is_empty().then(|| node_text.to_string()),
+            metavariable,
+            children,
+        }
+    }
+
+    fn matches(&self, node: Node, text: &str, captures: &mut HashMap<String, String>) -> bool {
+        if let Some(name) = &self.metavariable {
+            let node_text = &text[node.byte_range()];
+            return match captures.get(name) {
+                // A metavariable used several times must match the same text each time.
+                Some(captured) => captured == node_text,
+                None => {
+                    captures.insert(name.clone(), node_text.to_string());
+                    true
+                }
+            };
+        }
+
+        if self.kind_id != node.kind_id() {
+            return false;
+        }
+        if let Some(pattern_text) = &self.text {
+            return node.child_count() == 0 && pattern_text == &text[node.byte_range()];
+        }
+
+        let mut cursor = node.walk();
+        let mut children = node.children(&mut cursor).filter(|child| !child.is_extra());
+        for pattern_child in &self.children {
+            match children.next() {
+                Some(child) if pattern_child.matches(child, text, captures) => {}
+                _ => return false,
+            }
+        }
+        children.next().is_none()
+    }
+}
+
+fn collect_code_matches(
+    pattern: &PatternNode,
+    root: Node,
+    text: &str,
+    range: &Range<usize>,
+    matches: &mut Vec<StructuralMatch>,
+) {
+    let mut cursor = root.walk();
+    loop {
+        let node = cursor.node();
+        let overlaps = node.start_byte() < range.end && node.end_byte() > range.start;
+        let mut matched = false;
+        if overlaps
+            && node.start_byte() >= range.start
+            && node.end_byte() <= range.end
+            && node.kind_id() == pattern.kind_id
+        {
+            let mut captures = HashMap::default();
+            if pattern.matches(node, text, &mut captures) {
+                matches.push(StructuralMatch {
+                    range: node.byte_range(),
+                    captures,
+                });
+                matched = true;
+            }
+        }
+
+        // Matches don't overlap, so there's no need to look for other matches inside of one.
+        if overlaps && !matched && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return;
+            }
+        }
+    }
+}
+
+fn collect_query_matches(
+    query: &Query,
+    root: Node,
+    text: &str,
+    range: &Range<usize>,
+    matches: &mut Vec<StructuralMatch>,
+) {
+    let capture_names = query.capture_names();
+    let match_capture_ix = capture_names
+        .iter()
+        .position(|name| *name == MATCH_CAPTURE_NAME);
+    with_query_cursor(|cursor| {
+        cursor.set_byte_range(range.clone());
+        for mat in cursor.matches(query, root, text.as_bytes()) {
+            let matched_node = match match_capture_ix {
+                Some(ix) => mat
+                    .captures
+                    .iter()
+                    .find(|capture| capture.index as usize == ix)
+                    .map(|capture| capture.node),
+                None => mat
+                    .captures
+                    .iter()
+                    .map(|capture| capture.node)
+                    .max_by_key(|node| node.byte_range().len()),
+            };
+            let Some(matched_node) = matched_node else {
+                continue;
+            };
+            let matched_range = matched_node.byte_range();
+            if matched_range.start < range.start || matched_range.end > range.end {
+                continue;
+            }
+
+            let captures = mat
+                .captures
+                .iter()
+                .map(|capture| {
+                    (
+                        capture_names[capture.index as usize].to_string(),
+                        text[capture.node.byte_range()].to_string(),
+                    )
+                })
+                .collect();
+            matches.push(StructuralMatch {
+                range: matched_range,
+                captures,
+            });
+        }
+    });
+}
@@ -1,7 +1,15 @@
-use crate::Project;
-use anyhow::{Context as _, Result};
-use collections::HashMap;
-use gpui::{AnyWindowHandle, AppContext, Context, Entity, Model, ModelContext, Task, WeakModel};
+use crate::{Event, Project};
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, TypedEnvelope, UserId};
+use collections::{btree_map, BTreeMap, HashMap, HashSet};
+use futures::{
+    channel::mpsc::{self, UnboundedSender},
+    StreamExt as _,
+};
+use gpui::{
+    AnyWindowHandle, AppContext, AsyncAppContext, Context, Entity, Model, ModelContext,
+    SharedString, Subscription, Task, WeakModel,
+};
 use itertools::Itertools;
 use language::LanguageName;
 use settings::{Settings, SettingsLocation};
@@ -12,19 +20,45 @@ use std::{
     iter,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 use task::{Shell, SpawnInTerminal};
 use terminal::{
     terminal_settings::{self, TerminalSettings, VenvSettings},
     TaskState, TaskStatus, Terminal, TerminalBuilder,
 };
-use util::ResultExt;
+use util::{post_inc, ResultExt};
 
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
+/// How often the content of a shared terminal is sent to the guests, at most.
+const SHARED_TERMINAL_UPDATE_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    /// The terminals shared by the host with the guests of the project, by their id.
+    shared: HashMap<u64, SharedTerminal>,
+    next_shared_terminal_id: u64,
+    /// The mirrors of the terminals shared by the host, on guests.
+    remote: BTreeMap<u64, RemoteTerminal>,
+}
+
+struct SharedTerminal {
+    terminal: WeakModel<Terminal>,
+    /// The users allowed to write to the terminal.
+    writers: HashSet<UserId>,
+    /// The user who wrote to the terminal since the last update sent to the guests.
+    typing_user_id: Option<UserId>,
+    pending_update: Option<Task<()>>,
+    _subscriptions: [Subscription; 2],
+}
+
+struct RemoteTerminal {
+    terminal: Model<Terminal>,
+    input_tx: UnboundedSender<Vec<u8>>,
+    _forward_input: Task<()>,
 }
 
 /// Terminals are opened either for the users shell, or to run a task.
@@ -40,6 +74,7 @@ pub enum TerminalKind {
 /// SshCommand describes how to connect to a remote server
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SshCommand {
+    program: String,
     arguments: Vec<String>,
 }
 
@@ -67,10 +102,10 @@ impl Project {
     fn ssh_details(&self, cx: &AppContext) -> Option<(String, SshCommand)> {
         if let Some(ssh_client) = &self.ssh_client {
             let ssh_client = ssh_client.read(cx);
-            if let Some(args) = ssh_client.ssh_args() {
+            if let Some((program, arguments)) = ssh_client.terminal_command() {
                 return Some((
                     ssh_client.connection_options().host.clone(),
-                    SshCommand { arguments: args },
+                    SshCommand { program, arguments },
                 ));
             }
         }
@@ -451,6 +486,331 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakModel<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Shares the given terminal with the guests of the project. They can view it, and write to
+    /// it once allowed with [`Project::set_terminal_write_access`].
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<u64> {
+        if self.is_via_collab() {
+            return Err(anyhow!("only the host can share terminals"));
+        }
+        if self.remote_id().is_none() {
+            return Err(anyhow!("project is not shared"));
+        }
+        if let Some(terminal_id) = self.shared_terminal_id(terminal) {
+            return Ok(terminal_id);
+        }
+
+        let terminal_id = post_inc(&mut self.terminals.next_shared_terminal_id);
+        let subscriptions = [
+            cx.subscribe(terminal, move |this, _, event, cx| {
+                if matches!(
+                    event,
+                    terminal::Event::Wakeup | terminal::Event::TitleChanged
+                ) {
+                    this.schedule_shared_terminal_update(terminal_id, cx);
+                }
+            }),
+            cx.observe_release(terminal, move |this, _, cx| {
+                this.stop_sharing_terminal(terminal_id, cx);
+            }),
+        ];
+        self.terminals.shared.insert(
+            terminal_id,
+            SharedTerminal {
+                terminal: terminal.downgrade(),
+                writers: HashSet::default(),
+                typing_user_id: None,
+                pending_update: None,
+                _subscriptions: subscriptions,
+            },
+        );
+        self.send_shared_terminal_update(terminal_id, cx);
+        cx.notify();
+        Ok(terminal_id)
+    }
+
+    pub fn unshare_terminal(&mut self, terminal: &Model<Terminal>, cx: &mut ModelContext<Self>) {
+        if let Some(terminal_id) = self.shared_terminal_id(terminal) {
+            self.stop_sharing_terminal(terminal_id, cx);
+        }
+    }
+
+    /// Returns the id of the given terminal among the terminals shared by the host, or of the
+    /// terminal it mirrors on guests.
+    pub fn shared_terminal_id(&self, terminal: &Model<Terminal>) -> Option<u64> {
+        let shared = self.terminals.shared.iter().find_map(|(id, shared)| {
+            (shared.terminal.entity_id() == terminal.entity_id()).then_some(*id)
+        });
+        shared.or_else(|| {
+            self.terminals
+                .remote
+                .iter()
+                .find_map(|(id, remote)| (remote.terminal == *terminal).then_some(*id))
+        })
+    }
+
+    /// Returns the users allowed to write to the given shared terminal.
+    pub fn terminal_writers(&self, terminal: &Model<Terminal>) -> Option<&HashSet<UserId>> {
+        let terminal_id = self.shared_terminal_id(terminal)?;
+        Some(&self.terminals.shared.get(&terminal_id)?.writers)
+    }
+
+    pub fn set_terminal_write_access(
+        &mut self,
+        terminal: &Model<Terminal>,
+        user_id: UserId,
+        allowed: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(terminal_id) = self.shared_terminal_id(terminal) else {
+            return;
+        };
+        let Some(shared) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        let changed = if allowed {
+            shared.writers.insert(user_id)
+        } else {
+            shared.writers.remove(&user_id)
+        };
+        if changed {
+            self.send_shared_terminal_update(terminal_id, cx);
+            cx.notify();
+        }
+    }
+
+    /// Returns the mirrors of the terminals shared by the host, on guests.
+    pub fn remote_shared_terminals(&self) -> impl Iterator<Item = (u64, &Model<Terminal>)> {
+        self.terminals
+            .remote
+            .iter()
+            .map(|(id, remote)| (*id, &remote.terminal))
+    }
+
+    pub fn remote_shared_terminal(&self, terminal_id: u64) -> Option<&Model<Terminal>> {
+        Some(&self.terminals.remote.get(&terminal_id)?.terminal)
+    }
+
+    fn stop_sharing_terminal(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        if self.terminals.shared.remove(&terminal_id).is_none() {
+            return;
+        }
+        if let Some(project_id) = self.remote_id() {
+            self.client
+                .send(proto::UnshareTerminal {
+                    project_id,
+                    terminal_id,
+                })
+                .log_err();
+        }
+        cx.notify();
+    }
+
+    fn schedule_shared_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(shared) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        if shared.pending_update.is_some() {
+            return;
+        }
+        shared.pending_update = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(SHARED_TERMINAL_UPDATE_INTERVAL)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.send_shared_terminal_update(terminal_id, cx)
+            })
+            .ok();
+        }));
+    }
+
+    pub(crate) fn send_shared_terminal_updates(&mut self, cx: &mut ModelContext<Self>) {
+        let terminal_ids = self.terminals.shared.keys().copied().collect::<Vec<_>>();
+        for terminal_id in terminal_ids {
+            self.send_shared_terminal_update(terminal_id, cx);
+        }
+    }
+
+    fn send_shared_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let Some(shared) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        shared.pending_update = None;
+        let Some(terminal) = shared.terminal.upgrade() else {
+            return;
+        };
+        let terminal = terminal.read(cx);
+        self.client
+            .send(proto::UpdateSharedTerminal {
+                project_id,
+                terminal_id,
+                title: terminal.title(false),
+                content: terminal.mirrored_content(),
+                writer_user_ids: shared.writers.iter().copied().collect(),
+                typing_user_id: shared.typing_user_id.take(),
+            })
+            .log_err();
+    }
+
+    pub(crate) fn forget_shared_terminals(&mut self, cx: &mut AppContext) {
+        self.terminals.shared.clear();
+        for remote in std::mem::take(&mut self.terminals.remote).into_values() {
+            remote
+                .terminal
+                .update(cx, |terminal, _| terminal.set_mirror_input(None));
+        }
+    }
+
+    fn update_remote_terminal(
+        &mut self,
+        update: proto::UpdateSharedTerminal,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let project_id = self.remote_id().context("project is not shared")?;
+        let user_store = self.user_store.read(cx);
+        let current_user_id = user_store.current_user().map(|user| user.id);
+        let typing_login = update
+            .typing_user_id
+            .filter(|user_id| Some(*user_id) != current_user_id)
+            .and_then(|user_id| user_store.get_cached_user(user_id))
+            .map(|user| SharedString::from(user.github_login.clone()));
+        let can_write = !self.is_read_only(cx)
+            && current_user_id.map_or(false, |user_id| update.writer_user_ids.contains(&user_id));
+
+        let terminal_id = update.terminal_id;
+        let remote = match self.terminals.remote.entry(terminal_id) {
+            btree_map::Entry::Occupied(entry) => entry.into_mut(),
+            btree_map::Entry::Vacant(entry) => {
+                let cursor_shape = TerminalSettings::get_global(cx)
+                    .cursor_shape
+                    .unwrap_or_default();
+                let (completion_tx, _) = bounded(1);
+                let terminal = cx.new_model(|cx| {
+                    TerminalBuilder::new_mirror(cursor_shape, completion_tx).subscribe(cx)
+                });
+
+                // Input is sent in order, one write at a time.
+                let (input_tx, mut input_rx) = mpsc::unbounded();
+                let client = self.client.clone();
+                let forward_input = cx.background_executor().spawn(async move {
+                    while let Some(input) = input_rx.next().await {
+                        client
+                            .request(proto::SharedTerminalInput {
+                                project_id,
+                                terminal_id,
+                                input,
+                            })
+                            .await
+                            .log_err();
+                    }
+                });
+
+                cx.emit(Event::Toast {
+                    notification_id: format!("shared-terminal-{terminal_id}").into(),
+                    message: format!(
+                        "The host shared the terminal \"{}\". \
+                        Open it with the `terminal: open shared terminals` action.",
+                        update.title
+                    ),
+                });
+                entry.insert(RemoteTerminal {
+                    terminal,
+                    input_tx,
+                    _forward_input: forward_input,
+                })
+            }
+        };
+
+        let input_tx = can_write.then(|| remote.input_tx.clone());
+        remote.terminal.update(cx, |terminal, cx| {
+            terminal.set_mirror_input(input_tx);
+            terminal.set_mirrored_content(&update.content, Some(update.title), cx);
+            if let Some(login) = typing_login {
+                terminal.show_typing_collaborator(login, cx);
+            }
+        });
+        cx.notify();
+        Ok(())
+    }
+
+    pub(crate) async fn handle_update_shared_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateSharedTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let sender_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            if this.host().map(|host| host.peer_id) != Some(sender_id) {
+                return Err(anyhow!("only the host can share terminals"));
+            }
+            this.update_remote_terminal(envelope.payload, cx)
+        })?
+    }
+
+    pub(crate) async fn handle_unshare_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UnshareTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let sender_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            if this.host().map(|host| host.peer_id) != Some(sender_id) {
+                return Err(anyhow!("only the host can unshare terminals"));
+            }
+            if let Some(remote) = this.terminals.remote.remove(&envelope.payload.terminal_id) {
+                remote
+                    .terminal
+                    .update(cx, |terminal, _| terminal.set_mirror_input(None));
+                cx.notify();
+            }
+            Ok(())
+        })?
+    }
+
+    pub(crate) async fn handle_shared_terminal_input(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SharedTerminalInput>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let sender_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            let user_id = this
+                .collaborators
+                .get(&sender_id)
+                .context("unknown collaborator")?
+                .user_id;
+            let shared = this
+                .terminals
+                .shared
+                .get_mut(&envelope.payload.terminal_id)
+                .context("terminal is not shared")?;
+            if !shared.writers.contains(&user_id) {
+                return Err(anyhow!("not allowed to write to the terminal"));
+            }
+            let terminal = shared.terminal.upgrade().context("terminal was closed")?;
+            shared.typing_user_id = Some(user_id);
+
+            let login = this
+                .user_store
+                .read(cx)
+                .get_cached_user(user_id)
+                .map_or_else(
+                    || SharedString::from("A collaborator"),
+                    |user| SharedString::from(user.github_login.clone()),
+                );
+            terminal.update(cx, |terminal, cx| {
+                terminal.collaborator_input(envelope.payload.input, login, cx)
+            });
+            Ok(proto::Ack {})
+        })?
+    }
 }
 
 fn wrap_for_ssh(
@@ -500,10 +860,9 @@ fn wrap_for_ssh(
     };
     let shell_invocation = format!("sh -c {}", shlex::try_quote(&commands).unwrap());
 
-    let program = "ssh".to_string();
+    let program = ssh_command.program.clone();
     let mut args = ssh_command.arguments.clone();
 
-    args.push("-t".to_string());
     args.push(shell_invocation);
     (program, args)
 }
@@ -24,9 +24,11 @@ use smol::{
 };
 use text::ReplicaId;
 use util::{paths::SanitieditsyncPath, ResultExt};
-use worktree::{Entry, ProjectEntryId, Worktree, WorktreeId, WorktreeSettings};
+use worktree::{
+    CreatedEntry, Entry, EntryKind, ProjectEntryId, Worktree, WorktreeId, WorktreeSettings,
+};
 
-use crate::{search::SearchQuery, LspStore, ProjectPath};
+use crate::{relativize_path, search::SearchQuery, ProjectPath};
 
 struct MatchingEntry {
     worktree_path: Arc<Path>,
@@ -68,9 +70,9 @@ impl EventEmitter<WorktreeStoreEvent> for WorktreeStore {}
 
 impl WorktreeStore {
     pub fn init(client: &AnyProtoClient) {
-        client.add_model_request_handler(Self::handle_create_project_entry);
         client.add_model_request_handler(Self::handle_copy_project_entry);
-        client.add_model_request_handler(Self::handle_delete_project_entry);
+        client.add_model_request_handler(Self::handle_read_project_entry);
+        client.add_model_request_handler(Self::handle_write_project_entry);
         client.add_model_request_handler(Self::handle_expand_project_entry);
         client.add_model_request_handler(Self::handle_git_branches);
         client.add_model_request_handler(Self::handle_update_branch);
@@ -203,6 +205,122 @@ impl WorktreeStore {
             .cloned()
     }
 
+    /// Copies an entry, along with everything inside of it, into a worktree that can belong to
+    /// another store, e.g. to a project on another host. When both worktrees are local, this
+    /// falls back to [`Worktree::copy_entry`]; otherwise the content of the copied files is read
+    /// from the source worktree and written into the target one.
+    ///
+    /// Local sources are read from the file system, so that ignored and excluded files are
+    /// copied too. For remote sources, the unloaded directories are loaded before copying, but
+    /// the files excluded by the host aren't shared, so they aren't copied.
+    ///
+    /// Returns the entry created for the copied entry itself.
+    pub fn copy_entry_to_worktree(
+        &self,
+        entry_id: ProjectEntryId,
+        target_worktree: Model<Worktree>,
+        new_path: impl Into<Arc<Path>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<Entry>>> {
+        let target_path = new_path.into();
+        let Some((source_worktree, source_path)) = self
+            .worktree_and_entry_for_id(entry_id, cx)
+            .map(|(worktree, entry)| (worktree, entry.path.clone()))
+        else {
+            return Task::ready(Err(anyhow!("no worktree for entry {entry_id:?}")));
+        };
+
+        if let Some((source, target)) = source_worktree
+            .read(cx)
+            .as_local()
+            .zip(target_worktree.read(cx).as_local())
+        {
+            let relative_source_path = match source.absolutize(&source_path) {
+                Ok(source_abs_path) => relativize_path(&target.abs_path(), &source_abs_path),
+                Err(error) => return Task::ready(Err(error)),
+            };
+            let Some(target_root_id) = target.root_entry().map(|entry| entry.id) else {
+                return Task::ready(Err(anyhow!("target worktree has no root entry")));
+            };
+            return target_worktree.update(cx, |worktree, cx| {
+                worktree.copy_entry(target_root_id, Some(relative_source_path), target_path, cx)
+            });
+        }
+
+        let local_source = source_worktree
+            .read(cx)
+            .as_local()
+            .map(|source| (source.fs().clone(), source.absolutize(&source_path)));
+        cx.spawn(|_, mut cx| async move {
+            let copied_entries = if let Some((fs, source_abs_path)) = local_source {
+                let source_abs_path = source_abs_path?;
+                list_dir_recursive(fs.as_ref(), &source_abs_path)
+                    .await?
+                    .into_iter()
+                    .map(|(relative_path, abs_path, is_dir)| {
+                        (
+                            relative_path,
+                            is_dir,
+                            CopySource::Disk(fs.clone(), abs_path),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                load_subtree(&source_worktree, &source_path, &mut cx).await?;
+                source_worktree.read_with(&cx, |source, _| {
+                    source
+                        .traverse_from_path(true, true, true, &source_path)
+                        .take_while(|entry| entry.path.starts_with(&source_path))
+                        .map(|entry| {
+                            let relative_path = entry.path.strip_prefix(&source_path).unwrap();
+                            (
+                                relative_path.to_path_buf(),
+                                entry.is_dir(),
+                                CopySource::Entry(entry.id),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })?
+            };
+
+            let mut copied_entry = None;
+            for (relative_path, is_dir, source) in copied_entries {
+                let new_path: Arc<Path> = if relative_path == Path::new("") {
+                    target_path.clone()
+                } else {
+                    target_path.join(relative_path).into()
+                };
+                let new_entry = if is_dir {
+                    match target_worktree
+                        .update(&mut cx, |worktree, cx| {
+                            worktree.create_entry(new_path, true, cx)
+                        })?
+                        .await?
+                    {
+                        CreatedEntry::Included(entry) => Some(entry),
+                        CreatedEntry::Excluded { .. } => None,
+                    }
+                } else {
+                    let content = match source {
+                        CopySource::Entry(entry_id) => {
+                            source_worktree
+                                .update(&mut cx, |worktree, cx| worktree.read_entry(entry_id, cx))?
+                                .await?
+                        }
+                        CopySource::Disk(fs, abs_path) => fs.load_bytes(&abs_path).await?,
+                    };
+                    target_worktree
+                        .update(&mut cx, |worktree, cx| {
+                            worktree.write_entry(new_path, content, cx)
+                        })?
+                        .await?
+                };
+                copied_entry.get_or_insert(new_entry);
+            }
+            Ok(copied_entry.flatten())
+        })
+    }
+
     pub fn create_worktree(
         &mut self,
         abs_path: impl Into<SanitieditsyncPath>,
@@ -1002,96 +1120,43 @@ impl WorktreeStore {
         Ok(())
     }
 
-    pub async fn handle_create_project_entry(
+    pub async fn handle_copy_project_entry(
         this: Model<Self>,
-        envelope: TypedEnvelope<proto::CreateProjectEntry>,
+        envelope: TypedEnvelope<proto::CopyProjectEntry>,
         mut cx: AsyncAppContext,
     ) -> Result<proto::ProjectEntryResponse> {
+        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
         let worktree = this.update(&mut cx, |this, cx| {
-            let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
-            this.worktree_for_id(worktree_id, cx)
+            this.worktree_for_entry(entry_id, cx)
                 .ok_or_else(|| anyhow!("worktree not found"))
         })??;
-        Worktree::handle_create_entry(worktree, envelope.payload, cx).await
-    }
-
-    pub async fn handle_rename_project_entry(
-        this: Model<super::Project>,
-        envelope: TypedEnvelope<proto::RenameProjectEntry>,
-        mut cx: AsyncAppContext,
-    ) -> Result<proto::ProjectEntryResponse> {
-        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
-        let (worktree_id, worktree, old_path, is_dir) = this
-            .update(&mut cx, |this, cx| {
-                this.worktree_store
-                    .read(cx)
-                    .worktree_and_entry_for_id(entry_id, cx)
-                    .map(|(worktree, entry)| {
-                        (
-                            worktree.read(cx).id(),
-                            worktree,
-                            entry.path.clone(),
-                            entry.is_dir(),
-                        )
-                    })
-            })?
-            .ok_or_else(|| anyhow!("worktree not found"))?;
-        let (old_abs_path, new_abs_path) = {
-            let root_path = worktree.update(&mut cx, |this, _| this.abs_path())?;
-            (
-                root_path.join(&old_path),
-                root_path.join(&envelope.payload.new_path),
-            )
-        };
-        let lsp_store = this
-            .update(&mut cx, |this, _| this.lsp_store())?
-            .downgrade();
-        LspStore::will_rename_entry(
-            lsp_store,
-            worktree_id,
-            &old_abs_path,
-            &new_abs_path,
-            is_dir,
-            cx.clone(),
-        )
-        .await;
-        let response = Worktree::handle_rename_entry(worktree, envelope.payload, cx.clone()).await;
-        this.update(&mut cx, |this, cx| {
-            this.lsp_store().read(cx).did_rename_entry(
-                worktree_id,
-                &old_abs_path,
-                &new_abs_path,
-                is_dir,
-            );
-        })
-        .ok();
-        response
+        Worktree::handle_copy_entry(worktree, envelope.payload, cx).await
     }
 
-    pub async fn handle_copy_project_entry(
+    pub async fn handle_read_project_entry(
         this: Model<Self>,
-        envelope: TypedEnvelope<proto::CopyProjectEntry>,
+        envelope: TypedEnvelope<proto::ReadProjectEntry>,
         mut cx: AsyncAppContext,
-    ) -> Result<proto::ProjectEntryResponse> {
+    ) -> Result<proto::ReadProjectEntryResponse> {
         let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
         let worktree = this.update(&mut cx, |this, cx| {
             this.worktree_for_entry(entry_id, cx)
                 .ok_or_else(|| anyhow!("worktree not found"))
         })??;
-        Worktree::handle_copy_entry(worktree, envelope.payload, cx).await
+        Worktree::handle_read_entry(worktree, envelope.payload, cx).await
     }
 
-    pub async fn handle_delete_project_entry(
+    pub async fn handle_write_project_entry(
         this: Model<Self>,
-        envelope: TypedEnvelope<proto::DeleteProjectEntry>,
+        envelope: TypedEnvelope<proto::WriteProjectEntry>,
         mut cx: AsyncAppContext,
     ) -> Result<proto::ProjectEntryResponse> {
-        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
         let worktree = this.update(&mut cx, |this, cx| {
-            this.worktree_for_entry(entry_id, cx)
+            let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+            this.worktree_for_id(worktree_id, cx)
                 .ok_or_else(|| anyhow!("worktree not found"))
         })??;
-        Worktree::handle_delete_entry(worktree, envelope.payload, cx).await
+        Worktree::handle_write_entry(worktree, envelope.payload, cx).await
     }
 
     pub async fn handle_expand_project_entry(
@@ -1176,3 +1241,74 @@ impl WorktreeHandle {
         }
     }
 }
+
+/// Where the content of a file copied by [`WorktreeStore::copy_entry_to_worktree`] is read from.
+enum CopySource {
+    Entry(ProjectEntryId),
+    Disk(Arc<dyn Fs>, PathBuf),
+}
+
+/// Loads the unloaded directories inside the given path of a worktree, such as ignored ones,
+/// until all of its descendants are in the worktree's snapshot.
+async fn load_subtree(
+    worktree: &Model<Worktree>,
+    path: &Path,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let mut expanded_entry_ids = HashSet::default();
+    loop {
+        let unloaded_entries = worktree.read_with(cx, |worktree, _| {
+            worktree
+                .traverse_from_path(true, true, true, path)
+                .take_while(|entry| entry.path.starts_with(path))
+                .filter(|entry| entry.kind == EntryKind::UnloadedDir)
+                .map(|entry| (entry.id, entry.path.clone()))
+                .collect::<Vec<_>>()
+        })?;
+        if unloaded_entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut expansions = Vec::new();
+        for (entry_id, entry_path) in unloaded_entries {
+            if !expanded_entry_ids.insert(entry_id) {
+                return Err(anyhow!("failed to load directory {entry_path:?}"));
+            }
+            if let Some(expansion) =
+                worktree.update(cx, |worktree, cx| worktree.expand_entry(entry_id, cx))?
+            {
+                expansions.push(expansion);
+            }
+        }
+        futures::future::try_join_all(expansions).await?;
+    }
+}
+
+/// Lists everything inside the given path on the file system, parents first, as their paths
+/// relative to it and absolute, along with whether they are directories. The path itself is
+/// listed with an empty relative path.
+async fn list_dir_recursive(fs: &dyn Fs, abs_path: &Path) -> Result<Vec<(PathBuf, PathBuf, bool)>> {
+    let mut entries = Vec::new();
+    let mut pending_paths = vec![PathBuf::new()];
+    while let Some(relative_path) = pending_paths.pop() {
+        let path = if relative_path == Path::new("") {
+            abs_path.to_path_buf()
+        } else {
+            abs_path.join(&relative_path)
+        };
+        let metadata = fs
+            .metadata(&path)
+            .await?
+            .ok_or_else(|| anyhow!("path does not exist: {path:?}"))?;
+        if metadata.is_dir {
+            let mut children = fs.read_dir(&path).await?;
+            while let Some(child) = children.next().await {
+                if let Some(file_name) = child?.file_name() {
+                    pending_paths.push(relative_path.join(file_name));
+                }
+            }
+        }
+        entries.push((relative_path, path, metadata.is_dir));
+    }
+    Ok(entries)
+}
@@ -0,0 +1,308 @@
+//! Renaming several project entries at once, with a pattern describing their new names.
+
+use std::{ffi::OsStr, path::Path, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use editor::Editor;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    SharedString, Subscription, View, ViewContext, VisualContext as _,
+};
+use project::{Project, ProjectEntryId, ProjectPath};
+use ui::prelude::*;
+use workspace::{notifications::DetachAndPromptErr, ModalView};
+
+const DEFAULT_PATTERN: &str = "{name}_{n}.{ext}";
+
+/// A modal to rename the marked entries of the project panel, showing a preview of the new names.
+pub(crate) struct BulkRename {
+    project: Model<Project>,
+    entries: Vec<(ProjectEntryId, ProjectPath)>,
+    pattern_editor: View<Editor>,
+    renames: Result<Vec<Arc<Path>>, SharedString>,
+    _subscription: Subscription,
+}
+
+impl ModalView for BulkRename {}
+
+impl EventEmitter<DismissEvent> for BulkRename {}
+
+impl FocusableView for BulkRename {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.pattern_editor.focus_handle(cx)
+    }
+}
+
+impl BulkRename {
+    /// Creates a modal to rename the given entries, numbered in the given order.
+    pub(crate) fn new(
+        project: Model<Project>,
+        entries: Vec<(ProjectEntryId, ProjectPath)>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let pattern_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_text(DEFAULT_PATTERN, cx);
+            editor.select_all(&Default::default(), cx);
+            editor
+        });
+        let subscription = cx.subscribe(&pattern_editor, |this, _, event, cx| match event {
+            editor::EditorEvent::Blurred => cx.emit(DismissEvent),
+            editor::EditorEvent::BufferEdited { .. } => this.update_renames(cx),
+            _ => {}
+        });
+
+        let mut this = Self {
+            project,
+            entries,
+            pattern_editor,
+            renames: Ok(Vec::new()),
+            _subscription: subscription,
+        };
+        this.update_renames(cx);
+        this
+    }
+
+    fn update_renames(&mut self, cx: &mut ViewContext<Self>) {
+        let pattern = self.pattern_editor.read(cx).text(cx);
+        let paths = self
+            .entries
+            .iter()
+            .map(|(_, project_path)| project_path.path.clone())
+            .collect::<Vec<_>>();
+        self.renames = bulk_renames(&pattern, &paths)
+            .and_then(|new_paths| self.check_conflicts(new_paths, cx))
+            .map_err(|error| error.to_string().into());
+        cx.notify();
+    }
+
+    /// Checks that no two entries get the same path, and that no entry gets the path of an
+    /// existing entry.
+    fn check_conflicts(
+        &self,
+        new_paths: Vec<Arc<Path>>,
+        cx: &AppContext,
+    ) -> Result<Vec<Arc<Path>>> {
+        let project = self.project.read(cx);
+        let mut renamed_paths = HashMap::default();
+        for ((_, old_path), new_path) in self.entries.iter().zip(&new_paths) {
+            if *new_path == old_path.path {
+                continue;
+            }
+            let new_project_path = ProjectPath {
+                worktree_id: old_path.worktree_id,
+                path: new_path.clone(),
+            };
+            if project.entry_for_path(&new_project_path, cx).is_some() {
+                return Err(anyhow!("{new_path:?} already exists"));
+            }
+            if let Some(other_path) = renamed_paths.insert(new_project_path, old_path) {
+                return Err(anyhow!(
+                    "{:?} and {:?} would both be renamed to {new_path:?}",
+                    other_path.path,
+                    old_path.path
+                ));
+            }
+        }
+        Ok(new_paths)
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let Ok(new_paths) = &self.renames else {
+            return;
+        };
+        let renames = self
+            .entries
+            .iter()
+            .zip(new_paths)
+            .filter(|((_, old_path), new_path)| old_path.path != **new_path)
+            .map(|((entry_id, _), new_path)| (*entry_id, new_path.clone()))
+            .collect::<Vec<_>>();
+        let project = self.project.clone();
+        cx.spawn(|_, mut cx| async move {
+            for (entry_id, new_path) in renames {
+                project
+                    .update(&mut cx, |project, cx| {
+                        project.rename_entry(entry_id, new_path, cx)
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to rename entries", cx, |_, _| None);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BulkRename {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let preview = match &self.renames {
+            Ok(new_paths) => v_flex()
+                .id("bulk-rename-preview")
+                .max_h(rems(20.))
+                .overflow_y_scroll()
+                .children(
+                    self.entries
+                        .iter()
+                        .zip(new_paths)
+                        .map(|((_, old_path), new_path)| render_rename(&old_path.path, new_path)),
+                ),
+            Err(error) => v_flex()
+                .id("bulk-rename-preview")
+                .child(Label::new(error.clone()).color(Color::Error)),
+        };
+
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("BulkRename")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.pattern_editor.clone()),
+            )
+            .child(
+                v_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(
+                        Label::new(format!(
+                            "Rename {} entries. Use {{name}}, {{ext}} and {{n}} for the name, \
+                             extension and number of each entry.",
+                            self.entries.len()
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .child(preview),
+            )
+    }
+}
+
+fn render_rename(old_path: &Path, new_path: &Path) -> impl IntoElement {
+    let new_name_color = if old_path == new_path {
+        Color::Muted
+    } else {
+        Color::Default
+    };
+    h_flex()
+        .gap_2()
+        .child(Label::new(file_name(old_path)).color(Color::Muted))
+        .child(Label::new("→").color(Color::Muted))
+        .child(Label::new(file_name(new_path)).color(new_name_color))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Returns the paths of the given entries after renaming them according to the pattern, keeping
+/// them in their directories.
+///
+/// The pattern can refer to the name of each entry without its extension with `{name}`, to its
+/// extension with `{ext}` and to its position among the renamed entries, starting at 1, with
+/// `{n}`. For entries without an extension, the dot preceding `{ext}` is left out.
+fn bulk_renames(pattern: &str, paths: &[Arc<Path>]) -> Result<Vec<Arc<Path>>> {
+    paths
+        .iter()
+        .enumerate()
+        .map(|(ix, path)| {
+            let name = path.file_stem().map_or("".into(), OsStr::to_string_lossy);
+            let ext = path.extension().map_or("".into(), OsStr::to_string_lossy);
+            let new_name = expand_pattern(pattern, &name, &ext, ix + 1)?;
+            if new_name.is_empty()
+                || new_name == "."
+                || new_name == ".."
+                || new_name.contains(['/', '\\'])
+            {
+                return Err(anyhow!("{new_name:?} is not a valid file name"));
+            }
+            let mut new_path = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            new_path.push(new_name);
+            Ok(Arc::from(new_path))
+        })
+        .collect()
+}
+
+fn expand_pattern(pattern: &str, name: &str, ext: &str, n: usize) -> Result<String> {
+    let mut result = String::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| anyhow!("unclosed placeholder in {pattern:?}"))?
+            + start;
+        match &rest[start + 1..end] {
+            "name" => result.push_str(name),
+            "n" => result.push_str(&n.to_string()),
+            "ext" if ext.is_empty() => {
+                if result.ends_with('.') {
+                    result.pop();
+                }
+            }
+            "ext" => result.push_str(ext),
+            placeholder => return Err(anyhow!("unknown placeholder {{{placeholder}}}")),
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bulk_renames() {
+        let paths = ["src/main.rs", "src/lib.rs", "README", "docs/.gitignore"]
+            .into_iter()
+            .map(|path| Arc::from(Path::new(path)))
+            .collect::<Vec<_>>();
+        let renames = |pattern| {
+            bulk_renames(pattern, &paths).map(|paths| {
+                paths
+                    .iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(
+            renames("{name}_{n}.{ext}").unwrap(),
+            [
+                "src/main_1.rs",
+                "src/lib_2.rs",
+                "README_3",
+                "docs/.gitignore_4"
+            ]
+        );
+        assert_eq!(
+            renames("{n}-{name}.bak").unwrap(),
+            [
+                "src/1-main.bak",
+                "src/2-lib.bak",
+                "3-README.bak",
+                "docs/4-.gitignore.bak"
+            ]
+        );
+        assert!(renames("{name}_{index}").is_err());
+        assert!(renames("{name").is_err());
+        assert!(renames("{name}/{ext}").is_err());
+        assert!(renames("").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+mod bulk_rename;
 mod project_panel_settings;
 mod utils;
 
@@ -17,24 +18,22 @@ use editor::{
 use file_icons::FileIcons;
 
 use anyhow::{anyhow, Context as _, Result};
+use bulk_rename::BulkRename;
 use collections::{hash_map, BTreeSet, HashMap};
 use command_palette_hooks::CommandPaletteFilter;
 use git::repository::GitFileStatus;
 use gpui::{
     actions, anchored, deferred, div, impl_actions, point, px, size, uniform_list, Action,
     AnyElement, AppContext, AssetSource, AsyncWindowContext, Bounds, ClipboardItem, DismissEvent,
-    Div, DragMoveEvent, EventEmitter, ExternalPaths, FocusHandle, FocusableView, Hsla,
+    Div, DragMoveEvent, EventEmitter, ExternalPaths, FocusHandle, FocusableView, Global, Hsla,
     InteractiveElement, KeyContext, ListHorizontalSizingBehavior, ListSizingBehavior, Model,
     MouseButton, MouseDownEvent, ParentElement, Pixels, Point, PromptLevel, Render, ScrollStrategy,
     Stateful, Styled, Subscription, Task, UniformListScrollHandle, View, ViewContext,
-    VisualContext as _, WeakView, WindowContext,
+    VisualContext as _, WeakModel, WeakView, WindowContext,
 };
 use indexmap::IndexMap;
 use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
-use project::{
-    relativize_path, Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath, Worktree,
-    WorktreeId,
-};
+use project::{Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath, Worktree, WorktreeId};
 use project_panel_settings::{
     ProjectPanelDockPosition, ProjectPanelSettings, ShowDiagnostics, ShowIndentGuides,
 };
@@ -91,7 +90,6 @@ pub struct ProjectPanel {
     context_menu: Option<(View<ContextMenu>, Point<Pixels>, Subscription)>,
     edit_state: Option<EditState>,
     filename_editor: View<Editor>,
-    clipboard: Option<ClipboardEntry>,
     _dragged_entry_destination: Option<Arc<Path>>,
     workspace: WeakView<Workspace>,
     width: Option<Pixels>,
@@ -130,6 +128,14 @@ enum ClipboardEntry {
     Cut(BTreeSet<SelectedEntry>),
 }
 
+/// The entries last copied or cut in any project panel, along with the project they belong to.
+/// It's shared by the panels of all windows, so that entries can be pasted into other projects,
+/// including remote ones.
+#[derive(Default)]
+struct GlobalClipboard(Option<(WeakModel<Project>, ClipboardEntry)>);
+
+impl Global for GlobalClipboard {}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct EntryDetails {
     filename: String,
@@ -387,7 +393,6 @@ impl ProjectPanel {
                 edit_state: None,
                 context_menu: None,
                 filename_editor,
-                clipboard: None,
                 _dragged_entry_destination: None,
                 workspace: workspace.weak_handle(),
                 width: None,
@@ -668,7 +673,7 @@ impl ProjectPanel {
                             .action("Duplicate", Box::new(Duplicate))
                             // TODO: Paste should always be visible, cbut disabled when clipboard is empty
                             .map(|menu| {
-                                if self.clipboard.as_ref().is_some() {
+                                if self.clipboard(cx).is_some() {
                                     menu.action("Paste", Box::new(Paste))
                                 } else {
                                     menu.disabled_action("Paste", Box::new(Paste))
@@ -1160,6 +1165,10 @@ impl ProjectPanel {
     }
 
     fn rename(&mut self, _: &Rename, cx: &mut ViewContext<Self>) {
+        if self.marked_entries().len() > 1 {
+            self.bulk_rename(cx);
+            return;
+        }
         if let Some(SelectedEntry {
             worktree_id,
             entry_id,
@@ -1201,6 +1210,43 @@ impl ProjectPanel {
         }
     }
 
+    fn bulk_rename(&mut self, cx: &mut ViewContext<Self>) {
+        let project = self.project.read(cx);
+        // Entries inside marked directories move along with them, so they keep their names.
+        let mut entries = self
+            .disjoint_entries(cx)
+            .into_iter()
+            .filter_map(|selection| {
+                let worktree = project.worktree_for_id(selection.worktree_id, cx)?;
+                let entry = worktree.read(cx).entry_for_id(selection.entry_id)?;
+                Some((
+                    entry.id,
+                    ProjectPath {
+                        worktree_id: selection.worktree_id,
+                        path: entry.path.clone(),
+                    },
+                    entry.is_file(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|(_, a, a_is_file), (_, b, b_is_file)| {
+            a.worktree_id
+                .cmp(&b.worktree_id)
+                .then_with(|| compare_paths((&a.path, *a_is_file), (&b.path, *b_is_file)))
+        });
+        let entries = entries
+            .into_iter()
+            .map(|(entry_id, project_path, _)| (entry_id, project_path))
+            .collect();
+
+        let project = self.project.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(cx, |cx| BulkRename::new(project, entries, cx));
+            })
+            .ok();
+    }
+
     fn trash(&mut self, action: &Trash, cx: &mut ViewContext<Self>) {
         self.remove(true, action.skip_prompt, cx);
     }
@@ -1744,7 +1790,7 @@ impl ProjectPanel {
     fn cut(&mut self, _: &Cut, cx: &mut ViewContext<Self>) {
         let entries = self.disjoint_entries(cx);
         if !entries.is_empty() {
-            self.clipboard = Some(ClipboardEntry::Cut(entries));
+            self.set_clipboard(ClipboardEntry::Cut(entries), cx);
             cx.notify();
         }
     }
@@ -1752,13 +1798,25 @@ impl ProjectPanel {
     fn copy(&mut self, _: &Copy, cx: &mut ViewContext<Self>) {
         let entries = self.disjoint_entries(cx);
         if !entries.is_empty() {
-            self.clipboard = Some(ClipboardEntry::Copied(entries));
+            self.set_clipboard(ClipboardEntry::Copied(entries), cx);
             cx.notify();
         }
     }
 
+    fn set_clipboard(&self, entry: ClipboardEntry, cx: &mut AppContext) {
+        cx.set_global(GlobalClipboard(Some((self.project.downgrade(), entry))));
+    }
+
+    /// Returns the copied or cut entries, along with the project they belong to, which can be
+    /// another project than the panel's.
+    fn clipboard<'a>(&self, cx: &'a AppContext) -> Option<(Model<Project>, &'a ClipboardEntry)> {
+        let (project, entry) = cx.try_global::<GlobalClipboard>()?.0.as_ref()?;
+        Some((project.upgrade()?, entry))
+    }
+
     fn create_paste_path(
         &self,
+        source_project: &Model<Project>,
         source: &SelectedEntry,
         (worktree, target_entry): (Model<Worktree>, &Entry),
         cx: &AppContext,
@@ -1768,8 +1826,7 @@ impl ProjectPanel {
         if target_entry.is_file() || (target_entry.is_dir() && target_entry.id == source.entry_id) {
             new_path.pop();
         }
-        let clipboard_entry_file_name = self
-            .project
+        let clipboard_entry_file_name = source_project
             .read(cx)
             .path_for_entry(source.entry_id, cx)?
             .path
@@ -1806,10 +1863,11 @@ impl ProjectPanel {
             let (worktree, entry) = self.selected_entry_handle(cx)?;
             let entry = entry.clone();
             let worktree_id = worktree.read(cx).id();
-            let clipboard_entries = self
-                .clipboard
-                .as_ref()
-                .filter(|clipboard| !clipboard.items().is_empty())?;
+            let (source_project, clipboard_entries) = self
+                .clipboard(cx)
+                .filter(|(_, clipboard)| !clipboard.items().is_empty())?;
+            let clipboard_entries = clipboard_entries.clone();
+            let is_same_project = source_project == self.project;
             enum PasteTask {
                 Rename(Task<Result<CreatedEntry>>),
                 Copy(Task<Result<Option<Entry>>>),
@@ -1818,42 +1876,57 @@ impl ProjectPanel {
                 IndexMap::default();
             let clip_is_cut = clipboard_entries.is_cut();
             for clipboard_entry in clipboard_entries.items() {
-                let new_path =
-                    self.create_paste_path(clipboard_entry, self.selected_sub_entry(cx)?, cx)?;
+                let new_path = self.create_paste_path(
+                    &source_project,
+                    clipboard_entry,
+                    self.selected_sub_entry(cx)?,
+                    cx,
+                )?;
                 let clip_entry_id = clipboard_entry.entry_id;
-                let is_same_worktree = clipboard_entry.worktree_id == worktree_id;
-                let relative_worktree_source_path = if !is_same_worktree {
-                    let target_base_path = worktree.read(cx).abs_path();
-                    let clipboard_project_path =
-                        self.project.read(cx).path_for_entry(clip_entry_id, cx)?;
-                    let clipboard_abs_path = self
-                        .project
-                        .read(cx)
-                        .absolute_path(&clipboard_project_path, cx)?;
-                    Some(relativize_path(
-                        &target_base_path,
-                        clipboard_abs_path.as_path(),
-                    ))
-                } else {
-                    None
-                };
+                let is_same_worktree =
+                    is_same_project && clipboard_entry.worktree_id == worktree_id;
                 let task = if clip_is_cut && is_same_worktree {
                     let task = self.project.update(cx, |project, cx| {
                         project.rename_entry(clip_entry_id, new_path, cx)
                     });
                     PasteTask::Rename(task)
-                } else {
-                    let entry_id = if is_same_worktree {
-                        clip_entry_id
-                    } else {
-                        entry.id
-                    };
+                } else if is_same_worktree {
                     let task = self.project.update(cx, |project, cx| {
-                        project.copy_entry(entry_id, relative_worktree_source_path, new_path, cx)
+                        project.copy_entry(clip_entry_id, None, new_path, cx)
                     });
                     PasteTask::Copy(task)
+                } else {
+                    // Entries of other worktrees, which can be on other hosts, are copied by
+                    // content rather than through a shared file system.
+                    let target_worktree = worktree.clone();
+                    let task = source_project.read(cx).worktree_store().update(
+                        cx,
+                        |worktree_store, cx| {
+                            worktree_store.copy_entry_to_worktree(
+                                clip_entry_id,
+                                target_worktree,
+                                new_path,
+                                cx,
+                            )
+                        },
+                    );
+                    PasteTask::Copy(task)
                 };
-                let needs_delete = !is_same_worktree && clip_is_cut;
+                // Moving an entry of another worktree deletes it once it's copied. Files that
+                // a remote host doesn't share aren't copied, so directories of remote worktrees
+                // are kept rather than lose them.
+                let needs_delete = !is_same_worktree
+                    && clip_is_cut
+                    && source_project
+                        .read(cx)
+                        .worktree_for_entry(clip_entry_id, cx)
+                        .map_or(false, |worktree| {
+                            let worktree = worktree.read(cx);
+                            worktree.is_local()
+                                || worktree
+                                    .entry_for_id(clip_entry_id)
+                                    .map_or(false, |entry| entry.is_file())
+                        });
                 paste_entry_tasks.insert((clip_entry_id, needs_delete), task);
             }
 
@@ -1890,13 +1963,11 @@ impl ProjectPanel {
                 }
                 // remove entry for cut in difference worktree
                 for entry_id in need_delete_ids {
-                    project_panel
-                        .update(&mut cx, |project_panel, cx| {
-                            project_panel
-                                .project
-                                .update(cx, |project, cx| project.delete_entry(entry_id, true, cx))
-                                .ok_or_else(|| anyhow!("no such entry"))
-                        })??
+                    source_project
+                        .update(&mut cx, |project, cx| {
+                            project.delete_entry(entry_id, true, cx)
+                        })?
+                        .ok_or_else(|| anyhow!("no such entry"))?
                         .await?;
                 }
 
@@ -2612,6 +2683,7 @@ impl ProjectPanel {
                     .clone();
                 for selection in selections.items() {
                     let new_path = self.create_paste_path(
+                        &self.project,
                         selection,
                         (target_worktree.clone(), &target_entry),
                         cx,
@@ -2807,10 +2879,9 @@ impl ProjectPanel {
                         is_marked,
                         is_editing: false,
                         is_processing: false,
-                        is_cut: self
-                            .clipboard
-                            .as_ref()
-                            .map_or(false, |e| e.is_cut() && e.items().contains(&selection)),
+                        is_cut: self.clipboard(cx).map_or(false, |(project, e)| {
+                            project == self.project && e.is_cut() && e.items().contains(&selection)
+                        }),
                         filename_text_color,
                         diagnostic_severity,
                         git_status: status,
@@ -5532,6 +5603,71 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_copy_paste_between_projects(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root1",
+            json!({
+                "one.txt": "one",
+                "a": {
+                    "two.txt": "two",
+                    "b": { "three.txt": "three" },
+                },
+            }),
+        )
+        .await;
+        fs.insert_tree("/root2", json!({ "four.txt": "four" }))
+            .await;
+
+        let project1 = Project::test(fs.clone(), ["/root1".as_ref()], cx).await;
+        let workspace1 = cx.add_window(|cx| Workspace::test_new(project1.clone(), cx));
+        let cx1 = &mut VisualTestContext::from_window(*workspace1, cx);
+        let panel1 = workspace1.update(cx1, ProjectPanel::new).unwrap();
+
+        let project2 = Project::test(fs.clone(), ["/root2".as_ref()], cx).await;
+        let workspace2 = cx.add_window(|cx| Workspace::test_new(project2.clone(), cx));
+        let cx2 = &mut VisualTestContext::from_window(*workspace2, cx);
+        let panel2 = workspace2.update(cx2, ProjectPanel::new).unwrap();
+
+        toggle_expand_dir(&panel1, "root1/a", cx1);
+        select_path(&panel1, "root1/a", cx1);
+        panel1.update(cx1, |panel, cx| panel.copy(&Default::default(), cx));
+
+        select_path(&panel2, "root2/four.txt", cx2);
+        panel2.update(cx2, |panel, cx| panel.paste(&Default::default(), cx));
+        cx2.executor().run_until_parked();
+
+        toggle_expand_dir(&panel2, "root2/a", cx2);
+        toggle_expand_dir(&panel2, "root2/a/b", cx2);
+        assert_eq!(
+            visible_entries_as_strings(&panel2, 0..50, cx2),
+            &[
+                "v root2",
+                "    v a  <== selected",
+                "        v b",
+                "              three.txt",
+                "          two.txt",
+                "      four.txt",
+            ]
+        );
+        assert_eq!(
+            fs.load("/root2/a/b/three.txt".as_ref()).await.unwrap(),
+            "three"
+        );
+
+        select_path(&panel1, "root1/one.txt", cx1);
+        panel1.update(cx1, |panel, cx| panel.cut(&Default::default(), cx));
+        select_path(&panel2, "root2/four.txt", cx2);
+        panel2.update(cx2, |panel, cx| panel.paste(&Default::default(), cx));
+        cx2.executor().run_until_parked();
+
+        assert_eq!(fs.load("/root2/one.txt".as_ref()).await.unwrap(), "one");
+        assert!(!fs.is_file("/root1/one.txt".as_ref()).await);
+    }
+
     #[gpui::test]
     async fn test_copy_paste_directory(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -7808,6 +7944,51 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_bulk_rename_nested_entries(cx: &mut gpui::TestAppContext) {
+        init_test_with_editor(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "dir": {
+                    "a.txt": "",
+                },
+                "b.txt": "",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace.update(cx, ProjectPanel::new).unwrap();
+
+        toggle_expand_dir(&panel, "root/dir", cx);
+        select_path_with_mark(&panel, "root/dir", cx);
+        select_path_with_mark(&panel, "root/dir/a.txt", cx);
+        select_path_with_mark(&panel, "root/b.txt", cx);
+        panel.update(cx, |panel, cx| panel.rename(&Rename, cx));
+        cx.run_until_parked();
+        assert!(
+            workspace
+                .update(cx, |workspace, cx| workspace.active_modal::<BulkRename>(cx))
+                .unwrap()
+                .is_some(),
+            "Renaming several marked entries should open the bulk rename modal"
+        );
+
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert!(fs.is_dir("/root/dir_1".as_ref()).await);
+        assert!(
+            fs.is_file("/root/dir_1/a.txt".as_ref()).await,
+            "Entries inside a renamed directory should keep their names"
+        );
+        assert!(fs.is_file("/root/b_2.txt".as_ref()).await);
+    }
+
     fn select_path_with_mark(
         panel: &View<ProjectPanel>,
         path: impl AsRef<Path>,
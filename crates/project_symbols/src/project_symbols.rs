@@ -5,8 +5,9 @@ use gpui::{
     ViewContext, WeakView, WindowContext,
 };
 use ordered_float::OrderedFloat;
-use picker::{Picker, PickerDelegate};
+use picker::{Picker, PickerDelegate, PickerSettings, PreviewLocation};
 use project::{Project, Symbol};
+use settings::Settings;
 use std::{borrow::Cow, cmp::Reverse, sync::Arc};
 use theme::ActiveTheme;
 use util::ResultExt;
@@ -16,6 +17,7 @@ use workspace::{
 };
 
 pub fn init(cx: &mut AppContext) {
+    PickerSettings::register(cx);
     cx.observe_new_views(
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
             workspace.register_action(|workspace, _: &workspace::ToggleProjectSymbols, cx| {
@@ -146,6 +148,32 @@ impl PickerDelegate for ProjectSymbolsDelegate {
 
     fn dismissed(&mut self, _cx: &mut ViewContext<Picker<Self>>) {}
 
+    fn preview_location(
+        &self,
+        ix: usize,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Task<anyhow::Result<PreviewLocation>>> {
+        if !PickerSettings::get_global(cx).preview {
+            return None;
+        }
+
+        let symbol = self.symbols[self.matches.get(ix)?.candidate_id].clone();
+        let buffer = self.project.update(cx, |project, cx| {
+            project.open_buffer_for_symbol(&symbol, cx)
+        });
+        Some(cx.spawn(|_, cx| async move {
+            let buffer = buffer.await?;
+            let range = buffer.read_with(&cx, |buffer, _| {
+                let to_point = |point, bias| {
+                    let point = buffer.clip_point_utf16(point, bias);
+                    buffer.offset_to_point(buffer.point_utf16_to_offset(point))
+                };
+                to_point(symbol.range.start, Bias::Left)..to_point(symbol.range.end, Bias::Right)
+            })?;
+            Ok(PreviewLocation { buffer, range })
+        }))
+    }
+
     fn match_count(&self) -> usize {
         self.matches.len()
     }
@@ -253,7 +281,7 @@ impl PickerDelegate for ProjectSymbolsDelegate {
 mod tests {
     use super::*;
     use futures::StreamExt;
-    use gpui::{SemanticVersion, TestAppContext, VisualContext};
+    use gpui::{SemanticVersion, TestAppContext, VisualContext, VisualTestContext};
     use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
     use project::FakeFs;
     use serde_json::json;
@@ -383,6 +411,91 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_project_symbols_preview(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<PickerSettings>(cx, |settings| {
+                    settings.preview = Some(true)
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({ "a.rs": "fn one() {}", "b.rs": "fn two() {}" }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_servers =
+            language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+        let _buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp("/dir/a.rs", cx)
+            })
+            .await
+            .unwrap();
+
+        let fake_symbols = [symbol("one", "/dir/a.rs"), symbol("two", "/dir/b.rs")];
+        let fake_server = fake_servers.next().await.unwrap();
+        fake_server.handle_request::<lsp::WorkspaceSymbolRequest, _, _>(move |_, _| {
+            let fake_symbols = fake_symbols.clone();
+            async move {
+                Ok(Some(lsp::WorkspaceSymbolResponse::Flat(
+                    fake_symbols.to_vec(),
+                )))
+            }
+        });
+
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let symbols = cx.new_view(|cx| {
+            Picker::uniform_list(
+                ProjectSymbolsDelegate::new(workspace.downgrade(), project.clone()),
+                cx,
+            )
+        });
+        symbols.update(cx, |p, cx| p.update_matches("o".to_string(), cx));
+        cx.run_until_parked();
+
+        let assert_previews_selected_symbol = |cx: &mut VisualTestContext| {
+            cx.run_until_parked();
+            symbols.update(cx, |symbols, cx| {
+                let delegate = &symbols.delegate;
+                let selected = &delegate.matches[delegate.selected_match_index];
+                let symbol = &delegate.symbols[selected.candidate_id];
+                let location = symbols.previewed_location(cx).unwrap();
+                let buffer = location.buffer.read(cx);
+                assert_eq!(buffer.file().unwrap().path(), &symbol.path.path);
+                symbol.name.clone()
+            })
+        };
+
+        let first_symbol = assert_previews_selected_symbol(cx);
+        symbols.update(cx, |symbols, cx| {
+            symbols.select_next(&Default::default(), cx)
+        });
+        let second_symbol = assert_previews_selected_symbol(cx);
+        assert_ne!(
+            first_symbol, second_symbol,
+            "Moving the selection should preview the newly selected symbol"
+        );
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let store = SettingsStore::test(cx);
@@ -393,6 +506,7 @@ mod tests {
             Project::init_settings(cx);
             workspace::init_settings(cx);
             editor::init(cx);
+            PickerSettings::register(cx);
         });
     }
 
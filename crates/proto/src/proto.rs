@@ -198,6 +198,8 @@ messages!(
     (GetDocumentHighlightsResponse, Background),
     (GetHover, Background),
     (GetHoverResponse, Background),
+    (GetInlineValues, Background),
+    (GetInlineValuesResponse, Background),
     (GetNotifications, Foreground),
     (GetNotificationsResponse, Foreground),
     (GetPrivateUserInfo, Foreground),
@@ -206,6 +208,8 @@ messages!(
     (GetProjectSymbolsResponse, Background),
     (GetReferences, Background),
     (GetReferencesResponse, Background),
+    (GetSelectionRanges, Background),
+    (GetSelectionRangesResponse, Background),
     (GetSignatureHelp, Background),
     (GetSignatureHelpResponse, Background),
     (GetSupermavenApiKey, Background),
@@ -252,6 +256,8 @@ messages!(
     (PrepareRename, Background),
     (PrepareRenameResponse, Background),
     (ProjectEntryResponse, Foreground),
+    (ReadProjectEntry, Background),
+    (ReadProjectEntryResponse, Background),
     (CountLanguageModelTokens, Background),
     (CountLanguageModelTokensResponse, Background),
     (RefreshLlmToken, Background),
@@ -314,6 +320,17 @@ messages!(
     (UpdateWorktree, Foreground),
     (UpdateWorktreeSettings, Foreground),
     (UsersResponse, Foreground),
+    (WriteProjectEntry, Foreground),
+    (UpdateSharedTerminal, Foreground),
+    (UnshareTerminal, Foreground),
+    (SharedTerminalInput, Foreground),
+    (GetCommentThreads, Background),
+    (GetCommentThreadsResponse, Background),
+    (CreateCommentThread, Background),
+    (AddComment, Background),
+    (SetCommentThreadResolved, Background),
+    (CommentThreadResponse, Background),
+    (UpdateCommentThread, Foreground),
     (LspExtExpandMacro, Background),
     (LspExtExpandMacroResponse, Background),
     (LspExtOpenDocs, Background),
@@ -373,7 +390,13 @@ messages!(
     (SyncExtensions, Background),
     (SyncExtensionsResponse, Background),
     (InstallExtension, Background),
+    (SyncLanguageServerBundle, Background),
+    (SyncLanguageServerBundleResponse, Background),
+    (InstallLanguageServerBundle, Background),
     (RegisterBufferWithLanguageServers, Background),
+    (WillPerformFileOperation, Foreground),
+    (WillPerformFileOperationResponse, Foreground),
+    (ApplyResourceOperations, Foreground),
 );
 
 request_messages!(
@@ -408,11 +431,13 @@ request_messages!(
     (GetImplementation, GetImplementationResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
     (GetHover, GetHoverResponse),
+    (GetInlineValues, GetInlineValuesResponse),
     (GetLlmToken, GetLlmTokenResponse),
     (GetNotifications, GetNotificationsResponse),
     (GetPrivateUserInfo, GetPrivateUserInfoResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
     (GetReferences, GetReferencesResponse),
+    (GetSelectionRanges, GetSelectionRangesResponse),
     (GetSignatureHelp, GetSignatureHelpResponse),
     (GetStagedText, GetStagedTextResponse),
     (GetSupermavenApiKey, GetSupermavenApiKeyResponse),
@@ -440,6 +465,7 @@ request_messages!(
     (PerformRename, PerformRenameResponse),
     (Ping, Ack),
     (PrepareRename, PrepareRenameResponse),
+    (ReadProjectEntry, ReadProjectEntryResponse),
     (CountLanguageModelTokens, CountLanguageModelTokensResponse),
     (RefreshInlayHints, Ack),
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
@@ -472,6 +498,12 @@ request_messages!(
     (UpdateParticipantLocation, Ack),
     (UpdateProject, Ack),
     (UpdateWorktree, Ack),
+    (WriteProjectEntry, ProjectEntryResponse),
+    (SharedTerminalInput, Ack),
+    (GetCommentThreads, GetCommentThreadsResponse),
+    (CreateCommentThread, CommentThreadResponse),
+    (AddComment, CommentThreadResponse),
+    (SetCommentThreadResolved, CommentThreadResponse),
     (LspExtExpandMacro, LspExtExpandMacroResponse),
     (LspExtOpenDocs, LspExtOpenDocsResponse),
     (SetRoomParticipantRole, Ack),
@@ -500,7 +532,11 @@ request_messages!(
     (CancelLanguageServerWork, Ack),
     (SyncExtensions, SyncExtensionsResponse),
     (InstallExtension, Ack),
+    (SyncLanguageServerBundle, SyncLanguageServerBundleResponse),
+    (InstallLanguageServerBundle, Ack),
     (RegisterBufferWithLanguageServers, Ack),
+    (WillPerformFileOperation, WillPerformFileOperationResponse),
+    (ApplyResourceOperations, Ack),
 );
 
 entity_messages!(
@@ -527,8 +563,10 @@ entity_messages!(
     GetImplementation,
     GetDocumentHighlights,
     GetHover,
+    GetInlineValues,
     GetProjectSymbols,
     GetReferences,
+    GetSelectionRanges,
     GetSignatureHelp,
     GetStagedText,
     GetTypeDefinition,
@@ -545,6 +583,7 @@ entity_messages!(
     OpenBufferForSymbol,
     PerformRename,
     PrepareRename,
+    ReadProjectEntry,
     RefreshInlayHints,
     ReloadBuffers,
     RemoveProjectCollaborator,
@@ -565,6 +604,10 @@ entity_messages!(
     UpdateProjectCollaborator,
     UpdateWorktree,
     UpdateWorktreeSettings,
+    WriteProjectEntry,
+    UpdateSharedTerminal,
+    UnshareTerminal,
+    SharedTerminalInput,
     LspExtExpandMacro,
     LspExtOpenDocs,
     AdvertiseContexts,
@@ -587,6 +630,8 @@ entity_messages!(
     GetPathMetadata,
     CancelLanguageServerWork,
     RegisterBufferWithLanguageServers,
+    WillPerformFileOperation,
+    ApplyResourceOperations,
 );
 
 entity_messages!(
@@ -597,6 +642,7 @@ entity_messages!(
     UpdateChannelMessage,
     UpdateChannelBuffer,
     UpdateChannelBufferCollaborators,
+    UpdateCommentThread,
 );
 
 impl From<Timestamp> for SystemTime {
@@ -0,0 +1,121 @@
+//! Uploads the language servers and the Node runtime installed locally to SSH hosts that can't
+//! download them themselves, when `upload_language_servers_over_ssh` is set for the connection.
+
+use std::{
+    env::consts,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use futures::StreamExt as _;
+use gpui::{AsyncAppContext, Model};
+use http_client::HttpClient;
+use project::Fs;
+use remote::SshRemoteClient;
+
+/// Uploads the language servers that are installed locally but missing on the server, along with
+/// Editsync's Node runtime if the server lacks it, and installs them there.
+pub(crate) async fn upload_language_server_bundle(
+    client: Model<SshRemoteClient>,
+    fs: Arc<dyn Fs>,
+    http: Arc<dyn HttpClient>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let response = client
+        .update(cx, |client, _| {
+            client
+                .proto_client()
+                .request(proto::SyncLanguageServerBundle {})
+        })?
+        .await?;
+    let tmp_dir = PathBuf::from(&response.tmp_dir);
+    let same_platform = response.os == consts::OS && response.arch == consts::ARCH;
+
+    let mut language_servers = Vec::new();
+    let mut entries = fs.read_dir(paths::languages_dir()).await?;
+    while let Some(path) = entries.next().await {
+        let path = path?;
+        let Some(name) = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+        if response.language_servers.contains(&name)
+            || !is_installed_language_server(fs.as_ref(), &path).await?
+        {
+            continue;
+        }
+        // Servers installed from npm run anywhere, but the others are native binaries.
+        if !same_platform && !fs.is_dir(&path.join("node_modules")).await {
+            log::info!(
+                "Not uploading language server {name}, which doesn't run on {} {}",
+                response.os,
+                response.arch
+            );
+            continue;
+        }
+
+        log::info!("Uploading language server {name}");
+        client
+            .update(cx, |client, cx| {
+                client.upload_directory(path.clone(), tmp_dir.join(&name), cx)
+            })?
+            .await?;
+        language_servers.push(name);
+    }
+
+    let mut node_archive = None;
+    if !response.has_node {
+        let download_dir = tempfile::tempdir()?;
+        let archive = node_runtime::download_managed_node_archive(
+            &http,
+            &response.os,
+            &response.arch,
+            download_dir.path(),
+        )
+        .await;
+        match archive {
+            Ok(archive) => {
+                log::info!("Uploading Node runtime");
+                client
+                    .update(cx, |client, cx| {
+                        client.upload_directory(
+                            download_dir.path().to_owned(),
+                            tmp_dir.join("node"),
+                            cx,
+                        )
+                    })?
+                    .await?;
+                node_archive = archive
+                    .file_name()
+                    .map(|file_name| format!("node/{}", file_name.to_string_lossy()));
+            }
+            Err(error) => log::error!("Failed to download Node runtime for the server: {error:?}"),
+        }
+    }
+
+    if language_servers.is_empty() && node_archive.is_none() {
+        return Ok(());
+    }
+
+    client
+        .update(cx, |client, _| {
+            client
+                .proto_client()
+                .request(proto::InstallLanguageServerBundle {
+                    language_servers,
+                    node_archive,
+                })
+        })?
+        .await?;
+
+    Ok(())
+}
+
+/// Whether a directory in the languages directory holds a language server. A server whose
+/// download failed leaves an empty directory behind.
+async fn is_installed_language_server(fs: &dyn Fs, path: &Path) -> Result<bool> {
+    Ok(fs.is_dir(path).await && fs.read_dir(path).await?.next().await.is_some())
+}
@@ -0,0 +1,678 @@
+//! A panel listing the ports forwarded to the server of a remote project, where forwards can be
+//! added and removed while the project is open.
+//!
+//! Forwards are saved with the project in the `ssh_connections` setting, and added again when the
+//! project is reopened. Ports of well-known dev servers printed in the project's terminals are
+//! suggested as new forwards.
+
+use std::{collections::BTreeSet, sync::Arc, sync::LazyLock, time::Duration};
+
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use futures::{channel::mpsc, StreamExt as _};
+use gpui::{
+    actions, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView, Model, Subscription,
+    Task, View, WeakView,
+};
+use project::{Fs, Project};
+use regex::Regex;
+use remote::SshPortForward;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{update_settings_file, Settings, SettingsSources};
+use terminal::Terminal;
+use ui::{prelude::*, IconButtonShape, ListItem, ListItemSpacing, Tooltip};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::DetachAndPromptErr,
+    SerialieditsyncSshProject, Workspace,
+};
+
+use crate::ssh_connections::{SshConnection, SshProject, SshSettings};
+
+const PORTS_PANEL_KEY: &str = "PortsPanel";
+/// How long to wait for terminal output to settle before looking for ports in it.
+const SCAN_DEBOUNCE: Duration = Duration::from_millis(500);
+/// How many of the last lines of each terminal are looked at for ports.
+const SCANNED_LINES: usize = 20;
+/// Ports commonly used by dev servers, which are suggested when they're printed in a terminal.
+const DEV_SERVER_PORTS: &[u16] = &[
+    1313, 3000, 3001, 4000, 4200, 4321, 5000, 5173, 5500, 6006, 8000, 8008, 8080, 8081, 8888, 9000,
+];
+
+actions!(ports_panel, [ToggleFocus]);
+
+pub fn init(cx: &mut AppContext) {
+    PortsPanelSettings::register(cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<PortsPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PortsPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct PortsPanelSettingsContent {
+    /// Whether to show the ports panel button in the status bar of remote projects.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the ports panel.
+    ///
+    /// Default: right
+    pub dock: Option<DockPosition>,
+    /// Default width of the ports panel in pixels.
+    ///
+    /// Default: 300
+    pub default_width: Option<f32>,
+}
+
+impl Settings for PortsPanelSettings {
+    const KEY: Option<&'static str> = Some("ports_panel");
+
+    type FileContent = PortsPanelSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerialieditsyncPortsPanel {
+    width: Option<Pixels>,
+}
+
+pub struct PortsPanel {
+    project: Model<Project>,
+    ssh_project: Option<SerialieditsyncSshProject>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    port_editor: View<Editor>,
+    port_error: Option<SharedString>,
+    width: Option<Pixels>,
+    detected_ports: BTreeSet<u16>,
+    dismissed_ports: BTreeSet<u16>,
+    pending_scan: Option<Task<()>>,
+    pending_serialization: Task<Option<()>>,
+    _watch_terminals: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl PortsPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let ssh_project = workspace.serialieditsync_ssh_project();
+        let fs = workspace.app_state().fs.clone();
+
+        cx.new_view(|cx| {
+            let port_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Port to forward, e.g. 3000 or 8080:3000", cx);
+                editor
+            });
+
+            // Terminals are watched through a channel, as views can't be updated while a model
+            // is being created.
+            let (terminals_tx, mut terminals_rx) = mpsc::unbounded();
+            for terminal in project.read(cx).local_terminal_handles() {
+                if let Some(terminal) = terminal.upgrade() {
+                    terminals_tx.unbounded_send(terminal).ok();
+                }
+            }
+            let mut subscriptions = vec![cx.observe_new_models(move |_: &mut Terminal, cx| {
+                terminals_tx.unbounded_send(cx.handle()).ok();
+            })];
+            if let Some(ssh_client) = project.read(cx).ssh_client() {
+                subscriptions.push(cx.observe(&ssh_client, |_, _, cx| cx.notify()));
+            }
+            let watch_terminals = cx.spawn(|this, mut cx| async move {
+                while let Some(terminal) = terminals_rx.next().await {
+                    if this
+                        .update(&mut cx, |this, cx| this.watch_terminal(terminal, cx))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+
+            let this = Self {
+                project,
+                ssh_project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                port_editor,
+                port_error: None,
+                width: None,
+                detected_ports: BTreeSet::new(),
+                dismissed_ports: BTreeSet::new(),
+                pending_scan: None,
+                pending_serialization: Task::ready(None),
+                _watch_terminals: watch_terminals,
+                _subscriptions: subscriptions,
+            };
+            this.restore_port_forwards(cx);
+            this
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialieditsync_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(PORTS_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerialieditsyncPortsPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialieditsync_panel) = serialieditsync_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialieditsync_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        PORTS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerialieditsyncPortsPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn port_forwards(&self, cx: &AppContext) -> Vec<SshPortForward> {
+        self.project
+            .read(cx)
+            .ssh_client()
+            .map(|ssh_client| ssh_client.read(cx).port_forwards())
+            .unwrap_or_default()
+    }
+
+    /// Adds the forwards saved for this project, which are only kept for as long as the
+    /// project is open.
+    fn restore_port_forwards(&self, cx: &mut ViewContext<Self>) {
+        let (Some(ssh_client), Some(ssh_project)) =
+            (self.project.read(cx).ssh_client(), &self.ssh_project)
+        else {
+            return;
+        };
+        let saved_forwards = SshSettings::get_global(cx)
+            .ssh_connections()
+            .find(|connection| is_project_connection(connection, ssh_project))
+            .and_then(|connection| {
+                connection
+                    .projects
+                    .into_iter()
+                    .find(|project| project.paths == ssh_project.paths)
+            })
+            .map(|project| project.port_forwards)
+            .unwrap_or_default();
+        for forward in saved_forwards {
+            ssh_client
+                .update(cx, |ssh_client, cx| {
+                    ssh_client.add_port_forward(forward, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn forward_port(&mut self, forward: SshPortForward, cx: &mut ViewContext<Self>) {
+        let Some(ssh_client) = self.project.read(cx).ssh_client() else {
+            return;
+        };
+        let task = ssh_client.update(cx, |ssh_client, cx| {
+            ssh_client.add_port_forward(forward.clone(), cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            task.await?;
+            this.update(&mut cx, |this, cx| {
+                this.update_saved_port_forwards(cx, move |forwards| {
+                    if !forwards.contains(&forward) {
+                        forwards.push(forward);
+                    }
+                });
+            })
+        })
+        .detach_and_prompt_err("Failed to forward port", cx, |_, _| None);
+    }
+
+    fn remove_port_forward(&mut self, local_port: u16, cx: &mut ViewContext<Self>) {
+        let Some(ssh_client) = self.project.read(cx).ssh_client() else {
+            return;
+        };
+        let task = ssh_client.update(cx, |ssh_client, cx| {
+            ssh_client.remove_port_forward(local_port, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            task.await?;
+            this.update(&mut cx, |this, cx| {
+                this.update_saved_port_forwards(cx, move |forwards| {
+                    forwards.retain(|forward| forward.local_port != local_port);
+                });
+            })
+        })
+        .detach_and_prompt_err("Failed to stop forwarding port", cx, |_, _| None);
+    }
+
+    /// Updates the forwards saved for this project, adding the project to its server in the
+    /// settings if it isn't there yet.
+    fn update_saved_port_forwards(
+        &self,
+        cx: &mut ViewContext<Self>,
+        update: impl 'static + Send + FnOnce(&mut Vec<SshPortForward>),
+    ) {
+        let Some(ssh_project) = self.ssh_project.clone() else {
+            return;
+        };
+        update_settings_file::<SshSettings>(self.fs.clone(), cx, move |settings, _| {
+            let connections = settings.ssh_connections.get_or_insert_with(Vec::new);
+            let connection = match connections
+                .iter()
+                .position(|connection| is_project_connection(connection, &ssh_project))
+            {
+                Some(ix) => &mut connections[ix],
+                None => {
+                    connections.push(SshConnection {
+                        host: ssh_project.host.clone().into(),
+                        username: ssh_project.user.clone(),
+                        port: ssh_project.port,
+                        ..Default::default()
+                    });
+                    connections.last_mut().unwrap()
+                }
+            };
+            // Projects are ordered by their forwards too, so they're taken out to be updated.
+            let mut project = connection
+                .projects
+                .iter()
+                .find(|project| project.paths == ssh_project.paths)
+                .cloned()
+                .unwrap_or_else(|| SshProject {
+                    paths: ssh_project.paths.clone(),
+                    ..Default::default()
+                });
+            connection.projects.remove(&project);
+            update(&mut project.port_forwards);
+            connection.projects.insert(project);
+        });
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let text = self.port_editor.read(cx).text(cx);
+        let Some(forward) = parse_port_forward(text.trim()) else {
+            self.port_error = Some(format!("{text:?} is not a port to forward").into());
+            cx.notify();
+            return;
+        };
+        self.port_error = None;
+        self.port_editor.update(cx, |editor, cx| editor.clear(cx));
+        self.forward_port(forward, cx);
+    }
+
+    fn watch_terminal(&mut self, terminal: Model<Terminal>, cx: &mut ViewContext<Self>) {
+        if self.project.read(cx).ssh_client().is_none() {
+            return;
+        }
+        cx.subscribe(&terminal, |this, _, event, cx| {
+            if let terminal::Event::Wakeup = event {
+                this.scan_terminals_soon(cx);
+            }
+        })
+        .detach();
+        self.scan_terminals_soon(cx);
+    }
+
+    fn scan_terminals_soon(&mut self, cx: &mut ViewContext<Self>) {
+        if self.pending_scan.is_some() {
+            return;
+        }
+        self.pending_scan = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(SCAN_DEBOUNCE).await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_scan = None;
+                this.scan_terminals(cx);
+            })
+            .ok();
+        }));
+    }
+
+    /// Looks for dev server ports in the last lines of the terminals of the project.
+    fn scan_terminals(&mut self, cx: &mut ViewContext<Self>) {
+        let mut detected_ports = BTreeSet::new();
+        for terminal in self.project.read(cx).local_terminal_handles() {
+            let Some(terminal) = terminal.upgrade() else {
+                continue;
+            };
+            for line in terminal.read(cx).last_n_non_empty_lines(SCANNED_LINES) {
+                detected_ports.extend(dev_server_ports(&line));
+            }
+        }
+        if !detected_ports.is_subset(&self.detected_ports) {
+            self.detected_ports.extend(detected_ports);
+            cx.notify();
+        }
+    }
+
+    /// Returns the detected ports that aren't forwarded yet, nor dismissed.
+    fn suggested_ports(&self, cx: &AppContext) -> Vec<u16> {
+        let forwards = self.port_forwards(cx);
+        self.detected_ports
+            .iter()
+            .copied()
+            .filter(|port| {
+                !self.dismissed_ports.contains(port)
+                    && !forwards
+                        .iter()
+                        .any(|forward| forward.local_port == *port || forward.remote_port == *port)
+            })
+            .collect()
+    }
+
+    fn render_port_forward(
+        &self,
+        forward: SshPortForward,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let local_port = forward.local_port;
+        ListItem::new(("port-forward", local_port as usize))
+            .spacing(ListItemSpacing::Sparse)
+            .start_slot(Icon::new(IconName::Link).color(Color::Muted))
+            .child(Label::new(format!(
+                "localhost:{} → {}:{}",
+                forward.local_port,
+                forward.remote_host.as_deref().unwrap_or("localhost"),
+                forward.remote_port
+            )))
+            .end_slot(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new(("open-port", local_port as usize), IconName::ArrowUpRight)
+                            .shape(IconButtonShape::Square)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Open in Browser", cx))
+                            .on_click(move |_, cx| {
+                                cx.open_url(&format!("http://localhost:{local_port}"))
+                            }),
+                    )
+                    .child(
+                        IconButton::new(("remove-port", local_port as usize), IconName::Close)
+                            .shape(IconButtonShape::Square)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Stop Forwarding", cx))
+                            .on_click(cx.listener(move |this, _, cx| {
+                                this.remove_port_forward(local_port, cx)
+                            })),
+                    ),
+            )
+    }
+
+    fn render_suggested_port(&self, port: u16, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        ListItem::new(("suggested-port", port as usize))
+            .spacing(ListItemSpacing::Sparse)
+            .start_slot(Icon::new(IconName::Terminal).color(Color::Muted))
+            .child(Label::new(format!("Port {port}")))
+            .end_slot(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new(("forward-port", port as usize), "Forward")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(move |this, _, cx| {
+                                this.forward_port(SshPortForward::new(port), cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new(("dismiss-port", port as usize), IconName::Close)
+                            .shape(IconButtonShape::Square)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::text("Dismiss", cx))
+                            .on_click(cx.listener(move |this, _, cx| {
+                                this.dismissed_ports.insert(port);
+                                cx.notify();
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Render for PortsPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let panel = v_flex()
+            .key_context("PortsPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_2()
+            .gap_2();
+
+        if self.project.read(cx).ssh_client().is_none() {
+            return panel.child(
+                Label::new("Ports can only be forwarded for projects on remote servers.")
+                    .color(Color::Muted),
+            );
+        }
+
+        let forwards = self.port_forwards(cx);
+        let suggested_ports = self.suggested_ports(cx);
+        panel
+            .on_action(cx.listener(Self::confirm))
+            .child(Label::new("Forwarded Ports").size(LabelSize::Small))
+            .child(
+                v_flex()
+                    .when(forwards.is_empty(), |this| {
+                        this.child(Label::new("No ports are forwarded.").color(Color::Muted))
+                    })
+                    .children(
+                        forwards
+                            .into_iter()
+                            .map(|forward| self.render_port_forward(forward, cx)),
+                    ),
+            )
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .border_1()
+                    .rounded_md()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.port_editor.clone()),
+            )
+            .when_some(self.port_error.clone(), |this, error| {
+                this.child(Label::new(error).color(Color::Error))
+            })
+            .when(!suggested_ports.is_empty(), |this| {
+                this.child(Label::new("Detected in Terminals").size(LabelSize::Small))
+                    .child(
+                        v_flex().children(
+                            suggested_ports
+                                .into_iter()
+                                .map(|port| self.render_suggested_port(port, cx)),
+                        ),
+                    )
+            })
+    }
+}
+
+impl FocusableView for PortsPanel {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        if self.project.read(cx).ssh_client().is_some() {
+            self.port_editor.focus_handle(cx)
+        } else {
+            self.focus_handle.clone()
+        }
+    }
+}
+
+impl EventEmitter<PanelEvent> for PortsPanel {}
+
+impl Panel for PortsPanel {
+    fn persistent_name() -> &'static str {
+        "PortsPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        PortsPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        update_settings_file::<PortsPanelSettings>(self.fs.clone(), cx, move |settings, _| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| PortsPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        (PortsPanelSettings::get_global(cx).button && self.project.read(cx).is_via_ssh())
+            .then_some(IconName::Link)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Ports Panel")
+    }
+
+    fn icon_label(&self, cx: &WindowContext) -> Option<String> {
+        let count = self.port_forwards(cx).len();
+        (count > 0).then(|| count.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        9
+    }
+}
+
+fn is_project_connection(
+    connection: &SshConnection,
+    ssh_project: &SerialieditsyncSshProject,
+) -> bool {
+    connection.host.as_ref() == ssh_project.host
+        && connection.username == ssh_project.user
+        && connection.port == ssh_project.port
+}
+
+/// Parses a forward typed in the panel, either a port forwarded to the same remote port,
+/// `local:remote`, or `local:host:remote`.
+fn parse_port_forward(text: &str) -> Option<SshPortForward> {
+    let parts = text.split(':').collect::<Vec<_>>();
+    match parts.as_slice() {
+        [port] => Some(SshPortForward::new(port.parse().ok()?)),
+        [local_port, remote_port] => Some(SshPortForward {
+            local_port: local_port.parse().ok()?,
+            remote_host: None,
+            remote_port: remote_port.parse().ok()?,
+        }),
+        [local_port, remote_host, remote_port] if !remote_host.is_empty() => Some(SshPortForward {
+            local_port: local_port.parse().ok()?,
+            remote_host: Some(remote_host.to_string()),
+            remote_port: remote_port.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+/// Returns the well-known dev server ports that the given line of terminal output says are
+/// listened on, either as a local address or as a port number.
+fn dev_server_ports(line: &str) -> impl Iterator<Item = u16> + '_ {
+    static PORT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?i)(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]):(\d+)|\bport\s+(\d+)")
+            .unwrap()
+    });
+    PORT_REGEX.captures_iter(line).filter_map(|captures| {
+        let port = captures.get(1).or_else(|| captures.get(2))?;
+        let port = port.as_str().parse::<u16>().ok()?;
+        DEV_SERVER_PORTS.contains(&port).then_some(port)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_port_forward() {
+        assert_eq!(parse_port_forward("3000"), Some(SshPortForward::new(3000)));
+        assert_eq!(
+            parse_port_forward("8080:3000"),
+            Some(SshPortForward {
+                local_port: 8080,
+                remote_host: None,
+                remote_port: 3000,
+            })
+        );
+        assert_eq!(
+            parse_port_forward("8080:db.internal:5432"),
+            Some(SshPortForward {
+                local_port: 8080,
+                remote_host: Some("db.internal".to_string()),
+                remote_port: 5432,
+            })
+        );
+        assert_eq!(parse_port_forward(""), None);
+        assert_eq!(parse_port_forward("70000"), None);
+        assert_eq!(parse_port_forward("8080::3000"), None);
+    }
+
+    #[test]
+    fn test_dev_server_ports() {
+        let ports = |line| dev_server_ports(line).collect::<Vec<_>>();
+        assert_eq!(ports("  ➜  Local:   http://localhost:5173/"), vec![5173]);
+        assert_eq!(
+            ports("Starting development server at http://127.0.0.1:8000/"),
+            vec![8000]
+        );
+        assert_eq!(ports("Server listening on port 3000"), vec![3000]);
+        assert_eq!(ports("Listening on [::]:8080"), vec![8080]);
+        // Ports that aren't commonly used by dev servers aren't suggested.
+        assert_eq!(ports("Connected to localhost:5432"), Vec::<u16>::new());
+        assert_eq!(ports("Compiled 3000 modules"), Vec::<u16>::new());
+    }
+}
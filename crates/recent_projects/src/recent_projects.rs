@@ -1,4 +1,6 @@
 pub mod disconnected_overlay;
+mod language_server_bundle;
+pub mod ports_panel;
 mod remote_servers;
 mod ssh_connections;
 pub use ssh_connections::{is_connecting_over_ssh, open_ssh_project};
@@ -32,6 +34,7 @@ use workspace::{
 
 pub fn init(cx: &mut AppContext) {
     SshSettings::register(cx);
+    ports_panel::init(cx);
     cx.observe_new_views(RecentProjects::register).detach();
     cx.observe_new_views(RemoteServerProjects::register)
         .detach();
@@ -318,12 +321,13 @@ impl PickerDelegate for RecentProjectsDelegate {
                                     ..Default::default()
                                 };
 
-                                let connection_options = SshSettings::get_global(cx)
+                                let mut connection_options = SshSettings::get_global(cx)
                                     .connection_options_for(
                                         ssh_project.host.clone(),
                                         ssh_project.port,
                                         ssh_project.user.clone(),
                                     );
+                                connection_options.container = ssh_project.container.clone();
 
                                 let paths = ssh_project.paths.iter().map(PathBuf::from).collect();
 
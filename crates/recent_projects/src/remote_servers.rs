@@ -13,11 +13,12 @@ use gpui::Task;
 use gpui::WeakView;
 use gpui::{
     AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
-    PromptLevel, ScrollHandle, View, ViewContext,
+    PathPromptOptions, PromptLevel, ScrollHandle, View, ViewContext,
 };
 use picker::Picker;
 use project::Project;
 use remote::ssh_session::ConnectionIdentifier;
+use remote::DevContainer;
 use remote::SshConnectionOptions;
 use remote::SshRemoteClient;
 use settings::update_settings_file;
@@ -174,7 +175,13 @@ impl ProjectPicker {
                                     .as_mut()
                                     .and_then(|connections| connections.get_mut(ix))
                                 {
-                                    server.projects.insert(SshProject { paths });
+                                    if !server.projects.iter().any(|project| project.paths == paths)
+                                    {
+                                        server.projects.insert(SshProject {
+                                            paths,
+                                            ..Default::default()
+                                        });
+                                    }
                                 }
                             }
                         });
@@ -261,6 +268,7 @@ struct ProjectEntry {
 struct DefaultState {
     scrollbar: ScrollbarState,
     add_new_server: NavigableEntry,
+    open_dev_container: NavigableEntry,
     servers: Vec<ProjectEntry>,
 }
 impl DefaultState {
@@ -268,6 +276,7 @@ impl DefaultState {
         let handle = ScrollHandle::new();
         let scrollbar = ScrollbarState::new(handle.clone());
         let add_new_server = NavigableEntry::new(&handle, cx);
+        let open_dev_container = NavigableEntry::new(&handle, cx);
         let servers = SshSettings::get_global(cx)
             .ssh_connections()
             .map(|connection| {
@@ -289,6 +298,7 @@ impl DefaultState {
         Self {
             scrollbar,
             add_new_server,
+            open_dev_container,
             servers,
         }
     }
@@ -709,6 +719,42 @@ impl RemoteServerProjects {
             )
     }
 
+    /// Prompts for a local folder with a dev container configuration, then builds or starts its
+    /// container and opens the folder inside it.
+    fn open_dev_container(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(app_state) = self
+            .workspace
+            .update(cx, |workspace, _| workspace.app_state().clone())
+            .log_err()
+        else {
+            return;
+        };
+        let runtime = SshSettings::get_global(cx)
+            .container_runtime
+            .unwrap_or_default();
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let Some(folder) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            let dev_container = DevContainer::load(&folder).await?;
+            this.update(&mut cx, |_, cx| cx.emit(DismissEvent))?;
+            open_ssh_project(
+                dev_container.connection_options(runtime),
+                vec![dev_container.workspace_folder()],
+                app_state,
+                OpenOptions::default(),
+                &mut cx,
+            )
+            .await
+        })
+        .detach_and_prompt_err("Failed to open dev container", cx, |_, _| None);
+    }
+
     fn render_ssh_project(
         &mut self,
         server_ix: usize,
@@ -860,6 +906,7 @@ impl RemoteServerProjects {
                     nickname: None,
                     args: connection_options.args.unwrap_or_default(),
                     upload_binary_over_ssh: None,
+                    upload_language_servers_over_ssh: None,
                 })
         });
     }
@@ -1251,6 +1298,20 @@ impl RemoteServerProjects {
 
                 cx.notify();
             }));
+        let dev_container_button = div()
+            .id("ssh-open-dev-container-container")
+            .track_focus(&state.open_dev_container.focus_handle)
+            .anchor_scroll(state.open_dev_container.scroll_anchor.clone())
+            .child(
+                ListItem::new("open-dev-container-button")
+                    .toggle_state(state.open_dev_container.focus_handle.contains_focused(cx))
+                    .inset(true)
+                    .spacing(ui::ListItemSpacing::Sparse)
+                    .start_slot(Icon::new(IconName::FolderOpen).color(Color::Muted))
+                    .child(Label::new("Open Dev Container…"))
+                    .on_click(cx.listener(|this, _, cx| this.open_dev_container(cx))),
+            )
+            .on_action(cx.listener(|this, _: &menu::Confirm, cx| this.open_dev_container(cx)));
 
         let ui::ScrollableHandle::NonUniform(scroll_handle) = scroll_state.scroll_handle() else {
             unreachable!()
@@ -1264,6 +1325,7 @@ impl RemoteServerProjects {
                 .track_scroll(&scroll_handle)
                 .size_full()
                 .child(connect_button)
+                .child(dev_container_button)
                 .child(
                     List::new()
                         .empty_message(
@@ -1283,7 +1345,8 @@ impl RemoteServerProjects {
                 )
                 .into_any_element(),
         )
-        .entry(state.add_new_server.clone());
+        .entry(state.add_new_server.clone())
+        .entry(state.open_dev_container.clone());
 
         for server in &state.servers {
             for (navigation_state, _) in &server.projects {
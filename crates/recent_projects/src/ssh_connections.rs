@@ -17,7 +17,9 @@ use language::CursorShape;
 use markdown::{Markdown, MarkdownStyle};
 use release_channel::ReleaseChannel;
 use remote::ssh_session::ConnectionIdentifier;
-use remote::{SshConnectionOptions, SshPlatform, SshRemoteClient};
+use remote::{
+    ContainerRuntime, SshConnectionOptions, SshPlatform, SshPortForward, SshRemoteClient,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
@@ -26,11 +28,14 @@ use ui::{
     prelude::*, ActiveTheme, Color, Icon, IconName, IconSize, InteractiveElement, IntoElement,
     Label, LabelCommon, Styled, ViewContext, VisualContext, WindowContext,
 };
-use workspace::{AppState, ModalView, Workspace};
+use workspace::{notifications::DetachAndPromptErr, AppState, ModalView, Workspace};
+
+use crate::language_server_bundle::upload_language_server_bundle;
 
 #[derive(Deserialize)]
 pub struct SshSettings {
     pub ssh_connections: Option<Vec<SshConnection>>,
+    pub container_runtime: Option<ContainerRuntime>,
 }
 
 impl SshSettings {
@@ -49,11 +54,15 @@ impl SshSettings {
                 return SshConnectionOptions {
                     nickname: conn.nickname,
                     upload_binary_over_ssh: conn.upload_binary_over_ssh.unwrap_or_default(),
+                    upload_language_servers_over_ssh: conn
+                        .upload_language_servers_over_ssh
+                        .unwrap_or_default(),
                     args: Some(conn.args),
                     host,
                     port,
                     username,
                     password: None,
+                    container: None,
                 };
             }
         }
@@ -87,6 +96,11 @@ pub struct SshConnection {
     // limited outbound internet access.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub upload_binary_over_ssh: Option<bool>,
+    // If this is set to true, Editsync will upload the language servers and the Node runtime
+    // installed on your local machine to the server, for the ones the server doesn't have.
+    // Useful if your SSH server can't download them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upload_language_servers_over_ssh: Option<bool>,
 }
 
 impl From<SshConnection> for SshConnectionOptions {
@@ -99,6 +113,10 @@ impl From<SshConnection> for SshConnectionOptions {
             args: Some(val.args),
             nickname: val.nickname,
             upload_binary_over_ssh: val.upload_binary_over_ssh.unwrap_or_default(),
+            upload_language_servers_over_ssh: val
+                .upload_language_servers_over_ssh
+                .unwrap_or_default(),
+            container: None,
         }
     }
 }
@@ -106,11 +124,18 @@ impl From<SshConnection> for SshConnectionOptions {
 #[derive(Clone, Default, Serialize, PartialEq, Eq, PartialOrd, Ord, Deserialize, JsonSchema)]
 pub struct SshProject {
     pub paths: Vec<String>,
+    /// Ports forwarded to the server while the project is open.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub port_forwards: Vec<SshPortForward>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct RemoteSettingsContent {
     pub ssh_connections: Option<Vec<SshConnection>>,
+    /// The program used to build and run dev containers.
+    ///
+    /// Default: docker
+    pub container_runtime: Option<ContainerRuntime>,
 }
 
 impl Settings for SshSettings {
@@ -634,8 +659,26 @@ pub async fn open_ssh_project(
         window
             .update(cx, |workspace, cx| {
                 if let Some(client) = workspace.project().read(cx).ssh_client().clone() {
-                    ExtensionStore::global(cx)
-                        .update(cx, |store, cx| store.register_ssh_client(client, cx));
+                    ExtensionStore::global(cx).update(cx, |store, cx| {
+                        store.register_ssh_client(client.clone(), cx)
+                    });
+
+                    if client
+                        .read(cx)
+                        .connection_options()
+                        .upload_language_servers_over_ssh
+                    {
+                        let fs = workspace.app_state().fs.clone();
+                        let http = workspace.client().http_client();
+                        cx.spawn(|_, mut cx| async move {
+                            upload_language_server_bundle(client, fs, http, &mut cx).await
+                        })
+                        .detach_and_prompt_err(
+                            "Failed to upload language servers",
+                            cx,
+                            |_, _| None,
+                        );
+                    }
                 }
             })
             .ok();
@@ -0,0 +1,496 @@
+//! Runs the remote server in a container on the local machine, starting it through `docker exec`
+//! or `podman exec` rather than over ssh.
+
+use crate::{
+    devcontainer::DevContainer,
+    ssh_session::{
+        multiplex, parse_platform, proxy_command, server_binary_path, RemoteConnection,
+        SshClientDelegate, SshConnectionOptions, SshPortForward,
+    },
+};
+use anyhow::{anyhow, Context as _, Result};
+use async_trait::async_trait;
+use futures::channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender};
+use gpui::{AppContext, AsyncAppContext, SemanticVersion, Task};
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use smol::process::{self, Stdio};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering::SeqCst},
+        Arc,
+    },
+};
+
+/// The program used to manage containers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn program(&self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+}
+
+/// The container the server runs in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerTarget {
+    /// An existing container, by name or id.
+    Container(String),
+    /// The container described by the `devcontainer.json` of a local folder, which is created
+    /// or started when connecting.
+    DevContainer(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContainerOptions {
+    pub runtime: ContainerRuntime,
+    pub target: ContainerTarget,
+    /// The user to run the server as, instead of the default user of the container.
+    pub user: Option<String>,
+}
+
+impl ContainerOptions {
+    pub fn display_name(&self) -> String {
+        match &self.target {
+            ContainerTarget::Container(container) => {
+                format!("{}:{}", self.runtime.program(), container)
+            }
+            ContainerTarget::DevContainer(local_folder) => format!(
+                "{} (dev container)",
+                local_folder
+                    .file_name()
+                    .unwrap_or(local_folder.as_os_str())
+                    .to_string_lossy()
+            ),
+        }
+    }
+}
+
+/// Runs a program in a container through the container runtime.
+#[derive(Clone)]
+pub(crate) struct ContainerExec {
+    pub(crate) runtime: ContainerRuntime,
+    pub(crate) container: String,
+    pub(crate) user: Option<String>,
+}
+
+impl ContainerExec {
+    /// Returns a command running the given program in the home directory of the user.
+    fn command(&self, program: &str, args: &[&str]) -> process::Command {
+        let mut command = util::command::new_smol_command(self.runtime.program());
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .args(["exec", "-i"]);
+        if let Some(user) = &self.user {
+            command.args(["-u", user]);
+        }
+        command
+            .arg(&self.container)
+            .args(["sh", "-c", "cd && exec \"$0\" \"$@\"", program])
+            .args(args);
+        log::debug!(
+            "{} exec {} {program} {args:?}",
+            self.runtime.program(),
+            self.container
+        );
+        command
+    }
+
+    pub(crate) async fn run_command(&self, program: &str, args: &[&str]) -> Result<String> {
+        let output = self.command(program, args).output().await?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(anyhow!(
+                "failed to run command in container: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    }
+
+    /// Copies a local file or directory into the container, owned by the user running the server.
+    async fn copy(&self, src_path: &Path, dest_path: &Path) -> Result<()> {
+        let output = util::command::new_smol_command(self.runtime.program())
+            .arg("cp")
+            .arg(src_path)
+            .arg(format!("{}:{}", self.container, dest_path.display()))
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "failed to copy {} -> {}: {}",
+                src_path.display(),
+                dest_path.display(),
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        // Copied files are owned by root, so hand them over to the user.
+        let owner = format!(
+            "{}:{}",
+            self.run_command("id", &["-u"]).await?.trim(),
+            self.run_command("id", &["-g"]).await?.trim()
+        );
+        let output = util::command::new_smol_command(self.runtime.program())
+            .args(["exec", "-u", "0", &self.container, "chown", "-R", &owner])
+            .arg(dest_path)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "failed to change the owner of {}: {}",
+                dest_path.display(),
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct ContainerRemoteConnection {
+    connection_options: SshConnectionOptions,
+    exec: ContainerExec,
+    remote_binary_path: PathBuf,
+    killed: AtomicBool,
+}
+
+impl ContainerRemoteConnection {
+    pub(crate) async fn new(
+        connection_options: SshConnectionOptions,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Self> {
+        let options = connection_options
+            .container
+            .clone()
+            .context("no container to connect to")?;
+
+        let exec = match &options.target {
+            ContainerTarget::Container(container) => {
+                delegate.set_status(Some("Starting container"), cx);
+                start_container(options.runtime, container).await?;
+                ContainerExec {
+                    runtime: options.runtime,
+                    container: container.clone(),
+                    user: options.user.clone(),
+                }
+            }
+            ContainerTarget::DevContainer(local_folder) => {
+                let dev_container = DevContainer::load(local_folder).await?;
+                let mut exec = dev_container.up(options.runtime, &delegate, cx).await?;
+                if options.user.is_some() {
+                    exec.user = options.user.clone();
+                }
+                exec
+            }
+        };
+
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        })?;
+        let remote_binary_path =
+            ensure_server_binary(&exec, &delegate, release_channel, version, commit, cx).await?;
+
+        Ok(Self {
+            connection_options,
+            exec,
+            remote_binary_path,
+            killed: AtomicBool::new(false),
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for ContainerRemoteConnection {
+    async fn kill(&self) -> Result<()> {
+        // The proxy process is killed along with the task multiplexing it, and there is no
+        // connection to close besides it.
+        self.killed.store(true, SeqCst);
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        self.killed.load(SeqCst)
+    }
+
+    async fn forward_port(&self, _forward: &SshPortForward) -> Result<()> {
+        // Container ports are published when the container is created, and can't be added to a
+        // running one.
+        Err(anyhow!(
+            "ports can't be forwarded to a running container, publish them with `runArgs` in devcontainer.json instead"
+        ))
+    }
+
+    async fn cancel_port_forward(&self, _forward: &SshPortForward) -> Result<()> {
+        Ok(())
+    }
+
+    fn terminal_command(&self) -> (String, Vec<String>) {
+        let mut args = vec!["exec".to_string(), "-it".to_string()];
+        if let Some(user) = &self.exec.user {
+            args.extend(["-u".to_string(), user.clone()]);
+        }
+        args.extend([
+            self.exec.container.clone(),
+            "sh".to_string(),
+            "-c".to_string(),
+        ]);
+        (self.exec.runtime.program().to_string(), args)
+    }
+
+    fn connection_options(&self) -> SshConnectionOptions {
+        self.connection_options.clone()
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: PathBuf,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let exec = self.exec.clone();
+        cx.background_executor()
+            .spawn(async move { exec.copy(&src_path, &dest_path).await })
+    }
+
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let start_proxy_command =
+            proxy_command(&self.remote_binary_path, &unique_identifier, reconnect);
+        let proxy_process = match self
+            .exec
+            .command("sh", &["-c", &start_proxy_command])
+            // IMPORTANT: we kill this process when we drop the task that uses it.
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)))
+            }
+        };
+
+        multiplex(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+}
+
+/// Starts the given container if it exists but isn't running.
+pub(crate) async fn start_container(runtime: ContainerRuntime, container: &str) -> Result<()> {
+    let output = util::command::new_smol_command(runtime.program())
+        .args(["inspect", "--format", "{{.State.Running}}", container])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "no container named {container:?}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    if String::from_utf8_lossy(&output.stdout).trim() == "true" {
+        return Ok(());
+    }
+
+    let output = util::command::new_smol_command(runtime.program())
+        .args(["start", container])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "failed to start container {container:?}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Installs the server binary in the container, unless it's already there. The binary is
+/// downloaded locally and copied in, as containers often don't have the tools to download it.
+async fn ensure_server_binary(
+    exec: &ContainerExec,
+    delegate: &Arc<dyn SshClientDelegate>,
+    release_channel: ReleaseChannel,
+    version: SemanticVersion,
+    commit: Option<AppCommitSha>,
+    cx: &mut AsyncAppContext,
+) -> Result<PathBuf> {
+    let dst_path = server_binary_path(release_channel, version, commit);
+    if exec
+        .run_command(&dst_path.to_string_lossy(), &["version"])
+        .await
+        .is_ok()
+    {
+        return Ok(dst_path);
+    }
+
+    let wanted_version = match release_channel {
+        ReleaseChannel::Nightly => None,
+        ReleaseChannel::Dev => anyhow::bail!(
+            "no remote server exists in the container at ({:?})",
+            dst_path
+        ),
+        _ => Some(version),
+    };
+    let platform = parse_platform(&exec.run_command("uname", &["-sm"]).await?)?;
+    let src_path = delegate
+        .download_server_binary_locally(platform, release_channel, wanted_version, cx)
+        .await?;
+
+    delegate.set_status(Some("Uploading remote development server"), cx);
+    let home = exec.run_command("sh", &["-c", "echo \"$HOME\""]).await?;
+    let tmp_path_gz = PathBuf::from(home.trim()).join(format!(
+        "{}-upload-{}.gz",
+        dst_path.to_string_lossy(),
+        std::process::id()
+    ));
+    if let Some(parent) = tmp_path_gz.parent() {
+        exec.run_command("mkdir", &["-p", &parent.to_string_lossy()])
+            .await?;
+    }
+    exec.copy(&src_path, &tmp_path_gz)
+        .await
+        .context("failed to upload server binary")?;
+
+    delegate.set_status(Some("Extracting remote development server"), cx);
+    let tmp_path_gz = tmp_path_gz.to_string_lossy();
+    let script = crate::shell_script!(
+        "gunzip -f {tmp_path_gz} && chmod 755 {tmp_path} && mv {tmp_path} {dst_path}",
+        tmp_path_gz = &tmp_path_gz,
+        tmp_path = tmp_path_gz.strip_suffix(".gz").unwrap(),
+        dst_path = &dst_path.to_string_lossy()
+    );
+    exec.run_command("sh", &["-c", &script]).await?;
+    Ok(dst_path)
+}
+
+/// Runs the server as a process on the local machine, so that tests can exercise a real server
+/// binary without ssh or a container runtime.
+#[cfg(any(test, feature = "test-support"))]
+pub(crate) struct LocalProcessConnection {
+    pub(crate) connection_options: SshConnectionOptions,
+    pub(crate) server_binary: PathBuf,
+    pub(crate) killed: AtomicBool,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+#[async_trait(?Send)]
+impl RemoteConnection for LocalProcessConnection {
+    async fn kill(&self) -> Result<()> {
+        self.killed.store(true, SeqCst);
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        self.killed.load(SeqCst)
+    }
+
+    // The server runs on the local machine, so its ports are already reachable.
+    async fn forward_port(&self, _forward: &SshPortForward) -> Result<()> {
+        Ok(())
+    }
+
+    async fn cancel_port_forward(&self, _forward: &SshPortForward) -> Result<()> {
+        Ok(())
+    }
+
+    fn terminal_command(&self) -> (String, Vec<String>) {
+        ("sh".to_string(), vec!["-c".to_string()])
+    }
+
+    fn connection_options(&self) -> SshConnectionOptions {
+        self.connection_options.clone()
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: PathBuf,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let output = util::command::new_smol_command("cp")
+            .arg("-R")
+            .arg(&src_path)
+            .arg(&dest_path)
+            .output();
+        cx.background_executor().spawn(async move {
+            let output = output.await?;
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "failed to copy directory {} -> {}: {}",
+                    src_path.display(),
+                    dest_path.display(),
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+            }
+            Ok(())
+        })
+    }
+
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        _delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Task<Result<i32>> {
+        let start_proxy_command = proxy_command(&self.server_binary, &unique_identifier, reconnect);
+        let proxy_process = match util::command::new_smol_command("sh")
+            .args(["-c", &start_proxy_command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)))
+            }
+        };
+
+        multiplex(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+}
@@ -0,0 +1,443 @@
+//! Creates and starts the containers described by `devcontainer.json` files, as defined by the
+//! Development Containers specification (https://containers.dev).
+//!
+//! Only containers built from an image or a Dockerfile are supported. Containers are labeled with
+//! the local folder they were created for, like the reference implementation does, so that an
+//! existing container is reused rather than created again.
+
+use crate::{
+    container_session::{
+        start_container, ContainerExec, ContainerOptions, ContainerRuntime, ContainerTarget,
+    },
+    ssh_session::{SshClientDelegate, SshConnectionOptions},
+};
+use anyhow::{anyhow, Context as _, Result};
+use collections::{BTreeMap, HashMap};
+use gpui::AsyncAppContext;
+use serde::Deserialize;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+const LOCAL_FOLDER_LABEL: &str = "devcontainer.local_folder";
+const CONFIG_FILE_LABEL: &str = "devcontainer.config_file";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DevContainerConfig {
+    name: Option<String>,
+    image: Option<String>,
+    build: Option<BuildConfig>,
+    /// The Dockerfile to build, from before `build` was introduced.
+    docker_file: Option<String>,
+    context: Option<String>,
+    docker_compose_file: Option<serde_json::Value>,
+    workspace_folder: Option<String>,
+    remote_user: Option<String>,
+    container_user: Option<String>,
+    #[serde(default)]
+    run_args: Vec<String>,
+    #[serde(default)]
+    container_env: HashMap<String, String>,
+    post_create_command: Option<LifecycleCommand>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildConfig {
+    dockerfile: Option<String>,
+    context: Option<String>,
+    #[serde(default)]
+    args: HashMap<String, String>,
+    target: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum LifecycleCommand {
+    /// A command line, run by a shell.
+    Shell(String),
+    /// A program and its arguments, run without a shell.
+    Exec(Vec<String>),
+    /// Named commands, which are run one after the other.
+    Parallel(BTreeMap<String, LifecycleCommand>),
+}
+
+/// A local folder with a `devcontainer.json`.
+pub struct DevContainer {
+    local_folder: PathBuf,
+    config_path: PathBuf,
+    config: DevContainerConfig,
+}
+
+impl DevContainer {
+    /// Returns the path of the `devcontainer.json` of the given folder, if it has one.
+    pub fn config_path(local_folder: &Path) -> Option<PathBuf> {
+        [
+            local_folder.join(".devcontainer").join("devcontainer.json"),
+            local_folder.join(".devcontainer.json"),
+        ]
+        .into_iter()
+        .find(|path| path.is_file())
+    }
+
+    pub async fn load(local_folder: &Path) -> Result<Self> {
+        let config_path = Self::config_path(local_folder)
+            .with_context(|| format!("no devcontainer.json in {}", local_folder.display()))?;
+        let content = smol::fs::read_to_string(&config_path).await?;
+        let config = serde_json_lenient::from_str(&content)
+            .with_context(|| format!("failed to parse {}", config_path.display()))?;
+        Ok(Self {
+            local_folder: local_folder.to_path_buf(),
+            config_path,
+            config,
+        })
+    }
+
+    pub fn name(&self) -> String {
+        self.config.name.clone().unwrap_or_else(|| {
+            self.local_folder
+                .file_name()
+                .unwrap_or(self.local_folder.as_os_str())
+                .to_string_lossy()
+                .to_string()
+        })
+    }
+
+    /// The folder the local folder is mounted at in the container.
+    pub fn workspace_folder(&self) -> PathBuf {
+        match &self.config.workspace_folder {
+            Some(workspace_folder) => PathBuf::from(self.substitute(workspace_folder, None)),
+            None => Path::new("/workspaces").join(self.local_folder_basename()),
+        }
+    }
+
+    /// The options to connect to the container of this folder with.
+    pub fn connection_options(&self, runtime: ContainerRuntime) -> SshConnectionOptions {
+        SshConnectionOptions {
+            host: self.name(),
+            container: Some(ContainerOptions {
+                runtime,
+                target: ContainerTarget::DevContainer(self.local_folder.clone()),
+                user: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Starts the container of this folder, creating it first if there's none, and returns how
+    /// to run programs in it.
+    pub(crate) async fn up(
+        &self,
+        runtime: ContainerRuntime,
+        delegate: &Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<ContainerExec> {
+        let user = self
+            .config
+            .remote_user
+            .clone()
+            .or_else(|| self.config.container_user.clone());
+        let local_folder_label = format!("{LOCAL_FOLDER_LABEL}={}", self.local_folder.display());
+
+        let existing_container = run(
+            runtime,
+            &[
+                "ps",
+                "--all",
+                "--quiet",
+                "--filter",
+                &format!("label={local_folder_label}"),
+            ],
+        )
+        .await?;
+        if let Some(container) = existing_container.lines().next() {
+            delegate.set_status(Some("Starting dev container"), cx);
+            start_container(runtime, container).await?;
+            return Ok(ContainerExec {
+                runtime,
+                container: container.to_string(),
+                user,
+            });
+        }
+
+        if self.config.docker_compose_file.is_some() {
+            anyhow::bail!("dev containers using Docker Compose aren't supported");
+        }
+        let image = match self.build_image(runtime, delegate, cx).await? {
+            Some(image) => image,
+            None => self
+                .config
+                .image
+                .clone()
+                .context("devcontainer.json must specify either an image or a Dockerfile")?,
+        };
+
+        delegate.set_status(Some("Creating dev container"), cx);
+        let workspace_folder = self.workspace_folder();
+        let mut args = vec![
+            "run".to_string(),
+            "--detach".to_string(),
+            "--label".to_string(),
+            local_folder_label,
+            "--label".to_string(),
+            format!("{CONFIG_FILE_LABEL}={}", self.config_path.display()),
+            "--mount".to_string(),
+            format!(
+                "type=bind,source={},target={}",
+                self.local_folder.display(),
+                workspace_folder.display()
+            ),
+            "--workdir".to_string(),
+            workspace_folder.to_string_lossy().to_string(),
+        ];
+        for (name, value) in &self.config.container_env {
+            args.push("--env".to_string());
+            args.push(format!(
+                "{name}={}",
+                self.substitute(value, Some(&workspace_folder))
+            ));
+        }
+        if let Some(container_user) = &self.config.container_user {
+            args.push("--user".to_string());
+            args.push(container_user.clone());
+        }
+        args.extend(
+            self.config
+                .run_args
+                .iter()
+                .map(|arg| self.substitute(arg, Some(&workspace_folder))),
+        );
+        args.extend([
+            image,
+            "sh".to_string(),
+            "-c".to_string(),
+            // Keep the container running until it's stopped.
+            "while sleep 1000; do :; done".to_string(),
+        ]);
+        let container = run(runtime, &args).await?.trim().to_string();
+        let exec = ContainerExec {
+            runtime,
+            container,
+            user,
+        };
+
+        if let Some(command) = &self.config.post_create_command {
+            delegate.set_status(Some("Running post-create command"), cx);
+            run_lifecycle_command(&exec, command, &workspace_folder).await?;
+        }
+        Ok(exec)
+    }
+
+    /// Builds the image of the container if it's built from a Dockerfile, returning its tag.
+    async fn build_image(
+        &self,
+        runtime: ContainerRuntime,
+        delegate: &Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Option<String>> {
+        let build = self.config.build.as_ref();
+        let Some(dockerfile) = build
+            .and_then(|build| build.dockerfile.as_ref())
+            .or(self.config.docker_file.as_ref())
+        else {
+            return Ok(None);
+        };
+        let config_dir = self
+            .config_path
+            .parent()
+            .context("devcontainer.json has no parent directory")?;
+        let context = build
+            .and_then(|build| build.context.as_ref())
+            .or(self.config.context.as_ref())
+            .map_or(config_dir.to_path_buf(), |context| config_dir.join(context));
+
+        delegate.set_status(Some("Building dev container image"), cx);
+        let tag = format!(
+            "editsync-dev-container-{}",
+            self.local_folder_basename()
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        '-'
+                    }
+                })
+                .collect::<String>()
+        );
+        let mut args = vec![
+            "build".to_string(),
+            "--file".to_string(),
+            config_dir.join(dockerfile).to_string_lossy().to_string(),
+            "--tag".to_string(),
+            tag.clone(),
+        ];
+        if let Some(build) = build {
+            for (name, value) in &build.args {
+                args.push("--build-arg".to_string());
+                args.push(format!("{name}={}", self.substitute(value, None)));
+            }
+            if let Some(target) = &build.target {
+                args.push("--target".to_string());
+                args.push(target.clone());
+            }
+        }
+        args.push(context.to_string_lossy().to_string());
+        run(runtime, &args).await?;
+        Ok(Some(tag))
+    }
+
+    fn local_folder_basename(&self) -> String {
+        self.local_folder
+            .file_name()
+            .unwrap_or(self.local_folder.as_os_str())
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Replaces the variables the specification allows in configuration values.
+    fn substitute(&self, value: &str, container_workspace_folder: Option<&Path>) -> String {
+        let mut result = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('}') else {
+                rest = &rest[start..];
+                break;
+            };
+            let variable = &rest[start + 2..start + end];
+            let replacement = match variable.split_once(':') {
+                Some(("localEnv", name)) => {
+                    let (name, default) = name.split_once(':').unwrap_or((name, ""));
+                    Some(std::env::var(name).unwrap_or_else(|_| default.to_string()))
+                }
+                _ => match variable {
+                    "localWorkspaceFolder" => Some(self.local_folder.to_string_lossy().to_string()),
+                    "localWorkspaceFolderBasename" => Some(self.local_folder_basename()),
+                    "containerWorkspaceFolder" => container_workspace_folder
+                        .map(|folder| folder.to_string_lossy().to_string()),
+                    "containerWorkspaceFolderBasename" => container_workspace_folder
+                        .and_then(|folder| folder.file_name())
+                        .map(|name| name.to_string_lossy().to_string()),
+                    _ => None,
+                },
+            };
+            match replacement {
+                Some(replacement) => result.push_str(&replacement),
+                None => result.push_str(&rest[start..=start + end]),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+async fn run_lifecycle_command(
+    exec: &ContainerExec,
+    command: &LifecycleCommand,
+    workspace_folder: &Path,
+) -> Result<()> {
+    let command_line = match command {
+        LifecycleCommand::Shell(command_line) => command_line.clone(),
+        LifecycleCommand::Exec(args) => args
+            .iter()
+            .map(|arg| shlex::try_quote(arg).map(|arg| arg.to_string()))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(" "),
+        LifecycleCommand::Parallel(commands) => {
+            for command in commands.values() {
+                Box::pin(run_lifecycle_command(exec, command, workspace_folder)).await?;
+            }
+            return Ok(());
+        }
+    };
+    let script = lifecycle_script(&command_line, workspace_folder)?;
+    exec.run_command("sh", &["-c", &script]).await?;
+    Ok(())
+}
+
+/// Returns the script running the given command line in the workspace folder. The command line is
+/// interpreted by the shell, as lifecycle commands given as strings can use its syntax.
+fn lifecycle_script(command_line: &str, workspace_folder: &Path) -> Result<String> {
+    let workspace_folder = workspace_folder.to_string_lossy();
+    Ok(format!(
+        "cd {} && {command_line}",
+        shlex::try_quote(&workspace_folder)?
+    ))
+}
+
+/// Runs the container runtime with the given arguments, returning its output.
+async fn run(runtime: ContainerRuntime, args: &[impl AsRef<std::ffi::OsStr>]) -> Result<String> {
+    let output = util::command::new_smol_command(runtime.program())
+        .args(args)
+        .output()
+        .await
+        .with_context(|| format!("failed to run {}", runtime.program()))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(anyhow!(
+            "{} {} failed: {}",
+            runtime.program(),
+            args.first()
+                .map(|arg| arg.as_ref().to_string_lossy().to_string())
+                .unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_substitute() {
+        let config: DevContainerConfig = serde_json_lenient::from_str(
+            r#"{
+                // Comments are allowed.
+                "name": "App",
+                "build": { "dockerfile": "Dockerfile", "args": { "VARIANT": "bookworm" } },
+                "workspaceFolder": "/src/${localWorkspaceFolderBasename}",
+                "runArgs": ["--env", "ROOT=${containerWorkspaceFolder}"],
+                "remoteUser": "dev",
+                "postCreateCommand": { "deps": ["npm", "install"], "hooks": "make hooks" },
+            }"#,
+        )
+        .unwrap();
+        let dev_container = DevContainer {
+            local_folder: PathBuf::from("/home/me/app"),
+            config_path: PathBuf::from("/home/me/app/.devcontainer/devcontainer.json"),
+            config,
+        };
+
+        assert_eq!(dev_container.name(), "App");
+        assert_eq!(dev_container.workspace_folder(), Path::new("/src/app"));
+        assert_eq!(
+            dev_container.substitute(
+                &dev_container.config.run_args[1],
+                Some(&dev_container.workspace_folder())
+            ),
+            "ROOT=/src/app"
+        );
+        assert_eq!(
+            dev_container.substitute("${unknown} and ${localWorkspaceFolder}", None),
+            "${unknown} and /home/me/app"
+        );
+        assert!(matches!(
+            dev_container.config.post_create_command,
+            Some(LifecycleCommand::Parallel(_))
+        ));
+    }
+
+    #[test]
+    fn test_lifecycle_script() {
+        assert_eq!(
+            lifecycle_script("npm install && npm run build", Path::new("/work space")).unwrap(),
+            "cd '/work space' && npm install && npm run build"
+        );
+    }
+}
@@ -1,9 +1,13 @@
+pub mod container_session;
+pub mod devcontainer;
 pub mod json_log;
 pub mod protocol;
 pub mod proxy;
 pub mod ssh_session;
 
+pub use container_session::{ContainerOptions, ContainerRuntime, ContainerTarget};
+pub use devcontainer::DevContainer;
 pub use ssh_session::{
-    ConnectionState, SshClientDelegate, SshConnectionOptions, SshPlatform, SshRemoteClient,
-    SshRemoteEvent,
+    ConnectionState, SshClientDelegate, SshConnectionOptions, SshPlatform, SshPortForward,
+    SshRemoteClient, SshRemoteEvent,
 };
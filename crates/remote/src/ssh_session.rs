@@ -1,4 +1,5 @@
 use crate::{
+    container_session::{ContainerOptions, ContainerRemoteConnection},
     json_log::LogRecord,
     protocol::{
         message_len_from_buffer, read_message_with_len, write_message, MessageId, MESSAGE_LEN_SIZE,
@@ -53,6 +54,50 @@ use util::ResultExt;
 )]
 pub struct SshProjectId(pub u64);
 
+/// A local port forwarded over ssh to a port of the remote host, or of a host reachable from it.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
+pub struct SshPortForward {
+    pub local_port: u16,
+    /// The host to connect to from the remote host.
+    ///
+    /// Default: localhost
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_host: Option<String>,
+    pub remote_port: u16,
+}
+
+impl SshPortForward {
+    /// Forwards the given local port to the same port of the remote host.
+    pub fn new(port: u16) -> Self {
+        Self {
+            local_port: port,
+            remote_host: None,
+            remote_port: port,
+        }
+    }
+
+    /// The argument of ssh's `-L` option for this forward.
+    fn ssh_arg(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.local_port,
+            self.remote_host.as_deref().unwrap_or("localhost"),
+            self.remote_port
+        )
+    }
+}
+
 #[derive(Clone)]
 pub struct SshSocket {
     connection_options: SshConnectionOptions,
@@ -69,6 +114,12 @@ pub struct SshConnectionOptions {
 
     pub nickname: Option<String>,
     pub upload_binary_over_ssh: bool,
+    /// When set, the language servers and Node runtime installed locally are uploaded to the
+    /// server, for hosts that can't download them.
+    pub upload_language_servers_over_ssh: bool,
+    /// When set, the server runs in this container on the local machine, and is reached
+    /// through the container runtime rather than over ssh.
+    pub container: Option<ContainerOptions>,
 }
 
 #[macro_export]
@@ -162,6 +213,8 @@ impl SshConnectionOptions {
             password: None,
             nickname: None,
             upload_binary_over_ssh: false,
+            upload_language_servers_over_ssh: false,
+            container: None,
         })
     }
 
@@ -192,6 +245,9 @@ impl SshConnectionOptions {
     }
 
     pub fn connection_string(&self) -> String {
+        if let Some(container) = &self.container {
+            return container.display_name();
+        }
         let host = if let Some(username) = &self.username {
             format!("{}@{}", username, self.host)
         } else {
@@ -295,6 +351,26 @@ impl SshSocket {
             .arg(format!("ControlPath={}", self.socket_path.display()))
     }
 
+    /// Adds or cancels a port forward of the master connection.
+    async fn control_port_forward(&self, operation: &str, forward: &SshPortForward) -> Result<()> {
+        let mut command = util::command::new_smol_command("ssh");
+        let output = self
+            .ssh_options(&mut command)
+            .args(["-O", operation, "-L", &forward.ssh_arg()])
+            .arg(self.connection_options.ssh_url())
+            .output()
+            .await?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "failed to {operation} port forward {}: {}",
+                forward.ssh_arg(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
     fn ssh_args(&self) -> Vec<String> {
         vec![
             "-o".to_string(),
@@ -304,6 +380,12 @@ impl SshSocket {
             self.connection_options.ssh_url(),
         ]
     }
+
+    fn terminal_command(&self) -> (String, Vec<String>) {
+        let mut args = self.ssh_args();
+        args.push("-t".to_string());
+        ("ssh".to_string(), args)
+    }
 }
 
 const MAX_MISSED_HEARTBEATS: usize = 5;
@@ -472,6 +554,7 @@ pub struct SshRemoteClient {
     unique_identifier: String,
     connection_options: SshConnectionOptions,
     state: Arc<Mutex<Option<State>>>,
+    port_forwards: Arc<Mutex<Vec<SshPortForward>>>,
 }
 
 #[derive(Debug)]
@@ -536,6 +619,7 @@ impl SshRemoteClient {
                     unique_identifier: unique_identifier.clone(),
                     connection_options: connection_options.clone(),
                     state: Arc::new(Mutex::new(Some(State::Connecting))),
+                    port_forwards: Default::default(),
                 })?;
 
                 let ssh_connection = cx
@@ -692,6 +776,7 @@ impl SshRemoteClient {
 
         let unique_identifier = self.unique_identifier.clone();
         let client = self.client.clone();
+        let port_forwards = self.port_forwards.clone();
         let reconnect_task = cx.spawn(|this, mut cx| async move {
             macro_rules! failed {
                 ($error:expr, $attempts:expr, $ssh_connection:expr, $delegate:expr) => {
@@ -752,6 +837,12 @@ impl SshRemoteClient {
                 failed!(error, attempts, ssh_connection, delegate);
             };
 
+            // The forwards belonged to the connection that was killed, so they are added again.
+            let forwards = port_forwards.lock().clone();
+            for forward in &forwards {
+                ssh_connection.forward_port(forward).await.log_err();
+            }
+
             State::Connected {
                 ssh_connection,
                 delegate,
@@ -982,12 +1073,86 @@ impl SshRemoteClient {
         self.client.subscribe_to_entity(remote_id, entity);
     }
 
-    pub fn ssh_args(&self) -> Option<Vec<String>> {
+    /// Returns the program and arguments that run the shell command line given as an additional
+    /// argument on the remote, attached to a terminal.
+    pub fn terminal_command(&self) -> Option<(String, Vec<String>)> {
         self.state
             .lock()
             .as_ref()
             .and_then(|state| state.ssh_connection())
-            .map(|ssh_connection| ssh_connection.ssh_args())
+            .map(|ssh_connection| ssh_connection.terminal_command())
+    }
+
+    /// Returns the ports forwarded to the remote host.
+    pub fn port_forwards(&self) -> Vec<SshPortForward> {
+        self.port_forwards.lock().clone()
+    }
+
+    /// Forwards a local port to the remote host, for as long as this client is connected.
+    pub fn add_port_forward(
+        &mut self,
+        forward: SshPortForward,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if self
+            .port_forwards
+            .lock()
+            .iter()
+            .any(|existing| existing.local_port == forward.local_port)
+        {
+            return Task::ready(Err(anyhow!(
+                "local port {} is already forwarded",
+                forward.local_port
+            )));
+        }
+        let Some(connection) = self.connection() else {
+            return Task::ready(Err(anyhow!("no ssh connection")));
+        };
+        cx.spawn(|this, mut cx| async move {
+            connection.forward_port(&forward).await?;
+            this.update(&mut cx, |this, cx| {
+                this.port_forwards.lock().push(forward);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Stops forwarding the given local port.
+    pub fn remove_port_forward(
+        &mut self,
+        local_port: u16,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(forward) = self
+            .port_forwards
+            .lock()
+            .iter()
+            .find(|forward| forward.local_port == local_port)
+            .cloned()
+        else {
+            return Task::ready(Ok(()));
+        };
+        let connection = self.connection();
+        cx.spawn(|this, mut cx| async move {
+            if let Some(connection) = connection {
+                connection.cancel_port_forward(&forward).await?;
+            }
+            this.update(&mut cx, |this, cx| {
+                this.port_forwards
+                    .lock()
+                    .retain(|forward| forward.local_port != local_port);
+                cx.notify();
+            })
+        })
+    }
+
+    fn connection(&self) -> Option<Arc<dyn RemoteConnection>> {
+        match self.state.lock().as_ref()? {
+            State::Connected { ssh_connection, .. }
+            | State::HeartbeatMissed { ssh_connection, .. }
+            | State::ReconnectFailed { ssh_connection, .. } => Some(ssh_connection.clone()),
+            _ => None,
+        }
     }
 
     pub fn upload_directory(
@@ -1088,6 +1253,34 @@ impl SshRemoteClient {
         (opts, server_client)
     }
 
+    /// Registers a connection that runs the given server binary as a local process, and returns
+    /// the options to connect to it with.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn local_process_server(
+        server_binary: PathBuf,
+        cx: &mut AppContext,
+    ) -> SshConnectionOptions {
+        let port = cx.default_global::<ConnectionPool>().connections.len() as u16 + 1;
+        let opts = SshConnectionOptions {
+            host: "<local>".to_string(),
+            port: Some(port),
+            ..Default::default()
+        };
+        let connection: Arc<dyn RemoteConnection> =
+            Arc::new(crate::container_session::LocalProcessConnection {
+                connection_options: opts.clone(),
+                server_binary,
+                killed: Default::default(),
+            });
+        cx.update_default_global(|pool: &mut ConnectionPool, _| {
+            pool.connections.insert(
+                opts.clone(),
+                ConnectionPoolEntry::Connecting(Task::ready(Ok(connection)).shared()),
+            );
+        });
+        opts
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub async fn fake_client(
         opts: SshConnectionOptions,
@@ -1155,9 +1348,15 @@ impl ConnectionPool {
                 let opts = opts.clone();
                 let delegate = delegate.clone();
                 |mut cx| async move {
-                    let connection = SshRemoteConnection::new(opts.clone(), delegate, &mut cx)
-                        .await
-                        .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>);
+                    let connection = if opts.container.is_some() {
+                        ContainerRemoteConnection::new(opts.clone(), delegate, &mut cx)
+                            .await
+                            .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                    } else {
+                        SshRemoteConnection::new(opts.clone(), delegate, &mut cx)
+                            .await
+                            .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                    };
 
                     cx.update_global(|pool: &mut Self, _| {
                         debug_assert!(matches!(
@@ -1195,7 +1394,7 @@ impl From<SshRemoteClient> for AnyProtoClient {
 }
 
 #[async_trait(?Send)]
-trait RemoteConnection: Send + Sync {
+pub(crate) trait RemoteConnection: Send + Sync {
     #[allow(clippy::too_many_arguments)]
     fn start_proxy(
         &self,
@@ -1215,7 +1414,11 @@ trait RemoteConnection: Send + Sync {
     ) -> Task<Result<()>>;
     async fn kill(&self) -> Result<()>;
     fn has_been_killed(&self) -> bool;
-    fn ssh_args(&self) -> Vec<String>;
+    async fn forward_port(&self, forward: &SshPortForward) -> Result<()>;
+    async fn cancel_port_forward(&self, forward: &SshPortForward) -> Result<()>;
+    /// The program and arguments that run the shell command line given as an additional
+    /// argument, attached to a terminal.
+    fn terminal_command(&self) -> (String, Vec<String>);
     fn connection_options(&self) -> SshConnectionOptions;
 
     #[cfg(any(test, feature = "test-support"))]
@@ -1244,8 +1447,16 @@ impl RemoteConnection for SshRemoteConnection {
         self.master_process.lock().is_none()
     }
 
-    fn ssh_args(&self) -> Vec<String> {
-        self.socket.ssh_args()
+    async fn forward_port(&self, forward: &SshPortForward) -> Result<()> {
+        self.socket.control_port_forward("forward", forward).await
+    }
+
+    async fn cancel_port_forward(&self, forward: &SshPortForward) -> Result<()> {
+        self.socket.control_port_forward("cancel", forward).await
+    }
+
+    fn terminal_command(&self) -> (String, Vec<String>) {
+        self.socket.terminal_command()
     }
 
     fn connection_options(&self) -> SshConnectionOptions {
@@ -1311,30 +1522,7 @@ impl RemoteConnection for SshRemoteConnection {
             return Task::ready(Err(anyhow!("Remote binary path not set")));
         };
 
-        let mut start_proxy_command = shell_script!(
-            "exec {binary_path} proxy --identifier {identifier}",
-            binary_path = &remote_binary_path.to_string_lossy(),
-            identifier = &unique_identifier,
-        );
-
-        if let Some(rust_log) = std::env::var("RUST_LOG").ok() {
-            start_proxy_command = format!(
-                "RUST_LOG={} {}",
-                shlex::try_quote(&rust_log).unwrap(),
-                start_proxy_command
-            )
-        }
-        if let Some(rust_backtrace) = std::env::var("RUST_BACKTRACE").ok() {
-            start_proxy_command = format!(
-                "RUST_BACKTRACE={} {}",
-                shlex::try_quote(&rust_backtrace).unwrap(),
-                start_proxy_command
-            )
-        }
-        if reconnect {
-            start_proxy_command.push_str(" --reconnect");
-        }
-
+        let start_proxy_command = proxy_command(&remote_binary_path, &unique_identifier, reconnect);
         let ssh_proxy_process = match self
             .socket
             .ssh_command("sh", &["-c", &start_proxy_command])
@@ -1348,7 +1536,7 @@ impl RemoteConnection for SshRemoteConnection {
             }
         };
 
-        Self::multiplex(
+        multiplex(
             ssh_proxy_process,
             incoming_tx,
             outgoing_rx,
@@ -1555,135 +1743,7 @@ impl SshRemoteConnection {
 
     async fn platform(&self) -> Result<SshPlatform> {
         let uname = self.socket.run_command("uname", &["-sm"]).await?;
-        let Some((os, arch)) = uname.split_once(" ") else {
-            Err(anyhow!("unknown uname: {uname:?}"))?
-        };
-
-        let os = match os.trim() {
-            "Darwin" => "macos",
-            "Linux" => "linux",
-            _ => Err(anyhow!(
-                "Prebuilt remote servers are not yet available for {os:?}. See https://editsync.khulnasoft.com/docs/remote-development"
-            ))?,
-        };
-        // exclude armv5,6,7 as they are 32-bit.
-        let arch = if arch.starts_with("armv8")
-            || arch.starts_with("armv9")
-            || arch.starts_with("arm64")
-            || arch.starts_with("aarch64")
-        {
-            "aarch64"
-        } else if arch.starts_with("x86") {
-            "x86_64"
-        } else {
-            Err(anyhow!(
-                "Prebuilt remote servers are not yet available for {arch:?}. See https://editsync.khulnasoft.com/docs/remote-development"
-            ))?
-        };
-
-        Ok(SshPlatform { os, arch })
-    }
-
-    fn multiplex(
-        mut ssh_proxy_process: Child,
-        incoming_tx: UnboundedSender<Envelope>,
-        mut outgoing_rx: UnboundedReceiver<Envelope>,
-        mut connection_activity_tx: Sender<()>,
-        cx: &AsyncAppContext,
-    ) -> Task<Result<i32>> {
-        let mut child_stderr = ssh_proxy_process.stderr.take().unwrap();
-        let mut child_stdout = ssh_proxy_process.stdout.take().unwrap();
-        let mut child_stdin = ssh_proxy_process.stdin.take().unwrap();
-
-        let mut stdin_buffer = Vec::new();
-        let mut stdout_buffer = Vec::new();
-        let mut stderr_buffer = Vec::new();
-        let mut stderr_offset = 0;
-
-        let stdin_task = cx.background_executor().spawn(async move {
-            while let Some(outgoing) = outgoing_rx.next().await {
-                write_message(&mut child_stdin, &mut stdin_buffer, outgoing).await?;
-            }
-            anyhow::Ok(())
-        });
-
-        let stdout_task = cx.background_executor().spawn({
-            let mut connection_activity_tx = connection_activity_tx.clone();
-            async move {
-                loop {
-                    stdout_buffer.resize(MESSAGE_LEN_SIZE, 0);
-                    let len = child_stdout.read(&mut stdout_buffer).await?;
-
-                    if len == 0 {
-                        return anyhow::Ok(());
-                    }
-
-                    if len < MESSAGE_LEN_SIZE {
-                        child_stdout.read_exact(&mut stdout_buffer[len..]).await?;
-                    }
-
-                    let message_len = message_len_from_buffer(&stdout_buffer);
-                    let envelope =
-                        read_message_with_len(&mut child_stdout, &mut stdout_buffer, message_len)
-                            .await?;
-                    connection_activity_tx.try_send(()).ok();
-                    incoming_tx.unbounded_send(envelope).ok();
-                }
-            }
-        });
-
-        let stderr_task: Task<anyhow::Result<()>> = cx.background_executor().spawn(async move {
-            loop {
-                stderr_buffer.resize(stderr_offset + 1024, 0);
-
-                let len = child_stderr
-                    .read(&mut stderr_buffer[stderr_offset..])
-                    .await?;
-                if len == 0 {
-                    return anyhow::Ok(());
-                }
-
-                stderr_offset += len;
-                let mut start_ix = 0;
-                while let Some(ix) = stderr_buffer[start_ix..stderr_offset]
-                    .iter()
-                    .position(|b| b == &b'\n')
-                {
-                    let line_ix = start_ix + ix;
-                    let content = &stderr_buffer[start_ix..line_ix];
-                    start_ix = line_ix + 1;
-                    if let Ok(record) = serde_json::from_slice::<LogRecord>(content) {
-                        record.log(log::logger())
-                    } else {
-                        eprintln!("(remote) {}", String::from_utf8_lossy(content));
-                    }
-                }
-                stderr_buffer.drain(0..start_ix);
-                stderr_offset -= start_ix;
-
-                connection_activity_tx.try_send(()).ok();
-            }
-        });
-
-        cx.spawn(|_| async move {
-            let result = futures::select! {
-                result = stdin_task.fuse() => {
-                    result.context("stdin")
-                }
-                result = stdout_task.fuse() => {
-                    result.context("stdout")
-                }
-                result = stderr_task.fuse() => {
-                    result.context("stderr")
-                }
-            };
-
-            let status = ssh_proxy_process.status().await?.code().unwrap_or(1);
-            match result {
-                Ok(_) => Ok(status),
-                Err(error) => Err(error),
-            }
-        })
+        parse_platform(&uname)
     }
 
     #[allow(unused)]
@@ -1695,21 +1755,7 @@ impl SshRemoteConnection {
         commit: Option<AppCommitSha>,
         cx: &mut AsyncAppContext,
     ) -> Result<PathBuf> {
-        let version_str = match release_channel {
-            ReleaseChannel::Nightly => {
-                let commit = commit.map(|s| s.0.to_string()).unwrap_or_default();
-
-                format!("{}-{}", version, commit)
-            }
-            ReleaseChannel::Dev => "build".to_string(),
-            _ => version.to_string(),
-        };
-        let binary_name = format!(
-            "editsync-remote-server-{}-{}",
-            release_channel.dev_name(),
-            version_str
-        );
-        let dst_path = paths::remote_server_dir_relative().join(binary_name);
+        let dst_path = server_binary_path(release_channel, version, commit);
         let tmp_path_gz = PathBuf::from(format!(
             "{}-download-{}.gz",
             dst_path.to_string_lossy(),
@@ -2051,6 +2097,193 @@ impl SshRemoteConnection {
     }
 }
 
+/// Forwards messages between the given channels and the stdio of a process running the
+/// server's proxy, logging its stderr, and returns its exit code once it exits.
+pub(crate) fn multiplex(
+    mut ssh_proxy_process: Child,
+    incoming_tx: UnboundedSender<Envelope>,
+    mut outgoing_rx: UnboundedReceiver<Envelope>,
+    mut connection_activity_tx: Sender<()>,
+    cx: &AsyncAppContext,
+) -> Task<Result<i32>> {
+    let mut child_stderr = ssh_proxy_process.stderr.take().unwrap();
+    let mut child_stdout = ssh_proxy_process.stdout.take().unwrap();
+    let mut child_stdin = ssh_proxy_process.stdin.take().unwrap();
+
+    let mut stdin_buffer = Vec::new();
+    let mut stdout_buffer = Vec::new();
+    let mut stderr_buffer = Vec::new();
+    let mut stderr_offset = 0;
+
+    let stdin_task = cx.background_executor().spawn(async move {
+        while let Some(outgoing) = outgoing_rx.next().await {
+            write_message(&mut child_stdin, &mut stdin_buffer, outgoing).await?;
+        }
+        anyhow::Ok(())
+    });
+
+    let stdout_task = cx.background_executor().spawn({
+        let mut connection_activity_tx = connection_activity_tx.clone();
+        async move {
+            loop {
+                stdout_buffer.resize(MESSAGE_LEN_SIZE, 0);
+                let len = child_stdout.read(&mut stdout_buffer).await?;
+
+                if len == 0 {
+                    return anyhow::Ok(());
+                }
+
+                if len < MESSAGE_LEN_SIZE {
+                    child_stdout.read_exact(&mut stdout_buffer[len..]).await?;
+                }
+
+                let message_len = message_len_from_buffer(&stdout_buffer);
+                let envelope =
+                    read_message_with_len(&mut child_stdout, &mut stdout_buffer, message_len)
+                        .await?;
+                connection_activity_tx.try_send(()).ok();
+                incoming_tx.unbounded_send(envelope).ok();
+            }
+        }
+    });
+
+    let stderr_task: Task<anyhow::Result<()>> = cx.background_executor().spawn(async move {
+        loop {
+            stderr_buffer.resize(stderr_offset + 1024, 0);
+
+            let len = child_stderr
+                .read(&mut stderr_buffer[stderr_offset..])
+                .await?;
+            if len == 0 {
+                return anyhow::Ok(());
+            }
+
+            stderr_offset += len;
+            let mut start_ix = 0;
+            while let Some(ix) = stderr_buffer[start_ix..stderr_offset]
+                .iter()
+                .position(|b| b == &b'\n')
+            {
+                let line_ix = start_ix + ix;
+                let content = &stderr_buffer[start_ix..line_ix];
+                start_ix = line_ix + 1;
+                if let Ok(record) = serde_json::from_slice::<LogRecord>(content) {
+                    record.log(log::logger())
+                } else {
+                    eprintln!("(remote) {}", String::from_utf8_lossy(content));
+                }
+            }
+            stderr_buffer.drain(0..start_ix);
+            stderr_offset -= start_ix;
+
+            connection_activity_tx.try_send(()).ok();
+        }
+    });
+
+    cx.spawn(|_| async move {
+        let result = futures::select! {
+            result = stdin_task.fuse() => {
+                result.context("stdin")
+            }
+            result = stdout_task.fuse() => {
+                result.context("stdout")
+            }
+            result = stderr_task.fuse() => {
+                result.context("stderr")
+            }
+        };
+
+        let status = ssh_proxy_process.status().await?.code().unwrap_or(1);
+        match result {
+            Ok(_) => Ok(status),
+            Err(error) => Err(error),
+        }
+    })
+}
+
+/// Parses the output of `uname -sm` on the remote.
+pub(crate) fn parse_platform(uname: &str) -> Result<SshPlatform> {
+    let Some((os, arch)) = uname.split_once(" ") else {
+        Err(anyhow!("unknown uname: {uname:?}"))?
+    };
+
+    let os = match os.trim() {
+        "Darwin" => "macos",
+        "Linux" => "linux",
+        _ => Err(anyhow!(
+            "Prebuilt remote servers are not yet available for {os:?}. See https://editsync.khulnasoft.com/docs/remote-development"
+        ))?,
+    };
+    // exclude armv5,6,7 as they are 32-bit.
+    let arch = if arch.starts_with("armv8")
+        || arch.starts_with("armv9")
+        || arch.starts_with("arm64")
+        || arch.starts_with("aarch64")
+    {
+        "aarch64"
+    } else if arch.starts_with("x86") {
+        "x86_64"
+    } else {
+        Err(anyhow!(
+            "Prebuilt remote servers are not yet available for {arch:?}. See https://editsync.khulnasoft.com/docs/remote-development"
+        ))?
+    };
+
+    Ok(SshPlatform { os, arch })
+}
+
+/// Returns the command line that starts the server's proxy from the given binary.
+pub(crate) fn proxy_command(binary_path: &Path, identifier: &str, reconnect: bool) -> String {
+    let mut command = shell_script!(
+        "exec {binary_path} proxy --identifier {identifier}",
+        binary_path = &binary_path.to_string_lossy(),
+        identifier = identifier,
+    );
+
+    if let Some(rust_log) = std::env::var("RUST_LOG").ok() {
+        command = format!(
+            "RUST_LOG={} {}",
+            shlex::try_quote(&rust_log).unwrap(),
+            command
+        )
+    }
+    if let Some(rust_backtrace) = std::env::var("RUST_BACKTRACE").ok() {
+        command = format!(
+            "RUST_BACKTRACE={} {}",
+            shlex::try_quote(&rust_backtrace).unwrap(),
+            command
+        )
+    }
+    if reconnect {
+        command.push_str(" --reconnect");
+    }
+    command
+}
+
+/// Returns the path of the server binary matching this version of the app, relative to the
+/// home directory of the remote user.
+pub(crate) fn server_binary_path(
+    release_channel: ReleaseChannel,
+    version: SemanticVersion,
+    commit: Option<AppCommitSha>,
+) -> PathBuf {
+    let version_str = match release_channel {
+        ReleaseChannel::Nightly => {
+            let commit = commit.map(|s| s.0.to_string()).unwrap_or_default();
+
+            format!("{}-{}", version, commit)
+        }
+        ReleaseChannel::Dev => "build".to_string(),
+        _ => version.to_string(),
+    };
+    let binary_name = format!(
+        "editsync-remote-server-{}-{}",
+        release_channel.dev_name(),
+        version_str
+    );
+    paths::remote_server_dir_relative().join(binary_name)
+}
+
 type ResponseChannels = Mutex<HashMap<MessageId, oneshot::Sender<(Envelope, oneshot::Sender<()>)>>>;
 
 pub struct ChannelClient {
@@ -2411,8 +2644,16 @@ mod fake {
             false
         }
 
-        fn ssh_args(&self) -> Vec<String> {
-            Vec::new()
+        async fn forward_port(&self, _forward: &SshPortForward) -> Result<()> {
+            Ok(())
+        }
+
+        async fn cancel_port_forward(&self, _forward: &SshPortForward) -> Result<()> {
+            Ok(())
+        }
+
+        fn terminal_command(&self) -> (String, Vec<String>) {
+            ("sh".to_string(), vec!["-c".to_string()])
         }
         fn upload_directory(
             &self,
@@ -2510,3 +2751,59 @@ mod fake {
         fn set_status(&self, _: Option<&str>, _: &mut AsyncAppContext) {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use std::os::unix::fs::PermissionsExt as _;
+
+    #[gpui::test]
+    async fn test_local_process_server(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        // Stands in for the server binary: it checks that it was started as a proxy, and then
+        // echoes every message it receives.
+        let temp_dir = tempfile::tempdir().unwrap();
+        let server_binary = temp_dir.path().join("remote_server");
+        std::fs::write(
+            &server_binary,
+            "#!/bin/sh\n[ \"$1\" = proxy ] && [ \"$2\" = --identifier ] || exit 2\nexec cat\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&server_binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let opts = cx.update(|cx| SshRemoteClient::local_process_server(server_binary, cx));
+        let delegate: Arc<dyn SshClientDelegate> = Arc::new(fake::Delegate);
+        let connection = cx
+            .update(|cx| {
+                cx.update_default_global(|pool: &mut ConnectionPool, cx| {
+                    pool.connect(opts.clone(), &delegate, cx)
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(connection.connection_options(), opts);
+
+        let (incoming_tx, mut incoming_rx) = mpsc::unbounded();
+        let (outgoing_tx, outgoing_rx) = mpsc::unbounded();
+        let (connection_activity_tx, _connection_activity_rx) = mpsc::channel(1);
+        let proxy = connection.start_proxy(
+            "test".to_string(),
+            false,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            delegate,
+            &mut cx.to_async(),
+        );
+
+        let envelope = proto::Ping {}.into_envelope(1, None, None);
+        outgoing_tx.unbounded_send(envelope.clone()).unwrap();
+        assert_eq!(incoming_rx.next().await.unwrap(), envelope);
+
+        // Closing the connection ends the server's input, so it exits.
+        drop(outgoing_tx);
+        assert_eq!(proxy.await.unwrap(), 0);
+    }
+}
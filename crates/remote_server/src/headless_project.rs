@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Result};
 use extension::ExtensionHostProxy;
 use extension_host::headless_host::HeadlessExtensionStore;
-use fs::Fs;
+use fs::{Fs, RemoveOptions, RenameOptions};
 use gpui::{AppContext, AsyncAppContext, Context as _, Model, ModelContext, PromptLevel};
 use http_client::HttpClient;
 use language::{proto::serialize_operation, Buffer, BufferEvent, LanguageRegistry};
@@ -23,7 +23,7 @@ use rpc::{
 use settings::initial_server_settings_content;
 use smol::stream::StreamExt;
 use std::{
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     sync::{atomic::AtomicUsize, Arc},
 };
 use util::ResultExt;
@@ -40,6 +40,7 @@ pub struct HeadlessProject {
     pub next_entry_id: Arc<AtomicUsize>,
     pub languages: Arc<LanguageRegistry>,
     pub extensions: Model<HeadlessExtensionStore>,
+    pub node_runtime: NodeRuntime,
 }
 
 pub struct HeadlessAppState {
@@ -158,7 +159,7 @@ impl HeadlessProject {
             http_client.clone(),
             paths::remote_extensions_dir().to_path_buf(),
             proxy,
-            node_runtime,
+            node_runtime.clone(),
             cx,
         );
 
@@ -176,6 +177,8 @@ impl HeadlessProject {
         client.add_request_handler(cx.weak_model(), Self::handle_get_path_metadata);
         client.add_request_handler(cx.weak_model(), Self::handle_shutdown_remote_server);
         client.add_request_handler(cx.weak_model(), Self::handle_ping);
+        client.add_request_handler(cx.weak_model(), Self::handle_sync_language_server_bundle);
+        client.add_request_handler(cx.weak_model(), Self::handle_install_language_server_bundle);
 
         client.add_model_request_handler(Self::handle_add_worktree);
         client.add_request_handler(cx.weak_model(), Self::handle_remove_worktree);
@@ -184,6 +187,7 @@ impl HeadlessProject {
         client.add_model_request_handler(Self::handle_open_new_buffer);
         client.add_model_request_handler(Self::handle_find_search_candidates);
         client.add_model_request_handler(Self::handle_open_server_settings);
+        client.add_model_request_handler(Self::handle_synchronize_buffers);
 
         client.add_model_request_handler(BufferStore::handle_update_buffer);
         client.add_model_message_handler(BufferStore::handle_close_buffer);
@@ -215,6 +219,7 @@ impl HeadlessProject {
             next_entry_id: Default::default(),
             languages,
             extensions,
+            node_runtime,
         }
     }
 
@@ -451,6 +456,18 @@ impl HeadlessProject {
         })
     }
 
+    pub async fn handle_synchronize_buffers(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SynchronizeBuffers>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::SynchronizeBuffersResponse> {
+        this.update(&mut cx, |this, cx| {
+            this.buffer_store.update(cx, |buffer_store, cx| {
+                buffer_store.synchronize_downstream_buffers(envelope.payload.buffers, cx)
+            })
+        })?
+    }
+
     pub async fn handle_open_server_settings(
         this: Model<Self>,
         _: TypedEnvelope<proto::OpenServerSettings>,
@@ -594,6 +611,97 @@ impl HeadlessProject {
         Ok(proto::Ack {})
     }
 
+    pub async fn handle_sync_language_server_bundle(
+        this: Model<Self>,
+        _envelope: TypedEnvelope<proto::SyncLanguageServerBundle>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::SyncLanguageServerBundleResponse> {
+        let fs = cx.read_model(&this, |this, _| this.fs.clone())?;
+        let tmp_dir = paths::remote_language_server_uploads_dir();
+        fs.remove_dir(
+            tmp_dir,
+            RemoveOptions {
+                recursive: true,
+                ignore_if_not_exists: true,
+            },
+        )
+        .await?;
+        fs.create_dir(tmp_dir).await?;
+
+        let mut language_servers = Vec::new();
+        let mut entries = fs.read_dir(paths::languages_dir()).await?;
+        while let Some(path) = entries.next().await {
+            let path = path?;
+            if !fs.is_dir(&path).await {
+                continue;
+            }
+            // A server whose download failed leaves an empty directory behind.
+            if fs.read_dir(&path).await?.next().await.is_none() {
+                continue;
+            }
+            if let Some(file_name) = path.file_name() {
+                language_servers.push(file_name.to_string_lossy().to_string());
+            }
+        }
+
+        let node_runtime = cx.read_model(&this, |this, _| this.node_runtime.clone())?;
+        let has_node = node_runtime.has_system_node().await
+            || match node_runtime::managed_node_dir() {
+                Ok(node_dir) => fs.is_dir(&node_dir).await,
+                Err(_) => false,
+            };
+
+        Ok(proto::SyncLanguageServerBundleResponse {
+            tmp_dir: tmp_dir.to_string_lossy().to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            language_servers,
+            has_node,
+        })
+    }
+
+    pub async fn handle_install_language_server_bundle(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::InstallLanguageServerBundle>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let fs = cx.read_model(&this, |this, _| this.fs.clone())?;
+        let tmp_dir = paths::remote_language_server_uploads_dir();
+
+        for name in &envelope.payload.language_servers {
+            let name = uploaded_path(name)?;
+            if name.components().count() != 1 {
+                return Err(anyhow!("invalid language server name {name:?}"));
+            }
+            let target = paths::languages_dir().join(name);
+            fs.remove_dir(
+                &target,
+                RemoveOptions {
+                    recursive: true,
+                    ignore_if_not_exists: true,
+                },
+            )
+            .await?;
+            fs.rename(&tmp_dir.join(name), &target, RenameOptions::default())
+                .await?;
+        }
+
+        if let Some(node_archive) = envelope.payload.node_archive {
+            let archive = tmp_dir.join(uploaded_path(&node_archive)?);
+            node_runtime::install_managed_node_archive(fs.as_ref(), &archive).await?;
+        }
+
+        // Servers that failed to download can now start from what was installed.
+        this.update(&mut cx, |this, cx| {
+            let buffers = this.buffer_store.read(cx).buffers().collect::<Vec<_>>();
+            this.lsp_store.update(cx, |lsp_store, cx| {
+                lsp_store.restart_language_servers_for_buffers(buffers, cx)
+            });
+        })?;
+
+        Ok(proto::Ack {})
+    }
+
     pub async fn handle_ping(
         _this: Model<Self>,
         _envelope: TypedEnvelope<proto::Ping>,
@@ -604,6 +712,19 @@ impl HeadlessProject {
     }
 }
 
+/// Checks that a path sent by the client stays within the uploads directory.
+fn uploaded_path(path: &str) -> Result<&Path> {
+    let path = Path::new(path);
+    if path.as_os_str().is_empty()
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(anyhow!("invalid uploaded path {path:?}"));
+    }
+    Ok(path)
+}
+
 fn prompt_to_proto(
     prompt: &project::LanguageServerPromptRequest,
 ) -> proto::language_server_prompt_request::Level {
@@ -619,3 +740,26 @@ fn prompt_to_proto(
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uploaded_path() {
+        assert_eq!(
+            uploaded_path("node/node.tar.gz").unwrap(),
+            Path::new("node/node.tar.gz")
+        );
+        assert_eq!(
+            uploaded_path("json-language-server").unwrap(),
+            Path::new("json-language-server")
+        );
+
+        assert!(uploaded_path("").is_err());
+        assert!(uploaded_path("..").is_err());
+        assert!(uploaded_path("node/../../bin").is_err());
+        assert!(uploaded_path("./node").is_err());
+        assert!(uploaded_path("/usr/bin").is_err());
+    }
+}
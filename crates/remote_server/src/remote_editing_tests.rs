@@ -15,13 +15,16 @@ use project::{
     search::{SearchQuery, SearchResult},
     Project, ProjectPath,
 };
-use remote::SshRemoteClient;
+use remote::{ConnectionState, SshRemoteClient};
 use serde_json::json;
 use settings::{initial_server_settings_content, Settings, SettingsLocation, SettingsStore};
 use smol::stream::StreamExt;
 use std::{
+    cell::RefCell,
     path::{Path, PathBuf},
+    rc::Rc,
     sync::Arc,
+    time::Duration,
 };
 
 #[gpui::test]
@@ -537,6 +540,168 @@ async fn test_remote_lsp(cx: &mut TestAppContext, server_cx: &mut TestAppContext
     })
 }
 
+#[gpui::test]
+async fn test_remote_lsp_file_operations(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "src": {
+                    "lib.rs": "mod one;"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, headless) = init_test(&fs, cx, server_cx).await;
+
+    fs.insert_tree(
+        "/code/project1/.editsync",
+        json!({
+            "settings.json": r#"
+          {
+            "languages": {"Rust":{"language_servers":["rust-analyzer"]}},
+            "lsp": {
+              "rust-analyzer": {
+                "binary": {
+                  "path": "~/.cargo/bin/rust-analyzer"
+                }
+              }
+            }
+          }"#
+        }),
+    )
+    .await;
+
+    cx.update_model(&project, |project, _| {
+        project.languages().register_test_language(LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".into()],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        project.languages().register_fake_lsp_adapter(
+            "Rust",
+            FakeLspAdapter {
+                name: "rust-analyzer",
+                ..Default::default()
+            },
+        )
+    });
+
+    let watched_paths = lsp::FileOperationRegistrationOptions {
+        filters: vec![lsp::FileOperationFilter {
+            scheme: Some("file".to_owned()),
+            pattern: lsp::FileOperationPattern {
+                glob: "**/*.rs".to_owned(),
+                matches: Some(lsp::FileOperationPatternKind::File),
+                options: None,
+            },
+        }],
+    };
+    let mut fake_lsp = server_cx.update(|cx| {
+        headless.read(cx).languages.register_fake_language_server(
+            LanguageServerName("rust-analyzer".into()),
+            lsp::ServerCapabilities {
+                workspace: Some(lsp::WorkspaceServerCapabilities {
+                    workspace_folders: None,
+                    file_operations: Some(lsp::WorkspaceFileOperationsServerCapabilities {
+                        did_create: Some(watched_paths.clone()),
+                        will_create: Some(watched_paths),
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            },
+            None,
+        )
+    });
+
+    cx.run_until_parked();
+
+    let worktree_id = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap()
+        .0
+        .read_with(cx, |worktree, _| worktree.id());
+    cx.run_until_parked();
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_buffer_with_lsp((worktree_id, Path::new("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    let fake_lsp = fake_lsp.next().await.unwrap();
+
+    let proposed_edits = Rc::new(RefCell::new(Vec::new()));
+    cx.update(|cx| {
+        let proposed_edits = proposed_edits.clone();
+        cx.subscribe(&project, move |_, event, _| {
+            if let project::Event::FileOperationEditsProposed { edits, .. } = event {
+                proposed_edits.borrow_mut().push(edits.clone());
+            }
+        })
+        .detach();
+    });
+
+    fake_lsp.handle_request::<lsp::request::WillCreateFiles, _, _>(|params, _| async move {
+        assert_eq!(params.files[0].uri, "file:///code/project1/src/one.rs");
+        Ok(Some(lsp::WorkspaceEdit {
+            changes: Some(
+                [(
+                    lsp::Url::from_file_path("/code/project1/src/lib.rs").unwrap(),
+                    vec![lsp::TextEdit::new(
+                        lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+                        "pub ".to_string(),
+                    )],
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            ..Default::default()
+        }))
+    });
+    let mut did_create =
+        fake_lsp.handle_notification::<lsp::notification::DidCreateFiles, _>(|params, _| {
+            assert_eq!(params.files[0].uri, "file:///code/project1/src/one.rs");
+        });
+
+    project
+        .update(cx, |project, cx| {
+            project.create_entry((worktree_id, "src/one.rs"), false, cx)
+        })
+        .await
+        .unwrap();
+    did_create.next().await.unwrap();
+    cx.run_until_parked();
+
+    // The edits are reviewed on the client, and only land on the server once applied there.
+    let edits = proposed_edits.borrow_mut().pop().unwrap();
+    assert_eq!(edits.buffer_edits.len(), 1);
+    assert_eq!(edits.buffer_edits[0].0, buffer);
+    let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+    let server_buffer = headless.read_with(server_cx, |headless, cx| {
+        headless.buffer_store.read(cx).get(buffer_id).unwrap()
+    });
+    server_buffer.read_with(server_cx, |buffer, _| assert_eq!(buffer.text(), "mod one;"));
+
+    cx.update(|cx| edits.apply(cx));
+    cx.run_until_parked();
+    server_buffer.read_with(server_cx, |buffer, _| {
+        assert_eq!(buffer.text(), "pub mod one;")
+    });
+}
+
 #[gpui::test]
 async fn test_remote_cancel_language_server_work(
     cx: &mut TestAppContext,
@@ -703,6 +868,132 @@ async fn test_remote_cancel_language_server_work(
     }
 }
 
+#[gpui::test]
+async fn test_remote_install_language_server_bundle(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }"
+                }
+            },
+        }),
+    )
+    .await;
+    fs.insert_tree(
+        paths::languages_dir(),
+        json!({
+            "rust-analyzer": { "rust-analyzer": "" },
+            "failed-download": {},
+        }),
+    )
+    .await;
+
+    let (project, headless) = init_test(&fs, cx, server_cx).await;
+
+    cx.update_model(&project, |project, _| {
+        project.languages().register_test_language(LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".into()],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        project.languages().register_fake_lsp_adapter(
+            "Rust",
+            FakeLspAdapter {
+                name: "rust-analyzer",
+                ..Default::default()
+            },
+        )
+    });
+
+    let mut fake_lsp = server_cx.update(|cx| {
+        headless.read(cx).languages.register_fake_language_server(
+            LanguageServerName("rust-analyzer".into()),
+            Default::default(),
+            None,
+        )
+    });
+
+    cx.run_until_parked();
+
+    let worktree_id = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap()
+        .0
+        .read_with(cx, |worktree, _| worktree.id());
+    cx.run_until_parked();
+
+    let (_buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_buffer_with_lsp((worktree_id, Path::new("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    fake_lsp.next().await.unwrap();
+
+    // The server reports the language servers it has, ignoring those that failed to download.
+    let client = cx.read(|cx| {
+        project
+            .read(cx)
+            .ssh_client()
+            .unwrap()
+            .read(cx)
+            .proto_client()
+    });
+    let response = client
+        .request(proto::SyncLanguageServerBundle {})
+        .await
+        .unwrap();
+    let uploads_dir = paths::remote_language_server_uploads_dir();
+    assert_eq!(PathBuf::from(&response.tmp_dir), *uploads_dir);
+    assert_eq!(response.language_servers, ["rust-analyzer"]);
+    assert!(!response.has_node);
+
+    // Once a language server is uploaded, it is installed and the language servers restart.
+    fs.insert_tree(
+        uploads_dir.join("json-language-server"),
+        json!({ "server.js": "" }),
+    )
+    .await;
+    client
+        .request(proto::InstallLanguageServerBundle {
+            language_servers: vec!["json-language-server".into()],
+            node_archive: None,
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    assert!(
+        fs.is_file(&paths::languages_dir().join("json-language-server/server.js"))
+            .await
+    );
+    assert!(!fs.is_dir(&uploads_dir.join("json-language-server")).await);
+    fake_lsp.next().await.unwrap();
+
+    // Paths outside of the uploads directory are rejected.
+    assert!(client
+        .request(proto::InstallLanguageServerBundle {
+            language_servers: vec!["../rust-analyzer".into()],
+            node_archive: None,
+        })
+        .await
+        .is_err());
+}
+
 #[gpui::test]
 async fn test_remote_reload(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
@@ -1095,6 +1386,100 @@ async fn test_reconnect(cx: &mut TestAppContext, server_cx: &mut TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_offline_edits_resync_on_reconnect(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, headless) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, Path::new("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+    let server_buffer = headless.read_with(server_cx, |headless, cx| {
+        headless.buffer_store.read(cx).get(buffer_id).unwrap()
+    });
+
+    let toasts = Rc::new(RefCell::new(Vec::new()));
+    cx.update(|cx| {
+        let toasts = toasts.clone();
+        cx.subscribe(&project, move |_, event, _| {
+            if let project::Event::Toast { message, .. } = event {
+                toasts.borrow_mut().push(message.clone());
+            }
+        })
+        .detach();
+    });
+
+    let client = cx.read(|cx| project.read(cx).ssh_client().unwrap());
+    client
+        .update(cx, |client, cx| client.simulate_disconnect(cx))
+        .detach();
+    while client.read_with(cx, |client, _| client.connection_state()) == ConnectionState::Connected
+    {
+        cx.executor().advance_clock(Duration::from_secs(10));
+    }
+
+    // Edit the buffer on both ends while the connection is lost.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "// offline\n")], None, cx);
+    });
+    server_buffer.update(server_cx, |buffer, cx| {
+        let ix = buffer.text().find('1').unwrap();
+        buffer.edit([(ix..ix + 1, "100")], None, cx);
+    });
+
+    for _ in 0..20 {
+        if client.read_with(cx, |client, _| client.connection_state()) == ConnectionState::Connected
+        {
+            break;
+        }
+        cx.executor().advance_clock(Duration::from_secs(10));
+    }
+    cx.run_until_parked();
+
+    assert_eq!(
+        client.read_with(cx, |client, _| client.connection_state()),
+        ConnectionState::Connected
+    );
+    let expected_text = "// offline\nfn one() -> usize { 100 }";
+    buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), expected_text));
+    server_buffer.read_with(server_cx, |buffer, _| {
+        assert_eq!(buffer.text(), expected_text)
+    });
+    assert_eq!(
+        toasts.borrow().len(),
+        1,
+        "the concurrent edits should be reported"
+    );
+}
+
 #[gpui::test]
 async fn test_remote_root_rename(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
@@ -382,6 +382,7 @@ fn init_paths() -> anyhow::Result<()> {
         paths::temp_dir(),
         paths::remote_extensions_dir(),
         paths::remote_extensions_uploads_dir(),
+        paths::remote_language_server_uploads_dir(),
     ]
     .iter()
     {
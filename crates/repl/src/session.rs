@@ -23,7 +23,7 @@ use language::Point;
 use project::Fs;
 use runtimelib::{
     ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
-    ShutdownRequest,
+    MimeType, ShutdownRequest,
 };
 use std::{env::temp_dir, ops::Range, sync::Arc, time::Duration};
 use theme::ActiveTheme;
@@ -44,6 +44,8 @@ struct EditorBlock {
     invalidation_anchor: Anchor,
     block_id: CustomBlockId,
     execution_view: View<ExecutionView>,
+    /// The plain text representation of the result of the execution, shown after the code.
+    result: Option<String>,
 }
 
 type CloseBlockFn =
@@ -105,10 +107,20 @@ impl EditorBlock {
             invalidation_anchor,
             block_id,
             execution_view,
+            result: None,
         })
     }
 
     fn handle_message(&mut self, message: &JupyterMessage, cx: &mut ViewContext<Session>) {
+        if let JupyterMessageContent::ExecuteResult(result) = &message.content {
+            if let Some(MimeType::Plain(text)) = result.data.richest(|mime_type| match mime_type {
+                MimeType::Plain(_) => 1,
+                _ => 0,
+            }) {
+                self.result = Some(text.clone());
+            }
+        }
+
         self.execution_view.update(cx, |execution_view, cx| {
             execution_view.push_message(&message.content, cx);
         });
@@ -317,6 +329,8 @@ impl Session {
             let mut blocks_to_remove: HashSet<CustomBlockId> = HashSet::default();
 
             self.blocks.retain(|_id, block| {
+                // The editor discards inline values on edit, since they may no longer match the code.
+                block.result = None;
                 if block.invalidation_anchor.is_valid(&snapshot) {
                     true
                 } else {
@@ -351,12 +365,27 @@ impl Session {
         self.editor
             .update(cx, |editor, cx| {
                 editor.remove_blocks(blocks_to_remove, None, cx);
+                editor.clear_inline_values::<Self>(cx);
             })
             .ok();
 
         self.blocks.clear();
     }
 
+    /// Shows the result of each execution at the end of the last line of its code.
+    fn update_inline_values(&mut self, cx: &mut ViewContext<Self>) {
+        let values = self
+            .blocks
+            .values()
+            .filter_map(|block| Some((block.code_range.end, block.result.clone()?)))
+            .collect::<Vec<_>>();
+        self.editor
+            .update(cx, |editor, cx| {
+                editor.set_inline_values::<Self>(values, cx);
+            })
+            .ok();
+    }
+
     pub fn execute(
         &mut self,
         code: String,
@@ -398,6 +427,7 @@ impl Session {
                 editor.remove_blocks(blocks_to_remove, None, cx);
             })
             .ok();
+        self.update_inline_values(cx);
 
         let status = match &self.kernel {
             Kernel::Restarting => ExecutionStatus::Restarting,
@@ -417,6 +447,7 @@ impl Session {
                 if let Some(session) = session_view.upgrade() {
                     session.update(cx, |session, cx| {
                         session.blocks.remove(&parent_message_id);
+                        session.update_inline_values(cx);
                         cx.notify();
                     });
                 }
@@ -517,6 +548,9 @@ impl Session {
 
         if let Some(block) = self.blocks.get_mut(parent_message_id) {
             block.handle_message(message, cx);
+            if let JupyterMessageContent::ExecuteResult(_) = &message.content {
+                self.update_inline_values(cx);
+            }
         }
     }
 
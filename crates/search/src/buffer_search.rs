@@ -3,7 +3,8 @@ mod registrar;
 use crate::{
     search_bar::render_nav_button, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll,
     ReplaceNext, SearchOptions, SelectAllMatches, SelectNextMatch, SelectPrevMatch,
-    ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection, ToggleWholeWord,
+    ToggleCaseSensitive, TogglePreserveCase, ToggleRegex, ToggleReplace, ToggleSelection,
+    ToggleWholeWord,
 };
 use any_vec::AnyVec;
 use collections::HashMap;
@@ -367,11 +368,21 @@ impl Render for BufferSearchBar {
         let replace_line = should_show_replace_input.then(|| {
             h_flex()
                 .gap_2()
-                .child(input_base_styles().child(self.render_text_input(
-                    &self.replacement_editor,
-                    cx.theme().colors().text,
-                    cx,
-                )))
+                .child(
+                    input_base_styles()
+                        .child(self.render_text_input(
+                            &self.replacement_editor,
+                            cx.theme().colors().text,
+                            cx,
+                        ))
+                        .child(self.render_search_option_button(
+                            SearchOptions::PRESERVE_CASE,
+                            focus_handle.clone(),
+                            cx.listener(|this, _, cx| {
+                                this.toggle_preserve_case(&TogglePreserveCase, cx)
+                            }),
+                        )),
+                )
                 .child(
                     h_flex()
                         .min_w_64()
@@ -443,6 +454,9 @@ impl Render for BufferSearchBar {
             .when(self.supported_options().regex, |this| {
                 this.on_action(cx.listener(Self::toggle_regex))
             })
+            .when(self.supported_options().replacement, |this| {
+                this.on_action(cx.listener(Self::toggle_preserve_case))
+            })
             .when(self.supported_options().selection, |this| {
                 this.on_action(cx.listener(Self::toggle_selection))
             })
@@ -545,6 +559,11 @@ impl BufferSearchBar {
                 this.toggle_replace(action, cx);
             }
         }));
+        registrar.register_handler(ForDeployed(|this, action: &TogglePreserveCase, cx| {
+            if this.supported_options().replacement {
+                this.toggle_preserve_case(action, cx);
+            }
+        }));
         registrar.register_handler(WithResults(|this, action: &SelectNextMatch, cx| {
             this.select_next_match(action, cx);
         }));
@@ -982,6 +1001,20 @@ impl BufferSearchBar {
         self.toggle_search_option(SearchOptions::REGEX, cx)
     }
 
+    fn toggle_preserve_case(&mut self, _: &TogglePreserveCase, cx: &mut ViewContext<Self>) {
+        // Only the replacements depend on the case being preserved, so there is nothing to search.
+        self.search_options.toggle(SearchOptions::PRESERVE_CASE);
+        self.default_options = self.search_options;
+        cx.notify();
+    }
+
+    fn replacement_query(&self, query: &SearchQuery, cx: &WindowContext) -> SearchQuery {
+        query
+            .clone()
+            .with_replacement(self.replacement(cx))
+            .with_preserve_case(self.search_options.contains(SearchOptions::PRESERVE_CASE))
+    }
+
     fn clear_active_searchable_item_matches(&mut self, cx: &mut WindowContext) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.active_match_index = None;
@@ -1221,10 +1254,7 @@ impl BufferSearchBar {
                         .get(&searchable_item.downgrade())
                     {
                         if let Some(active_index) = self.active_match_index {
-                            let query = query
-                                .as_ref()
-                                .clone()
-                                .with_replacement(self.replacement(cx));
+                            let query = self.replacement_query(query, cx);
                             searchable_item.replace(matches.at(active_index), &query, cx);
                             self.select_next_match(&SelectNextMatch, cx);
                         }
@@ -1247,10 +1277,7 @@ impl BufferSearchBar {
                         .searchable_items_with_matches
                         .get(&searchable_item.downgrade())
                     {
-                        let query = query
-                            .as_ref()
-                            .clone()
-                            .with_replacement(self.replacement(cx));
+                        let query = self.replacement_query(query, cx);
                         searchable_item.replace_all(&mut matches.iter(), &query, cx);
                     }
                 }
@@ -2210,6 +2237,72 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_replace_preserving_case(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+
+        search_bar
+            .update(cx, |search_bar, cx| {
+                search_bar.search("a regular", Some(SearchOptions::NONE), cx)
+            })
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.toggle_preserve_case(&TogglePreserveCase, cx);
+            assert!(search_bar.has_search_option(SearchOptions::PRESERVE_CASE));
+            assert!(search_bar
+                .default_options
+                .contains(SearchOptions::PRESERVE_CASE));
+            assert_eq!(
+                search_bar.active_match_index,
+                Some(0),
+                "Toggling the option shouldn't clear the matches"
+            );
+
+            search_bar.replacement_editor.update(cx, |editor, cx| {
+                editor.set_text("the usual", cx);
+            });
+            search_bar.replace_all(&ReplaceAll, cx)
+        });
+        assert_eq!(
+            editor.update(cx, |this, cx| this.text(cx)),
+            r#"
+        The usual expression (shortened as regex or regexp;[1] also referred to as
+        rational expression[2][3]) is a sequence of characters that specifies a search
+        pattern in text. Usually such patterns are used by string-searching algorithms
+        for "find" or "find and replace" operations on strings, or for input validation.
+        "#
+            .unindent(),
+            "The replacement should follow the case of the match"
+        );
+
+        search_bar
+            .update(cx, |search_bar, cx| {
+                search_bar.toggle_preserve_case(&TogglePreserveCase, cx);
+                assert!(!search_bar.has_search_option(SearchOptions::PRESERVE_CASE));
+                search_bar.search("the usual", None, cx)
+            })
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.replacement_editor.update(cx, |editor, cx| {
+                editor.set_text("a REGULAR", cx);
+            });
+            search_bar.replace_all(&ReplaceAll, cx)
+        });
+        assert_eq!(
+            editor.update(cx, |this, cx| this.text(cx)),
+            r#"
+        a REGULAR expression (shortened as regex or regexp;[1] also referred to as
+        rational expression[2][3]) is a sequence of characters that specifies a search
+        pattern in text. Usually such patterns are used by string-searching algorithms
+        for "find" or "find and replace" operations on strings, or for input validation.
+        "#
+            .unindent(),
+            "Without the option, the replacement should be inserted as is"
+        );
+    }
+
     struct ReplacementTestParams<'a> {
         editor: &'a View<Editor>,
         search_bar: &'a View<BufferSearchBar>,
@@ -1,12 +1,17 @@
 use crate::{
-    buffer_search::Deploy, BufferSearchBar, FocusSearch, NextHistoryQuery, PreviousHistoryQuery,
-    ReplaceAll, ReplaceNext, SearchOptions, SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive,
-    ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleWholeWord,
+    buffer_search::Deploy,
+    saved_searches::{SaveSearch, SavedSearch, SavedSearches},
+    BufferSearchBar, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
+    SearchOptions, SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
+    TogglePreserveCase, ToggleRegex, ToggleReplace, ToggleStructural, ToggleWholeWord,
 };
 use collections::{HashMap, HashSet};
 use editor::{
-    actions::SelectAll, items::active_match_index, scroll::Autoscroll, Anchor, Editor,
-    EditorElement, EditorEvent, EditorSettings, EditorStyle, MultiBuffer, MAX_TAB_TITLE_LEN,
+    actions::SelectAll,
+    items::{active_match_index, replacement_for_match},
+    scroll::Autoscroll,
+    Anchor, Editor, EditorElement, EditorEvent, EditorSettings, EditorStyle, MultiBuffer,
+    MAX_TAB_TITLE_LEN,
 };
 use futures::StreamExt;
 use gpui::{
@@ -23,6 +28,7 @@ use project::{
     search_history::SearchHistoryCursor,
     Project, ProjectPath,
 };
+use rpc::proto::{self, PeerId};
 use settings::Settings;
 use std::{
     any::{Any, TypeId},
@@ -37,15 +43,21 @@ use ui::{
 };
 use util::paths::PathMatcher;
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, ItemHandle},
+    item::{BreadcrumbText, Dedup, FollowEvent, FollowableItem, Item, ItemEvent, ItemHandle},
     searchable::{Direction, SearchableItem, SearchableItemHandle},
     DeploySearch, ItemNavHistory, NewSearch, ToolbarItemEvent, ToolbarItemLocation,
-    ToolbarItemView, Workspace, WorkspaceId,
+    ToolbarItemView, ViewId, Workspace, WorkspaceId,
 };
 
 actions!(
     project_search,
-    [SearchInNew, ToggleFocus, NextField, ToggleFilters]
+    [
+        SearchInNew,
+        ToggleFocus,
+        NextField,
+        ToggleFilters,
+        ToggleReplacementExclusion
+    ]
 );
 
 #[derive(Default)]
@@ -55,6 +67,7 @@ impl Global for ActiveSettings {}
 
 pub fn init(cx: &mut AppContext) {
     cx.set_global(ActiveSettings::default());
+    workspace::FollowableViewRegistry::register::<ProjectSearchView>(cx);
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         register_workspace_action(workspace, move |search_bar, _: &Deploy, cx| {
             search_bar.focus_search(cx);
@@ -74,6 +87,12 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, cx);
         });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleStructural, cx| {
+            search_bar.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+        });
+        register_workspace_action(workspace, move |search_bar, _: &TogglePreserveCase, cx| {
+            search_bar.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+        });
         register_workspace_action(workspace, move |search_bar, action: &ToggleReplace, cx| {
             search_bar.toggle_replace(action, cx)
         });
@@ -94,6 +113,12 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action_for_present_search(workspace, |workspace, action, cx| {
             ProjectSearchView::search_in_new(workspace, action, cx)
         });
+        register_workspace_action_for_present_search(workspace, |workspace, _: &SaveSearch, cx| {
+            if let Some(search_view) = workspace.active_item_as::<ProjectSearchView>(cx) {
+                let search = search_view.read(cx).saved_search(String::new(), cx);
+                SavedSearches::toggle(workspace, Some(search), cx);
+            }
+        });
 
         // Both on present and dismissed search, we need to unconditionally handle those actions to focus from the editor.
         workspace.register_action(move |workspace, action: &DeploySearch, cx| {
@@ -129,6 +154,9 @@ pub struct ProjectSearch {
     last_search_query_text: Option<String>,
     search_id: usize,
     no_results: Option<bool>,
+    /// Whether the active query is a structural pattern that's invalid in the languages of all
+    /// searched files.
+    invalid_query: bool,
     limit_reached: bool,
     search_history_cursor: SearchHistoryCursor,
     search_included_history_cursor: SearchHistoryCursor,
@@ -157,7 +185,17 @@ pub struct ProjectSearchView {
     excluded_files_editor: View<Editor>,
     filters_enabled: bool,
     replace_enabled: bool,
+    /// The indices of the matches that replacing all matches leaves untouched.
+    excluded_matches: HashSet<usize>,
+    /// The indices of the matches that were replaced since the last search, whose replacements are
+    /// no longer previewed.
+    replaced_matches: HashSet<usize>,
     included_opened_only: bool,
+    remote_id: Option<ViewId>,
+    /// The id of the last search of the followed leader that was run in this view.
+    leader_search_id: Option<u64>,
+    /// The match the followed leader has selected, selected once the results contain it.
+    leader_match_index: Option<usize>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -185,6 +223,7 @@ impl ProjectSearch {
             last_search_query_text: None,
             search_id: 0,
             no_results: None,
+            invalid_query: false,
             limit_reached: false,
             search_history_cursor: Default::default(),
             search_included_history_cursor: Default::default(),
@@ -204,6 +243,7 @@ impl ProjectSearch {
             last_search_query_text: self.last_search_query_text.clone(),
             search_id: self.search_id,
             no_results: self.no_results,
+            invalid_query: self.invalid_query,
             limit_reached: self.limit_reached,
             search_history_cursor: self.search_history_cursor.clone(),
             search_included_history_cursor: self.search_included_history_cursor.clone(),
@@ -226,7 +266,10 @@ impl ProjectSearch {
     }
 
     fn search(&mut self, query: SearchQuery, cx: &mut ModelContext<Self>) {
-        let search = self.project.update(cx, |project, cx| {
+        let searched_languages = query
+            .is_structural()
+            .then(|| self.project.read(cx).searched_languages(&query, cx));
+        self.project.update(cx, |project, _| {
             project
                 .search_history_mut(SearchInputKind::Query)
                 .add(&mut self.search_history_cursor, query.as_str().to_string());
@@ -242,22 +285,37 @@ impl ProjectSearch {
                     .search_history_mut(SearchInputKind::Exclude)
                     .add(&mut self.search_excluded_history_cursor, excluded);
             }
-            project.search(query.clone(), cx)
         });
         self.last_search_query_text = Some(query.as_str().to_string());
         self.search_id += 1;
-        self.active_query = Some(query);
+        self.active_query = Some(query.clone());
+        self.invalid_query = false;
         self.match_ranges.clear();
         self.pending_search = Some(cx.spawn(|this, mut cx| async move {
-            let mut matches = search.ready_chunks(1024);
             let this = this.upgrade()?;
-            this.update(&mut cx, |this, cx| {
-                this.match_ranges.clear();
-                this.excerpts.update(cx, |this, cx| this.clear(cx));
-                this.no_results = Some(true);
-                this.limit_reached = false;
-            })
-            .ok()?;
+            // Structural patterns are validated against the languages of the searched files,
+            // which have to be loaded first.
+            let invalid_query = match searched_languages {
+                Some(languages) => !query.is_valid_in(&languages.await),
+                None => false,
+            };
+            let search = this
+                .update(&mut cx, |this, cx| {
+                    this.match_ranges.clear();
+                    this.excerpts.update(cx, |this, cx| this.clear(cx));
+                    this.no_results = Some(true);
+                    this.invalid_query = invalid_query;
+                    this.limit_reached = false;
+                    if invalid_query {
+                        this.pending_search.take();
+                        cx.notify();
+                        return None;
+                    }
+                    let project = this.project.clone();
+                    Some(project.update(cx, |project, cx| project.search(query, cx)))
+                })
+                .ok()??;
+            let mut matches = search.ready_chunks(1024);
 
             let mut limit_reached = false;
             while let Some(results) = matches.next().await {
@@ -326,6 +384,7 @@ impl Render for ProjectSearchView {
                 .flex_1()
                 .size_full()
                 .track_focus(&self.focus_handle(cx))
+                .on_action(cx.listener(Self::toggle_replacement_exclusion))
                 .child(self.results_editor.clone())
         } else {
             let model = self.model.read(cx);
@@ -551,6 +610,92 @@ impl Item for ProjectSearchView {
     }
 }
 
+impl FollowableItem for ProjectSearchView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        Some(proto::view::Variant::ProjectSearch(self.follow_state(cx)))
+    }
+
+    fn from_state_proto(
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<anyhow::Result<View<Self>>>> {
+        let Some(proto::view::Variant::ProjectSearch(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::ProjectSearch(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let project = workspace.read(cx).project().clone();
+        let model = cx.new_model(|cx| ProjectSearch::new(project, cx));
+        let view = cx.new_view(|cx| {
+            let mut view = ProjectSearchView::new(workspace.downgrade(), model, cx, None);
+            view.remote_id = Some(remote_id);
+            view.apply_follow_state(state, cx);
+            view
+        });
+        Some(Task::ready(Ok(view)))
+    }
+
+    fn to_follow_event(_: &Self::Event) -> Option<FollowEvent> {
+        None
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &Self::Event,
+        update: &mut Option<proto::update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        match event {
+            ViewEvent::UpdateTab
+            | ViewEvent::EditorEvent(EditorEvent::SelectionsChanged { .. }) => {
+                *update = Some(proto::update_view::Variant::ProjectSearch(
+                    self.follow_state(cx),
+                ));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let proto::update_view::Variant::ProjectSearch(state) = message else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "received a non-project-search update for a project search"
+            )));
+        };
+        self.apply_follow_state(state, cx);
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, _: &mut ViewContext<Self>) {
+        if leader_peer_id.is_none() {
+            self.leader_search_id = None;
+            self.leader_match_index = None;
+        }
+    }
+
+    fn dedup(&self, _: &Self, _: &WindowContext) -> Option<Dedup> {
+        None
+    }
+}
+
 impl ProjectSearchView {
     pub fn get_matches(&self, cx: &AppContext) -> Vec<Range<Anchor>> {
         self.model.read(cx).match_ranges.clone()
@@ -575,6 +720,12 @@ impl ProjectSearchView {
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut ViewContext<Self>) {
         self.search_options.toggle(option);
+        // Regular expressions and syntax patterns are different query languages.
+        if option == SearchOptions::REGEX && self.search_options.contains(option) {
+            self.search_options.remove(SearchOptions::STRUCTURAL);
+        } else if option == SearchOptions::STRUCTURAL && self.search_options.contains(option) {
+            self.search_options.remove(SearchOptions::REGEX);
+        }
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.model.read(cx).project.downgrade(),
@@ -595,30 +746,38 @@ impl ProjectSearchView {
             return;
         };
 
-        let query = self.model.read(cx).active_query.clone();
-        if let Some(query) = query {
-            let query = query.with_replacement(self.replacement(cx));
-
-            // TODO: Do we need the clone here?
-            let mat = self.model.read(cx).match_ranges[active_index].clone();
-            self.results_editor.update(cx, |editor, cx| {
-                editor.replace(&mat, &query, cx);
-            });
+        if let Some(query) = self.replacement_query(cx) {
+            if !self.excluded_matches.contains(&active_index) {
+                // TODO: Do we need the clone here?
+                let mat = self.model.read(cx).match_ranges[active_index].clone();
+                self.results_editor.update(cx, |editor, cx| {
+                    editor.replace(&mat, &query, cx);
+                });
+                self.replaced_matches.insert(active_index);
+                self.update_replacement_previews(cx);
+            }
             self.select_match(Direction::Next, cx)
         }
     }
     pub fn replacement(&self, cx: &AppContext) -> String {
         self.replacement_editor.read(cx).text(cx)
     }
+    fn replacement_query(&self, cx: &AppContext) -> Option<SearchQuery> {
+        let query = self.model.read(cx).active_query.clone()?;
+        Some(
+            query
+                .with_replacement(self.replacement(cx))
+                .with_preserve_case(self.search_options.contains(SearchOptions::PRESERVE_CASE)),
+        )
+    }
     fn replace_all(&mut self, _: &ReplaceAll, cx: &mut ViewContext<Self>) {
         if self.active_match_index.is_none() {
             return;
         }
 
-        let Some(query) = self.model.read(cx).active_query.as_ref() else {
+        let Some(query) = self.replacement_query(cx) else {
             return;
         };
-        let query = query.clone().with_replacement(self.replacement(cx));
 
         let match_ranges = self
             .model
@@ -627,13 +786,64 @@ impl ProjectSearchView {
             return;
         }
 
+        let indices_to_replace = (0..match_ranges.len())
+            .filter(|ix| !self.excluded_matches.contains(ix) && !self.replaced_matches.contains(ix))
+            .collect::<Vec<_>>();
+        let ranges_to_replace = indices_to_replace
+            .iter()
+            .map(|ix| match_ranges[*ix].clone())
+            .collect::<Vec<_>>();
+        self.replaced_matches.extend(indices_to_replace);
         self.results_editor.update(cx, |editor, cx| {
-            editor.replace_all(&mut match_ranges.iter(), &query, cx);
+            editor.replace_all(&mut ranges_to_replace.iter(), &query, cx);
         });
 
         self.model.update(cx, |model, _cx| {
             model.match_ranges = match_ranges;
         });
+        self.update_replacement_previews(cx);
+    }
+
+    /// Excludes the match under the cursor from the replacements, or includes it again.
+    fn toggle_replacement_exclusion(
+        &mut self,
+        _: &ToggleReplacementExclusion,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(active_index) = self.active_match_index else {
+            return;
+        };
+        if !self.excluded_matches.remove(&active_index) {
+            self.excluded_matches.insert(active_index);
+        }
+        self.update_replacement_previews(cx);
+    }
+
+    /// Previews the replacement of every match that is neither excluded nor already replaced in
+    /// the results editor, while the replacement input is shown.
+    fn update_replacement_previews(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(query) = self.replacement_query(cx).filter(|_| self.replace_enabled) else {
+            self.results_editor
+                .update(cx, |editor, cx| editor.clear_replacement_previews(cx));
+            return;
+        };
+
+        let match_ranges = self.model.read(cx).match_ranges.clone();
+        let snapshot = self.results_editor.read(cx).buffer().read(cx).snapshot(cx);
+        let previews = match_ranges
+            .into_iter()
+            .enumerate()
+            .filter(|(ix, _)| {
+                !self.excluded_matches.contains(ix) && !self.replaced_matches.contains(ix)
+            })
+            .filter_map(|(_, range)| {
+                let replacement = replacement_for_match(&snapshot, &range, &query)?;
+                Some((range, replacement))
+            })
+            .collect::<Vec<_>>();
+        self.results_editor.update(cx, |editor, cx| {
+            editor.set_replacement_previews(previews, cx);
+        });
     }
 
     pub fn new(
@@ -700,6 +910,14 @@ impl ProjectSearchView {
             }
             editor
         });
+        subscriptions.push(cx.subscribe(
+            &replacement_editor,
+            |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    this.update_replacement_previews(cx);
+                }
+            },
+        ));
         let results_editor = cx.new_view(|cx| {
             let mut editor = Editor::for_multibuffer(excerpts, Some(project.clone()), true, cx);
             editor.set_searchable(false);
@@ -770,7 +988,12 @@ impl ProjectSearchView {
             excluded_files_editor,
             filters_enabled,
             replace_enabled: false,
+            excluded_matches: HashSet::default(),
+            replaced_matches: HashSet::default(),
             included_opened_only: false,
+            remote_id: None,
+            leader_search_id: None,
+            leader_match_index: None,
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
@@ -816,6 +1039,64 @@ impl ProjectSearchView {
         Self::existing_or_new_search(workspace, existing, action, cx);
     }
 
+    /// Runs a saved search in the project search of the active pane, or in a new one.
+    pub(crate) fn deploy_saved_search(
+        workspace: &mut Workspace,
+        saved_search: &SavedSearch,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        Self::deploy_search(workspace, &DeploySearch::find(), cx);
+        if let Some(search_view) = workspace.active_item_as::<ProjectSearchView>(cx) {
+            search_view.update(cx, |search_view, cx| {
+                search_view.apply_saved_search(saved_search, cx)
+            });
+        }
+    }
+
+    /// Captures the current inputs of the search, to be saved under the given name.
+    pub(crate) fn saved_search(&self, name: String, cx: &AppContext) -> SavedSearch {
+        let (files_to_include, files_to_exclude) = if self.filters_enabled {
+            (
+                self.included_files_editor.read(cx).text(cx),
+                self.excluded_files_editor.read(cx).text(cx),
+            )
+        } else {
+            Default::default()
+        };
+        SavedSearch {
+            name,
+            query: self.query_editor.read(cx).text(cx),
+            files_to_include,
+            files_to_exclude,
+            options: self.search_options,
+            replacement: self
+                .replace_enabled
+                .then(|| self.replacement_editor.read(cx).text(cx)),
+        }
+    }
+
+    fn apply_saved_search(&mut self, saved_search: &SavedSearch, cx: &mut ViewContext<Self>) {
+        self.search_options = saved_search.options;
+        self.set_search_editor(SearchInputKind::Query, &saved_search.query, cx);
+        self.set_search_editor(SearchInputKind::Include, &saved_search.files_to_include, cx);
+        self.set_search_editor(SearchInputKind::Exclude, &saved_search.files_to_exclude, cx);
+        self.filters_enabled =
+            !saved_search.files_to_include.is_empty() || !saved_search.files_to_exclude.is_empty();
+        self.replace_enabled = saved_search.replacement.is_some();
+        if let Some(replacement) = &saved_search.replacement {
+            self.replacement_editor
+                .update(cx, |editor, cx| editor.set_text(replacement.as_str(), cx));
+        }
+
+        // Searches saved without a query only preset the scope of the next search.
+        if saved_search.query.is_empty() {
+            self.focus_query_editor(cx);
+        } else {
+            self.search(cx);
+        }
+        cx.notify();
+    }
+
     fn search_in_new(workspace: &mut Workspace, _: &SearchInNew, cx: &mut ViewContext<Workspace>) {
         if let Some(search_view) = workspace
             .active_item(cx)
@@ -965,7 +1246,33 @@ impl ProjectSearchView {
                 }
             };
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+                open_buffers,
+                &[],
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+
+                    Some(query)
+                }
+                Err(_e) => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
+
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1026,7 +1333,8 @@ impl ProjectSearchView {
         if query.as_ref().is_some_and(|query| query.is_empty()) {
             return None;
         }
-        query
+        let preserve_case = self.search_options.contains(SearchOptions::PRESERVE_CASE);
+        query.map(|query| query.with_preserve_case(preserve_case))
     }
 
     fn open_buffers(&self, cx: &mut ViewContext<Self>) -> Vec<Model<Buffer>> {
@@ -1119,14 +1427,23 @@ impl ProjectSearchView {
     }
 
     fn model_changed(&mut self, cx: &mut ViewContext<Self>) {
+        if self.model.read(cx).invalid_query {
+            self.panels_with_errors.insert(InputPanel::Query);
+        }
         let match_ranges = self.model.read(cx).match_ranges.clone();
         if match_ranges.is_empty() {
             self.active_match_index = None;
+            self.excluded_matches.clear();
+            self.replaced_matches.clear();
         } else {
             self.active_match_index = Some(0);
             self.update_match_index(cx);
             let prev_search_id = mem::replace(&mut self.search_id, self.model.read(cx).search_id);
             let is_new_search = self.search_id != prev_search_id;
+            if is_new_search {
+                self.excluded_matches.clear();
+                self.replaced_matches.clear();
+            }
             self.results_editor.update(cx, |editor, cx| {
                 if is_new_search {
                     let range_to_select = match_ranges
@@ -1147,11 +1464,67 @@ impl ProjectSearchView {
                 self.focus_results_editor(cx);
             }
         }
+        self.update_replacement_previews(cx);
+        self.select_leader_match(cx);
 
         cx.emit(ViewEvent::UpdateTab);
         cx.notify();
     }
 
+    fn follow_state(&self, cx: &AppContext) -> proto::view::ProjectSearch {
+        let search = self.saved_search(String::new(), cx);
+        proto::view::ProjectSearch {
+            query: search.query,
+            files_to_include: search.files_to_include,
+            files_to_exclude: search.files_to_exclude,
+            options: search.options.bits() as u32,
+            search_id: self.model.read(cx).search_id as u64,
+            active_match_index: self.active_match_index.map(|index| index as u64),
+        }
+    }
+
+    /// Runs the leader's search if it wasn't run yet, and selects the leader's active match.
+    fn apply_follow_state(
+        &mut self,
+        state: proto::view::ProjectSearch,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.leader_search_id != Some(state.search_id) {
+            self.leader_search_id = Some(state.search_id);
+            self.search_options = SearchOptions::from_bits_truncate(state.options as u8);
+            self.filters_enabled =
+                !state.files_to_include.is_empty() || !state.files_to_exclude.is_empty();
+            self.set_search_editor(SearchInputKind::Query, &state.query, cx);
+            self.set_search_editor(SearchInputKind::Include, &state.files_to_include, cx);
+            self.set_search_editor(SearchInputKind::Exclude, &state.files_to_exclude, cx);
+            if !state.query.is_empty() {
+                self.search(cx);
+            }
+        }
+        self.leader_match_index = state.active_match_index.map(|index| index as usize);
+        self.select_leader_match(cx);
+        cx.notify();
+    }
+
+    fn select_leader_match(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(index) = self.leader_match_index else {
+            return;
+        };
+        if self.active_match_index == Some(index) {
+            return;
+        }
+        let Some(range_to_select) = self.model.read(cx).match_ranges.get(index).cloned() else {
+            return;
+        };
+        self.results_editor.update(cx, |editor, cx| {
+            let range_to_select = editor.range_for_match(&range_to_select);
+            editor.unfold_ranges(&[range_to_select.clone()], false, true, cx);
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges([range_to_select])
+            });
+        });
+    }
+
     fn update_match_index(&mut self, cx: &mut ViewContext<Self>) {
         let results_editor = self.results_editor.read(cx);
         let new_index = active_match_index(
@@ -1363,7 +1736,10 @@ impl ProjectSearchBar {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
                 search_view.toggle_search_option(option, cx);
-                if search_view.model.read(cx).active_query.is_some() {
+                if option == SearchOptions::PRESERVE_CASE {
+                    // Only the replacements depend on the case being preserved, not the matches.
+                    search_view.update_replacement_previews(cx);
+                } else if search_view.model.read(cx).active_query.is_some() {
                     search_view.search(cx);
                 }
             });
@@ -1379,6 +1755,7 @@ impl ProjectSearchBar {
         if let Some(search) = &self.active_project_search {
             search.update(cx, |this, cx| {
                 this.replace_enabled = !this.replace_enabled;
+                this.update_replacement_previews(cx);
                 let editor_to_focus = if this.replace_enabled {
                     this.replacement_editor.focus_handle(cx)
                 } else {
@@ -1631,6 +2008,13 @@ impl Render for ProjectSearchBar {
                         cx.listener(|this, _, cx| {
                             this.toggle_search_option(SearchOptions::REGEX, cx);
                         }),
+                    ))
+                    .child(SearchOptions::STRUCTURAL.as_button(
+                        self.is_option_enabled(SearchOptions::STRUCTURAL, cx),
+                        focus_handle.clone(),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+                        }),
                     )),
             );
 
@@ -1782,11 +2166,18 @@ impl Render for ProjectSearchBar {
             .child(h_flex().min_w_64().child(mode_column).child(matches_column));
 
         let replace_line = search.replace_enabled.then(|| {
-            let replace_column =
-                input_base_styles().child(self.render_text_input(&search.replacement_editor, cx));
-
             let focus_handle = search.replacement_editor.read(cx).focus_handle(cx);
 
+            let replace_column = input_base_styles()
+                .child(self.render_text_input(&search.replacement_editor, cx))
+                .child(SearchOptions::PRESERVE_CASE.as_button(
+                    self.is_option_enabled(SearchOptions::PRESERVE_CASE, cx),
+                    focus_handle.clone(),
+                    cx.listener(|this, _, cx| {
+                        this.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+                    }),
+                ));
+
             let replace_actions =
                 h_flex()
                     .min_w_64()
@@ -2055,6 +2446,7 @@ pub mod tests {
     use super::*;
     use editor::{display_map::DisplayRow, DisplayPoint};
     use gpui::{Action, TestAppContext, VisualTestContext, WindowHandle};
+    use language::{Language, LanguageConfig, LanguageMatcher};
     use project::FakeFs;
     use serde_json::json;
     use settings::SettingsStore;
@@ -2187,6 +2579,158 @@ pub mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_project_search_replacement_previews(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace = window.root(cx).unwrap();
+        let search = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|cx| {
+            ProjectSearchView::new(workspace.downgrade(), search.clone(), cx, None)
+        });
+
+        perform_search(search_view, "TWO", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.replace_enabled = true;
+                search_view.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+                search_view
+                    .replacement_editor
+                    .update(cx, |editor, cx| editor.set_text("seven", cx));
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(
+                    search_view
+                        .results_editor
+                        .update(cx, |editor, cx| editor.display_text(cx)),
+                    "\n\n\nconst THREE: usize = one::ONE + twoseven::TWOSEVEN;\n\n\n\n\nconst TWOSEVEN: usize = one::ONE + one::ONE;\n",
+                    "Each match should be followed by its replacement, in the case of the match"
+                );
+
+                search_view.select_match(Direction::Next, cx);
+            })
+            .unwrap();
+
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(search_view.active_match_index, Some(1));
+                search_view.toggle_replacement_exclusion(&ToggleReplacementExclusion, cx);
+                assert_eq!(
+                    search_view
+                        .results_editor
+                        .update(cx, |editor, cx| editor.display_text(cx)),
+                    "\n\n\nconst THREE: usize = one::ONE + twoseven::TWO;\n\n\n\n\nconst TWOSEVEN: usize = one::ONE + one::ONE;\n",
+                    "Excluded matches should not be previewed"
+                );
+
+                search_view.replace_all(&ReplaceAll, cx);
+                assert_eq!(
+                    search_view
+                        .results_editor
+                        .update(cx, |editor, cx| editor.display_text(cx)),
+                    "\n\n\nconst THREE: usize = one::ONE + seven::TWO;\n\n\n\n\nconst SEVEN: usize = one::ONE + one::ONE;\n",
+                    "Replacing all matches should leave excluded matches untouched"
+                );
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_structural_replacement_previews(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "main.rs": "fn main() {\n    foo(1, 2);\n    foo(x);\n}\n",
+                "lib.rs": "fn lib() {\n    foo(3, 4);\n}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace = window.root(cx).unwrap();
+        let search = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|cx| {
+            ProjectSearchView::new(workspace.downgrade(), search.clone(), cx, None)
+        });
+
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+            })
+            .unwrap();
+        perform_search(search_view, "foo($A, $B)", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.replace_enabled = true;
+                search_view
+                    .replacement_editor
+                    .update(cx, |editor, cx| editor.set_text("foo($B, $A)", cx));
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(search_view.model.read(cx).match_ranges.len(), 2);
+                let text = search_view
+                    .results_editor
+                    .update(cx, |editor, cx| editor.display_text(cx));
+                assert!(
+                    text.contains("foo(1, 2)foo(2, 1);") && text.contains("foo(3, 4)foo(4, 3);"),
+                    "Each match should be followed by its replacement, with the captures \
+                    expanded: {text:?}"
+                );
+
+                search_view.replace_next(&ReplaceNext, cx);
+                let text = search_view
+                    .results_editor
+                    .update(cx, |editor, cx| editor.display_text(cx));
+                assert_eq!(
+                    text.matches(")foo(").count(),
+                    1,
+                    "Only the remaining match should be previewed after replacing one: {text:?}"
+                );
+
+                search_view.replace_all(&ReplaceAll, cx);
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        for (path, expected_text) in [
+            (
+                "/dir/main.rs",
+                "fn main() {\n    foo(2, 1);\n    foo(x);\n}\n",
+            ),
+            ("/dir/lib.rs", "fn lib() {\n    foo(4, 3);\n}\n"),
+        ] {
+            let buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(path, cx))
+                .await
+                .unwrap();
+            buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), expected_text));
+        }
+    }
+
     #[gpui::test]
     async fn test_deploy_project_search_focus(cx: &mut TestAppContext) {
         init_test(cx);
@@ -3685,6 +4229,20 @@ pub mod tests {
         });
     }
 
+    fn rust_lang() -> Arc<Language> {
+        Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        ))
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
@@ -3701,6 +4259,61 @@ pub mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_following_project_search(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace = window.root(cx).unwrap();
+        let leader_search = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let leader_view = cx.add_window(|cx| {
+            ProjectSearchView::new(workspace.downgrade(), leader_search.clone(), cx, None)
+        });
+        let follower_search = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let follower_view = cx.add_window(|cx| {
+            ProjectSearchView::new(workspace.downgrade(), follower_search.clone(), cx, None)
+        });
+
+        perform_search(leader_view, "TWO", cx);
+        leader_view
+            .update(cx, |view, cx| view.select_match(Direction::Next, cx))
+            .unwrap();
+
+        let mut update = None;
+        leader_view
+            .update(cx, |view, cx| {
+                assert!(view.add_event_to_update_proto(&ViewEvent::UpdateTab, &mut update, cx));
+            })
+            .unwrap();
+        follower_view
+            .update(cx, |view, cx| {
+                view.apply_update_proto(&project, update.unwrap(), cx)
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        follower_view
+            .update(cx, |view, cx| {
+                assert_eq!(view.search_query_text(cx), "TWO");
+                assert_eq!(view.model.read(cx).match_ranges.len(), 3);
+                assert_eq!(view.active_match_index, Some(1));
+            })
+            .unwrap();
+    }
+
     fn perform_search(
         search_view: WindowHandle<ProjectSearchView>,
         text: impl Into<Arc<str>>,
@@ -0,0 +1,443 @@
+//! Named project searches, saved with their query, file filters, options and replacement so that
+//! complex searches can be run again from any workspace. A saved search without a query acts as a
+//! preset for the scope of the next search.
+
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Task,
+    View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::{project_search::ProjectSearchView, SearchOptions};
+
+pub use persistence::SAVED_SEARCHES;
+
+actions!(project_search, [SaveSearch, OpenSavedSearch]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &OpenSavedSearch, cx| {
+            SavedSearches::toggle(workspace, None, cx);
+        });
+    })
+    .detach();
+}
+
+/// The inputs of a project search, saved under a name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    pub files_to_include: String,
+    pub files_to_exclude: String,
+    pub options: SearchOptions,
+    /// The replacement, when the search was saved with its replacement input shown.
+    pub replacement: Option<String>,
+}
+
+impl SavedSearch {
+    fn description(&self) -> String {
+        let mut description = if self.query.is_empty() {
+            "Search scope".to_string()
+        } else {
+            self.query.clone()
+        };
+        if !self.files_to_include.is_empty() {
+            description.push_str(&format!(" in {}", self.files_to_include));
+        }
+        if !self.files_to_exclude.is_empty() {
+            description.push_str(&format!(" excluding {}", self.files_to_exclude));
+        }
+        if let Some(replacement) = &self.replacement {
+            description.push_str(&format!(" → {replacement}"));
+        }
+        description
+    }
+}
+
+/// A picker listing the saved searches, which either runs the selected search, or saves the
+/// current project search under a new or existing name.
+pub struct SavedSearches {
+    picker: View<Picker<SavedSearchesDelegate>>,
+}
+
+impl SavedSearches {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        search_to_save: Option<SavedSearch>,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let saved_searches = SAVED_SEARCHES
+            .saved_searches()
+            .log_err()
+            .unwrap_or_default();
+        let weak_workspace = cx.view().downgrade();
+        workspace.toggle_modal(cx, move |cx| {
+            let delegate = SavedSearchesDelegate {
+                saved_searches: cx.view().downgrade(),
+                workspace: weak_workspace,
+                searches: saved_searches,
+                search_to_save,
+                entries: Vec::new(),
+                selected_index: 0,
+            };
+            Self {
+                picker: cx.new_view(|cx| Picker::uniform_list(delegate, cx)),
+            }
+        });
+    }
+}
+
+impl Render for SavedSearches {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for SavedSearches {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SavedSearches {}
+impl ModalView for SavedSearches {}
+
+enum SavedSearchEntry {
+    /// Saves the current project search under the name typed in the picker.
+    SaveAs(String),
+    Saved(StringMatch),
+}
+
+pub struct SavedSearchesDelegate {
+    saved_searches: WeakView<SavedSearches>,
+    workspace: WeakView<Workspace>,
+    searches: Vec<SavedSearch>,
+    /// The search to save when the picker was opened to save one rather than to run one.
+    search_to_save: Option<SavedSearch>,
+    entries: Vec<SavedSearchEntry>,
+    selected_index: usize,
+}
+
+impl SavedSearchesDelegate {
+    fn delete_saved_search(&self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(SavedSearchEntry::Saved(mat)) = self.entries.get(ix) else {
+            return;
+        };
+        let name = self.searches[mat.candidate_id].name.clone();
+        cx.spawn(move |this, mut cx| async move {
+            SAVED_SEARCHES.delete_saved_search(name).await.log_err();
+            let searches = SAVED_SEARCHES
+                .saved_searches()
+                .log_err()
+                .unwrap_or_default();
+            this.update(&mut cx, move |picker, cx| {
+                picker.delegate.searches = searches;
+                picker.delegate.selected_index = ix.saturating_sub(1);
+                picker.update_matches(picker.query(cx), cx)
+            })
+        })
+        .detach();
+    }
+}
+
+impl PickerDelegate for SavedSearchesDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        if self.search_to_save.is_some() {
+            "Name the search to save…".into()
+        } else {
+            "Run a saved search…".into()
+        }
+    }
+
+    fn no_matches_text(&self, _: &mut WindowContext) -> SharedString {
+        if self.search_to_save.is_some() {
+            "Type a name for the search".into()
+        } else {
+            "No saved searches".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .searches
+            .iter()
+            .enumerate()
+            .map(|(id, search)| StringMatchCandidate::new(id, &search.name))
+            .collect::<Vec<_>>();
+        let background = cx.background_executor().clone();
+        cx.spawn(|picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    let name = query.trim();
+                    let is_new_name = !name.is_empty()
+                        && delegate.searches.iter().all(|search| search.name != name);
+                    delegate.entries = (delegate.search_to_save.is_some() && is_new_name)
+                        .then(|| SavedSearchEntry::SaveAs(name.to_string()))
+                        .into_iter()
+                        .chain(matches.into_iter().map(SavedSearchEntry::Saved))
+                        .collect();
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.entries.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(entry) = self.entries.get(self.selected_index) else {
+            return;
+        };
+        let name = match entry {
+            SavedSearchEntry::SaveAs(name) => name.clone(),
+            SavedSearchEntry::Saved(mat) => self.searches[mat.candidate_id].name.clone(),
+        };
+
+        if let Some(search_to_save) = self.search_to_save.take() {
+            let search = SavedSearch {
+                name,
+                ..search_to_save
+            };
+            db::write_and_log(cx, move || SAVED_SEARCHES.save_search(search));
+        } else if let SavedSearchEntry::Saved(mat) = entry {
+            let search = self.searches[mat.candidate_id].clone();
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    ProjectSearchView::deploy_saved_search(workspace, &search, cx);
+                })
+                .log_err();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.saved_searches
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected);
+        match self.entries.get(ix)? {
+            SavedSearchEntry::SaveAs(name) => Some(
+                item.start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                    .child(Label::new(format!("Save as “{name}”"))),
+            ),
+            SavedSearchEntry::Saved(mat) => {
+                let search = &self.searches[mat.candidate_id];
+                let delete_button = IconButton::new("delete", IconName::Close)
+                    .icon_size(IconSize::Small)
+                    .on_click(cx.listener(move |picker, _, cx| {
+                        cx.stop_propagation();
+                        cx.prevent_default();
+                        picker.delegate.delete_saved_search(ix, cx);
+                    }))
+                    .tooltip(|cx| Tooltip::text("Delete Saved Search", cx));
+                Some(
+                    item.child(
+                        v_flex()
+                            .child(HighlightedLabel::new(
+                                search.name.clone(),
+                                mat.positions.clone(),
+                            ))
+                            .child(
+                                Label::new(search.description())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .single_line(),
+                            ),
+                    )
+                    .map(|item| {
+                        if selected {
+                            item.end_slot(delete_button)
+                        } else {
+                            item.end_hover_slot(delete_button)
+                        }
+                    }),
+                )
+            }
+        }
+    }
+}
+
+mod persistence {
+    use anyhow::Result;
+    use db::{
+        define_connection, query,
+        sqlez::{
+            bindable::{Bind, Column, StaticColumnCount},
+            statement::Statement,
+        },
+        sqlez_macros::sql,
+    };
+
+    use super::SavedSearch;
+    use crate::SearchOptions;
+
+    impl StaticColumnCount for SavedSearch {
+        fn column_count() -> usize {
+            6
+        }
+    }
+
+    impl Bind for SavedSearch {
+        fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+            let next_index = statement.bind(&self.name, start_index)?;
+            let next_index = statement.bind(&self.query, next_index)?;
+            let next_index = statement.bind(&self.files_to_include, next_index)?;
+            let next_index = statement.bind(&self.files_to_exclude, next_index)?;
+            let next_index = statement.bind(&u16::from(self.options.bits()), next_index)?;
+            statement.bind(&self.replacement, next_index)
+        }
+    }
+
+    impl Column for SavedSearch {
+        fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+            let (name, next_index) = String::column(statement, start_index)?;
+            let (query, next_index) = String::column(statement, next_index)?;
+            let (files_to_include, next_index) = String::column(statement, next_index)?;
+            let (files_to_exclude, next_index) = String::column(statement, next_index)?;
+            let (options, next_index) = u16::column(statement, next_index)?;
+            let (replacement, next_index) = Option::<String>::column(statement, next_index)?;
+            Ok((
+                SavedSearch {
+                    name,
+                    query,
+                    files_to_include,
+                    files_to_exclude,
+                    options: SearchOptions::from_bits_truncate(options as u8),
+                    replacement,
+                },
+                next_index,
+            ))
+        }
+    }
+
+    define_connection!(
+        pub static ref SAVED_SEARCHES: SavedSearchesDb<()> =
+            &[sql!(
+                CREATE TABLE IF NOT EXISTS saved_searches(
+                    name TEXT PRIMARY KEY,
+                    query TEXT NOT NULL,
+                    files_to_include TEXT NOT NULL,
+                    files_to_exclude TEXT NOT NULL,
+                    options INTEGER NOT NULL,
+                    replacement TEXT
+                ) STRICT;
+            )];
+    );
+
+    impl SavedSearchesDb {
+        query! {
+            pub fn saved_searches() -> Result<Vec<SavedSearch>> {
+                SELECT name, query, files_to_include, files_to_exclude, options, replacement
+                FROM saved_searches
+                ORDER BY name
+            }
+        }
+
+        query! {
+            pub async fn save_search(search: SavedSearch) -> Result<()> {
+                INSERT OR REPLACE INTO saved_searches
+                    (name, query, files_to_include, files_to_exclude, options, replacement)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            }
+        }
+
+        query! {
+            pub async fn delete_saved_search(name: String) -> Result<()> {
+                DELETE FROM saved_searches WHERE name = ?
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[gpui::test]
+        async fn test_saved_searches() {
+            let db = SavedSearchesDb(db::open_test_db("test_saved_searches").await);
+            assert_eq!(db.saved_searches().unwrap(), Vec::new());
+
+            let todos = SavedSearch {
+                name: "Team TODOs".to_string(),
+                query: "TODO\\(team-x\\)".to_string(),
+                files_to_include: "services/**".to_string(),
+                files_to_exclude: "**/generated/**".to_string(),
+                options: SearchOptions::REGEX | SearchOptions::CASE_SENSITIVE,
+                replacement: None,
+            };
+            let scope = SavedSearch {
+                name: "Frontend".to_string(),
+                query: String::new(),
+                files_to_include: "web/**".to_string(),
+                files_to_exclude: String::new(),
+                options: SearchOptions::NONE,
+                replacement: Some("new".to_string()),
+            };
+            db.save_search(todos.clone()).await.unwrap();
+            db.save_search(scope.clone()).await.unwrap();
+            assert_eq!(db.saved_searches().unwrap(), [scope.clone(), todos.clone()]);
+
+            let todos = SavedSearch {
+                files_to_exclude: String::new(),
+                ..todos
+            };
+            db.save_search(todos.clone()).await.unwrap();
+            db.delete_saved_search(scope.name).await.unwrap();
+            assert_eq!(db.saved_searches().unwrap(), [todos]);
+        }
+    }
+}
@@ -11,12 +11,14 @@ use workspace::{Toast, Workspace};
 
 pub mod buffer_search;
 pub mod project_search;
+pub mod saved_searches;
 pub(crate) mod search_bar;
 
 pub fn init(cx: &mut AppContext) {
     menu::init();
     buffer_search::init(cx);
     project_search::init(cx);
+    saved_searches::init(cx);
 }
 
 actions!(
@@ -27,6 +29,8 @@ actions!(
         ToggleCaseSensitive,
         ToggleIncludeIgnored,
         ToggleRegex,
+        ToggleStructural,
+        TogglePreserveCase,
         ToggleReplace,
         ToggleSelection,
         SelectNextMatch,
@@ -47,6 +51,8 @@ bitflags! {
         const CASE_SENSITIVE = 0b010;
         const INCLUDE_IGNORED = 0b100;
         const REGEX = 0b1000;
+        const STRUCTURAL = 0b10000;
+        const PRESERVE_CASE = 0b100000;
     }
 }
 
@@ -57,6 +63,8 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => "Match Case Sensitively",
             SearchOptions::INCLUDE_IGNORED => "Also search files ignored by configuration",
             SearchOptions::REGEX => "Use Regular Expressions",
+            SearchOptions::STRUCTURAL => "Match Syntax Patterns",
+            SearchOptions::PRESERVE_CASE => "Preserve Case",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -67,6 +75,8 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::Sliders,
             SearchOptions::REGEX => ui::IconName::Regex,
+            SearchOptions::STRUCTURAL => ui::IconName::ListTree,
+            SearchOptions::PRESERVE_CASE => ui::IconName::Font,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -77,6 +87,8 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
             SearchOptions::REGEX => Box::new(ToggleRegex),
+            SearchOptions::STRUCTURAL => Box::new(ToggleStructural),
+            SearchOptions::PRESERVE_CASE => Box::new(TogglePreserveCase),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -91,6 +103,8 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
+        options.set(SearchOptions::PRESERVE_CASE, query.preserve_case());
         options
     }
 
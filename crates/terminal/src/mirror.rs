@@ -0,0 +1,162 @@
+//! Encodes the visible content of a terminal as escape sequences, which redraw it in a terminal
+//! mirroring it.
+
+use std::fmt::Write as _;
+
+use alacritty_terminal::{
+    event::EventListener,
+    term::cell::{Cell, Flags},
+    vte::ansi::{Color, NamedColor, Processor},
+    Term,
+};
+
+/// The cell flags kept in a mirrored terminal, with their SGR parameters.
+const STYLE_FLAGS: [(Flags, u8); 6] = [
+    (Flags::BOLD, 1),
+    (Flags::DIM, 2),
+    (Flags::ITALIC, 3),
+    (Flags::UNDERLINE, 4),
+    (Flags::INVERSE, 7),
+    (Flags::STRIKEOUT, 9),
+];
+
+pub(crate) fn screen_to_escape_sequences<T: EventListener>(term: &Term<T>) -> String {
+    let content = term.renderable_content();
+    let display_offset = content.display_offset as i32;
+    let mut output = String::from("\x1b[0m\x1b[H\x1b[2J");
+    let mut current_line = None;
+    let mut current_style = String::new();
+    let mut pending_spaces = 0;
+    for indexed in content.display_iter {
+        let cell = indexed.cell;
+        if cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
+        }
+        if current_line != Some(indexed.point.line) {
+            current_line = Some(indexed.point.line);
+            pending_spaces = 0;
+            let row = indexed.point.line.0 + display_offset + 1;
+            write!(output, "\x1b[{row};1H").ok();
+        }
+
+        // Blank cells are only written before other cells, so that lines don't end with spaces
+        // which would wrap in a narrower terminal.
+        let style = cell_style(cell);
+        if cell.c == ' ' && style.is_empty() {
+            pending_spaces += 1;
+            continue;
+        }
+        if pending_spaces > 0 {
+            if !current_style.is_empty() {
+                output.push_str("\x1b[0m");
+                current_style.clear();
+            }
+            output.extend(std::iter::repeat(' ').take(pending_spaces));
+            pending_spaces = 0;
+        }
+        if style != current_style {
+            output.push_str("\x1b[0m");
+            output.push_str(&style);
+            current_style = style;
+        }
+        output.push(cell.c);
+        if let Some(zerowidth) = cell.zerowidth() {
+            output.extend(zerowidth);
+        }
+    }
+
+    let cursor = content.cursor.point;
+    write!(
+        output,
+        "\x1b[0m\x1b[{};{}H",
+        cursor.line.0 + display_offset + 1,
+        cursor.column.0 + 1
+    )
+    .ok();
+    output
+}
+
+pub(crate) fn apply_escape_sequences<T: EventListener>(term: &mut Term<T>, content: &str) {
+    let mut processor: Processor = Processor::new();
+    for byte in content.bytes() {
+        processor.advance(term, byte);
+    }
+}
+
+fn cell_style(cell: &Cell) -> String {
+    let mut parameters = STYLE_FLAGS
+        .iter()
+        .filter(|(flag, _)| cell.flags.contains(*flag))
+        .map(|(_, parameter)| parameter.to_string())
+        .collect::<Vec<_>>();
+    parameters.extend(color_parameters(cell.fg, 38));
+    parameters.extend(color_parameters(cell.bg, 48));
+    if parameters.is_empty() {
+        String::new()
+    } else {
+        format!("\x1b[{}m", parameters.join(";"))
+    }
+}
+
+/// Returns the SGR parameters setting the given color, or none for the default color.
+fn color_parameters(color: Color, parameter: u8) -> Option<String> {
+    match color {
+        Color::Named(named) if (named as usize) <= NamedColor::BrightWhite as usize => {
+            Some(format!("{parameter};5;{}", named as usize))
+        }
+        Color::Named(_) => None,
+        Color::Indexed(index) => Some(format!("{parameter};5;{index}")),
+        Color::Spec(rgb) => Some(format!("{parameter};2;{};{};{}", rgb.r, rgb.g, rgb.b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        event::VoidListener,
+        index::{Column, Line, Point},
+        term::Config,
+    };
+
+    use super::*;
+    use crate::TerminalSize;
+
+    fn line_text(term: &Term<VoidListener>, line: i32) -> String {
+        let mut text = String::new();
+        for cell in &term.grid()[Line(line)] {
+            text.push(cell.c);
+        }
+        text.trim_end().to_string()
+    }
+
+    #[test]
+    fn test_mirrored_screen() {
+        let mut source = Term::new(Config::default(), &TerminalSize::default(), VoidListener);
+        apply_escape_sequences(
+            &mut source,
+            "\x1b[1mcargo\x1b[0m test\r\n\x1b[38;5;2mok\x1b[0m    done\r\n$ ",
+        );
+
+        let mut mirror = Term::new(Config::default(), &TerminalSize::default(), VoidListener);
+        apply_escape_sequences(&mut mirror, "stale content");
+        apply_escape_sequences(&mut mirror, &screen_to_escape_sequences(&source));
+
+        assert_eq!(line_text(&mirror, 0), "cargo test");
+        assert_eq!(line_text(&mirror, 1), "ok    done");
+        assert_eq!(line_text(&mirror, 2), "$");
+        assert!(mirror.grid()[Point::new(Line(0), Column(0))]
+            .flags
+            .contains(Flags::BOLD));
+        assert!(!mirror.grid()[Point::new(Line(0), Column(6))]
+            .flags
+            .contains(Flags::BOLD));
+        assert_eq!(
+            mirror.grid()[Point::new(Line(1), Column(0))].fg,
+            Color::Indexed(2)
+        );
+        assert_eq!(mirror.grid().cursor.point, Point::new(Line(2), Column(2)));
+    }
+}
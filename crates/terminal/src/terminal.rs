@@ -2,6 +2,7 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod mirror;
 mod pty_info;
 pub mod terminal_settings;
 
@@ -91,6 +92,7 @@ const SCROLL_MULTIPLIER: f32 = 4.;
 #[cfg(not(target_os = "macos"))]
 const SCROLL_MULTIPLIER: f32 = 1.;
 const MAX_SEARCH_LINES: usize = 100;
+const TYPING_INDICATOR_DURATION: Duration = Duration::from_secs(2);
 const DEBUG_TERMINAL_WIDTH: Pixels = px(500.);
 const DEBUG_TERMINAL_HEIGHT: Pixels = px(30.);
 const DEBUG_CELL_WIDTH: Pixels = px(5.);
@@ -108,6 +110,7 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    TypingCollaboratorChanged,
 }
 
 #[derive(Clone, Debug)]
@@ -433,7 +436,7 @@ impl TerminalBuilder {
             }
         };
 
-        let pty_info = PtyProcessInfo::new(&pty);
+        let pty_info = Some(PtyProcessInfo::new(&pty));
 
         //And connect them together
         let event_loop = EventLoop::new(
@@ -450,7 +453,7 @@ impl TerminalBuilder {
 
         let terminal = Terminal {
             task,
-            pty_tx: Notifier(pty_tx),
+            pty_tx: Some(Notifier(pty_tx)),
             completion_tx,
             term,
             term_config: config,
@@ -473,6 +476,9 @@ impl TerminalBuilder {
             vi_mode_enabled: false,
             is_ssh_terminal,
             python_venv_directory,
+            mirror_input_tx: None,
+            typing_collaborator: None,
+            typing_collaborator_task: None,
         };
 
         Ok(TerminalBuilder {
@@ -481,6 +487,58 @@ impl TerminalBuilder {
         })
     }
 
+    /// Creates a terminal without a process, that mirrors a terminal of a collaborator. Its content
+    /// is set with [`Terminal::set_mirrored_content`], and its input is ignored unless it is
+    /// forwarded with [`Terminal::set_mirror_input`].
+    pub fn new_mirror(cursor_shape: CursorShape, completion_tx: Sender<()>) -> TerminalBuilder {
+        let config = Config {
+            scrolling_history: 0,
+            default_cursor_style: AlacCursorStyle::from(cursor_shape),
+            ..Config::default()
+        };
+        let (events_tx, events_rx) = unbounded();
+        let term = Term::new(
+            config.clone(),
+            &TerminalSize::default(),
+            EditsyncListener(events_tx),
+        );
+
+        let terminal = Terminal {
+            task: None,
+            pty_tx: None,
+            completion_tx,
+            term: Arc::new(FairMutex::new(term)),
+            term_config: config,
+            title_override: None,
+            events: VecDeque::with_capacity(10),
+            last_content: Default::default(),
+            last_mouse: None,
+            matches: Vec::new(),
+            selection_head: None,
+            pty_info: None,
+            breadcrumb_text: String::new(),
+            scroll_px: px(0.),
+            last_mouse_position: None,
+            next_link_id: 0,
+            selection_phase: SelectionPhase::Ended,
+            secondary_pressed: false,
+            hovered_word: false,
+            url_regex: RegexSearch::new(URL_REGEX).unwrap(),
+            word_regex: RegexSearch::new(WORD_REGEX).unwrap(),
+            vi_mode_enabled: false,
+            is_ssh_terminal: false,
+            python_venv_directory: None,
+            mirror_input_tx: None,
+            typing_collaborator: None,
+            typing_collaborator_task: None,
+        };
+
+        TerminalBuilder {
+            terminal,
+            events_rx,
+        }
+    }
+
     pub fn subscribe(mut self, cx: &ModelContext<Terminal>) -> Terminal {
         //Event loop
         cx.spawn(|terminal, mut cx| async move {
@@ -606,7 +664,8 @@ pub enum SelectionPhase {
 }
 
 pub struct Terminal {
-    pty_tx: Notifier,
+    /// The channel to the process of the terminal, which mirrored terminals don't have.
+    pty_tx: Option<Notifier>,
     completion_tx: Sender<()>,
     term: Arc<FairMutex<Term<EditsyncListener>>>,
     term_config: Config,
@@ -619,7 +678,7 @@ pub struct Terminal {
     pub last_content: TerminalContent,
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
-    pub pty_info: PtyProcessInfo,
+    pub pty_info: Option<PtyProcessInfo>,
     title_override: Option<SharedString>,
     pub python_venv_directory: Option<PathBuf>,
     scroll_px: Pixels,
@@ -632,6 +691,11 @@ pub struct Terminal {
     task: Option<TaskState>,
     vi_mode_enabled: bool,
     is_ssh_terminal: bool,
+    /// The channel through which a mirrored terminal forwards its input to the terminal it
+    /// mirrors, when the collaborator owning it allows writing to it.
+    mirror_input_tx: Option<UnboundedSender<Vec<u8>>>,
+    typing_collaborator: Option<SharedString>,
+    typing_collaborator_task: Option<Task<()>>,
 }
 
 pub struct TaskState {
@@ -714,7 +778,11 @@ impl Terminal {
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
 
-                if self.pty_info.has_changed() {
+                if self
+                    .pty_info
+                    .as_mut()
+                    .map_or(false, |pty_info| pty_info.has_changed())
+                {
                     cx.emit(Event::TitleChanged);
                 }
             }
@@ -756,7 +824,9 @@ impl Terminal {
 
                 self.last_content.size = new_size;
 
-                self.pty_tx.0.send(Msg::Resize(new_size.into())).ok();
+                if let Some(pty_tx) = &self.pty_tx {
+                    pty_tx.0.send(Msg::Resize(new_size.into())).ok();
+                }
 
                 term.resize(new_size);
             }
@@ -1145,11 +1215,15 @@ impl Terminal {
 
     ///Write the Input payload to the tty.
     fn write_to_pty(&self, input: String) {
-        self.pty_tx.notify(input.into_bytes());
+        self.write_bytes_to_pty(input.into_bytes());
     }
 
     fn write_bytes_to_pty(&self, input: Vec<u8>) {
-        self.pty_tx.notify(input);
+        if let Some(pty_tx) = &self.pty_tx {
+            pty_tx.notify(input);
+        } else if let Some(mirror_input_tx) = &self.mirror_input_tx {
+            mirror_input_tx.unbounded_send(input).ok();
+        }
     }
 
     pub fn input(&mut self, input: String) {
@@ -1168,6 +1242,41 @@ impl Terminal {
         self.write_bytes_to_pty(input);
     }
 
+    /// Writes the input of a collaborator to the terminal, and shows them as typing in it.
+    pub fn collaborator_input(
+        &mut self,
+        input: Vec<u8>,
+        login: SharedString,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.input_bytes(input);
+        self.show_typing_collaborator(login, cx);
+    }
+
+    /// Shows the given collaborator as typing in this terminal, until they stop typing for a
+    /// moment.
+    pub fn show_typing_collaborator(&mut self, login: SharedString, cx: &mut ModelContext<Self>) {
+        if self.typing_collaborator.as_ref() != Some(&login) {
+            self.typing_collaborator = Some(login);
+            cx.emit(Event::TypingCollaboratorChanged);
+        }
+        self.typing_collaborator_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(TYPING_INDICATOR_DURATION)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.typing_collaborator = None;
+                cx.emit(Event::TypingCollaboratorChanged);
+            })
+            .ok();
+        }));
+    }
+
+    /// Returns the login of the collaborator who is typing in this terminal.
+    pub fn typing_collaborator(&self) -> Option<&SharedString> {
+        self.typing_collaborator.as_ref()
+    }
+
     pub fn toggle_vi_mode(&mut self) {
         self.events.push_back(InternalEvent::ToggleViMode);
     }
@@ -1367,6 +1476,45 @@ impl Terminal {
         lines
     }
 
+    /// Returns whether this terminal mirrors a terminal of a collaborator.
+    pub fn is_mirror(&self) -> bool {
+        self.pty_tx.is_none()
+    }
+
+    /// Sets the channel through which this mirrored terminal forwards its input, or makes it
+    /// read-only when there is none.
+    pub fn set_mirror_input(&mut self, input_tx: Option<UnboundedSender<Vec<u8>>>) {
+        if self.is_mirror() {
+            self.mirror_input_tx = input_tx;
+        }
+    }
+
+    /// Returns the escape sequences that redraw the visible content of this terminal, to set it as
+    /// the content of a mirror of it.
+    pub fn mirrored_content(&self) -> String {
+        mirror::screen_to_escape_sequences(&self.term.lock())
+    }
+
+    /// Replaces the content of this mirrored terminal with the given escape sequences, and its
+    /// title with the given one.
+    pub fn set_mirrored_content(
+        &mut self,
+        content: &str,
+        title: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if !self.is_mirror() {
+            return;
+        }
+        mirror::apply_escape_sequences(&mut self.term.lock(), content);
+        let title = title.map(SharedString::from);
+        if self.title_override != title {
+            self.title_override = title;
+            cx.emit(Event::TitleChanged);
+        }
+        cx.emit(Event::Wakeup);
+    }
+
     pub fn focus_in(&self) {
         if self.last_content.mode.contains(TermMode::FOCUS_IN_OUT) {
             self.write_to_pty("\x1b[I".to_string());
@@ -1413,7 +1561,7 @@ impl Terminal {
 
             if self.mouse_changed(point, side) {
                 if let Some(bytes) = mouse_moved_report(point, e, self.last_content.mode) {
-                    self.pty_tx.notify(bytes);
+                    self.write_bytes_to_pty(bytes);
                 }
             }
         } else if self.secondary_pressed {
@@ -1492,7 +1640,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, true, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             match e.button {
@@ -1546,7 +1694,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, false, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             if e.button == MouseButton::Left && setting.copy_on_select {
@@ -1584,7 +1732,7 @@ impl Terminal {
                 if let Some(scrolls) = scroll_report(point, scroll_lines, e, self.last_content.mode)
                 {
                     for scroll in scrolls {
-                        self.pty_tx.notify(scroll);
+                        self.write_bytes_to_pty(scroll);
                     }
                 };
             } else if self
@@ -1593,7 +1741,7 @@ impl Terminal {
                 .contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
                 && !e.shift
             {
-                self.pty_tx.notify(alt_scroll(scroll_lines))
+                self.write_bytes_to_pty(alt_scroll(scroll_lines))
             } else if scroll_lines != 0 {
                 let scroll = AlacScroll::Delta(scroll_lines);
 
@@ -1665,6 +1813,7 @@ impl Terminal {
     /// remote host, in case Editsync is connected to a remote host.
     fn client_side_working_directory(&self) -> Option<PathBuf> {
         self.pty_info
+            .as_ref()?
             .current
             .as_ref()
             .map(|process| process.cwd.clone())
@@ -1686,8 +1835,8 @@ impl Terminal {
                 .map(|title_override| title_override.to_string())
                 .unwrap_or_else(|| {
                     self.pty_info
-                        .current
                         .as_ref()
+                        .and_then(|pty_info| pty_info.current.as_ref())
                         .map(|fpi| {
                             let process_file = fpi
                                 .cwd
@@ -1856,7 +2005,9 @@ unsafe fn append_text_to_term(term: &mut Term<EditsyncListener>, text_lines: &[&
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        if let Some(pty_tx) = &self.pty_tx {
+            pty_tx.0.send(Msg::Shutdown).ok();
+        }
     }
 }
 
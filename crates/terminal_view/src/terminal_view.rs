@@ -6,14 +6,16 @@ use collections::HashSet;
 use editor::{actions::SelectAll, scroll::Autoscroll, Editor};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
-    anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent, EventEmitter,
-    FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton,
-    MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task, View,
-    VisualContext, WeakModel, WeakView,
+    actions, anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent,
+    EventEmitter, FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model,
+    MouseButton, MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task,
+    View, VisualContext, WeakModel, WeakView,
 };
+use itertools::Itertools;
 use language::Bias;
 use persistence::TERMINAL_DB;
 use project::{search::SearchQuery, terminals::TerminalKind, Fs, Metadata, Project};
+use rpc::proto::{self, PeerId};
 use terminal::{
     alacritty_terminal::{
         index::Point,
@@ -22,21 +24,24 @@ use terminal::{
     terminal_settings::{CursorShape, TerminalBlink, TerminalSettings, WorkingDirectory},
     Clear, Copy, Event, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp, ScrollPageDown,
     ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskStatus, Terminal,
-    TerminalSize, ToggleViMode,
+    TerminalBuilder, TerminalSize, ToggleViMode,
 };
 use terminal_element::{is_blank, TerminalElement};
 use terminal_panel::TerminalPanel;
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label, Tooltip};
 use util::{paths::PathWithPosition, ResultExt};
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, SerializableItem, TabContentParams},
+    item::{
+        BreadcrumbText, Dedup, FollowEvent, FollowableItem, Item, ItemEvent, SerializableItem,
+        TabContentParams,
+    },
     register_serializable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenVisible, ToolbarItemLocation, Workspace,
-    WorkspaceId,
+    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenVisible, ToolbarItemLocation, ViewId,
+    Workspace, WorkspaceId,
 };
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use editsync_actions::InlineAssist;
 use serde::Deserialize;
 use settings::{Settings, SettingsStore};
@@ -57,6 +62,9 @@ const REGEX_SPECIAL_CHARS: &[char] = &[
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 
+/// How often the content of a terminal that keeps changing is sent to its followers, at most.
+const FOLLOWER_UPDATE_INTERVAL: Duration = Duration::from_millis(50);
+
 const GIT_DIFF_PATH_PREFIXES: &[char] = &['a', 'b'];
 
 ///Event to transmit the scroll from the element to the view
@@ -71,14 +79,21 @@ pub struct SendKeystroke(String);
 
 impl_actions!(terminal, [SendText, SendKeystroke]);
 
+actions!(
+    terminal,
+    [ShareTerminal, UnshareTerminal, OpenSharedTerminals]
+);
+
 pub fn init(cx: &mut AppContext) {
     terminal_panel::init(cx);
     terminal::init(cx);
 
     register_serializable_item::<TerminalView>(cx);
+    workspace::FollowableViewRegistry::register::<TerminalView>(cx);
 
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         workspace.register_action(TerminalView::deploy);
+        workspace.register_action(TerminalView::open_shared_terminals);
     })
     .detach();
 }
@@ -112,6 +127,8 @@ pub struct TerminalView {
     show_breadcrumbs: bool,
     block_below_cursor: Option<Rc<BlockProperties>>,
     scroll_top: Pixels,
+    remote_id: Option<ViewId>,
+    pending_follower_update: Option<Task<()>>,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -138,6 +155,68 @@ impl TerminalView {
             .detach_and_log_err(cx);
     }
 
+    /// Opens the terminals shared by the host of the project that aren't open yet.
+    fn open_shared_terminals(
+        workspace: &mut Workspace,
+        _: &OpenSharedTerminals,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let open_terminals = workspace
+            .items_of_type::<TerminalView>(cx)
+            .map(|view| view.read(cx).terminal.entity_id())
+            .collect::<HashSet<_>>();
+        let terminals = project
+            .read(cx)
+            .remote_shared_terminals()
+            .filter(|(_, terminal)| !open_terminals.contains(&terminal.entity_id()))
+            .map(|(_, terminal)| terminal.clone())
+            .collect::<Vec<_>>();
+        for terminal in terminals {
+            let view = cx.new_view(|cx| {
+                TerminalView::new(
+                    terminal,
+                    workspace.weak_handle(),
+                    None,
+                    project.downgrade(),
+                    cx,
+                )
+            });
+            workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+        }
+    }
+
+    fn share_terminal(&mut self, _: &ShareTerminal, cx: &mut ViewContext<Self>) {
+        let terminal = self.terminal.clone();
+        self.project
+            .update(cx, |project, cx| {
+                project.share_terminal(&terminal, cx).log_err()
+            })
+            .ok();
+        cx.emit(ItemEvent::UpdateTab);
+    }
+
+    fn unshare_terminal(&mut self, _: &UnshareTerminal, cx: &mut ViewContext<Self>) {
+        let terminal = self.terminal.clone();
+        self.project
+            .update(cx, |project, cx| project.unshare_terminal(&terminal, cx))
+            .ok();
+        cx.emit(ItemEvent::UpdateTab);
+    }
+
+    fn mirror_state(&self, cx: &AppContext) -> proto::view::Terminal {
+        let terminal = self.terminal.read(cx);
+        let shared_terminal_id = self
+            .project
+            .upgrade()
+            .and_then(|project| project.read(cx).shared_terminal_id(&self.terminal));
+        proto::view::Terminal {
+            content: terminal.mirrored_content(),
+            title: Some(terminal.title(false)),
+            shared_terminal_id,
+        }
+    }
+
     pub fn new(
         terminal: Model<Terminal>,
         workspace: WeakView<Workspace>,
@@ -176,6 +255,8 @@ impl TerminalView {
             show_breadcrumbs: TerminalSettings::get_global(cx).toolbar.breadcrumbs,
             block_below_cursor: None,
             scroll_top: Pixels::ZERO,
+            remote_id: None,
+            pending_follower_update: None,
             _subscriptions: vec![
                 focus_in,
                 focus_out,
@@ -210,6 +291,9 @@ impl TerminalView {
             .map_or(false, |terminal_panel| {
                 terminal_panel.read(cx).assistant_enabled()
             });
+        let sharing = self.sharing_menu_state(cx);
+        let project = self.project.clone();
+        let terminal = self.terminal.clone();
         let context_menu = ContextMenu::build(cx, |menu, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
@@ -222,6 +306,33 @@ impl TerminalView {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
                 })
+                .when_some(sharing, |mut menu, (is_shared, collaborators)| {
+                    menu = menu.separator();
+                    if !is_shared {
+                        return menu.action("Share Terminal", Box::new(ShareTerminal));
+                    }
+                    menu = menu.action("Stop Sharing Terminal", Box::new(UnshareTerminal));
+                    for (user_id, login, can_write) in collaborators {
+                        let project = project.clone();
+                        let terminal = terminal.clone();
+                        menu = menu.toggleable_entry(
+                            format!("Allow {login} to Write"),
+                            can_write,
+                            IconPosition::Start,
+                            None,
+                            move |cx| {
+                                project
+                                    .update(cx, |project, cx| {
+                                        project.set_terminal_write_access(
+                                            &terminal, user_id, !can_write, cx,
+                                        )
+                                    })
+                                    .ok();
+                            },
+                        );
+                    }
+                    menu
+                })
                 .separator()
                 .action("Close", Box::new(CloseActiveItem { save_intent: None }))
         });
@@ -281,6 +392,39 @@ impl TerminalView {
         cx.notify();
     }
 
+    /// Returns whether the terminal is shared with the guests of the project, and which of them
+    /// may write to it, when the host can share it.
+    fn sharing_menu_state(
+        &self,
+        cx: &AppContext,
+    ) -> Option<(bool, Vec<(u64, SharedString, bool)>)> {
+        let project = self.project.upgrade()?;
+        let project = project.read(cx);
+        if project.remote_id().is_none() || project.is_via_collab() {
+            return None;
+        }
+        let Some(writers) = project.terminal_writers(&self.terminal) else {
+            return Some((false, Vec::new()));
+        };
+        let user_store = project.user_store().read(cx);
+        let collaborators = project
+            .collaborators()
+            .values()
+            .filter_map(|collaborator| user_store.get_cached_user(collaborator.user_id))
+            .map(|user| {
+                let can_write = writers.contains(&user.id);
+                (
+                    user.id,
+                    SharedString::from(user.github_login.clone()),
+                    can_write,
+                )
+            })
+            .sorted_by(|a, b| a.1.cmp(&b.1))
+            .dedup_by(|a, b| a.0 == b.0)
+            .collect();
+        Some((true, collaborators))
+    }
+
     fn clear(&mut self, _: &Clear, cx: &mut ViewContext<Self>) {
         self.scroll_top = px(0.);
         self.terminal.update(cx, |term, _| term.clear());
@@ -606,6 +750,24 @@ impl TerminalView {
         dispatch_context
     }
 
+    /// Updates the tab, and with it the followers, [`FOLLOWER_UPDATE_INTERVAL`] after the content
+    /// changes, coalescing the changes made meanwhile, as each update carries the whole screen.
+    fn schedule_follower_update(&mut self, cx: &mut ViewContext<Self>) {
+        if self.pending_follower_update.is_some() {
+            return;
+        }
+        self.pending_follower_update = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(FOLLOWER_UPDATE_INTERVAL)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_follower_update = None;
+                cx.emit(ItemEvent::UpdateTab);
+            })
+            .ok();
+        }));
+    }
+
     fn set_terminal(&mut self, terminal: Model<Terminal>, cx: &mut ViewContext<TerminalView>) {
         self._terminal_subscriptions =
             subscribe_for_terminal_events(&terminal, self.workspace.clone(), cx);
@@ -624,7 +786,7 @@ fn subscribe_for_terminal_events(
             Event::Wakeup => {
                 cx.notify();
                 cx.emit(Event::Wakeup);
-                cx.emit(ItemEvent::UpdateTab);
+                this.schedule_follower_update(cx);
                 cx.emit(SearchEvent::MatchesInvalidated);
             }
 
@@ -763,6 +925,7 @@ fn subscribe_for_terminal_events(
                 }
             },
             Event::BreadcrumbsChanged => cx.emit(ItemEvent::UpdateBreadcrumbs),
+            Event::TypingCollaboratorChanged => cx.emit(ItemEvent::UpdateTab),
             Event::CloseTerminal => cx.emit(ItemEvent::CloseItem),
             Event::SelectionsChanged => {
                 cx.invalidate_character_coordinates();
@@ -960,6 +1123,8 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
+            .on_action(cx.listener(TerminalView::share_terminal))
+            .on_action(cx.listener(TerminalView::unshare_terminal))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -1005,6 +1170,12 @@ impl Item for TerminalView {
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
         let terminal = self.terminal().read(cx);
         let title = terminal.title(true);
+        let is_shared = self.project.upgrade().map_or(false, |project| {
+            project
+                .read(cx)
+                .shared_terminal_id(&self.terminal)
+                .is_some()
+        });
         let rerun_button = |task_id: task::TaskId| {
             IconButton::new("rerun-icon", IconName::Rerun)
                 .icon_size(IconSize::Small)
@@ -1069,6 +1240,20 @@ impl Item for TerminalView {
                     }),
             )
             .child(Label::new(title).color(params.text_color()))
+            .when(is_shared, |tab| {
+                tab.child(
+                    Icon::new(IconName::UserGroup)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
+            .when_some(terminal.typing_collaborator(), |tab, login| {
+                tab.child(
+                    Label::new(format!("{login} is typing"))
+                        .size(LabelSize::Small)
+                        .color(Color::Accent),
+                )
+            })
             .into_any()
     }
 
@@ -1081,6 +1266,9 @@ impl Item for TerminalView {
         workspace_id: Option<WorkspaceId>,
         cx: &mut ViewContext<Self>,
     ) -> Option<View<Self>> {
+        if self.terminal.read(cx).is_mirror() {
+            return None;
+        }
         let window = cx.window_handle();
         let terminal = self
             .project
@@ -1162,6 +1350,105 @@ impl Item for TerminalView {
     }
 }
 
+impl FollowableItem for TerminalView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        Some(proto::view::Variant::Terminal(self.mirror_state(cx)))
+    }
+
+    fn from_state_proto(
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<anyhow::Result<View<Self>>>> {
+        let Some(proto::view::Variant::Terminal(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::Terminal(state)) = state.take() else {
+            unreachable!()
+        };
+
+        // Followers see a mirror of the leader's terminal, which has no process. When the leader's
+        // terminal is shared, it is the mirror of the shared terminal, so that it can be written to.
+        let project = workspace.read(cx).project().clone();
+        let shared_terminal = state.shared_terminal_id.and_then(|terminal_id| {
+            project
+                .read(cx)
+                .remote_shared_terminal(terminal_id)
+                .cloned()
+        });
+        let terminal = shared_terminal.unwrap_or_else(|| {
+            let cursor_shape = TerminalSettings::get_global(cx)
+                .cursor_shape
+                .unwrap_or_default();
+            let (completion_tx, _) = smol::channel::bounded(1);
+            cx.new_model(|cx| {
+                TerminalBuilder::new_mirror(cursor_shape, completion_tx).subscribe(cx)
+            })
+        });
+        terminal.update(cx, |terminal, cx| {
+            terminal.set_mirrored_content(&state.content, state.title, cx)
+        });
+        let project = project.downgrade();
+        let view = cx.new_view(|cx| {
+            let mut view = TerminalView::new(terminal, workspace.downgrade(), None, project, cx);
+            view.remote_id = Some(remote_id);
+            view
+        });
+        Some(Task::ready(Ok(view)))
+    }
+
+    fn to_follow_event(_: &Self::Event) -> Option<FollowEvent> {
+        None
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &Self::Event,
+        update: &mut Option<proto::update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        match event {
+            ItemEvent::UpdateTab => {
+                *update = Some(proto::update_view::Variant::Terminal(self.mirror_state(cx)));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let proto::update_view::Variant::Terminal(state) = message else {
+            return Task::ready(Err(anyhow!(
+                "received a non-terminal update for a terminal"
+            )));
+        };
+        self.terminal.update(cx, |terminal, cx| {
+            terminal.set_mirrored_content(&state.content, state.title, cx)
+        });
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, _: Option<PeerId>, _: &mut ViewContext<Self>) {}
+
+    fn dedup(&self, _: &Self, _: &WindowContext) -> Option<Dedup> {
+        None
+    }
+}
+
 impl SerializableItem for TerminalView {
     fn serialieditsync_item_kind() -> &'static str {
         "Terminal"
@@ -1319,6 +1606,8 @@ impl SearchableItem for TerminalView {
                 .unwrap()),
             ),
             SearchQuery::Regex { .. } => regex_search_for_query(&query),
+            // Terminal output has no syntax tree to match against.
+            SearchQuery::Structural { .. } => None,
         };
 
         if let Some(s) = searcher {
@@ -1404,7 +1693,7 @@ mod tests {
     use super::*;
     use gpui::TestAppContext;
     use project::{Entry, Project, ProjectPath, Worktree};
-    use std::path::Path;
+    use std::{cell::Cell, path::Path};
     use workspace::AppState;
 
     // Working directory calculation tests
@@ -1513,6 +1802,53 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_follower_updates_are_coalesced(cx: &mut TestAppContext) {
+        let (project, workspace) = init_test(cx).await;
+        let terminal = cx.new_model(|cx| {
+            let (completion_tx, _) = smol::channel::bounded(1);
+            TerminalBuilder::new_mirror(CursorShape::default(), completion_tx).subscribe(cx)
+        });
+        let view = cx
+            .add_window(|cx| {
+                TerminalView::new(
+                    terminal.clone(),
+                    workspace.downgrade(),
+                    None,
+                    project.downgrade(),
+                    cx,
+                )
+            })
+            .root_view(cx)
+            .unwrap();
+        let tab_updates = Rc::new(Cell::new(0));
+        cx.update(|cx| {
+            let tab_updates = tab_updates.clone();
+            cx.subscribe(&view, move |_, event: &ItemEvent, _| {
+                if let ItemEvent::UpdateTab = event {
+                    tab_updates.set(tab_updates.get() + 1);
+                }
+            })
+            .detach();
+        });
+
+        for ix in 0..3 {
+            terminal.update(cx, |terminal, cx| {
+                terminal.set_mirrored_content(&format!("line {ix}\r\n"), None, cx)
+            });
+        }
+        cx.run_until_parked();
+        assert_eq!(tab_updates.get(), 0);
+
+        cx.executor().advance_clock(FOLLOWER_UPDATE_INTERVAL);
+        cx.run_until_parked();
+        assert_eq!(
+            tab_updates.get(),
+            1,
+            "The changes made within the interval should be sent in a single update"
+        );
+    }
+
     /// Creates a worktree with 1 file: /root.txt
     pub async fn init_test(cx: &mut TestAppContext) -> (Model<Project>, View<Workspace>) {
         let params = cx.update(AppState::test);
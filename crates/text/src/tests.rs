@@ -32,6 +32,41 @@ fn test_edit() {
     assert_eq!(buffer.text(), "ghiamnoef");
 }
 
+#[test]
+fn test_summarize_edits() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abcdef".into());
+    let op1 = buffer.edit([(1..3, "X")]).timestamp();
+    let op2 = buffer.edit([(5..5, "gh")]).timestamp();
+    let op3 = buffer.edit([(3..4, "")]).timestamp();
+    assert_eq!(buffer.text(), "aXdfgh");
+
+    assert_eq!(
+        buffer.summarize_edits(&HashSet::from_iter([op1])),
+        EditsSummary {
+            ranges: vec![1..2],
+            deleted_text: vec!["bc".into()],
+        }
+    );
+    assert_eq!(
+        buffer.summarize_edits(&HashSet::from_iter([op2, op3])),
+        EditsSummary {
+            ranges: vec![3..3, 4..6],
+            deleted_text: vec!["e".into()],
+        }
+    );
+
+    // Text that was inserted and then deleted by another operation leaves an empty range.
+    buffer.edit([(1..2, "")]);
+    assert_eq!(buffer.text(), "adfgh");
+    assert_eq!(
+        buffer.summarize_edits(&HashSet::from_iter([op1])),
+        EditsSummary {
+            ranges: vec![1..1],
+            deleted_text: vec!["bc".into()],
+        }
+    );
+}
+
 #[gpui::test(iterations = 100)]
 fn test_random_edits(mut rng: StdRng) {
     let operations = env::var("OPERATIONS")
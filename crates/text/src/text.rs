@@ -528,6 +528,16 @@ pub struct EditOperation {
     pub new_text: Vec<Arc<str>>,
 }
 
+/// The edits made by a set of operations, as returned by [`BufferSnapshot::summarize_edits`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditsSummary {
+    /// The current ranges of the text inserted by the operations, in buffer order. Ranges are
+    /// empty where the operations only deleted text, or where their text was deleted since.
+    pub ranges: Vec<Range<usize>>,
+    /// The contiguous runs of text deleted by the operations, in buffer order.
+    pub deleted_text: Vec<String>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UndoOperation {
     pub timestamp: clock::Lamport,
@@ -2439,6 +2449,53 @@ impl BufferSnapshot {
         false
     }
 
+    /// Describes the edits made by the given operations in terms of the current text. Undo
+    /// operations and operations that haven't been applied to this buffer are ignored.
+    pub fn summarize_edits(&self, operations: &HashSet<clock::Lamport>) -> EditsSummary {
+        let mut summary = EditsSummary::default();
+        let mut visible_offset = 0;
+        let mut deleted_offset = 0;
+        let mut continues_deletion = false;
+        for fragment in self.fragments.iter() {
+            let inserted = operations.contains(&fragment.timestamp);
+            let deleted = !fragment.visible
+                && fragment
+                    .deletions
+                    .iter()
+                    .any(|timestamp| operations.contains(timestamp));
+
+            if inserted || deleted {
+                let end = if fragment.visible {
+                    visible_offset + fragment.len
+                } else {
+                    visible_offset
+                };
+                match summary.ranges.last_mut() {
+                    Some(range) if range.end == visible_offset => range.end = end,
+                    _ => summary.ranges.push(visible_offset..end),
+                }
+            }
+
+            if deleted {
+                let text = self
+                    .deleted_text
+                    .chunks_in_range(deleted_offset..deleted_offset + fragment.len);
+                match summary.deleted_text.last_mut() {
+                    Some(run) if continues_deletion => run.extend(text),
+                    _ => summary.deleted_text.push(text.collect()),
+                }
+            }
+            continues_deletion = deleted || (continues_deletion && !fragment.visible);
+
+            if fragment.visible {
+                visible_offset += fragment.len;
+            } else {
+                deleted_offset += fragment.len;
+            }
+        }
+        summary
+    }
+
     pub fn range_to_version(&self, range: Range<usize>, version: &clock::Global) -> Range<usize> {
         let mut offsets = self.offsets_to_version([range.start, range.end], version);
         offsets.next().unwrap()..offsets.next().unwrap()
@@ -0,0 +1,294 @@
+//! Windows showing panes detached from a workspace, sharing its project.
+//!
+//! A detached window holds a workspace without a database id, which refers to the window of the
+//! workspace it was detached from. That workspace saves the panes of its detached windows along
+//! with its own state, by the paths of their items, so that restoring it reopens them.
+
+use anyhow::{anyhow, Result};
+use collections::HashSet;
+use gpui::{
+    AnyWindowHandle, AsyncAppContext, Task, View, ViewContext, VisualContext as _, WindowBounds,
+    WindowContext, WindowHandle,
+};
+use util::ResultExt;
+use uuid::Uuid;
+
+use crate::{
+    layouts::LayoutPaneGroup,
+    persistence::{model::SerialieditsyncDetachedWindow, SerialieditsyncWindowBounds, DB},
+    AttachWindow, CloseWindow, DetachActiveItem, DetachActivePane, Member, Pane, SaveIntent,
+    Workspace, WorkspaceId,
+};
+
+impl Workspace {
+    /// Returns the window of the workspace this one was detached from, if any.
+    pub fn main_window(&self) -> Option<WindowHandle<Workspace>> {
+        self.main_window
+    }
+
+    /// Returns the windows showing panes detached from this workspace.
+    pub fn detached_windows(&self) -> &[WindowHandle<Workspace>] {
+        &self.detached_windows
+    }
+
+    pub(crate) fn detach_active_item(&mut self, _: &DetachActiveItem, cx: &mut ViewContext<Self>) {
+        let pane = self.active_pane.clone();
+        let Some(item) = pane.read(cx).active_item() else {
+            return;
+        };
+        let Some(layout_item) = self.layout_item(item.as_ref(), cx) else {
+            self.show_error(
+                &anyhow!("Only items with a path can be moved to another window"),
+                cx,
+            );
+            return;
+        };
+        let center_group = LayoutPaneGroup::Pane {
+            items: vec![layout_item],
+            active_item: Some(0),
+            active: true,
+        };
+        self.detach(
+            pane,
+            center_group,
+            [item.item_id()].into_iter().collect(),
+            cx,
+        );
+    }
+
+    pub(crate) fn detach_active_pane(&mut self, _: &DetachActivePane, cx: &mut ViewContext<Self>) {
+        let pane = self.active_pane.clone();
+        let item_ids = pane
+            .read(cx)
+            .items()
+            .filter(|item| self.layout_item(item.as_ref(), cx).is_some())
+            .map(|item| item.item_id())
+            .collect::<HashSet<_>>();
+        if item_ids.is_empty() {
+            return;
+        }
+        let mut center_group = self.layout_pane_group(&Member::Pane(pane.clone()), cx);
+        if let LayoutPaneGroup::Pane { active, .. } = &mut center_group {
+            *active = true;
+        }
+        self.detach(pane, center_group, item_ids, cx);
+    }
+
+    /// Opens the given panes in a new detached window, then closes the items they were opened from.
+    ///
+    /// Items are reopened rather than moved, as views can't change windows. The reopened items
+    /// share their buffers with the closed ones, so unsaved changes are kept. Items that couldn't
+    /// be reopened are left open.
+    fn detach(
+        &mut self,
+        pane: View<Pane>,
+        center_group: LayoutPaneGroup,
+        item_ids: HashSet<gpui::EntityId>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(main_window) = self
+            .main_window
+            .or_else(|| cx.window_handle().downcast::<Workspace>())
+        else {
+            return;
+        };
+        cx.spawn(|_, mut cx| async move {
+            let window = open_detached_window(main_window, center_group, None, &mut cx).await?;
+            let reopened_paths = window.update(&mut cx, |detached, cx| {
+                detached
+                    .items(cx)
+                    .filter_map(|item| item.project_path(cx))
+                    .collect::<HashSet<_>>()
+            })?;
+            pane.update(&mut cx, |pane, cx| {
+                let reopened_item_ids = pane
+                    .items()
+                    .filter(|item| {
+                        item_ids.contains(&item.item_id())
+                            && item
+                                .project_path(cx)
+                                .map_or(false, |path| reopened_paths.contains(&path))
+                    })
+                    .map(|item| item.item_id())
+                    .collect::<HashSet<_>>();
+                pane.close_items(cx, SaveIntent::Skip, move |item_id| {
+                    reopened_item_ids.contains(&item_id)
+                })
+            })?
+            .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Moves the items of this detached window back into the active pane of the window it was
+    /// detached from, and closes this window.
+    pub(crate) fn attach_window(&mut self, _: &AttachWindow, cx: &mut ViewContext<Self>) {
+        let Some(main_window) = self.main_window else {
+            return;
+        };
+        if self.items(cx).any(|item| item.project_path(cx).is_none()) {
+            self.show_error(
+                &anyhow!("Close the items without a path before moving them to another window"),
+                cx,
+            );
+            return;
+        }
+
+        let items = self.layout_pane_group(&self.center.root, cx).into_items();
+        let window = cx.window_handle();
+        cx.spawn(|_, mut cx| async move {
+            let open_tasks = main_window.update(&mut cx, |main, cx| {
+                items
+                    .iter()
+                    .filter_map(|item| {
+                        let project_path = item.project_path(main, cx)?;
+                        Some(main.open_path(project_path, None, false, cx))
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+            for open_task in open_tasks {
+                open_task.await.log_err();
+            }
+
+            window.update(&mut cx, |_, cx| cx.remove_window())?;
+            main_window.update(&mut cx, |main, cx| {
+                main.forget_detached_window(window, cx);
+                cx.activate_window();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Called when this workspace's window is about to be removed after being closed. A detached
+    /// window stops being part of its main window, while a main window closes its detached
+    /// windows, which stay saved so that they're reopened along with it.
+    pub(crate) fn close_detached_windows(&mut self, cx: &mut ViewContext<Self>) {
+        let window = cx.window_handle();
+        if let Some(main_window) = self.main_window.take() {
+            main_window
+                .update(cx, |main, cx| main.forget_detached_window(window, cx))
+                .ok();
+        } else {
+            for detached_window in self.detached_windows.drain(..) {
+                detached_window
+                    .update(cx, |detached, cx| {
+                        detached.main_window = None;
+                        detached.close_window(&CloseWindow, cx);
+                    })
+                    .ok();
+            }
+        }
+    }
+
+    fn forget_detached_window(&mut self, window: AnyWindowHandle, cx: &mut ViewContext<Self>) {
+        self.detached_windows
+            .retain(|detached| detached.window_id() != window.window_id());
+        // The detached windows are only saved along with the workspace while there are some, so
+        // that saving the workspace before they're restored doesn't forget them.
+        if self.detached_windows.is_empty() {
+            if let Some(database_id) = self.database_id() {
+                cx.background_executor()
+                    .spawn(DB.save_detached_windows(database_id, Vec::new()))
+                    .detach_and_log_err(cx);
+            }
+        } else {
+            self.serialize_workspace(cx);
+        }
+    }
+
+    /// Returns the state of the detached windows of this workspace that are still open.
+    pub(crate) fn serialize_detached_windows(
+        &self,
+        cx: &mut WindowContext,
+    ) -> Vec<SerialieditsyncDetachedWindow> {
+        self.detached_windows
+            .iter()
+            .filter_map(|window| {
+                window
+                    .update(cx, |detached, cx| {
+                        let center_group = detached.layout_pane_group(&detached.center.root, cx);
+                        anyhow::Ok(SerialieditsyncDetachedWindow {
+                            window_bounds: Some(SerialieditsyncWindowBounds(cx.window_bounds())),
+                            display: cx.display().and_then(|display| display.uuid().ok()),
+                            center_group: serde_json::to_string(&center_group)?,
+                        })
+                    })
+                    .ok()?
+                    .log_err()
+            })
+            .collect()
+    }
+
+    /// Reopens the detached windows saved for this workspace.
+    pub(crate) fn restore_detached_windows(
+        &mut self,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        if self.main_window.is_some() || !self.detached_windows.is_empty() {
+            return Task::ready(Ok(()));
+        }
+        let Some(main_window) = cx.window_handle().downcast::<Workspace>() else {
+            return Task::ready(Ok(()));
+        };
+        cx.spawn(|_, mut cx| async move {
+            for detached_window in DB.detached_windows(workspace_id).await? {
+                let Some(center_group) =
+                    serde_json::from_str::<LayoutPaneGroup>(&detached_window.center_group)
+                        .log_err()
+                else {
+                    continue;
+                };
+                let window_bounds = detached_window.window_bounds.map(|bounds| bounds.0);
+                open_detached_window(
+                    main_window,
+                    center_group,
+                    window_bounds.map(|bounds| (bounds, detached_window.display)),
+                    &mut cx,
+                )
+                .await
+                .log_err();
+            }
+            main_window.update(&mut cx, |_, cx| cx.activate_window())
+        })
+    }
+}
+
+/// Opens a window sharing the project of the given main window, showing the given panes.
+async fn open_detached_window(
+    main_window: WindowHandle<Workspace>,
+    center_group: LayoutPaneGroup,
+    window_bounds: Option<(WindowBounds, Option<Uuid>)>,
+    cx: &mut AsyncAppContext,
+) -> Result<WindowHandle<Workspace>> {
+    let (project, app_state) =
+        main_window.update(cx, |main, _| (main.project.clone(), main.app_state.clone()))?;
+    let display = window_bounds.and_then(|(_, display)| display);
+    let mut options = cx.update(|cx| (app_state.build_window_options)(display, cx))?;
+    if let Some((window_bounds, _)) = window_bounds {
+        options.window_bounds = Some(window_bounds);
+    }
+    let window = cx.open_window(options, |cx| {
+        cx.new_view(|cx| {
+            let mut workspace = Workspace::new(None, project, app_state, cx);
+            workspace.main_window = Some(main_window);
+            workspace
+        })
+    })?;
+    main_window.update(cx, |main, cx| {
+        main.detached_windows.push(window);
+        main.serialize_workspace(cx);
+    })?;
+
+    window
+        .update(cx, |_, cx| {
+            cx.spawn(|workspace, mut cx| async move {
+                let (center, active_pane) = center_group.build(workspace.clone(), &mut cx).await?;
+                workspace.update(&mut cx, |workspace, cx| {
+                    workspace.replace_center(center, active_pane, cx)
+                })
+            })
+        })?
+        .await?;
+    Ok(window)
+}
@@ -255,6 +255,11 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn has_conflict(&self, _: &AppContext) -> bool {
         false
     }
+    /// Whether the item has edits that weren't saved. Unlike [`Item::is_dirty`], this doesn't
+    /// account for the file of the item having been deleted.
+    fn has_unsaved_edits(&self, cx: &AppContext) -> bool {
+        self.is_dirty(cx)
+    }
     fn can_save(&self, _cx: &AppContext) -> bool {
         false
     }
@@ -431,6 +436,7 @@ pub trait ItemHandle: 'static + Send {
     fn is_dirty(&self, cx: &AppContext) -> bool;
     fn has_deleted_file(&self, cx: &AppContext) -> bool;
     fn has_conflict(&self, cx: &AppContext) -> bool;
+    fn has_unsaved_edits(&self, cx: &AppContext) -> bool;
     fn can_save(&self, cx: &AppContext) -> bool;
     fn save(
         &self,
@@ -801,6 +807,10 @@ impl<T: Item> ItemHandle for View<T> {
         self.read(cx).has_conflict(cx)
     }
 
+    fn has_unsaved_edits(&self, cx: &AppContext) -> bool {
+        self.read(cx).has_unsaved_edits(cx)
+    }
+
     fn can_save(&self, cx: &AppContext) -> bool {
         self.read(cx).can_save(cx)
     }
@@ -0,0 +1,332 @@
+//! Named arrangements of the center panes, docks and open items of a workspace, which can be saved
+//! and switched between.
+//!
+//! Unlike the state restored when reopening a workspace, layouts refer to the items they contain
+//! by their paths rather than by their ids, so only items with a project path are part of a layout.
+//! Other items, such as terminals and untitled buffers, are left out: the state saved by
+//! [`SerializableItem`](crate::SerializableItem)s is keyed by their ids and deleted once they're
+//! closed, so it couldn't be restored when switching back to a layout.
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use async_recursion::async_recursion;
+use gpui::{AppContext, AsyncWindowContext, Axis, Pixels, Task, View, ViewContext, WeakView};
+use project::ProjectPath;
+use serde::{Deserialize, Serialize};
+use util::ResultExt;
+
+use crate::{
+    dock::Dock,
+    item::ItemHandle,
+    persistence::{model::DockData, DB},
+    Member, Pane, PaneAxis, PaneGroup, Workspace,
+};
+
+/// The arrangement of a workspace saved under a name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct WorkspaceLayout {
+    center: LayoutPaneGroup,
+    left_dock: LayoutDock,
+    right_dock: LayoutDock,
+    bottom_dock: LayoutDock,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum LayoutPaneGroup {
+    Group {
+        axis: Axis,
+        flexes: Vec<f32>,
+        children: Vec<LayoutPaneGroup>,
+    },
+    Pane {
+        items: Vec<LayoutItem>,
+        active_item: Option<usize>,
+        active: bool,
+    },
+}
+
+/// An item of a layout, identified by the absolute path of its worktree and its path within it,
+/// as worktree ids don't outlive the project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LayoutItem {
+    worktree_path: PathBuf,
+    path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LayoutDock {
+    visible: bool,
+    active_panel: Option<String>,
+    zoom: bool,
+    size: Option<Pixels>,
+}
+
+impl Workspace {
+    /// Returns the names of the layouts saved for this workspace, in alphabetical order.
+    pub fn layout_names(&self, cx: &AppContext) -> Task<Result<Vec<String>>> {
+        let Some(workspace_id) = self.database_id() else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        cx.background_executor()
+            .spawn(DB.layout_names(workspace_id))
+    }
+
+    /// Saves the current arrangement of the workspace under the given name, replacing any layout
+    /// saved under the same name. Items without a project path aren't saved.
+    pub fn save_layout(&mut self, name: String, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(workspace_id) = self.database_id() else {
+            return Task::ready(Err(anyhow!("layouts can't be saved for this workspace")));
+        };
+        let layout = WorkspaceLayout {
+            center: self.layout_pane_group(&self.center.root, cx),
+            left_dock: self.layout_dock(&self.left_dock, cx),
+            right_dock: self.layout_dock(&self.right_dock, cx),
+            bottom_dock: self.layout_dock(&self.bottom_dock, cx),
+        };
+        cx.background_executor().spawn(async move {
+            let layout = serde_json::to_string(&layout)?;
+            DB.save_layout(workspace_id, name, layout).await
+        })
+    }
+
+    /// Deletes the layout saved under the given name.
+    pub fn delete_layout(&mut self, name: String, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(workspace_id) = self.database_id() else {
+            return Task::ready(Ok(()));
+        };
+        cx.background_executor()
+            .spawn(DB.delete_layout(workspace_id, name))
+    }
+
+    /// Replaces the center panes of the workspace with the ones of the layout saved under the
+    /// given name, opening its items, and shows, sizes and zooms the docks as they were saved.
+    /// Items without a project path are closed along with the panes they're in.
+    pub fn switch_layout(&mut self, name: String, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(workspace_id) = self.database_id() else {
+            return Task::ready(Err(anyhow!("no layouts are saved for this workspace")));
+        };
+        if self.items(cx).any(|item| item.is_dirty(cx)) {
+            return Task::ready(Err(anyhow!(
+                "Save or close the unsaved items before switching layouts"
+            )));
+        }
+
+        cx.spawn(|workspace, mut cx| async move {
+            let layout = DB
+                .layout(workspace_id, name.clone())
+                .await?
+                .with_context(|| format!("no layout named {name:?}"))?;
+            let layout: WorkspaceLayout = serde_json::from_str(&layout)?;
+
+            let (center, active_pane) = layout.center.build(workspace.clone(), &mut cx).await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                workspace.replace_center(center, active_pane, cx);
+
+                for (dock, layout_dock) in [
+                    (&workspace.left_dock, layout.left_dock),
+                    (&workspace.right_dock, layout.right_dock),
+                    (&workspace.bottom_dock, layout.bottom_dock),
+                ] {
+                    dock.update(cx, |dock, cx| {
+                        dock.serialieditsync_dock = Some(DockData {
+                            visible: layout_dock.visible,
+                            active_panel: layout_dock.active_panel,
+                            zoom: layout_dock.zoom,
+                        });
+                        dock.restore_state(cx);
+                        if layout_dock.size.is_some() {
+                            dock.resize_active_panel(layout_dock.size, cx);
+                        }
+                    });
+                }
+
+                workspace.serialize_workspace(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Replaces the center panes of the workspace with the given ones, built by
+    /// [`LayoutPaneGroup::build`].
+    pub(crate) fn replace_center(
+        &mut self,
+        center: Member,
+        active_pane: Option<View<Pane>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.remove_panes(self.center.root.clone(), cx);
+        self.center = PaneGroup::with_root(center);
+        let active_pane = active_pane.unwrap_or_else(|| self.center.first_pane());
+        self.set_active_pane(&active_pane, cx);
+        active_pane.update(cx, |pane, cx| pane.focus(cx));
+        cx.notify();
+    }
+
+    pub(crate) fn layout_pane_group(&self, member: &Member, cx: &AppContext) -> LayoutPaneGroup {
+        match member {
+            Member::Axis(PaneAxis {
+                axis,
+                members,
+                flexes,
+                ..
+            }) => LayoutPaneGroup::Group {
+                axis: *axis,
+                flexes: flexes.lock().clone(),
+                children: members
+                    .iter()
+                    .map(|member| self.layout_pane_group(member, cx))
+                    .collect(),
+            },
+            Member::Pane(pane) => {
+                let pane_ref = pane.read(cx);
+                let active_item_id = pane_ref.active_item().map(|item| item.item_id());
+                let mut items = Vec::new();
+                let mut active_item = None;
+                for item in pane_ref.items() {
+                    let Some(layout_item) = self.layout_item(item.as_ref(), cx) else {
+                        continue;
+                    };
+                    if Some(item.item_id()) == active_item_id {
+                        active_item = Some(items.len());
+                    }
+                    items.push(layout_item);
+                }
+                LayoutPaneGroup::Pane {
+                    items,
+                    active_item,
+                    active: *pane == self.active_pane,
+                }
+            }
+        }
+    }
+
+    /// Returns how a layout refers to the given item, or `None` for items without a project path,
+    /// which layouts leave out.
+    pub(crate) fn layout_item(&self, item: &dyn ItemHandle, cx: &AppContext) -> Option<LayoutItem> {
+        let project_path = item.project_path(cx)?;
+        let worktree = self
+            .project
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)?;
+        Some(LayoutItem {
+            worktree_path: worktree.read(cx).abs_path().to_path_buf(),
+            path: project_path.path.to_path_buf(),
+        })
+    }
+
+    fn layout_dock(&self, dock: &View<Dock>, cx: &ViewContext<Self>) -> LayoutDock {
+        let dock = dock.read(cx);
+        let active_panel = dock.active_panel();
+        LayoutDock {
+            visible: dock.is_open(),
+            active_panel: active_panel.map(|panel| panel.persistent_name().to_string()),
+            zoom: active_panel.map_or(false, |panel| panel.is_zoomed(cx)),
+            size: dock.active_panel_size(cx),
+        }
+    }
+}
+
+impl LayoutItem {
+    /// Returns the path of this item in the project of the given workspace, if its worktree is
+    /// still part of it.
+    pub(crate) fn project_path(
+        &self,
+        workspace: &Workspace,
+        cx: &AppContext,
+    ) -> Option<ProjectPath> {
+        let worktree_id = workspace
+            .project
+            .read(cx)
+            .worktrees(cx)
+            .find(|worktree| worktree.read(cx).abs_path().as_ref() == self.worktree_path)?
+            .read(cx)
+            .id();
+        Some(ProjectPath {
+            worktree_id,
+            path: self.path.as_path().into(),
+        })
+    }
+}
+
+impl LayoutPaneGroup {
+    /// Returns the items of all the panes of this group.
+    pub(crate) fn into_items(self) -> Vec<LayoutItem> {
+        match self {
+            LayoutPaneGroup::Group { children, .. } => children
+                .into_iter()
+                .flat_map(LayoutPaneGroup::into_items)
+                .collect(),
+            LayoutPaneGroup::Pane { items, .. } => items,
+        }
+    }
+
+    /// Creates the panes of this group and opens their items, returning the group and its active
+    /// pane. Empty panes are kept, so that the splits of the layout are preserved.
+    #[async_recursion(?Send)]
+    pub(crate) async fn build(
+        self,
+        workspace: WeakView<Workspace>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<(Member, Option<View<Pane>>)> {
+        match self {
+            LayoutPaneGroup::Group {
+                axis,
+                flexes,
+                children,
+            } => {
+                let mut members = Vec::new();
+                let mut active_pane = None;
+                for child in children {
+                    let (member, child_active_pane) = child.build(workspace.clone(), cx).await?;
+                    members.push(member);
+                    active_pane = active_pane.or(child_active_pane);
+                }
+                match members.len() {
+                    0 => bail!("layout contains an empty group"),
+                    1 => Ok((members.remove(0), active_pane)),
+                    _ => {
+                        let flexes = (flexes.len() == members.len()).then_some(flexes);
+                        Ok((
+                            Member::Axis(PaneAxis::load(axis, members, flexes)),
+                            active_pane,
+                        ))
+                    }
+                }
+            }
+            LayoutPaneGroup::Pane {
+                items,
+                active_item,
+                active,
+            } => {
+                let pane = workspace.update(cx, |workspace, cx| workspace.add_pane(cx))?;
+                let mut opened_items = Vec::new();
+                for item in items {
+                    let open_task = workspace.update(cx, |workspace, cx| {
+                        let project_path = item.project_path(workspace, cx)?;
+                        Some(workspace.open_path(project_path, Some(pane.downgrade()), false, cx))
+                    })?;
+                    // Items of worktrees that are no longer in the project, or that can't be
+                    // opened anymore, are left out.
+                    let opened_item = match open_task {
+                        Some(open_task) => open_task.await.log_err(),
+                        None => None,
+                    };
+                    opened_items.push(opened_item);
+                }
+
+                if let Some(active_item) = active_item
+                    .and_then(|ix| opened_items.get(ix))
+                    .and_then(|item| item.as_ref())
+                {
+                    pane.update(cx, |pane, cx| {
+                        if let Some(ix) = pane.index_for_item(active_item.as_ref()) {
+                            pane.activate_item(ix, false, false, cx);
+                        }
+                    })?;
+                }
+                Ok((Member::Pane(pane.clone()), active.then_some(pane)))
+            }
+        }
+    }
+}
@@ -24,14 +24,16 @@ use gpui::{
 use itertools::Itertools;
 use language::DiagnosticSeverity;
 use parking_lot::Mutex;
-use project::{Project, ProjectEntryId, ProjectPath, WorktreeId};
+use project::{PathChange, Project, ProjectEntryId, ProjectPath, UpdatedEntriesSet, WorktreeId};
 use serde::Deserialize;
 use settings::{Settings, SettingsStore};
 use std::{
     any::Any,
-    cmp, fmt, mem,
+    cmp,
+    ffi::OsStr,
+    fmt, mem,
     ops::ControlFlow,
-    path::PathBuf,
+    path::{Path, PathBuf},
     rc::Rc,
     sync::{
         atomic::{AtomicUsize, Ordering},
@@ -172,6 +174,7 @@ actions!(
         ActivateNextItem,
         ActivateLastItem,
         AlternateFile,
+        CloseDeletedItems,
         GoBack,
         GoForward,
         JoinIntoNext,
@@ -185,6 +188,8 @@ actions!(
         SplitVertical,
         SwapItemLeft,
         SwapItemRight,
+        SortItemsByDirectory,
+        SortItemsByLanguage,
         TogglePreviewTab,
         TogglePinTab,
     ]
@@ -617,6 +622,9 @@ impl Pane {
                     cx.notify();
                 }
             }
+            project::Event::WorktreeUpdatedEntries(worktree_id, changes) => {
+                this.close_removed_items(*worktree_id, changes, cx);
+            }
             _ => {}
         }
     }
@@ -1163,6 +1171,63 @@ impl Pane {
         self.activate_item(index + 1, true, true, cx);
     }
 
+    /// Sorts the unpinned tabs by the directory of their files, then by their file names.
+    pub fn sort_items_by_directory(&mut self, cx: &mut ViewContext<Self>) {
+        self.sort_unpinned_items(
+            |item, cx| {
+                let project_path = item.project_path(cx)?;
+                Some((
+                    project_path.worktree_id,
+                    project_path.path.parent().map(Path::to_path_buf),
+                    project_path.path.file_name().map(OsStr::to_os_string),
+                ))
+            },
+            cx,
+        );
+    }
+
+    /// Sorts the unpinned tabs by the language of their files, then by their paths.
+    pub fn sort_items_by_language(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let languages = project.read(cx).languages().clone();
+        self.sort_unpinned_items(
+            |item, cx| {
+                let project_path = item.project_path(cx)?;
+                let language = languages
+                    .available_language_for_path(&project_path.path)
+                    .map(|language| language.name());
+                Some((language.is_none(), language, project_path))
+            },
+            cx,
+        );
+    }
+
+    /// Stably sorts the unpinned tabs by the given key, keeping the tabs without one, such as
+    /// untitled buffers, after the others.
+    fn sort_unpinned_items<K: Ord>(
+        &mut self,
+        key: impl Fn(&dyn ItemHandle, &AppContext) -> Option<K>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let active_item_id = self.active_item().map(|item| item.item_id());
+        let mut keyed_items = self
+            .items
+            .drain(self.pinned_tab_count..)
+            .map(|item| (key(item.as_ref(), cx), item))
+            .collect::<Vec<_>>();
+        keyed_items.sort_by(|(a, _), (b, _)| (a.is_none(), a).cmp(&(b.is_none(), b)));
+        self.items
+            .extend(keyed_items.into_iter().map(|(_, item)| item));
+
+        if let Some(index) = active_item_id.and_then(|item_id| self.index_for_item_id(item_id)) {
+            self.active_item_index = index;
+        }
+        cx.emit(Event::ActivateItem { local: true });
+        cx.notify();
+    }
+
     pub fn close_active_item(
         &mut self,
         action: &CloseActiveItem,
@@ -1230,6 +1295,65 @@ impl Pane {
         }))
     }
 
+    /// Closes the tabs of the files removed from the given worktree, except for the ones with
+    /// unsaved edits, which are left for [`Pane::close_deleted_items`].
+    fn close_removed_items(
+        &mut self,
+        worktree_id: WorktreeId,
+        changes: &UpdatedEntriesSet,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let removed_paths = changes
+            .iter()
+            .filter(|(_, _, change)| *change == PathChange::Removed)
+            .map(|(path, _, _)| path)
+            .collect::<HashSet<_>>();
+        if removed_paths.is_empty() {
+            return;
+        }
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let item_ids: Vec<_> = self
+            .items()
+            .filter(|item| {
+                item.is_singleton(cx)
+                    && !item.has_unsaved_edits(cx)
+                    && item.project_path(cx).map_or(false, |project_path| {
+                        project_path.worktree_id == worktree_id
+                            && removed_paths.contains(&project_path.path)
+                            && project.read(cx).entry_for_path(&project_path, cx).is_none()
+                    })
+            })
+            .map(|item| item.item_id())
+            .collect();
+        for item_id in item_ids {
+            self.remove_item(item_id, false, true, cx);
+            self.nav_history.remove_item(item_id);
+        }
+    }
+
+    /// Closes the tabs of files that are no longer in the project, such as files that were
+    /// deleted while they had unsaved changes, or whose worktree was removed.
+    pub fn close_deleted_items(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(project) = self.project.upgrade() else {
+            return Task::ready(Ok(()));
+        };
+        let item_ids: Vec<_> = self
+            .items()
+            .filter(|item| {
+                item.is_singleton(cx)
+                    && item.project_path(cx).map_or(false, |project_path| {
+                        project.read(cx).entry_for_path(&project_path, cx).is_none()
+                    })
+            })
+            .map(|item| item.item_id())
+            .collect();
+        self.close_items(cx, SaveIntent::Close, move |item_id| {
+            item_ids.contains(&item_id)
+        })
+    }
+
     pub fn close_items_to_the_left(
         &mut self,
         action: &CloseItemsToTheLeft,
@@ -1317,8 +1441,8 @@ impl Pane {
             return;
         };
 
-        // Reduce over the activation history to get every dirty items up to max_tabs
-        // count.
+        // Reduce over the activation history to close the least recently used items,
+        // keeping dirty and pinned items, until there are fewer than max_tabs.
         let mut index_list = Vec::new();
         let mut items_len = self.items_len();
         let mut indexes: HashMap<EntityId, usize> = HashMap::default();
@@ -1332,6 +1456,9 @@ impl Pane {
             let Some(&index) = indexes.get(&entry.entity_id) else {
                 continue;
             };
+            if self.is_tab_pinned(index) {
+                continue;
+            }
             if let Some(true) = self.items.get(index).map(|item| item.is_dirty(cx)) {
                 continue;
             }
@@ -2299,6 +2426,13 @@ impl Pane {
                                 }
                             }),
                         )
+                        .entry(
+                            "Close Deleted",
+                            Some(Box::new(CloseDeletedItems)),
+                            cx.handler_for(&pane, |pane, cx| {
+                                pane.close_deleted_items(cx).detach_and_log_err(cx)
+                            }),
+                        )
                         .entry(
                             "Close All",
                             Some(Box::new(CloseAllItems {
@@ -2316,6 +2450,17 @@ impl Pane {
                                     task.detach_and_log_err(cx)
                                 }
                             }),
+                        )
+                        .separator()
+                        .entry(
+                            "Sort by Directory",
+                            Some(Box::new(SortItemsByDirectory)),
+                            cx.handler_for(&pane, |pane, cx| pane.sort_items_by_directory(cx)),
+                        )
+                        .entry(
+                            "Sort by Language",
+                            Some(Box::new(SortItemsByLanguage)),
+                            cx.handler_for(&pane, |pane, cx| pane.sort_items_by_language(cx)),
                         );
 
                     let pin_tab_entries = |menu: ContextMenu| {
@@ -2906,6 +3051,12 @@ impl Render for Pane {
             }))
             .on_action(cx.listener(|pane, _: &SwapItemLeft, cx| pane.swap_item_left(cx)))
             .on_action(cx.listener(|pane, _: &SwapItemRight, cx| pane.swap_item_right(cx)))
+            .on_action(
+                cx.listener(|pane, _: &SortItemsByDirectory, cx| pane.sort_items_by_directory(cx)),
+            )
+            .on_action(
+                cx.listener(|pane, _: &SortItemsByLanguage, cx| pane.sort_items_by_language(cx)),
+            )
             .on_action(cx.listener(|pane, action, cx| {
                 pane.toggle_pin_tab(action, cx);
             }))
@@ -2941,6 +3092,9 @@ impl Render for Pane {
                     }
                 }),
             )
+            .on_action(cx.listener(|pane: &mut Self, _: &CloseDeletedItems, cx| {
+                pane.close_deleted_items(cx).detach_and_log_err(cx)
+            }))
             .on_action(
                 cx.listener(|pane: &mut Self, action: &CloseItemsToTheLeft, cx| {
                     if let Some(task) = pane.close_items_to_the_left(action, cx) {
@@ -3405,6 +3559,177 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_add_item_capped_to_max_tabs_keeps_pinned_items(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        let item_a = add_labeled_item(&pane, "A", false, cx);
+        add_labeled_item(&pane, "B", false, cx);
+        add_labeled_item(&pane, "C", false, cx);
+        pane.update(cx, |pane, cx| {
+            let ix = pane.index_for_item_id(item_a.item_id()).unwrap();
+            pane.pin_tab_at(ix, cx);
+        });
+        set_max_tabs(cx, Some(3));
+        add_labeled_item(&pane, "D", false, cx);
+        add_labeled_item(&pane, "E", false, cx);
+        // The least recently used unpinned items are closed.
+        assert_item_labels(&pane, ["A", "D", "E*"], cx);
+    }
+
+    #[gpui::test]
+    async fn test_sort_items_by_directory(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        for (id, label, path) in [
+            (1, "pinned", Some("z/pinned.rs")),
+            (2, "untitled", None),
+            (3, "b-two", Some("b/two.rs")),
+            (4, "a-two", Some("a/two.rs")),
+            (5, "a-nested", Some("a/nested/one.rs")),
+            (6, "a-one", Some("a/one.rs")),
+        ] {
+            pane.update(cx, |pane, cx| {
+                let project_item = match path {
+                    Some(path) => TestProjectItem::new(id, path, cx),
+                    None => TestProjectItem::new_untitled(cx),
+                };
+                let item = cx.new_view(|cx| {
+                    TestItem::new(cx)
+                        .with_label(label)
+                        .with_project_items(&[project_item])
+                });
+                pane.add_item(Box::new(item), false, false, None, cx);
+            });
+        }
+        pane.update(cx, |pane, cx| {
+            pane.pin_tab_at(0, cx);
+            pane.activate_item(3, false, false, cx);
+        });
+        assert_item_labels(
+            &pane,
+            ["pinned", "untitled", "b-two", "a-two*", "a-nested", "a-one"],
+            cx,
+        );
+
+        pane.update(cx, |pane, cx| pane.sort_items_by_directory(cx));
+        // Pinned items stay in place, and items without a path go last.
+        assert_item_labels(
+            &pane,
+            ["pinned", "a-one", "a-two*", "a-nested", "b-two", "untitled"],
+            cx,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_sort_items_by_language(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        for (name, suffix) in [("Rust", "rs"), ("Markdown", "md")] {
+            project.read_with(cx, |project, _| {
+                project
+                    .languages()
+                    .register_test_language(language::LanguageConfig {
+                        name: name.into(),
+                        matcher: language::LanguageMatcher {
+                            path_suffixes: vec![suffix.to_string()],
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+            });
+        }
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        for (id, label, path) in [
+            (1, "main", Some("src/main.rs")),
+            (2, "untitled", None),
+            (3, "data", Some("data.bin")),
+            (4, "readme", Some("README.md")),
+            (5, "lib", Some("src/lib.rs")),
+        ] {
+            pane.update(cx, |pane, cx| {
+                let project_item = match path {
+                    Some(path) => TestProjectItem::new(id, path, cx),
+                    None => TestProjectItem::new_untitled(cx),
+                };
+                let item = cx.new_view(|cx| {
+                    TestItem::new(cx)
+                        .with_label(label)
+                        .with_project_items(&[project_item])
+                });
+                pane.add_item(Box::new(item), false, false, None, cx);
+            });
+        }
+        pane.update(cx, |pane, cx| pane.activate_item(0, false, false, cx));
+
+        pane.update(cx, |pane, cx| pane.sort_items_by_language(cx));
+        // Items are grouped by language, and items without one go last.
+        assert_item_labels(&pane, ["readme", "lib", "main*", "data", "untitled"], cx);
+    }
+
+    #[gpui::test]
+    async fn test_close_items_of_removed_files(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            serde_json::json!({ "a.txt": "", "b.txt": "", "c.txt": "" }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        for (id, label, path, is_dirty) in [
+            (1, "a", "a.txt", false),
+            (2, "b", "b.txt", true),
+            (3, "c", "c.txt", false),
+        ] {
+            pane.update(cx, |pane, cx| {
+                let project_item = TestProjectItem::new(id, path, cx);
+                project_item.update(cx, |project_item, _| {
+                    project_item.project_path = Some((worktree_id, path).into());
+                });
+                let item = cx.new_view(|cx| {
+                    TestItem::new(cx)
+                        .with_label(label)
+                        .with_dirty(is_dirty)
+                        .with_singleton(true)
+                        .with_project_items(&[project_item])
+                });
+                pane.add_item(Box::new(item), false, false, None, cx);
+            });
+        }
+        assert_item_labels(&pane, ["a", "b^", "c*"], cx);
+
+        for path in ["/root/a.txt", "/root/b.txt"] {
+            fs.remove_file(path.as_ref(), Default::default())
+                .await
+                .unwrap();
+        }
+        cx.executor().run_until_parked();
+        // Items with unsaved edits are kept open when their file is removed.
+        assert_item_labels(&pane, ["b^", "c*"], cx);
+    }
+
     #[gpui::test]
     async fn test_add_item_with_new_item(cx: &mut TestAppContext) {
         init_test(cx);
@@ -9,7 +9,7 @@ use gpui::{point, size, Axis, Bounds, WindowBounds, WindowId};
 
 use language::{LanguageName, Toolchain};
 use project::WorktreeId;
-use remote::ssh_session::SshProjectId;
+use remote::{ssh_session::SshProjectId, ContainerOptions};
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
@@ -22,8 +22,9 @@ use uuid::Uuid;
 use crate::WorkspaceId;
 
 use model::{
-    GroupId, LocalPaths, PaneId, SerialieditsyncItem, SerialieditsyncPane,
-    SerialieditsyncPaneGroup, SerialieditsyncSshProject, SerialieditsyncWorkspace,
+    GroupId, LocalPaths, PaneId, SerialieditsyncDetachedWindow, SerialieditsyncItem,
+    SerialieditsyncPane, SerialieditsyncPaneGroup, SerialieditsyncSshProject,
+    SerialieditsyncWorkspace,
 };
 
 use self::model::{DockStructure, LocalPathsOrder, SerialieditsyncWorkspaceLocation};
@@ -383,6 +384,37 @@ define_connection! {
     sql!(
         ALTER TABLE toolchains ADD COLUMN raw_json TEXT DEFAULT "{}";
     ),
+    sql!(
+        CREATE TABLE workspace_layouts(
+            workspace_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            layout TEXT NOT NULL, // JSON serialized `WorkspaceLayout`
+            PRIMARY KEY(workspace_id, name),
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        ) STRICT;
+    ),
+    sql!(
+        CREATE TABLE detached_windows(
+            workspace_id INTEGER NOT NULL, // The workspace the window was detached from
+            position INTEGER NOT NULL,
+            window_state TEXT,
+            window_x REAL,
+            window_y REAL,
+            window_width REAL,
+            window_height REAL,
+            display BLOB,
+            center_group TEXT NOT NULL, // JSON serialized `LayoutPaneGroup`
+            PRIMARY KEY(workspace_id, position),
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        ) STRICT;
+    ),
+    sql!(
+        ALTER TABLE ssh_projects ADD COLUMN container TEXT; // JSON serialized `ContainerOptions`
+    ),
     ];
 }
 
@@ -660,38 +692,47 @@ impl WorkspaceDb {
         port: Option<u16>,
         paths: Vec<String>,
         user: Option<String>,
+        container: Option<ContainerOptions>,
     ) -> Result<SerialieditsyncSshProject> {
         let paths = serde_json::to_string(&paths)?;
+        let container = container.as_ref().map(serde_json::to_string).transpose()?;
         if let Some(project) = self
-            .get_ssh_project(host.clone(), port, paths.clone(), user.clone())
+            .get_ssh_project(
+                host.clone(),
+                port,
+                paths.clone(),
+                user.clone(),
+                container.clone(),
+            )
             .await?
         {
             Ok(project)
         } else {
-            self.insert_ssh_project(host, port, paths, user)
+            self.insert_ssh_project(host, port, paths, user, container)
                 .await?
                 .ok_or_else(|| anyhow!("failed to insert ssh project"))
         }
     }
 
     query! {
-        async fn get_ssh_project(host: String, port: Option<u16>, paths: String, user: Option<String>) -> Result<Option<SerialieditsyncSshProject>> {
-            SELECT id, host, port, paths, user
+        async fn get_ssh_project(host: String, port: Option<u16>, paths: String, user: Option<String>, container: Option<String>) -> Result<Option<SerialieditsyncSshProject>> {
+            SELECT id, host, port, paths, user, container
             FROM ssh_projects
-            WHERE host IS ? AND port IS ? AND paths IS ? AND user IS ?
+            WHERE host IS ? AND port IS ? AND paths IS ? AND user IS ? AND container IS ?
             LIMIT 1
         }
     }
 
     query! {
-        async fn insert_ssh_project(host: String, port: Option<u16>, paths: String, user: Option<String>) -> Result<Option<SerialieditsyncSshProject>> {
+        async fn insert_ssh_project(host: String, port: Option<u16>, paths: String, user: Option<String>, container: Option<String>) -> Result<Option<SerialieditsyncSshProject>> {
             INSERT INTO ssh_projects(
                 host,
                 port,
                 paths,
-                user
-            ) VALUES (?1, ?2, ?3, ?4)
-            RETURNING id, host, port, paths, user
+                user,
+                container
+            ) VALUES (?1, ?2, ?3, ?4, ?5)
+            RETURNING id, host, port, paths, user, container
         }
     }
 
@@ -722,14 +763,14 @@ impl WorkspaceDb {
 
     query! {
         fn ssh_projects() -> Result<Vec<SerialieditsyncSshProject>> {
-            SELECT id, host, port, paths, user
+            SELECT id, host, port, paths, user, container
             FROM ssh_projects
         }
     }
 
     query! {
         fn ssh_project(id: u64) -> Result<SerialieditsyncSshProject> {
-            SELECT id, host, port, paths, user
+            SELECT id, host, port, paths, user, container
             FROM ssh_projects
             WHERE id = ?
         }
@@ -1078,6 +1119,82 @@ impl WorkspaceDb {
         }
     }
 
+    pub(crate) async fn save_detached_windows(
+        &self,
+        workspace_id: WorkspaceId,
+        detached_windows: Vec<SerialieditsyncDetachedWindow>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_detached_windows", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM detached_windows WHERE workspace_id = ?
+                ))?(workspace_id)
+                .context("Clearing old detached windows")?;
+
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO detached_windows(
+                        workspace_id,
+                        position,
+                        window_state,
+                        window_x,
+                        window_y,
+                        window_width,
+                        window_height,
+                        display,
+                        center_group
+                    )
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ))?;
+                for (position, detached_window) in detached_windows.iter().enumerate() {
+                    insert((workspace_id, position, detached_window))
+                        .context("Inserting detached window")?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    query! {
+        pub(crate) async fn detached_windows(workspace_id: WorkspaceId) -> Result<Vec<SerialieditsyncDetachedWindow>> {
+            SELECT window_state, window_x, window_y, window_width, window_height, display, center_group
+            FROM detached_windows
+            WHERE workspace_id = ?
+            ORDER BY position
+        }
+    }
+
+    query! {
+        pub(crate) async fn layout_names(workspace_id: WorkspaceId) -> Result<Vec<String>> {
+            SELECT name
+            FROM workspace_layouts
+            WHERE workspace_id = ?
+            ORDER BY name
+        }
+    }
+
+    query! {
+        pub(crate) async fn layout(workspace_id: WorkspaceId, name: String) -> Result<Option<String>> {
+            SELECT layout
+            FROM workspace_layouts
+            WHERE workspace_id = ? AND name = ?
+        }
+    }
+
+    query! {
+        pub(crate) async fn save_layout(workspace_id: WorkspaceId, name: String, layout: String) -> Result<()> {
+            INSERT OR REPLACE INTO workspace_layouts(workspace_id, name, layout)
+            VALUES (?, ?, ?)
+        }
+    }
+
+    query! {
+        pub(crate) async fn delete_layout(workspace_id: WorkspaceId, name: String) -> Result<()> {
+            DELETE FROM workspace_layouts
+            WHERE workspace_id = ? AND name = ?
+        }
+    }
+
     pub async fn toolchain(
         &self,
         workspace_id: WorkspaceId,
@@ -1532,7 +1649,7 @@ mod tests {
         };
 
         let ssh_project = db
-            .get_or_create_ssh_project("my-host".to_string(), Some(1234), vec![], None)
+            .get_or_create_ssh_project("my-host".to_string(), Some(1234), vec![], None, None)
             .await
             .unwrap();
 
@@ -1727,9 +1844,15 @@ mod tests {
         ]
         .into_iter()
         .map(|(host, user)| async {
-            db.get_or_create_ssh_project(host.to_string(), None, vec![], Some(user.to_string()))
-                .await
-                .unwrap()
+            db.get_or_create_ssh_project(
+                host.to_string(),
+                None,
+                vec![],
+                Some(user.to_string()),
+                None,
+            )
+            .await
+            .unwrap()
         })
         .collect::<Vec<_>>();
 
@@ -1800,7 +1923,7 @@ mod tests {
         );
 
         let project = db
-            .get_or_create_ssh_project(host.clone(), port, paths.clone(), user.clone())
+            .get_or_create_ssh_project(host.clone(), port, paths.clone(), user.clone(), None)
             .await
             .unwrap();
 
@@ -1810,7 +1933,7 @@ mod tests {
 
         // Test that calling the function again with the same parameters returns the same project
         let same_project = db
-            .get_or_create_ssh_project(host.clone(), port, paths.clone(), user.clone())
+            .get_or_create_ssh_project(host.clone(), port, paths.clone(), user.clone(), None)
             .await
             .unwrap();
 
@@ -1824,7 +1947,7 @@ mod tests {
         );
 
         let different_project = db
-            .get_or_create_ssh_project(host2.clone(), None, paths2.clone(), user2.clone())
+            .get_or_create_ssh_project(host2.clone(), None, paths2.clone(), user2.clone(), None)
             .await
             .unwrap();
 
@@ -1856,13 +1979,48 @@ mod tests {
 
         // Test that calling the function again with the same parameters returns the same project
         let same_project = db
-            .get_or_create_ssh_project(host.clone(), port, paths.clone(), user.clone())
+            .get_or_create_ssh_project(host.clone(), port, paths.clone(), user.clone(), None)
             .await
             .unwrap();
 
         assert_eq!(project.id, same_project.id);
     }
 
+    #[gpui::test]
+    async fn test_get_or_create_ssh_project_with_container() {
+        let db = WorkspaceDb(open_test_db("test_get_or_create_ssh_project_with_container").await);
+
+        let container = ContainerOptions {
+            runtime: remote::ContainerRuntime::Podman,
+            target: remote::ContainerTarget::Container("dev".to_string()),
+            user: None,
+        };
+        let paths = vec!["/workspaces/project".to_string()];
+
+        let ssh_project = db
+            .get_or_create_ssh_project("dev".to_string(), None, paths.clone(), None, None)
+            .await
+            .unwrap();
+        let container_project = db
+            .get_or_create_ssh_project(
+                "dev".to_string(),
+                None,
+                paths.clone(),
+                None,
+                Some(container.clone()),
+            )
+            .await
+            .unwrap();
+
+        assert_ne!(ssh_project.id, container_project.id);
+        assert_eq!(ssh_project.container, None);
+        assert_eq!(container_project.container, Some(container.clone()));
+        assert_eq!(
+            db.ssh_project(container_project.id.0).unwrap(),
+            container_project
+        );
+    }
+
     #[gpui::test]
     async fn test_get_ssh_projects() {
         let db = WorkspaceDb(open_test_db("test_get_ssh_projects").await);
@@ -1890,7 +2048,7 @@ mod tests {
 
         for (host, port, paths, user) in projects.iter() {
             let project = db
-                .get_or_create_ssh_project(host.clone(), *port, paths.clone(), user.clone())
+                .get_or_create_ssh_project(host.clone(), *port, paths.clone(), user.clone(), None)
                 .await
                 .unwrap();
 
@@ -1904,6 +2062,92 @@ mod tests {
         assert_eq!(stored_projects.len(), projects.len());
     }
 
+    #[gpui::test]
+    async fn test_workspace_layouts() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_workspace_layouts").await);
+        let workspace = default_workspace(&["/tmp"], &Default::default());
+        db.save_workspace(workspace.clone()).await;
+
+        db.save_layout(workspace.id, "review".into(), "{\"review\":1}".into())
+            .await
+            .unwrap();
+        db.save_layout(workspace.id, "debug".into(), "{\"debug\":1}".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.layout_names(workspace.id).await.unwrap(),
+            ["debug", "review"]
+        );
+        assert!(db.layout_names(WorkspaceId(5)).await.unwrap().is_empty());
+
+        db.save_layout(workspace.id, "review".into(), "{\"review\":2}".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.layout(workspace.id, "review".into()).await.unwrap(),
+            Some("{\"review\":2}".to_string())
+        );
+
+        db.delete_layout(workspace.id, "review".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.layout(workspace.id, "review".into()).await.unwrap(),
+            None
+        );
+        assert_eq!(db.layout_names(workspace.id).await.unwrap(), ["debug"]);
+
+        db.delete_workspace_by_id(workspace.id).await.unwrap();
+        assert_eq!(db.layout(workspace.id, "debug".into()).await.unwrap(), None);
+    }
+
+    #[gpui::test]
+    async fn test_detached_windows() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_detached_windows").await);
+        let workspace = default_workspace(&["/tmp"], &Default::default());
+        db.save_workspace(workspace.clone()).await;
+
+        let detached_windows = vec![
+            SerialieditsyncDetachedWindow {
+                window_bounds: Some(SerialieditsyncWindowBounds(WindowBounds::Windowed(
+                    Bounds {
+                        origin: point(px(10.), px(20.)),
+                        size: size(px(800.), px(600.)),
+                    },
+                ))),
+                display: Some(Uuid::from_u128(42)),
+                center_group: "{\"first\":1}".to_string(),
+            },
+            SerialieditsyncDetachedWindow {
+                window_bounds: None,
+                display: None,
+                center_group: "{\"second\":2}".to_string(),
+            },
+        ];
+        db.save_detached_windows(workspace.id, detached_windows.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.detached_windows(workspace.id).await.unwrap(),
+            detached_windows
+        );
+
+        db.save_detached_windows(workspace.id, detached_windows[1..].to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.detached_windows(workspace.id).await.unwrap(),
+            &detached_windows[1..]
+        );
+
+        db.delete_workspace_by_id(workspace.id).await.unwrap();
+        assert!(db.detached_windows(workspace.id).await.unwrap().is_empty());
+    }
+
     #[gpui::test]
     async fn test_simple_split() {
         env_logger::try_init().ok();
@@ -10,7 +10,7 @@ use db::sqlez::{
 };
 use gpui::{AsyncWindowContext, Model, View, WeakView};
 use project::Project;
-use remote::ssh_session::SshProjectId;
+use remote::{ssh_session::SshProjectId, ContainerOptions};
 use serde::{Deserialize, Serialize};
 use std::{
     path::{Path, PathBuf},
@@ -26,6 +26,8 @@ pub struct SerialieditsyncSshProject {
     pub port: Option<u16>,
     pub paths: Vec<String>,
     pub user: Option<String>,
+    /// The container the project is in, if it isn't opened over SSH.
+    pub container: Option<ContainerOptions>,
 }
 
 impl SerialieditsyncSshProject {
@@ -34,6 +36,12 @@ impl SerialieditsyncSshProject {
             .iter()
             .map(|path| {
                 let mut result = String::new();
+                if let Some(container) = &self.container {
+                    result.push_str(&container.display_name());
+                    result.push(':');
+                    result.push_str(path);
+                    return PathBuf::from(result);
+                }
                 if let Some(user) = &self.user {
                     result.push_str(user);
                     result.push('@');
@@ -52,7 +60,7 @@ impl SerialieditsyncSshProject {
 
 impl StaticColumnCount for SerialieditsyncSshProject {
     fn column_count() -> usize {
-        5
+        6
     }
 }
 
@@ -63,7 +71,13 @@ impl Bind for &SerialieditsyncSshProject {
         let next_index = statement.bind(&self.port, next_index)?;
         let raw_paths = serde_json::to_string(&self.paths)?;
         let next_index = statement.bind(&raw_paths, next_index)?;
-        statement.bind(&self.user, next_index)
+        let next_index = statement.bind(&self.user, next_index)?;
+        let raw_container = self
+            .container
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        statement.bind(&raw_container, next_index)
     }
 }
 
@@ -76,6 +90,10 @@ impl Column for SerialieditsyncSshProject {
         let paths: Vec<String> = serde_json::from_str(&raw_paths)?;
 
         let (user, _) = Option::<String>::column(statement, start_index + 4)?;
+        let (raw_container, _) = Option::<String>::column(statement, start_index + 5)?;
+        let container = raw_container
+            .map(|raw_container| serde_json::from_str(&raw_container))
+            .transpose()?;
 
         Ok((
             Self {
@@ -84,8 +102,9 @@ impl Column for SerialieditsyncSshProject {
                 port,
                 paths,
                 user,
+                container,
             },
-            start_index + 5,
+            start_index + 6,
         ))
     }
 }
@@ -305,6 +324,46 @@ impl Bind for DockData {
     }
 }
 
+/// A window showing panes detached from a workspace, whose panes are saved as a JSON serialized
+/// `LayoutPaneGroup`, as the workspace of a detached window has no id of its own.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct SerialieditsyncDetachedWindow {
+    pub(crate) window_bounds: Option<SerialieditsyncWindowBounds>,
+    pub(crate) display: Option<Uuid>,
+    pub(crate) center_group: String,
+}
+
+impl StaticColumnCount for SerialieditsyncDetachedWindow {
+    fn column_count() -> usize {
+        SerialieditsyncWindowBounds::column_count() + 2
+    }
+}
+
+impl Bind for &SerialieditsyncDetachedWindow {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        let next_index = statement.bind(&self.window_bounds, start_index)?;
+        let next_index = statement.bind(&self.display, next_index)?;
+        statement.bind(&self.center_group, next_index)
+    }
+}
+
+impl Column for SerialieditsyncDetachedWindow {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (window_bounds, next_index) =
+            Option::<SerialieditsyncWindowBounds>::column(statement, start_index)?;
+        let (display, next_index) = Option::<Uuid>::column(statement, next_index)?;
+        let (center_group, next_index) = String::column(statement, next_index)?;
+        Ok((
+            SerialieditsyncDetachedWindow {
+                window_bounds,
+                display,
+                center_group,
+            },
+            next_index,
+        ))
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) enum SerialieditsyncPaneGroup {
     Group {
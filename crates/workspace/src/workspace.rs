@@ -1,5 +1,7 @@
+mod detached_windows;
 pub mod dock;
 pub mod item;
+mod layouts;
 mod modal_layer;
 pub mod notifications;
 pub mod pane;
@@ -52,14 +54,11 @@ use notifications::{
 };
 pub use pane::*;
 pub use pane_group::*;
+use persistence::{model::SerialieditsyncWorkspace, SerialieditsyncWindowBounds, DB};
 pub use persistence::{
-    model::{ItemId, LocalPaths, SerialieditsyncWorkspaceLocation},
+    model::{ItemId, LocalPaths, SerialieditsyncSshProject, SerialieditsyncWorkspaceLocation},
     WorkspaceDb, DB as WORKSPACE_DB,
 };
-use persistence::{
-    model::{SerialieditsyncSshProject, SerialieditsyncWorkspace},
-    SerialieditsyncWindowBounds, DB,
-};
 use postage::stream::Stream;
 use project::{
     DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
@@ -132,11 +131,14 @@ actions!(
         ActivateNextPane,
         ActivatePreviousPane,
         AddFolderToProject,
+        AttachWindow,
         ClearAllNotifications,
         CloseAllDocks,
         CloseWindow,
         CopyPath,
         CopyRelativePath,
+        DetachActiveItem,
+        DetachActivePane,
         Feedback,
         FollowNextCollaborator,
         NewCenterTerminal,
@@ -216,6 +218,12 @@ pub struct CloseInactiveTabsAndPanes {
 #[derive(Clone, Deserialize, PartialEq)]
 pub struct SendKeystrokes(pub String);
 
+/// Switches to the layout saved under the given name.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct SwitchLayout {
+    pub name: String,
+}
+
 #[derive(Clone, Deserialize, PartialEq, Default)]
 pub struct Reload {
     pub binary_path: Option<PathBuf>,
@@ -246,6 +254,7 @@ impl_actions!(
         SaveAll,
         SwapPaneInDirection,
         SendKeystrokes,
+        SwitchLayout,
     ]
 );
 
@@ -782,6 +791,10 @@ pub struct Workspace {
     serialieditsync_ssh_project: Option<SerialieditsyncSshProject>,
     _items_serializer: Task<Result<()>>,
     session_id: Option<String>,
+    /// The window of the workspace this one was detached from, when this workspace shows panes
+    /// detached into their own window.
+    main_window: Option<WindowHandle<Workspace>>,
+    detached_windows: Vec<WindowHandle<Workspace>>,
 }
 
 impl EventEmitter<Event> for Workspace {}
@@ -1009,6 +1022,9 @@ impl Workspace {
                                 }
                             }
                         }
+                        if this.main_window.is_some() {
+                            this.serialize_workspace(cx);
+                        }
                         this.bounds_save_task_queued.take();
                     })
                     .ok();
@@ -1087,6 +1103,8 @@ impl Workspace {
             _items_serializer,
             session_id: Some(session_id),
             serialieditsync_ssh_project: None,
+            main_window: None,
+            detached_windows: Vec::new(),
         }
     }
 
@@ -1313,6 +1331,30 @@ impl Workspace {
         &self.project
     }
 
+    /// Returns whether this workspace should handle the events of its project that aren't tied to
+    /// a workspace, as several windows can show the same project. These are handled in the active
+    /// window if it shows the project, or else in the first window that does.
+    pub fn is_main_workspace_for_project(&self, cx: &WindowContext) -> bool {
+        let current_window = cx.window_handle();
+        // The current window's workspace is being updated, so it can't be read here.
+        let project_windows = cx
+            .windows()
+            .into_iter()
+            .filter(|window| {
+                *window == current_window
+                    || window
+                        .downcast::<Workspace>()
+                        .and_then(|workspace| workspace.read(cx).ok())
+                        .is_some_and(|workspace| workspace.project == self.project)
+            })
+            .collect::<Vec<_>>();
+        let main_window = cx
+            .active_window()
+            .filter(|window| project_windows.contains(window))
+            .or_else(|| project_windows.first().copied());
+        main_window == Some(current_window)
+    }
+
     pub fn recent_navigation_history(
         &self,
         limit: Option<usize>,
@@ -1737,7 +1779,8 @@ impl Workspace {
         let window = cx.window_handle();
         cx.spawn(|_, mut cx| async move {
             if prepare.await? {
-                window.update(&mut cx, |_, cx| {
+                window.update(&mut cx, |workspace, cx| {
+                    workspace.close_detached_windows(cx);
                     cx.remove_window();
                 })?;
             }
@@ -4130,6 +4173,13 @@ impl Workspace {
     }
 
     fn serialize_workspace(&mut self, cx: &mut ViewContext<Self>) {
+        // Detached windows are saved by the workspace they were detached from.
+        if let Some(main_window) = self.main_window {
+            main_window
+                .update(cx, |main, cx| main.serialize_workspace(cx))
+                .ok();
+            return;
+        }
         if self._schedule_serialize.is_none() {
             self._schedule_serialize = Some(cx.spawn(|this, mut cx| async move {
                 cx.background_executor()
@@ -4268,6 +4318,8 @@ impl Workspace {
         if let Some(location) = location {
             let center_group = build_serialieditsync_pane_group(&self.center.root, cx);
             let docks = build_serialieditsync_docks(self, cx);
+            let detached_windows =
+                (!self.detached_windows.is_empty()).then(|| self.serialize_detached_windows(cx));
             let window_bounds = Some(SerialieditsyncWindowBounds(cx.window_bounds()));
             let serialieditsync_workspace = SerialieditsyncWorkspace {
                 id: database_id,
@@ -4280,7 +4332,17 @@ impl Workspace {
                 session_id: self.session_id.clone(),
                 window_id: Some(cx.window_handle().window_id().as_u64()),
             };
-            return cx.spawn(|_| persistence::DB.save_workspace(serialieditsync_workspace));
+            return cx.spawn(|_| async move {
+                persistence::DB
+                    .save_workspace(serialieditsync_workspace)
+                    .await;
+                if let Some(detached_windows) = detached_windows {
+                    persistence::DB
+                        .save_detached_windows(database_id, detached_windows)
+                        .await
+                        .log_err();
+                }
+            });
         }
         Task::ready(())
     }
@@ -4446,6 +4508,10 @@ impl Workspace {
 
                     // Ensure that we mark the window as edited if we did load dirty items
                     workspace.update_window_edited(cx);
+
+                    workspace
+                        .restore_detached_windows(serialieditsync_workspace.id, cx)
+                        .detach_and_log_err(cx);
                 })
                 .ok();
 
@@ -4464,6 +4530,9 @@ impl Workspace {
             .on_action(cx.listener(Self::close_window))
             .on_action(cx.listener(Self::activate_pane_at_index))
             .on_action(cx.listener(Self::move_item_to_pane_at_index))
+            .on_action(cx.listener(Self::detach_active_item))
+            .on_action(cx.listener(Self::detach_active_pane))
+            .on_action(cx.listener(Self::attach_window))
             .on_action(cx.listener(|workspace, _: &Unfollow, cx| {
                 let pane = workspace.active_pane().clone();
                 workspace.unfollow_in_pane(&pane, cx);
@@ -4483,6 +4552,11 @@ impl Workspace {
                     .save_active_item(SaveIntent::SaveAs, cx)
                     .detach_and_prompt_err("Failed to save", cx, |_, _| None);
             }))
+            .on_action(cx.listener(|workspace, action: &SwitchLayout, cx| {
+                workspace
+                    .switch_layout(action.name.clone(), cx)
+                    .detach_and_prompt_err("Failed to switch layout", cx, |_, _| None);
+            }))
             .on_action(cx.listener(|workspace, _: &ActivatePreviousPane, cx| {
                 workspace.activate_previous_pane(cx)
             }))
@@ -5776,6 +5850,7 @@ fn serialize_ssh_project(
                     .map(|path| path.to_string_lossy().to_string())
                     .collect::<Vec<_>>(),
                 connection_options.username.clone(),
+                connection_options.container.clone(),
             )
             .await?;
 
@@ -8223,6 +8298,389 @@ mod tests {
         }
     }
 
+    mod detached_windows_tests {
+        use gpui::Context as _;
+
+        use super::*;
+
+        struct TestFileItem {
+            project_path: ProjectPath,
+        }
+
+        impl project::ProjectItem for TestFileItem {
+            fn try_open(
+                _project: &Model<Project>,
+                path: &ProjectPath,
+                cx: &mut AppContext,
+            ) -> Option<Task<gpui::Result<Model<Self>>>> {
+                let project_path = path.clone();
+                Some(
+                    cx.spawn(
+                        |mut cx| async move { cx.new_model(|_| TestFileItem { project_path }) },
+                    ),
+                )
+            }
+
+            fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+                None
+            }
+
+            fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+                Some(self.project_path.clone())
+            }
+
+            fn is_dirty(&self) -> bool {
+                false
+            }
+        }
+
+        pub(super) struct TestFileItemView {
+            item: Model<TestFileItem>,
+            focus_handle: FocusHandle,
+        }
+
+        impl Item for TestFileItemView {
+            type Event = ();
+
+            fn for_each_project_item(
+                &self,
+                cx: &AppContext,
+                f: &mut dyn FnMut(EntityId, &dyn project::ProjectItem),
+            ) {
+                f(self.item.entity_id(), self.item.read(cx))
+            }
+
+            fn is_singleton(&self, _: &AppContext) -> bool {
+                true
+            }
+        }
+
+        impl EventEmitter<()> for TestFileItemView {}
+
+        impl FocusableView for TestFileItemView {
+            fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+                self.focus_handle.clone()
+            }
+        }
+
+        impl Render for TestFileItemView {
+            fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+                Empty
+            }
+        }
+
+        impl ProjectItem for TestFileItemView {
+            type Item = TestFileItem;
+
+            fn for_project_item(
+                _project: Model<Project>,
+                item: Model<Self::Item>,
+                cx: &mut ViewContext<Self>,
+            ) -> Self {
+                Self {
+                    item,
+                    focus_handle: cx.focus_handle(),
+                }
+            }
+        }
+
+        pub(super) fn item_paths(workspace: &Workspace, cx: &AppContext) -> Vec<String> {
+            workspace
+                .items(cx)
+                .filter_map(|item| item.project_path(cx))
+                .map(|project_path| project_path.path.to_string_lossy().into_owned())
+                .collect()
+        }
+
+        #[gpui::test]
+        async fn test_detach_attach_and_restore_pane(cx: &mut TestAppContext) {
+            init_test(cx);
+            cx.update(|cx| register_project_item::<TestFileItemView>(cx));
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree("/root", json!({ "one.txt": "", "two.txt": "" }))
+                .await;
+            let project = Project::test(fs, ["/root".as_ref()], cx).await;
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
+            let workspace_id = DB.next_id().await.unwrap();
+
+            let (workspace, window_cx) = cx.add_window_view(|cx| {
+                let mut workspace = Workspace::test_new(project.clone(), cx);
+                workspace.database_id = Some(workspace_id);
+                workspace
+            });
+            for path in ["one.txt", "two.txt"] {
+                workspace
+                    .update(window_cx, |workspace, cx| {
+                        workspace.open_path((worktree_id, path), None, true, cx)
+                    })
+                    .await
+                    .unwrap();
+            }
+
+            // Detaching the pane moves its items into a new window.
+            workspace.update(window_cx, |workspace, cx| {
+                workspace.detach_active_pane(&DetachActivePane, cx)
+            });
+            window_cx.run_until_parked();
+            let detached_window = workspace.update(window_cx, |workspace, cx| {
+                assert!(item_paths(workspace, cx).is_empty());
+                assert_eq!(workspace.detached_windows().len(), 1);
+                workspace.detached_windows()[0]
+            });
+            window_cx.update(|cx| {
+                let detached = detached_window.read(cx).unwrap();
+                assert_eq!(item_paths(detached, cx), ["one.txt", "two.txt"]);
+                assert_eq!(
+                    detached.main_window().map(|window| window.window_id()),
+                    Some(cx.window_handle().window_id())
+                );
+            });
+
+            // Attaching the window moves its items back and closes it.
+            detached_window
+                .update(window_cx, |detached, cx| {
+                    detached.attach_window(&AttachWindow, cx)
+                })
+                .unwrap();
+            window_cx.run_until_parked();
+            workspace.update(window_cx, |workspace, cx| {
+                assert_eq!(item_paths(workspace, cx), ["one.txt", "two.txt"]);
+                assert!(workspace.detached_windows().is_empty());
+            });
+            assert!(!window_cx.windows().contains(&detached_window.into()));
+            assert!(DB.detached_windows(workspace_id).await.unwrap().is_empty());
+
+            // Detached windows are saved along with the workspace, and reopened with it.
+            workspace.update(window_cx, |workspace, cx| {
+                workspace.detach_active_pane(&DetachActivePane, cx)
+            });
+            window_cx.run_until_parked();
+            workspace
+                .update(window_cx, |workspace, cx| {
+                    workspace.serialize_workspace_internal(cx)
+                })
+                .await;
+            workspace.update(window_cx, |workspace, cx| {
+                workspace.close_window(&CloseWindow, cx)
+            });
+            window_cx.run_until_parked();
+            assert!(window_cx.windows().is_empty());
+            assert_eq!(DB.detached_windows(workspace_id).await.unwrap().len(), 1);
+
+            let (workspace, cx) = cx.add_window_view(|cx| {
+                let mut workspace = Workspace::test_new(project.clone(), cx);
+                workspace.database_id = Some(workspace_id);
+                workspace
+            });
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.restore_detached_windows(workspace_id, cx)
+                })
+                .await
+                .unwrap();
+            cx.run_until_parked();
+            let detached_window = workspace.update(cx, |workspace, cx| {
+                assert!(item_paths(workspace, cx).is_empty());
+                assert_eq!(workspace.detached_windows().len(), 1);
+                workspace.detached_windows()[0]
+            });
+            cx.update(|cx| {
+                let detached = detached_window.read(cx).unwrap();
+                assert_eq!(item_paths(detached, cx), ["one.txt", "two.txt"]);
+            });
+        }
+
+        #[gpui::test]
+        async fn test_detach_pane_with_dirty_untitled_item(cx: &mut TestAppContext) {
+            init_test(cx);
+            cx.update(|cx| register_project_item::<TestFileItemView>(cx));
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree("/root", json!({ "one.txt": "" })).await;
+            let project = Project::test(fs, ["/root".as_ref()], cx).await;
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.open_path((worktree_id, "one.txt"), None, true, cx)
+                })
+                .await
+                .unwrap();
+            let untitled = cx.new_view(|cx| {
+                TestItem::new(cx)
+                    .with_dirty(true)
+                    .with_project_items(&[TestProjectItem::new_untitled(cx)])
+            });
+
+            // Only the items that are reopened in the new window are closed, without losing the
+            // unsaved changes of the others.
+            workspace.update(cx, |workspace, cx| {
+                workspace.add_item_to_active_pane(Box::new(untitled.clone()), None, true, cx);
+                workspace.detach_active_pane(&DetachActivePane, cx)
+            });
+            cx.run_until_parked();
+            assert!(!cx.has_pending_prompt());
+            let detached_window = workspace.update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().read(cx);
+                assert_eq!(pane.items_len(), 1);
+                assert_eq!(
+                    pane.items().next().map(|item| item.item_id()),
+                    Some(untitled.item_id())
+                );
+                assert!(untitled.read(cx).is_dirty);
+                assert_eq!(workspace.detached_windows().len(), 1);
+                workspace.detached_windows()[0]
+            });
+            cx.update(|cx| {
+                let detached = detached_window.read(cx).unwrap();
+                assert_eq!(item_paths(detached, cx), ["one.txt"]);
+            });
+        }
+    }
+
+    mod layouts_tests {
+        use super::{
+            detached_windows_tests::{item_paths, TestFileItemView},
+            *,
+        };
+
+        #[gpui::test]
+        async fn test_save_and_switch_layouts(cx: &mut TestAppContext) {
+            init_test(cx);
+            cx.update(|cx| register_project_item::<TestFileItemView>(cx));
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree(
+                "/root",
+                json!({ "one.txt": "", "two.txt": "", "three.txt": "" }),
+            )
+            .await;
+            let project = Project::test(fs, ["/root".as_ref()], cx).await;
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
+            let workspace_id = DB.next_id().await.unwrap();
+            let (workspace, cx) = cx.add_window_view(|cx| {
+                let mut workspace = Workspace::test_new(project.clone(), cx);
+                workspace.database_id = Some(workspace_id);
+                workspace
+            });
+
+            let untitled = cx.new_view(|cx| {
+                TestItem::new(cx).with_project_items(&[TestProjectItem::new_untitled(cx)])
+            });
+            workspace.update(cx, |workspace, cx| {
+                workspace.add_item_to_active_pane(Box::new(untitled.clone()), None, true, cx);
+            });
+            for path in ["one.txt", "two.txt"] {
+                workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.open_path((worktree_id, path), None, true, cx)
+                    })
+                    .await
+                    .unwrap();
+            }
+            let right_pane = workspace.update(cx, |workspace, cx| {
+                workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Right, cx)
+            });
+            workspace
+                .update(cx, |workspace, cx| {
+                    let pane = Some(right_pane.downgrade());
+                    workspace.open_path((worktree_id, "one.txt"), pane, true, cx)
+                })
+                .await
+                .unwrap();
+            workspace.update(cx, |workspace, cx| {
+                workspace.set_active_pane(&right_pane, cx)
+            });
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.save_layout("split".into(), cx)
+                })
+                .await
+                .unwrap();
+
+            // Switch away to a layout with a single item.
+            workspace.update(cx, |workspace, cx| {
+                workspace.close_all_items_and_panes(&CloseAllItemsAndPanes::default(), cx)
+            });
+            cx.run_until_parked();
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.open_path((worktree_id, "three.txt"), None, true, cx)
+                })
+                .await
+                .unwrap();
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.save_layout("single".into(), cx)
+                })
+                .await
+                .unwrap();
+            assert_eq!(
+                workspace
+                    .update(cx, |workspace, cx| workspace.layout_names(cx))
+                    .await
+                    .unwrap(),
+                ["single", "split"]
+            );
+
+            // Switching back reopens the panes and their items, except for the ones without a
+            // project path.
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.switch_layout("split".into(), cx)
+                })
+                .await
+                .unwrap();
+            cx.run_until_parked();
+            workspace.update(cx, |workspace, cx| {
+                assert_eq!(workspace.panes().len(), 2);
+                let pane_paths = workspace
+                    .panes()
+                    .iter()
+                    .map(|pane| {
+                        pane.read(cx)
+                            .items()
+                            .filter_map(|item| item.project_path(cx))
+                            .map(|path| path.path.to_string_lossy().into_owned())
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(pane_paths, [vec!["one.txt", "two.txt"], vec!["one.txt"]]);
+                assert_eq!(workspace.active_pane(), &workspace.panes()[1]);
+                assert_eq!(
+                    workspace.panes()[0]
+                        .read(cx)
+                        .active_item()
+                        .and_then(|item| item.project_path(cx))
+                        .map(|path| path.path.to_string_lossy().into_owned()),
+                    Some("two.txt".to_string())
+                );
+                assert!(workspace
+                    .items(cx)
+                    .all(|item| item.item_id() != untitled.item_id()));
+            });
+
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.switch_layout("single".into(), cx)
+                })
+                .await
+                .unwrap();
+            cx.run_until_parked();
+            workspace.update(cx, |workspace, cx| {
+                assert_eq!(workspace.panes().len(), 1);
+                assert_eq!(item_paths(workspace, cx), ["three.txt"]);
+            });
+        }
+    }
+
     pub fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
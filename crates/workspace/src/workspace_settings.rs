@@ -136,8 +136,9 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: true
     pub show_user_picture: Option<bool>,
-    // Maximum open tabs in a pane. Will not close an unsaved
-    // tab. Set to `None` for unlimited tabs.
+    // Maximum open tabs in a pane. Closes the least recently used
+    // tabs, but not unsaved or pinned ones. Set to `None` for
+    // unlimited tabs.
     //
     // Default: none
     pub max_tabs: Option<NonZeroUsize>,
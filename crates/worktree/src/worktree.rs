@@ -39,7 +39,7 @@ use postage::{
 };
 use rpc::{
     proto::{self, split_worktree_update},
-    AnyProtoClient,
+    AnyProtoClient, ErrorCode,
 };
 pub use settings::WorktreeId;
 use settings::{Settings, SettingsLocation, SettingsStore};
@@ -914,6 +914,70 @@ impl Worktree {
         }
     }
 
+    /// Reads the content of a file entry. For remote worktrees, the content is requested from
+    /// the host.
+    pub fn read_entry(
+        &self,
+        entry_id: ProjectEntryId,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<Vec<u8>>> {
+        match self {
+            Worktree::Local(this) => {
+                let Some(path) = this.entry_for_id(entry_id).map(|entry| entry.path.clone()) else {
+                    return Task::ready(Err(anyhow!("no entry found for {entry_id:?}")));
+                };
+                let load = this.load_binary_file(&path, cx);
+                cx.background_executor()
+                    .spawn(async move { Ok(load.await?.content) })
+            }
+            Worktree::Remote(this) => {
+                let response = this.client.request(proto::ReadProjectEntry {
+                    project_id: this.project_id,
+                    entry_id: entry_id.to_proto(),
+                });
+                cx.spawn(|_, _| async move { Ok(response.await?.content) })
+            }
+        }
+    }
+
+    /// Writes a file with the given content, creating its parent directories if needed.
+    pub fn write_entry(
+        &mut self,
+        path: impl Into<Arc<Path>>,
+        content: Vec<u8>,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<Option<Entry>>> {
+        let path = path.into();
+        let worktree_id = self.id();
+        match self {
+            Worktree::Local(this) => this.write_entry(path, content, cx),
+            Worktree::Remote(this) => {
+                let response = this.client.request(proto::WriteProjectEntry {
+                    project_id: this.project_id,
+                    worktree_id: worktree_id.to_proto(),
+                    path: path.to_string_lossy().into(),
+                    content,
+                });
+                cx.spawn(move |this, mut cx| async move {
+                    let response = response.await?;
+                    match response.entry {
+                        Some(entry) => this
+                            .update(&mut cx, |worktree, cx| {
+                                worktree.as_remote_mut().unwrap().insert_entry(
+                                    entry,
+                                    response.worktree_scan_id as usize,
+                                    cx,
+                                )
+                            })?
+                            .await
+                            .map(Some),
+                        None => Ok(None),
+                    }
+                })
+            }
+        }
+    }
+
     pub fn expand_entry(
         &mut self,
         entry_id: ProjectEntryId,
@@ -1044,6 +1108,41 @@ impl Worktree {
             worktree_scan_id: scan_id as u64,
         })
     }
+
+    pub async fn handle_read_entry(
+        this: Model<Self>,
+        request: proto::ReadProjectEntry,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ReadProjectEntryResponse> {
+        let task = this.update(&mut cx, |this, cx| {
+            let entry_id = ProjectEntryId::from_proto(request.entry_id);
+            // Private files aren't shared with guests, like their buffers.
+            if this.entry_for_id(entry_id).map_or(false, |entry| entry.is_private) {
+                return Task::ready(Err(anyhow!(ErrorCode::UnsharedItem)));
+            }
+            this.read_entry(entry_id, cx)
+        })?;
+        Ok(proto::ReadProjectEntryResponse {
+            content: task.await?,
+        })
+    }
+
+    pub async fn handle_write_entry(
+        this: Model<Self>,
+        request: proto::WriteProjectEntry,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ProjectEntryResponse> {
+        let (scan_id, task) = this.update(&mut cx, |this, cx| {
+            (
+                this.scan_id(),
+                this.write_entry(PathBuf::from(request.path), request.content, cx),
+            )
+        })?;
+        Ok(proto::ProjectEntryResponse {
+            entry: task.await?.as_ref().map(|e| e.into()),
+            worktree_scan_id: scan_id as u64,
+        })
+    }
 }
 
 impl LocalWorktree {
@@ -1557,6 +1656,38 @@ impl LocalWorktree {
         })
     }
 
+    fn write_entry(
+        &self,
+        path: Arc<Path>,
+        content: Vec<u8>,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<Option<Entry>>> {
+        let abs_path = match self.absolutize(&path) {
+            Ok(path) => path,
+            Err(e) => return Task::ready(Err(e.context(format!("absolutizing path {path:?}")))),
+        };
+        let fs = self.fs.clone();
+        let write = cx.background_executor().spawn(async move {
+            if let Some(parent) = abs_path.parent() {
+                fs.create_dir(parent)
+                    .await
+                    .with_context(|| format!("creating directory {parent:?}"))?;
+            }
+            let mut content = content.as_slice();
+            fs.create_file_with(&abs_path, Pin::new(&mut content))
+                .await
+                .with_context(|| format!("writing file {abs_path:?}"))
+        });
+
+        cx.spawn(move |this, mut cx| async move {
+            write.await?;
+            this.update(&mut cx, |this, cx| {
+                this.as_local_mut().unwrap().refresh_entry(path, None, cx)
+            })?
+            .await
+        })
+    }
+
     fn delete_entry(
         &self,
         entry_id: ProjectEntryId,